@@ -0,0 +1,118 @@
+use criterion::{
+    black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput,
+};
+use druid::Data;
+use std::time::Duration;
+use zeitig::state::{
+    backend::{Backend, Sqlite},
+    export,
+    template::Template,
+    Content, History, SpentTime,
+};
+
+mod synthetic;
+use synthetic::Generator;
+
+const SIZES: &[usize] = &[100, 1_000, 10_000];
+
+/// Druid compares the state after every event, the timer ticks once a second.
+fn data_same(c: &mut Criterion) {
+    let mut group = c.benchmark_group("same");
+    for &size in SIZES {
+        let data = Generator::new(1).app_state(size);
+
+        let unchanged = data.clone();
+        group.bench_with_input(BenchmarkId::new("unchanged", size), &size, |b, _| {
+            b.iter(|| black_box(&data).same(black_box(&unchanged)))
+        });
+
+        let mut ticked = data.clone();
+        if let Some(active) = ticked.active.as_mut() {
+            active.duration += SpentTime::from(Duration::from_secs(1));
+        }
+        group.bench_with_input(BenchmarkId::new("tick", size), &size, |b, _| {
+            b.iter(|| black_box(&data).same(black_box(&ticked)))
+        });
+
+        let mut rebuilt = data.clone();
+        rebuilt.history = History::new(data.history.iter().cloned().collect(), true);
+        group.bench_with_input(BenchmarkId::new("rebuilt history", size), &size, |b, _| {
+            b.iter(|| black_box(&data).same(black_box(&rebuilt)))
+        });
+    }
+    group.finish();
+}
+
+fn serialization(c: &mut Criterion) {
+    let mut group = c.benchmark_group("serialization");
+    for &size in SIZES {
+        let data = Generator::new(2).app_state(size);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("categories csv", size), &size, |b, _| {
+            b.iter(|| {
+                let mut out = Vec::new();
+                export::categories_csv(&data.content, &data.history, &mut out).unwrap();
+                out
+            })
+        });
+    }
+    let content = Generator::new(3).content(200, 1_000, 20);
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("template json", |b| {
+        b.iter(|| {
+            let mut out = Vec::new();
+            Template::from_content(black_box(&content))
+                .write(&mut out)
+                .unwrap();
+            out
+        })
+    });
+    group.finish();
+}
+
+/// A fresh in-memory database containing `content`.
+fn database(content: &Content) -> Sqlite {
+    let mut backend = Sqlite::new(":memory:").unwrap();
+    backend.setup().unwrap();
+    backend.transfer_content(content).unwrap();
+    backend
+}
+
+fn sqlite(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sqlite");
+    group.sample_size(10);
+    for &size in SIZES {
+        let mut generator = Generator::new(4);
+        // Sessions are generated from the stored content, as ids are assigned by the database.
+        let content = database(&generator.content(20, 50, 5))
+            .load_content()
+            .unwrap();
+        let history = History::new(generator.sessions(&content, size), true);
+        group.throughput(Throughput::Elements(size as u64));
+
+        group.bench_with_input(BenchmarkId::new("insert", size), &size, |b, _| {
+            b.iter_batched(
+                || database(&content),
+                |mut backend| backend.transfer_history(&history).unwrap(),
+                BatchSize::PerIteration,
+            )
+        });
+
+        let mut backend = database(&content);
+        backend.transfer_history(&history).unwrap();
+        group.bench_with_input(BenchmarkId::new("load all", size), &size, |b, _| {
+            b.iter(|| backend.load_history(&content).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("load recent", size), &size, |b, _| {
+            b.iter(|| {
+                backend
+                    .load_sessions(&content, None, History::RECENT)
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, data_same, serialization, sqlite);
+criterion_main!(benches);
@@ -0,0 +1,118 @@
+//! Deterministic synthetic data, so runs can be compared with each other.
+
+use im::Vector;
+use std::sync::Arc;
+use zeitig::state::{
+    Action, ActiveSession, AppState, Content, Context, DateTime, History, Interruption, Session,
+    Subject, Topic,
+};
+
+/// A small linear congruential generator, good enough to spread the data.
+pub struct Generator(u64);
+
+impl Generator {
+    pub fn new(seed: u64) -> Self {
+        Generator(seed)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        self.0 >> 33
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+
+    pub fn content(&mut self, actions: usize, subjects: usize, contexts: usize) -> Content {
+        let name = |kind: &str, i: usize| -> Arc<str> { format!("{} {}", kind, i).into() };
+        let mut content = Content::default();
+        content.actions = (1..=actions)
+            .map(|id| Action {
+                id,
+                name: name("Action", id),
+                archived: false,
+                temporary: false,
+                color: None,
+            })
+            .collect();
+        content.subjects = (1..=subjects)
+            .map(|id| Subject {
+                id,
+                name: name("Subject", id),
+                archived: false,
+                temporary: false,
+                issue_url: None,
+                estimate: None,
+                rate: None,
+                idle_rule: Default::default(),
+                color: None,
+                parent: None,
+                final_report: None,
+            })
+            .collect();
+        content.contexts = (1..=contexts)
+            .map(|id| Context {
+                id,
+                name: name("Context", id),
+            })
+            .collect();
+        content
+    }
+
+    /// Sessions of 15 minutes to 2 hours with gaps in between, ending about now.
+    pub fn sessions(&mut self, content: &Content, count: usize) -> Vector<Session> {
+        let mut at = *DateTime::now() - time::Duration::hours(3 * count as i64);
+        let mut sessions = Vector::new();
+        for _ in 0..count {
+            let started = at + time::Duration::minutes(self.below(60) as i64);
+            let ended = started + time::Duration::minutes(15 + self.below(105) as i64);
+            let action = content.actions[self.below(content.actions.len() as u64) as usize].clone();
+            let subject =
+                content.subjects[self.below(content.subjects.len() as u64) as usize].clone();
+            let context = match content.contexts.len() as u64 {
+                0 => None,
+                n => Some(content.contexts[self.below(n) as usize].clone()),
+            };
+            let interruptions = (0..self.below(3))
+                .map(|i| Interruption {
+                    at: DateTime::from(started + time::Duration::minutes(5 * (i as i64 + 1))),
+                    cause: None,
+                })
+                .collect();
+            sessions.push_back(Session {
+                topic: Topic { action, subject },
+                started: started.into(),
+                ended: ended.into(),
+                context,
+                interruptions,
+                original_start: None,
+                intervals: Vector::new(),
+                note: None,
+                tags: Vector::new(),
+            });
+            at = ended;
+        }
+        sessions
+    }
+
+    /// A running application with `sessions` in memory, bypassing the eviction
+    /// of `History::add` so large histories can be measured.
+    pub fn app_state(&mut self, sessions: usize) -> AppState {
+        let mut content = self.content(20, 50, 5);
+        let history = History::new(self.sessions(&content, sessions), true);
+        for session in &history {
+            *content.time_table.get_mut(session.topic.clone()) += session.duration();
+            content.totals.add(session);
+        }
+        AppState {
+            content,
+            history,
+            active: Some(ActiveSession::new(DateTime::now())),
+            ..AppState::default()
+        }
+    }
+}
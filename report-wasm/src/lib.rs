@@ -0,0 +1,153 @@
+//! Sums up the sessions of a JSON backup in the browser, for the static
+//! report in `www`. The backup is read where it is opened and never sent anywhere.
+//!
+//! Only the parts of the backup needed for the totals are read, so newer
+//! backups with more fields can be explored as well.
+
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, convert::TryFrom, error::Error, time::Duration};
+use wasm_bindgen::prelude::*;
+
+/// Same as `TIMESTAMP_FORMAT` of the backup in the application.
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S.%N%z";
+
+#[derive(Deserialize)]
+struct Backup {
+    #[serde(default)]
+    sessions: Vec<Session>,
+}
+
+#[derive(Deserialize)]
+struct Session {
+    action: String,
+    subject: String,
+    started: String,
+    ended: String,
+    #[serde(default)]
+    paused: u64,
+    #[serde(default)]
+    intervals: Vec<Interval>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct Interval {
+    started: String,
+    ended: String,
+}
+
+/// Time spent on a topic within one period.
+#[derive(Serialize)]
+pub struct Total {
+    /// First day of the period, empty when summing up everything.
+    pub period: String,
+    pub action: String,
+    pub subject: String,
+    pub seconds: u64,
+}
+
+#[derive(Clone, Copy)]
+pub enum Period {
+    Day,
+    Week,
+    Month,
+    All,
+}
+
+impl Period {
+    pub fn parse(text: &str) -> Option<Self> {
+        match text {
+            "day" => Some(Period::Day),
+            "week" => Some(Period::Week),
+            "month" => Some(Period::Month),
+            "all" => Some(Period::All),
+            _ => None,
+        }
+    }
+
+    fn begin(self, date: time::Date) -> Option<time::Date> {
+        match self {
+            Period::Day => Some(date),
+            Period::Week => {
+                let days = date.weekday().number_days_from_monday();
+                Some(date - time::Duration::days(days as i64))
+            }
+            Period::Month => time::Date::try_from_ymd(date.year(), date.month(), 1).ok(),
+            Period::All => None,
+        }
+    }
+}
+
+fn timestamp(text: &str) -> Result<time::OffsetDateTime, String> {
+    time::OffsetDateTime::parse(text, TIMESTAMP_FORMAT)
+        .map_err(|err| format!("Invalid timestamp '{}': {}", text, err))
+}
+
+fn between(started: &str, ended: &str) -> Result<Duration, String> {
+    let elapsed = timestamp(ended)? - timestamp(started)?;
+    Ok(Duration::try_from(elapsed).unwrap_or_default())
+}
+
+impl Session {
+    /// Time worked, leaving out the breaks.
+    fn duration(&self) -> Result<Duration, String> {
+        if self.intervals.is_empty() {
+            let total = between(&self.started, &self.ended)?;
+            return Ok(total
+                .checked_sub(Duration::from_secs(self.paused))
+                .unwrap_or_default());
+        }
+        let mut worked = Duration::default();
+        for interval in &self.intervals {
+            worked += between(&interval.started, &interval.ended)?;
+        }
+        Ok(worked)
+    }
+
+    /// Whether the action, subject or one of the tags contains `filter`, ignoring case.
+    fn matches(&self, filter: &str) -> bool {
+        let filter = filter.trim().to_lowercase();
+        filter.is_empty()
+            || std::iter::once(&self.action)
+                .chain(std::iter::once(&self.subject))
+                .chain(&self.tags)
+                .any(|name| name.to_lowercase().contains(&filter))
+    }
+}
+
+/// Sums up the sessions of `backup` per period and topic, counting each
+/// session for the day it started on. Sorted by period, action and subject.
+pub fn totals(backup: &str, period: Period, filter: &str) -> Result<Vec<Total>, Box<dyn Error>> {
+    let backup: Backup = serde_json::from_str(backup)?;
+    let mut totals: BTreeMap<(String, String, String), Duration> = BTreeMap::new();
+    for session in backup.sessions.iter().filter(|s| s.matches(filter)) {
+        let day = timestamp(&session.started)?.date();
+        let begin = period
+            .begin(day)
+            .map(|begin| begin.format("%Y-%m-%d"))
+            .unwrap_or_default();
+        let key = (begin, session.action.clone(), session.subject.clone());
+        *totals.entry(key).or_default() += session.duration()?;
+    }
+    Ok(totals
+        .into_iter()
+        .map(|((period, action, subject), time)| Total {
+            period,
+            action,
+            subject,
+            seconds: time.as_secs(),
+        })
+        .collect())
+}
+
+/// The totals as JSON, for the script of the report. `period` is one of
+/// `day`, `week`, `month` or `all`.
+#[wasm_bindgen]
+pub fn summarize(backup: &str, period: &str, filter: &str) -> Result<String, JsValue> {
+    let period = Period::parse(period)
+        .ok_or_else(|| JsValue::from_str(&format!("Unknown period '{}'.", period)))?;
+    totals(backup, period, filter)
+        .and_then(|totals| Ok(serde_json::to_string(&totals)?))
+        .map_err(|err| JsValue::from_str(&err.to_string()))
+}
@@ -0,0 +1,62 @@
+// Connects the page to the wasm module built into ./pkg by
+// `wasm-pack build --target web --out-dir www/pkg`.
+import init, { summarize } from "./pkg/zeitig_report.js";
+
+const $ = (id) => document.getElementById(id);
+let backup = null;
+
+function duration(seconds) {
+  const minutes = Math.floor(seconds / 60);
+  return `${Math.floor(minutes / 60)}h ${minutes % 60}m`;
+}
+
+function row(cells, className) {
+  const tr = document.createElement("tr");
+  if (className) tr.className = className;
+  for (const [tag, text, cellClass] of cells) {
+    const cell = document.createElement(tag);
+    cell.textContent = text;
+    if (cellClass) cell.className = cellClass;
+    tr.appendChild(cell);
+  }
+  return tr;
+}
+
+function render() {
+  const table = $("totals");
+  table.replaceChildren();
+  if (backup === null) return;
+  let totals;
+  try {
+    totals = JSON.parse(summarize(backup, $("period").value, $("filter").value));
+  } catch (err) {
+    $("status").textContent = String(err);
+    return;
+  }
+  $("status").textContent = `${totals.length} totals`;
+
+  let period = null;
+  for (const total of totals) {
+    if (total.period !== period) {
+      period = total.period;
+      const sum = totals
+        .filter((other) => other.period === period)
+        .reduce((seconds, other) => seconds + other.seconds, 0);
+      table.appendChild(
+        row([["th", period || "Everything"], ["th", ""], ["th", duration(sum), "time"]], "period"),
+      );
+    }
+    table.appendChild(
+      row([["td", total.action], ["td", total.subject], ["td", duration(total.seconds), "time"]]),
+    );
+  }
+}
+
+await init();
+$("backup").addEventListener("change", async (event) => {
+  const file = event.target.files[0];
+  backup = file ? await file.text() : null;
+  render();
+});
+$("period").addEventListener("change", render);
+$("filter").addEventListener("input", render);
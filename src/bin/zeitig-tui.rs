@@ -0,0 +1,310 @@
+//! Tracks time in a terminal, for use over SSH or without a desktop. Works on
+//! the same data file as the window, built with `--features tui`.
+
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame, Terminal,
+};
+use std::{
+    error::Error,
+    io,
+    time::{Duration, Instant},
+};
+use zeitig::engine::{format_duration, Period, Running, Stopped, Summary, Topic, Tracker};
+
+/// How often the clock of the running session is redrawn.
+const TICK: Duration = Duration::from_secs(1);
+/// How often the summary is loaded again, as it reads the whole history.
+const SUMMARY_REFRESH: Duration = Duration::from_secs(60);
+
+static HELP: &str = "Tab switch list  ↑↓ select  Enter start  s stop  q quit";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Actions,
+    Subjects,
+}
+
+struct App {
+    tracker: Tracker,
+    actions: Vec<String>,
+    subjects: Vec<String>,
+    action_list: ListState,
+    subject_list: ListState,
+    focus: Focus,
+    running: Option<Running>,
+    summary: Option<Summary>,
+    refreshed: Instant,
+    status: String,
+}
+
+impl App {
+    fn new(tracker: Tracker) -> Result<Self, Box<dyn Error>> {
+        let actions = tracker.actions();
+        let subjects = tracker.subjects();
+        let mut app = App {
+            tracker,
+            action_list: selection(actions.len()),
+            subject_list: selection(subjects.len()),
+            actions,
+            subjects,
+            focus: Focus::Actions,
+            running: None,
+            summary: None,
+            refreshed: Instant::now(),
+            status: HELP.to_string(),
+        };
+        app.refresh()?;
+        Ok(app)
+    }
+
+    /// Loads the running session and the summary again, as both can be
+    /// changed from the window or the command line meanwhile.
+    fn refresh(&mut self) -> Result<(), Box<dyn Error>> {
+        self.running = self.tracker.running()?;
+        self.summary = Some(self.tracker.summary(Period::Day)?);
+        self.refreshed = Instant::now();
+        Ok(())
+    }
+
+    fn tick(&mut self) -> Result<(), Box<dyn Error>> {
+        if self.refreshed.elapsed() >= SUMMARY_REFRESH {
+            self.refresh()
+        } else {
+            self.running = self.tracker.running()?;
+            Ok(())
+        }
+    }
+
+    fn list(&mut self) -> (&mut ListState, usize) {
+        match self.focus {
+            Focus::Actions => (&mut self.action_list, self.actions.len()),
+            Focus::Subjects => (&mut self.subject_list, self.subjects.len()),
+        }
+    }
+
+    fn select(&mut self, step: isize) {
+        let (list, len) = self.list();
+        if let Some(selected) = list.selected() {
+            let next = (selected as isize + step).rem_euclid(len as isize);
+            list.select(Some(next as usize));
+        }
+    }
+
+    fn start(&mut self) -> Result<(), Box<dyn Error>> {
+        let action = self.action_list.selected().map(|i| &self.actions[i]);
+        let subject = self.subject_list.selected().map(|i| &self.subjects[i]);
+        let topic = match (action, subject) {
+            (Some(action), Some(subject)) => Topic {
+                action: action.clone(),
+                subject: subject.clone(),
+            },
+            _ => {
+                self.status = "Create an action and a subject first.".to_string();
+                return Ok(());
+            }
+        };
+        let stopped = self.tracker.start(&topic, None)?;
+        self.status = format!(
+            "{}Started {} {}",
+            describe(&stopped),
+            topic.action,
+            topic.subject
+        );
+        self.refresh()
+    }
+
+    fn stop(&mut self) -> Result<(), Box<dyn Error>> {
+        let stopped = self.tracker.stop()?;
+        self.status = match stopped {
+            Stopped::Nothing => "No session is running".to_string(),
+            stopped => describe(&stopped),
+        };
+        self.refresh()
+    }
+}
+
+fn selection(len: usize) -> ListState {
+    let mut state = ListState::default();
+    if len > 0 {
+        state.select(Some(0));
+    }
+    state
+}
+
+fn describe(stopped: &Stopped) -> String {
+    match stopped {
+        Stopped::Nothing => String::new(),
+        Stopped::Recorded(session) => format!(
+            "Stopped {} {} after {}. ",
+            session.topic.action,
+            session.topic.subject,
+            format_duration(session.worked)
+        ),
+        Stopped::Discarded(running) => format!(
+            "Discarded {} {} as it was too short. ",
+            running.topic.action, running.topic.subject
+        ),
+    }
+}
+
+fn draw(frame: &mut Frame, app: &mut App) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(5),
+            Constraint::Length(10),
+            Constraint::Length(1),
+        ])
+        .split(frame.size());
+
+    let running = match &app.running {
+        Some(running) => format!(
+            "{} {} for {}",
+            running.topic.action,
+            running.topic.subject,
+            format_duration(running.elapsed())
+        ),
+        None => "No session is running".to_string(),
+    };
+    frame.render_widget(
+        Paragraph::new(running).block(Block::default().borders(Borders::ALL).title("Running")),
+        rows[0],
+    );
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[1]);
+    let list = |title: &'static str, names: Vec<ListItem<'static>>, focused: bool| {
+        let border = if focused {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        List::new(names)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(border)
+                    .title(title),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+    };
+    let actions = app
+        .actions
+        .iter()
+        .map(|action| ListItem::new(action.clone()))
+        .collect();
+    let subjects = app
+        .subjects
+        .iter()
+        .map(|subject| ListItem::new(subject.clone()))
+        .collect();
+    frame.render_stateful_widget(
+        list("Actions", actions, app.focus == Focus::Actions),
+        columns[0],
+        &mut app.action_list,
+    );
+    frame.render_stateful_widget(
+        list("Subjects", subjects, app.focus == Focus::Subjects),
+        columns[1],
+        &mut app.subject_list,
+    );
+
+    let (title, lines) = match &app.summary {
+        Some(summary) => {
+            let mut lines: Vec<String> = summary
+                .totals
+                .iter()
+                .map(|(topic, time)| {
+                    format!(
+                        "{:>12}  {} {}",
+                        format_duration(*time),
+                        topic.action,
+                        topic.subject
+                    )
+                })
+                .collect();
+            lines.push(format!("{:>12}  Total", format_duration(summary.total())));
+            (summary.label.clone(), lines.join("\n"))
+        }
+        None => (String::new(), String::new()),
+    };
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title)),
+        rows[2],
+    );
+
+    frame.render_widget(Paragraph::new(app.status.as_str()), rows[3]);
+}
+
+fn run(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+) -> Result<(), Box<dyn Error>> {
+    loop {
+        terminal.draw(|frame| draw(frame, app))?;
+        if !event::poll(TICK)? {
+            app.tick()?;
+            continue;
+        }
+        let key = match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => key,
+            _ => continue,
+        };
+        let result = match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Tab | KeyCode::Left | KeyCode::Right => {
+                app.focus = match app.focus {
+                    Focus::Actions => Focus::Subjects,
+                    Focus::Subjects => Focus::Actions,
+                };
+                Ok(())
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                app.select(-1);
+                Ok(())
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                app.select(1);
+                Ok(())
+            }
+            KeyCode::Enter => app.start(),
+            KeyCode::Char('s') => app.stop(),
+            _ => Ok(()),
+        };
+        if let Err(err) = result {
+            app.status = err.to_string();
+        }
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let profile = match args.as_slice() {
+        [] => None,
+        [flag, profile] if flag == "--profile" => Some(profile.as_str()),
+        _ => return Err("Usage: zeitig-tui [--profile <name>]".into()),
+    };
+    let tracker = Tracker::open_profile(profile)?;
+    let mut app = App::new(tracker)?;
+
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+    let result = run(&mut terminal, &mut app);
+    // The terminal is restored even if tracking failed, so the error can be read.
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen)?;
+    result
+}
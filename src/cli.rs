@@ -0,0 +1,136 @@
+//! Commands to track time from a shell, working on the same data file as the window.
+//! The window only notices changes made here once it is started again.
+
+use std::error::Error;
+use zeitig::engine::{format_duration, Period, Stopped, Topic, Tracker};
+
+static USAGE: &str = "\
+Usage: zeitig [--profile <name>] [command]
+       zeitig [--profile <name>] --safe-mode
+       zeitig [--profile <name>] --read-only [file]
+
+Without a command the window is opened. A profile keeps its data apart from the
+others and is created when it is first used. With --read-only, the history and
+reports of the data file or the given copy are shown without changing it.
+With --safe-mode, the window opens without the tray, global shortcut, control
+endpoint, dashboard, speech command and the watchers of screen locks and
+launched applications, and logs everything, to find out whether a crash comes
+from one of them.
+
+Commands:
+  start <action> <subject> [context]  Start a session, ending the running one
+  stop                                End the running session
+  status                              Show the running session
+  add-action <name>                   Create an action
+  add-subject <name>                  Create a subject
+  report [day|week|month]             Print the time per topic in the current week or given period
+  verify <file>                       Check an exported file against its signature";
+
+pub fn run(command: &str, args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut tracker = Tracker::open_default()?;
+    if let Some(moved) = tracker.took_over() {
+        println!("Took over {} sessions from the old data file", moved);
+    }
+    match (command, args) {
+        ("start", [action, subject]) => start(&mut tracker, action, subject, None),
+        ("start", [action, subject, context]) => {
+            start(&mut tracker, action, subject, Some(context))
+        }
+        ("stop", []) => stop(&mut tracker),
+        ("status", []) => status(&mut tracker),
+        ("add-action", [name]) => tracker.create_action(name),
+        ("add-subject", [name]) => tracker.create_subject(name),
+        ("report", []) => report(&mut tracker, Period::Week),
+        ("report", [period]) => {
+            let period = match period.as_str() {
+                "day" => Period::Day,
+                "week" => Period::Week,
+                "month" => Period::Month,
+                _ => return usage_error(),
+            };
+            report(&mut tracker, period)
+        }
+        ("help", _) => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ => usage_error(),
+    }
+}
+
+fn usage_error() -> Result<(), Box<dyn Error>> {
+    eprintln!("{}", USAGE);
+    Err("Unknown command or arguments.".into())
+}
+
+fn start(
+    tracker: &mut Tracker,
+    action: &str,
+    subject: &str,
+    context: Option<&String>,
+) -> Result<(), Box<dyn Error>> {
+    let topic = tracker.find_topic(action, subject)?;
+    print_stopped(tracker.start(&topic, context.map(String::as_str))?);
+    if let Some(running) = tracker.running()? {
+        println!(
+            "Started {}",
+            describe(&running.topic, running.context.as_deref())
+        );
+    }
+    Ok(())
+}
+
+fn stop(tracker: &mut Tracker) -> Result<(), Box<dyn Error>> {
+    print_stopped(tracker.stop()?);
+    Ok(())
+}
+
+fn print_stopped(stopped: Stopped) {
+    match stopped {
+        Stopped::Nothing => {}
+        Stopped::Recorded(session) => println!(
+            "Stopped {} after {}",
+            describe(&session.topic, session.context.as_deref()),
+            format_duration(session.worked)
+        ),
+        Stopped::Discarded(running) => println!(
+            "Discarded {} as it was too short",
+            describe(&running.topic, running.context.as_deref())
+        ),
+    }
+}
+
+fn status(tracker: &mut Tracker) -> Result<(), Box<dyn Error>> {
+    match tracker.running()? {
+        Some(running) => println!(
+            "Running {} for {}",
+            describe(&running.topic, running.context.as_deref()),
+            format_duration(running.elapsed())
+        ),
+        None => println!("No session is running"),
+    }
+    Ok(())
+}
+
+fn report(tracker: &mut Tracker, period: Period) -> Result<(), Box<dyn Error>> {
+    let summary = tracker.summary(period)?;
+    println!("{}", summary.label);
+    for (topic, time) in &summary.totals {
+        println!(
+            "{:>12}  {} {}",
+            format_duration(*time),
+            topic.action,
+            topic.subject
+        );
+    }
+    println!("{:>12}  Total", format_duration(summary.total()));
+    Ok(())
+}
+
+fn describe(topic: &Topic, context: Option<&str>) -> String {
+    let topic = format!("{} {}", topic.action, topic.subject);
+    match context {
+        Some(context) => format!("{} @ {}", topic, context),
+        None => topic,
+    }
+}
@@ -1,20 +1,94 @@
-use crate::state::{
-    backend::{Backend, Sqlite},
-    paths, AppState, Session, SpentTime,
+use crate::{
+    platform,
+    state::{
+        backend::{Backend, ChangedElsewhere, Sqlite},
+        backup::Backup,
+        cleanup::Cleanup,
+        goal::Goal,
+        health::{self, Finding},
+        history::{Change, Conflict, Merge, SessionQuery},
+        launch::LaunchRule,
+        ledger::{self, Adjustment, DayOff},
+        legacy,
+        order::ListOrder,
+        paths,
+        snapshot::{Rotation, Snapshot},
+        Action, Alias, AppState, Content, Date, DateTime, History, Session, Settings, SpentTime,
+        Startup, Subject, Topic,
+    },
 };
 use druid::{
     widget::Controller, Env, Event, EventCtx, ExtEventSink, LifeCycle, LifeCycleCtx, Target, Widget,
 };
+use im::{vector, Vector};
 use std::{
     error::Error,
-    sync::mpsc::{self, Sender},
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, Sender},
+        Arc,
+    },
     thread,
 };
+use zeitig::engine::MIN_SESSION;
 
 enum BackendCommand {
     AddAction(String),
     AddSubject(String),
+    AddContext(String),
+    AddTag(String),
+    SetActionArchived(Action, bool),
+    SetSubjectArchived(Subject, bool),
+    SetActionTemporary(Action, bool),
+    SetSubjectTemporary(Subject, bool),
+    RenameAction(Action),
+    RenameSubject(Subject),
+    SetIssueUrl(Subject),
+    SetEstimate(Subject),
+    SetRate(Subject),
+    SetParent(Subject),
+    SetFinalReport(Subject),
+    SetIdleRule(Subject),
+    SetActionColor(Action),
+    SetSubjectColor(Subject),
+    SetNote(Session),
+    SetTags(Session),
+    SetPinned(Vector<Topic>),
+    SetLaunchRules(Vector<LaunchRule>),
+    SetActionOrder(ListOrder),
+    SetSubjectOrder(ListOrder),
+    SetGoals(Vector<Goal>),
+    SetDaysOff(Vector<DayOff>),
+    SetAdjustments(Vector<Adjustment>),
+    SetLockedBefore(Option<Date>),
+    ImportBackup(Backup),
+    ImportTemplate(Backup),
+    DeleteAction(Action),
+    DeleteSubject(Subject),
+    MergeActions(Merge<Action>),
+    MergeSubjects(Merge<Subject>),
+    SplitActions(Merge<Action>),
+    SplitSubjects(Merge<Subject>),
+    SetCategory(Subject, Option<Arc<str>>),
+    AddChecklistItem(Subject, String),
+    SetChecklistItemDone(usize, bool),
+    AddAlias(Alias),
+    DeleteAlias(Alias),
     AddSession(Session, SpentTime),
+    UpdateSession(Session, Session),
+    DeleteSession(Session),
+    RestoreSession(Session),
+    AmendSession(Session, Option<Session>),
+    MoveSessions(Vector<(Session, Arc<str>)>, bool),
+    ReplaceSessions(Vector<(Session, Session)>, bool),
+    SaveSettings(Settings),
+    LoadOlderSessions(Content, Option<DateTime>),
+    FindSessions(Content, SessionQuery),
+    Repair,
+    StopRunning(Content),
+    KeepChange(Content, Change),
+    Reload,
+    RestoreSnapshot(Snapshot),
     Stop,
 }
 
@@ -25,17 +99,144 @@ enum Continue {
 }
 
 pub mod msg {
-    use crate::state::{Action, Session, Subject};
+    use crate::state::{
+        backup::Backup,
+        cleanup::Cleanup,
+        goal::Goal,
+        health::Finding,
+        history::{Change, Conflict, Merge, SessionQuery},
+        launch::LaunchRule,
+        ledger::{Adjustment, DayOff},
+        order::ListOrder,
+        snapshot::Snapshot,
+        Action, Alias, ChecklistItem, Content, Context, Date, DateTime, History, Session, Settings,
+        Subject, Tag, Topic,
+    };
     use druid::Selector;
+    use im::Vector;
+    use std::sync::Arc;
 
     pub const STOP: Selector = Selector::new("zeitig.backend.stop");
 
     pub const ADD_ACTION: Selector<String> = Selector::new("zeitig.backend.add-action");
     pub const ADD_SUBJECT: Selector<String> = Selector::new("zeitig.backend.add-subject");
+    pub const ADD_CONTEXT: Selector<String> = Selector::new("zeitig.backend.add-context");
+    pub const ADD_TAG: Selector<String> = Selector::new("zeitig.backend.add-tag");
     pub const ADD_SESSION: Selector<Session> = Selector::new("zeitig.backend.add-session");
+    pub const SET_ACTION_ARCHIVED: Selector<(Action, bool)> =
+        Selector::new("zeitig.backend.set-action-archived");
+    pub const SET_SUBJECT_ARCHIVED: Selector<(Subject, bool)> =
+        Selector::new("zeitig.backend.set-subject-archived");
+    pub const SET_ACTION_TEMPORARY: Selector<(Action, bool)> =
+        Selector::new("zeitig.backend.set-action-temporary");
+    pub const SET_SUBJECT_TEMPORARY: Selector<(Subject, bool)> =
+        Selector::new("zeitig.backend.set-subject-temporary");
+    pub const RENAME_ACTION: Selector<Action> = Selector::new("zeitig.backend.rename-action");
+    pub const RENAME_SUBJECT: Selector<Subject> = Selector::new("zeitig.backend.rename-subject");
+    pub const SET_ISSUE_URL: Selector<Subject> = Selector::new("zeitig.backend.set-issue-url");
+    pub const SET_ESTIMATE: Selector<Subject> = Selector::new("zeitig.backend.set-estimate");
+    pub const SET_RATE: Selector<Subject> = Selector::new("zeitig.backend.set-rate");
+    pub const SET_PARENT: Selector<Subject> = Selector::new("zeitig.backend.set-parent");
+    pub const SET_FINAL_REPORT: Selector<Subject> =
+        Selector::new("zeitig.backend.set-final-report");
+    pub const SET_IDLE_RULE: Selector<Subject> = Selector::new("zeitig.backend.set-idle-rule");
+    pub const SET_ACTION_COLOR: Selector<Action> = Selector::new("zeitig.backend.set-action-color");
+    pub const SET_SUBJECT_COLOR: Selector<Subject> =
+        Selector::new("zeitig.backend.set-subject-color");
+    pub const SET_NOTE: Selector<Session> = Selector::new("zeitig.backend.set-note");
+    pub const SET_TAGS: Selector<Session> = Selector::new("zeitig.backend.set-tags");
+    pub const SET_PINNED: Selector<Vector<Topic>> = Selector::new("zeitig.backend.set-pinned");
+    pub const SET_LAUNCH_RULES: Selector<Vector<LaunchRule>> =
+        Selector::new("zeitig.backend.set-launch-rules");
+    pub const SET_ACTION_ORDER: Selector<ListOrder> =
+        Selector::new("zeitig.backend.set-action-order");
+    pub const SET_SUBJECT_ORDER: Selector<ListOrder> =
+        Selector::new("zeitig.backend.set-subject-order");
+    pub const SET_GOALS: Selector<Vector<Goal>> = Selector::new("zeitig.backend.set-goals");
+    pub const SET_DAYS_OFF: Selector<Vector<DayOff>> = Selector::new("zeitig.backend.set-days-off");
+    pub const SET_ADJUSTMENTS: Selector<Vector<Adjustment>> =
+        Selector::new("zeitig.backend.set-adjustments");
+    pub const SET_LOCKED_BEFORE: Selector<Option<Date>> =
+        Selector::new("zeitig.backend.set-locked-before");
+    /// Merges a backup into the data, which is loaded again afterwards.
+    pub const IMPORT_BACKUP: Selector<Backup> = Selector::new("zeitig.backend.import-backup");
+    /// Creates what a template holds and is still missing, read as a backup
+    /// without sessions.
+    pub const IMPORT_TEMPLATE: Selector<Backup> = Selector::new("zeitig.backend.import-template");
+    pub const DELETE_ACTION: Selector<Action> = Selector::new("zeitig.backend.delete-action");
+    pub const DELETE_SUBJECT: Selector<Subject> = Selector::new("zeitig.backend.delete-subject");
+    pub const MERGE_ACTIONS: Selector<Merge<Action>> =
+        Selector::new("zeitig.backend.merge-actions");
+    pub const MERGE_SUBJECTS: Selector<Merge<Subject>> =
+        Selector::new("zeitig.backend.merge-subjects");
+    /// Takes a merge back, creating the merged entry again.
+    pub const SPLIT_ACTIONS: Selector<Merge<Action>> =
+        Selector::new("zeitig.backend.split-actions");
+    pub const SPLIT_SUBJECTS: Selector<Merge<Subject>> =
+        Selector::new("zeitig.backend.split-subjects");
+    pub const SET_CATEGORY: Selector<(Subject, Option<Arc<str>>)> =
+        Selector::new("zeitig.backend.set-category");
+    pub const ADD_CHECKLIST_ITEM: Selector<(Subject, String)> =
+        Selector::new("zeitig.backend.add-checklist-item");
+    pub const SET_CHECKLIST_ITEM_DONE: Selector<(usize, bool)> =
+        Selector::new("zeitig.backend.set-checklist-item-done");
+    pub const ADD_ALIAS: Selector<Alias> = Selector::new("zeitig.backend.add-alias");
+    pub const DELETE_ALIAS: Selector<Alias> = Selector::new("zeitig.backend.delete-alias");
+    pub const UPDATE_SESSION: Selector<(Session, Session)> =
+        Selector::new("zeitig.backend.update-session");
+    pub const DELETE_SESSION: Selector<Session> = Selector::new("zeitig.backend.delete-session");
+    pub const RESTORE_SESSION: Selector<Session> = Selector::new("zeitig.backend.restore-session");
+    pub const AMEND_SESSION: Selector<(Session, Option<Session>)> =
+        Selector::new("zeitig.backend.amend-session");
+    /// Moves each session to the subject of the name, creating missing ones,
+    /// and loads the data again.
+    pub const MOVE_SESSIONS: Selector<Vector<(Session, Arc<str>)>> =
+        Selector::new("zeitig.backend.move-sessions");
+    /// Replaces each session with the changed one and loads the data again.
+    pub const REPLACE_SESSIONS: Selector<Vector<(Session, Session)>> =
+        Selector::new("zeitig.backend.replace-sessions");
+    pub const SAVE_SETTINGS: Selector<Settings> = Selector::new("zeitig.backend.save-settings");
+    pub const LOAD_OLDER_SESSIONS: Selector<Option<DateTime>> =
+        Selector::new("zeitig.backend.load-older-sessions");
+    /// Looks for sessions in the whole history.
+    pub const FIND_SESSIONS: Selector<SessionQuery> = Selector::new("zeitig.backend.find-sessions");
+    pub const REPAIR: Selector = Selector::new("zeitig.backend.repair");
+    /// Records the session started from the command line and reloads the data.
+    pub const STOP_RUNNING: Selector = Selector::new("zeitig.backend.stop-running");
+    /// Applies a change refused for a conflict to the session as it is stored now.
+    pub const KEEP_CHANGE: Selector<Change> = Selector::new("zeitig.backend.keep-change");
+    /// Loads everything again, dropping what is only known here.
+    pub const RELOAD: Selector = Selector::new("zeitig.backend.reload");
+    /// Replaces all data with a copy from the backup folder, after copying it too.
+    pub const RESTORE_SNAPSHOT: Selector<Snapshot> =
+        Selector::new("zeitig.backend.restore-snapshot");
 
     pub const ACTION_ADDED: Selector<Action> = Selector::new("zeitig.backend.action-added");
     pub const SUBJECT_ADDED: Selector<Subject> = Selector::new("zeitig.backend.subject-added");
+    pub const CONTEXT_ADDED: Selector<Context> = Selector::new("zeitig.backend.context-added");
+    pub const TAG_ADDED: Selector<Tag> = Selector::new("zeitig.backend.tag-added");
+    pub const CHECKLIST_ITEM_ADDED: Selector<ChecklistItem> =
+        Selector::new("zeitig.backend.checklist-item-added");
+    pub const OLDER_SESSIONS_LOADED: Selector<History> =
+        Selector::new("zeitig.backend.older-sessions-loaded");
+    pub const SESSIONS_FOUND: Selector<History> = Selector::new("zeitig.backend.sessions-found");
+    pub const CLEANUP_SUGGESTED: Selector<Cleanup> =
+        Selector::new("zeitig.backend.cleanup-suggested");
+    pub const SESSION_CONFLICT: Selector<Conflict> =
+        Selector::new("zeitig.backend.session-conflict");
+    pub const HEALTH_CHECKED: Selector<Vector<Finding>> =
+        Selector::new("zeitig.backend.health-checked");
+    pub const LOADED: Selector<(Content, History)> = Selector::new("zeitig.backend.loaded");
+    pub const LOAD_FAILED: Selector<String> = Selector::new("zeitig.backend.load-failed");
+    /// Overtime balance carried into this week, `None` if none is kept.
+    pub const CARRIED_OVER: Selector<Option<i64>> = Selector::new("zeitig.backend.carried-over");
+    /// Number of sessions added by importing a backup.
+    pub const BACKUP_IMPORTED: Selector<usize> = Selector::new("zeitig.backend.backup-imported");
+    /// Number of sessions moved to other subjects.
+    pub const SESSIONS_MOVED: Selector<usize> = Selector::new("zeitig.backend.sessions-moved");
+    /// Number of sessions replaced.
+    pub const SESSIONS_REPLACED: Selector<usize> =
+        Selector::new("zeitig.backend.sessions-replaced");
 
     pub const STOPPED: Selector = Selector::new("zeitig.backend.stopped");
     pub const ERROR: Selector<String> = Selector::new("zeitig.backend.error");
@@ -56,14 +257,33 @@ impl BackendController {
 
         let (sender, receiver) = mpsc::channel();
         let sink = ctx.get_external_handle();
-        let mut backend = Sqlite::new(paths::data_file()).unwrap();
-        backend.setup().unwrap();
         thread::spawn(move || {
+            let mut backend = match Self::load(&sink) {
+                Ok(backend) => Some(backend),
+                Err(err) => {
+                    log::error!("Failed to load data: {}", err);
+                    if sink
+                        .submit_command(msg::LOAD_FAILED, err.to_string(), Target::Auto)
+                        .is_err()
+                    {
+                        log::error!("Backend event sink has been closed while loading.");
+                    }
+                    None
+                }
+            };
             loop {
                 let cmd = receiver.recv().expect(
                     "The backend channel should not be closed while the backend is running.",
                 );
-                match Self::handle_command(cmd, &mut backend, &sink) {
+                // Without data there is nothing to do but waiting to be stopped.
+                let result = match (backend.as_mut(), cmd) {
+                    (Some((backend, rotation)), cmd) => {
+                        Self::handle_command(cmd, backend, rotation, &sink)
+                    }
+                    (None, BackendCommand::Stop) => Ok(Continue::No),
+                    (None, _) => Ok(Continue::Yes),
+                };
+                match result {
                     Ok(Continue::Yes) => {}
                     Ok(Continue::No) => break,
                     Err(err) => {
@@ -91,9 +311,116 @@ impl BackendController {
         self.sender = Some(sender);
     }
 
+    /// Opens the database and hands its content to the UI.
+    fn load(sink: &ExtEventSink) -> Result<(Sqlite, Rotation), Box<dyn Error>> {
+        if let Some(file) = paths::read_only() {
+            return Self::load_read_only(&file, sink);
+        }
+        let path = paths::data_file();
+        let fresh = !path.exists();
+        let mut backend = Sqlite::new(&path)?;
+        backend.setup()?;
+        if fresh {
+            if let Some(moved) = legacy::migrate(&mut backend, &paths::legacy_file())? {
+                platform::notify(
+                    "Data moved",
+                    &format!("{} sessions were taken over from the old data file.", moved),
+                );
+            }
+        }
+        let content = backend.load_content()?;
+        let rotation = Rotation::new(paths::snapshot_dir(), content.settings.backups_kept);
+        rotation.take(&mut backend);
+        let history = backend.load_sessions(&content, None, History::RECENT)?;
+        let folder = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_owned(),
+            _ => PathBuf::from("."),
+        };
+        let findings = health::check(&mut backend, &folder, &content, &history);
+        let months = content.settings.stale_months;
+        let cleanup = match months > 0.0 {
+            true => backend.unused_since(&content, &Cleanup::cutoff(months)),
+            false => Ok(Cleanup::default()),
+        };
+        sink.submit_command(msg::LOADED, (content.clone(), history), Target::Auto)?;
+        if let Err(err) = Self::carry_over(&mut backend, &content, sink) {
+            log::warn!("Cannot add up the overtime: {}", err);
+        }
+        if !findings.is_empty() {
+            sink.submit_command(msg::HEALTH_CHECKED, findings, Target::Auto)?;
+        }
+        // Only a suggestion, so failing to find stale entries doesn't fail loading.
+        match cleanup {
+            Ok(cleanup) if !cleanup.is_empty() => {
+                sink.submit_command(msg::CLEANUP_SUGGESTED, cleanup, Target::Auto)?;
+            }
+            Ok(_) => {}
+            Err(err) => log::warn!("Cannot look for unused entries: {}", err),
+        }
+        Ok((backend, rotation))
+    }
+
+    /// Opens a file only to look at it, leaving out everything that would change it.
+    fn load_read_only(
+        file: &Path,
+        sink: &ExtEventSink,
+    ) -> Result<(Sqlite, Rotation), Box<dyn Error>> {
+        let mut backend = Sqlite::open_read_only(file)?;
+        let content = backend.load_content()?;
+        let history = backend.load_sessions(&content, None, History::RECENT)?;
+        sink.submit_command(msg::LOADED, (content.clone(), history), Target::Auto)?;
+        Self::carry_over(&mut backend, &content, sink)?;
+        Ok((backend, Rotation::new(paths::snapshot_dir(), 0.0)))
+    }
+
+    fn reload(backend: &mut dyn Backend, sink: &ExtEventSink) -> Result<(), Box<dyn Error>> {
+        let content = backend.load_content()?;
+        let history = backend.load_sessions(&content, None, History::RECENT)?;
+        sink.submit_command(msg::LOADED, (content.clone(), history), Target::Auto)?;
+        Self::carry_over(backend, &content, sink)
+    }
+
+    /// Adds up the whole history for the overtime balance carried into this
+    /// week, the recent one only covers the current week for sure.
+    fn carry_over(
+        backend: &mut dyn Backend,
+        content: &Content,
+        sink: &ExtEventSink,
+    ) -> Result<(), Box<dyn Error>> {
+        let carryover = match content.settings.weekly_hours > 0.0 {
+            true => ledger::carryover(content, &backend.load_history(content)?),
+            false => None,
+        };
+        sink.submit_command(msg::CARRIED_OVER, carryover, Target::Auto)?;
+        Ok(())
+    }
+
+    /// Hands a change refused for being made elsewhere first to the UI to resolve.
+    fn refuse_conflict(
+        result: Result<(), Box<dyn Error>>,
+        change: Change,
+        sink: &ExtEventSink,
+    ) -> Result<(), Box<dyn Error>> {
+        match result {
+            Err(err) => match err.downcast_ref::<ChangedElsewhere>() {
+                Some(changed) => {
+                    let conflict = Conflict {
+                        change,
+                        deleted: changed.deleted,
+                    };
+                    sink.submit_command(msg::SESSION_CONFLICT, conflict, Target::Auto)?;
+                    Ok(())
+                }
+                None => Err(err),
+            },
+            Ok(()) => Ok(()),
+        }
+    }
+
     fn handle_command(
         cmd: BackendCommand,
         backend: &mut dyn Backend,
+        rotation: &mut Rotation,
         sink: &ExtEventSink,
     ) -> Result<Continue, Box<dyn Error>> {
         match cmd {
@@ -105,10 +432,241 @@ impl BackendController {
                 let subject = backend.create_subject(&name)?;
                 sink.submit_command(msg::SUBJECT_ADDED, subject, Target::Auto)?;
             }
+            BackendCommand::AddContext(name) => {
+                let context = backend.create_context(&name)?;
+                sink.submit_command(msg::CONTEXT_ADDED, context, Target::Auto)?;
+            }
+            BackendCommand::AddTag(name) => {
+                let tag = backend.create_tag(&name)?;
+                sink.submit_command(msg::TAG_ADDED, tag, Target::Auto)?;
+            }
+            BackendCommand::SetActionArchived(action, archived) => {
+                backend.set_action_archived(&action, archived)?;
+            }
+            BackendCommand::SetSubjectArchived(subject, archived) => {
+                backend.set_subject_archived(&subject, archived)?;
+            }
+            BackendCommand::SetActionTemporary(action, temporary) => {
+                backend.set_action_temporary(&action, temporary)?;
+            }
+            BackendCommand::SetSubjectTemporary(subject, temporary) => {
+                backend.set_subject_temporary(&subject, temporary)?;
+            }
+            BackendCommand::RenameAction(action) => {
+                backend.rename_action(&action)?;
+            }
+            BackendCommand::RenameSubject(subject) => {
+                backend.rename_subject(&subject)?;
+            }
+            BackendCommand::SetIssueUrl(subject) => {
+                backend.set_issue_url(&subject)?;
+            }
+            BackendCommand::SetEstimate(subject) => {
+                backend.set_estimate(&subject)?;
+            }
+            BackendCommand::SetRate(subject) => {
+                backend.set_rate(&subject)?;
+            }
+            BackendCommand::SetParent(subject) => {
+                backend.set_parent(&subject)?;
+            }
+            BackendCommand::SetFinalReport(subject) => {
+                backend.set_final_report(&subject)?;
+            }
+            BackendCommand::SetIdleRule(subject) => {
+                backend.set_idle_rule(&subject)?;
+            }
+            BackendCommand::SetActionColor(action) => {
+                backend.set_action_color(&action)?;
+            }
+            BackendCommand::SetSubjectColor(subject) => {
+                backend.set_subject_color(&subject)?;
+            }
+            BackendCommand::SetNote(session) => {
+                backend.set_note(&session)?;
+            }
+            BackendCommand::SetTags(session) => {
+                backend.set_tags(&session)?;
+            }
+            BackendCommand::SetLaunchRules(rules) => {
+                backend.set_launch_rules(&rules)?;
+            }
+            BackendCommand::SetPinned(pinned) => {
+                backend.set_pinned(&pinned)?;
+            }
+            BackendCommand::SetActionOrder(order) => {
+                backend.set_action_order(&order)?;
+            }
+            BackendCommand::SetSubjectOrder(order) => {
+                backend.set_subject_order(&order)?;
+            }
+            BackendCommand::SetGoals(goals) => {
+                backend.set_goals(&goals)?;
+            }
+            BackendCommand::SetDaysOff(days_off) => {
+                backend.set_days_off(&days_off)?;
+            }
+            BackendCommand::SetAdjustments(adjustments) => {
+                backend.set_adjustments(&adjustments)?;
+            }
+            BackendCommand::SetLockedBefore(before) => {
+                backend.set_locked_before(before)?;
+            }
+            BackendCommand::ImportBackup(backup) => {
+                rotation.take(backend);
+                let added = backend.import_backup(&backup)?;
+                Self::reload(backend, sink)?;
+                sink.submit_command(msg::BACKUP_IMPORTED, added, Target::Auto)?;
+            }
+            BackendCommand::ImportTemplate(backup) => {
+                backend.import_backup(&backup)?;
+                Self::reload(backend, sink)?;
+            }
+            BackendCommand::DeleteAction(action) => {
+                rotation.take(backend);
+                backend.delete_action(&action)?;
+            }
+            BackendCommand::DeleteSubject(subject) => {
+                rotation.take(backend);
+                backend.delete_subject(&subject)?;
+            }
+            BackendCommand::MergeActions(merge) => {
+                rotation.take(backend);
+                backend.merge_actions(&merge)?;
+                Self::reload(backend, sink)?;
+            }
+            BackendCommand::MergeSubjects(merge) => {
+                rotation.take(backend);
+                backend.merge_subjects(&merge)?;
+                Self::reload(backend, sink)?;
+            }
+            BackendCommand::SplitActions(merge) => {
+                backend.split_actions(&merge)?;
+                Self::reload(backend, sink)?;
+            }
+            BackendCommand::SplitSubjects(merge) => {
+                backend.split_subjects(&merge)?;
+                Self::reload(backend, sink)?;
+            }
+            BackendCommand::SetCategory(subject, category) => {
+                backend.set_category(&subject, category.as_deref())?;
+            }
+            BackendCommand::AddChecklistItem(subject, text) => {
+                let item = backend.create_checklist_item(&subject, &text)?;
+                sink.submit_command(msg::CHECKLIST_ITEM_ADDED, item, Target::Auto)?;
+            }
+            BackendCommand::SetChecklistItemDone(id, done) => {
+                backend.set_checklist_item_done(id, done)?;
+            }
+            BackendCommand::AddAlias(alias) => {
+                backend.add_alias(&alias)?;
+            }
+            BackendCommand::DeleteAlias(alias) => {
+                backend.delete_alias(&alias)?;
+            }
             BackendCommand::AddSession(session, total_duration) => {
                 backend.add_session(&session)?;
                 backend.update_time(&session.topic, &total_duration)?;
             }
+            BackendCommand::UpdateSession(original, updated) => {
+                let result = backend.update_session(&original, &updated);
+                Self::refuse_conflict(result, Change::Update(original, updated), sink)?;
+            }
+            BackendCommand::DeleteSession(session) => {
+                let result = backend.delete_session(&session);
+                Self::refuse_conflict(result, Change::Delete(session), sink)?;
+            }
+            BackendCommand::RestoreSession(session) => {
+                backend.restore_session(&session)?;
+            }
+            BackendCommand::AmendSession(original, amended) => {
+                let result = backend.amend_session(&original, amended.as_ref());
+                Self::refuse_conflict(result, Change::Amend(original, amended), sink)?;
+            }
+            BackendCommand::MoveSessions(moves, strict) => {
+                rotation.take(backend);
+                let content = backend.load_content()?;
+                backend.move_sessions(&content, &moves, strict)?;
+                Self::reload(backend, sink)?;
+                sink.submit_command(msg::SESSIONS_MOVED, moves.len(), Target::Auto)?;
+            }
+            BackendCommand::ReplaceSessions(replacements, strict) => {
+                rotation.take(backend);
+                backend.replace_sessions(&replacements, strict)?;
+                Self::reload(backend, sink)?;
+                let replaced = replacements.len();
+                sink.submit_command(msg::SESSIONS_REPLACED, replaced, Target::Auto)?;
+            }
+            BackendCommand::SaveSettings(settings) => {
+                backend.save_settings(&settings)?;
+                rotation.keep = settings.backups_kept as usize;
+                // The weekly hours or the start of the balance may have changed.
+                let content = backend.load_content()?;
+                Self::carry_over(backend, &content, sink)?;
+            }
+            BackendCommand::LoadOlderSessions(content, before) => {
+                let older = backend.load_sessions(&content, before.as_ref(), History::PAGE)?;
+                sink.submit_command(msg::OLDER_SESSIONS_LOADED, older, Target::Auto)?;
+            }
+            BackendCommand::FindSessions(content, query) => {
+                let found = backend.find_sessions(&content, &query)?;
+                sink.submit_command(msg::SESSIONS_FOUND, found, Target::Auto)?;
+            }
+            BackendCommand::Repair => {
+                rotation.take(backend);
+                backend.repair()?;
+                let problems = backend.check_integrity()?;
+                if !problems.is_empty() {
+                    let finding = Finding::Damaged(problems.join(" ").into());
+                    sink.submit_command(msg::HEALTH_CHECKED, vector![finding], Target::Auto)?;
+                }
+            }
+            BackendCommand::StopRunning(content) => {
+                if let Some(running) = backend.load_running(&content)? {
+                    let session = running.session(DateTime::now());
+                    // Too short sessions are discarded, as when stopped from the command line.
+                    if *session.duration() >= MIN_SESSION {
+                        backend.add_session(&session)?;
+                        backend.add_time(&session.topic, &session.duration())?;
+                    }
+                    backend.set_running(None)?;
+                }
+                Self::reload(backend, sink)?;
+            }
+            BackendCommand::KeepChange(content, change) => {
+                let current = backend.reload_session(&content, &change.original().key())?;
+                match (current, change) {
+                    (Some(current), Change::Update(_, updated)) => {
+                        backend.update_session(&current, &updated)?
+                    }
+                    (Some(current), Change::Delete(_)) => backend.delete_session(&current)?,
+                    (Some(current), Change::Amend(_, amended)) => {
+                        backend.amend_session(&current, amended.as_ref())?
+                    }
+                    // Deleted elsewhere, so the change is recorded as a new session.
+                    (None, change) => {
+                        if let Some(session) = change.changed() {
+                            backend.add_session(session)?;
+                            backend.add_time(&session.topic, &session.duration())?;
+                        }
+                    }
+                }
+                Self::reload(backend, sink)?;
+            }
+            BackendCommand::Reload => Self::reload(backend, sink)?,
+            BackendCommand::RestoreSnapshot(snapshot) => {
+                // Restoring is destructive too, unlike the others it fails without a copy.
+                rotation.copy(backend)?;
+                backend.restore_snapshot(&snapshot.path)?;
+                if let Err(err) = rotation.prune() {
+                    log::warn!("Cannot remove old copies of the data file: {}", err);
+                }
+                Self::reload(backend, sink)?;
+                platform::notify(
+                    "Backup restored",
+                    &format!("The data is back to how it was on {}.", snapshot.taken),
+                );
+            }
             BackendCommand::Stop => return Ok(Continue::No),
         }
         Ok(Continue::Yes)
@@ -126,6 +684,42 @@ impl<W: Widget<AppState>> Controller<AppState, W> for BackendController {
     ) {
         let sender = self.sender.as_ref().unwrap();
         match event {
+            Event::Command(cmd) if cmd.is(msg::LOADED) => {
+                let (content, history) = cmd.get_unchecked(msg::LOADED).to_owned();
+                data.content = content;
+                data.history = history;
+                data.startup = Startup::Ready;
+                // Goals reached before starting are not announced again.
+                data.reach_goals();
+            }
+            Event::Command(cmd) if cmd.is(msg::BACKUP_IMPORTED) => {
+                let added = cmd.get_unchecked(msg::BACKUP_IMPORTED);
+                platform::notify("Backup imported", &format!("{} sessions added.", added));
+            }
+            Event::Command(cmd) if cmd.is(msg::SESSIONS_MOVED) => {
+                let moved = cmd.get_unchecked(msg::SESSIONS_MOVED);
+                if let Some(manage) = data.manage.as_mut() {
+                    manage.status = format!("Moved {} sessions.", moved);
+                    manage.split.preview.clear();
+                }
+            }
+            Event::Command(cmd) if cmd.is(msg::SESSIONS_REPLACED) => {
+                let replaced = cmd.get_unchecked(msg::SESSIONS_REPLACED);
+                if let Some(manage) = data.manage.as_mut() {
+                    manage.status = format!("Trimmed {} sessions.", replaced);
+                    manage.trim.candidates.clear();
+                }
+            }
+            Event::Command(cmd) if cmd.is(msg::CARRIED_OVER) => {
+                data.setup.carryover = *cmd.get_unchecked(msg::CARRIED_OVER);
+            }
+            Event::Command(cmd) if cmd.is(msg::CLEANUP_SUGGESTED) => {
+                data.cleanup = Some(cmd.get_unchecked(msg::CLEANUP_SUGGESTED).to_owned());
+            }
+            Event::Command(cmd) if cmd.is(msg::LOAD_FAILED) => {
+                let message = cmd.get_unchecked(msg::LOAD_FAILED);
+                data.startup = Startup::Failed(message.as_str().into());
+            }
             Event::Command(cmd) if cmd.is(msg::ADD_ACTION) => {
                 let name = cmd.get_unchecked(msg::ADD_ACTION).to_owned();
                 sender.send(BackendCommand::AddAction(name)).unwrap();
@@ -134,6 +728,184 @@ impl<W: Widget<AppState>> Controller<AppState, W> for BackendController {
                 let name = cmd.get_unchecked(msg::ADD_SUBJECT).to_owned();
                 sender.send(BackendCommand::AddSubject(name)).unwrap();
             }
+            Event::Command(cmd) if cmd.is(msg::ADD_CONTEXT) => {
+                let name = cmd.get_unchecked(msg::ADD_CONTEXT).to_owned();
+                sender.send(BackendCommand::AddContext(name)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::ADD_TAG) => {
+                let name = cmd.get_unchecked(msg::ADD_TAG).to_owned();
+                sender.send(BackendCommand::AddTag(name)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SET_ACTION_ARCHIVED) => {
+                let (action, archived) = cmd.get_unchecked(msg::SET_ACTION_ARCHIVED).to_owned();
+                sender
+                    .send(BackendCommand::SetActionArchived(action, archived))
+                    .unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SET_SUBJECT_ARCHIVED) => {
+                let (subject, archived) = cmd.get_unchecked(msg::SET_SUBJECT_ARCHIVED).to_owned();
+                sender
+                    .send(BackendCommand::SetSubjectArchived(subject, archived))
+                    .unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SET_ACTION_TEMPORARY) => {
+                let (action, temporary) = cmd.get_unchecked(msg::SET_ACTION_TEMPORARY).to_owned();
+                sender
+                    .send(BackendCommand::SetActionTemporary(action, temporary))
+                    .unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SET_SUBJECT_TEMPORARY) => {
+                let (subject, temporary) = cmd.get_unchecked(msg::SET_SUBJECT_TEMPORARY).to_owned();
+                sender
+                    .send(BackendCommand::SetSubjectTemporary(subject, temporary))
+                    .unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::RENAME_ACTION) => {
+                let action = cmd.get_unchecked(msg::RENAME_ACTION).to_owned();
+                sender.send(BackendCommand::RenameAction(action)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::RENAME_SUBJECT) => {
+                let subject = cmd.get_unchecked(msg::RENAME_SUBJECT).to_owned();
+                sender.send(BackendCommand::RenameSubject(subject)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SET_ISSUE_URL) => {
+                let subject = cmd.get_unchecked(msg::SET_ISSUE_URL).to_owned();
+                sender.send(BackendCommand::SetIssueUrl(subject)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SET_ESTIMATE) => {
+                let subject = cmd.get_unchecked(msg::SET_ESTIMATE).to_owned();
+                sender.send(BackendCommand::SetEstimate(subject)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SET_RATE) => {
+                let subject = cmd.get_unchecked(msg::SET_RATE).to_owned();
+                sender.send(BackendCommand::SetRate(subject)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SET_PARENT) => {
+                let subject = cmd.get_unchecked(msg::SET_PARENT).to_owned();
+                sender.send(BackendCommand::SetParent(subject)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SET_FINAL_REPORT) => {
+                let subject = cmd.get_unchecked(msg::SET_FINAL_REPORT).to_owned();
+                sender
+                    .send(BackendCommand::SetFinalReport(subject))
+                    .unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SET_IDLE_RULE) => {
+                let subject = cmd.get_unchecked(msg::SET_IDLE_RULE).to_owned();
+                sender.send(BackendCommand::SetIdleRule(subject)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SET_ACTION_COLOR) => {
+                let action = cmd.get_unchecked(msg::SET_ACTION_COLOR).to_owned();
+                sender.send(BackendCommand::SetActionColor(action)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SET_SUBJECT_COLOR) => {
+                let subject = cmd.get_unchecked(msg::SET_SUBJECT_COLOR).to_owned();
+                sender
+                    .send(BackendCommand::SetSubjectColor(subject))
+                    .unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SET_NOTE) => {
+                let session = cmd.get_unchecked(msg::SET_NOTE).to_owned();
+                sender.send(BackendCommand::SetNote(session)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SET_TAGS) => {
+                let session = cmd.get_unchecked(msg::SET_TAGS).to_owned();
+                sender.send(BackendCommand::SetTags(session)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SET_LAUNCH_RULES) => {
+                let rules = cmd.get_unchecked(msg::SET_LAUNCH_RULES).to_owned();
+                sender.send(BackendCommand::SetLaunchRules(rules)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SET_PINNED) => {
+                let pinned = cmd.get_unchecked(msg::SET_PINNED).to_owned();
+                sender.send(BackendCommand::SetPinned(pinned)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SET_ACTION_ORDER) => {
+                let order = cmd.get_unchecked(msg::SET_ACTION_ORDER).to_owned();
+                sender.send(BackendCommand::SetActionOrder(order)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SET_SUBJECT_ORDER) => {
+                let order = cmd.get_unchecked(msg::SET_SUBJECT_ORDER).to_owned();
+                sender.send(BackendCommand::SetSubjectOrder(order)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SET_GOALS) => {
+                let goals = cmd.get_unchecked(msg::SET_GOALS).to_owned();
+                sender.send(BackendCommand::SetGoals(goals)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SET_DAYS_OFF) => {
+                let days_off = cmd.get_unchecked(msg::SET_DAYS_OFF).to_owned();
+                sender.send(BackendCommand::SetDaysOff(days_off)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SET_ADJUSTMENTS) => {
+                let adjustments = cmd.get_unchecked(msg::SET_ADJUSTMENTS).to_owned();
+                sender
+                    .send(BackendCommand::SetAdjustments(adjustments))
+                    .unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::IMPORT_BACKUP) => {
+                let backup = cmd.get_unchecked(msg::IMPORT_BACKUP).to_owned();
+                sender.send(BackendCommand::ImportBackup(backup)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::IMPORT_TEMPLATE) => {
+                let backup = cmd.get_unchecked(msg::IMPORT_TEMPLATE).to_owned();
+                sender.send(BackendCommand::ImportTemplate(backup)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SET_LOCKED_BEFORE) => {
+                let before = *cmd.get_unchecked(msg::SET_LOCKED_BEFORE);
+                sender
+                    .send(BackendCommand::SetLockedBefore(before))
+                    .unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::DELETE_ACTION) => {
+                let action = cmd.get_unchecked(msg::DELETE_ACTION).to_owned();
+                sender.send(BackendCommand::DeleteAction(action)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::DELETE_SUBJECT) => {
+                let subject = cmd.get_unchecked(msg::DELETE_SUBJECT).to_owned();
+                sender.send(BackendCommand::DeleteSubject(subject)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::MERGE_ACTIONS) => {
+                let merge = cmd.get_unchecked(msg::MERGE_ACTIONS).to_owned();
+                sender.send(BackendCommand::MergeActions(merge)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::MERGE_SUBJECTS) => {
+                let merge = cmd.get_unchecked(msg::MERGE_SUBJECTS).to_owned();
+                sender.send(BackendCommand::MergeSubjects(merge)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SPLIT_ACTIONS) => {
+                let merge = cmd.get_unchecked(msg::SPLIT_ACTIONS).to_owned();
+                sender.send(BackendCommand::SplitActions(merge)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SPLIT_SUBJECTS) => {
+                let merge = cmd.get_unchecked(msg::SPLIT_SUBJECTS).to_owned();
+                sender.send(BackendCommand::SplitSubjects(merge)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SET_CATEGORY) => {
+                let (subject, category) = cmd.get_unchecked(msg::SET_CATEGORY).to_owned();
+                sender
+                    .send(BackendCommand::SetCategory(subject, category))
+                    .unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::ADD_CHECKLIST_ITEM) => {
+                let (subject, text) = cmd.get_unchecked(msg::ADD_CHECKLIST_ITEM).to_owned();
+                sender
+                    .send(BackendCommand::AddChecklistItem(subject, text))
+                    .unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SET_CHECKLIST_ITEM_DONE) => {
+                let (id, done) = *cmd.get_unchecked(msg::SET_CHECKLIST_ITEM_DONE);
+                sender
+                    .send(BackendCommand::SetChecklistItemDone(id, done))
+                    .unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::ADD_ALIAS) => {
+                let alias = cmd.get_unchecked(msg::ADD_ALIAS).to_owned();
+                sender.send(BackendCommand::AddAlias(alias)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::DELETE_ALIAS) => {
+                let alias = cmd.get_unchecked(msg::DELETE_ALIAS).to_owned();
+                sender.send(BackendCommand::DeleteAlias(alias)).unwrap();
+            }
             Event::Command(cmd) if cmd.is(msg::ADD_SESSION) => {
                 let session = cmd.get_unchecked(msg::ADD_SESSION).to_owned();
                 let past_duration = data.content.time_table.get(&session.topic);
@@ -142,6 +914,91 @@ impl<W: Widget<AppState>> Controller<AppState, W> for BackendController {
                     .send(BackendCommand::AddSession(session, total_duration))
                     .unwrap();
             }
+            Event::Command(cmd) if cmd.is(msg::UPDATE_SESSION) => {
+                let (original, updated) = cmd.get_unchecked(msg::UPDATE_SESSION).to_owned();
+                sender
+                    .send(BackendCommand::UpdateSession(original, updated))
+                    .unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::DELETE_SESSION) => {
+                let session = cmd.get_unchecked(msg::DELETE_SESSION).to_owned();
+                sender.send(BackendCommand::DeleteSession(session)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::RESTORE_SESSION) => {
+                let session = cmd.get_unchecked(msg::RESTORE_SESSION).to_owned();
+                sender
+                    .send(BackendCommand::RestoreSession(session))
+                    .unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::AMEND_SESSION) => {
+                let (original, amended) = cmd.get_unchecked(msg::AMEND_SESSION).to_owned();
+                sender
+                    .send(BackendCommand::AmendSession(original, amended))
+                    .unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::MOVE_SESSIONS) => {
+                let moves = cmd.get_unchecked(msg::MOVE_SESSIONS).to_owned();
+                let strict = data.content.settings.strict_sessions;
+                sender
+                    .send(BackendCommand::MoveSessions(moves, strict))
+                    .unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::REPLACE_SESSIONS) => {
+                let replacements = cmd.get_unchecked(msg::REPLACE_SESSIONS).to_owned();
+                let strict = data.content.settings.strict_sessions;
+                sender
+                    .send(BackendCommand::ReplaceSessions(replacements, strict))
+                    .unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::SAVE_SETTINGS) => {
+                let settings = cmd.get_unchecked(msg::SAVE_SETTINGS).to_owned();
+                sender.send(BackendCommand::SaveSettings(settings)).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::HEALTH_CHECKED) => {
+                let findings = cmd.get_unchecked(msg::HEALTH_CHECKED);
+                data.findings.append(findings.clone());
+            }
+            Event::Command(cmd) if cmd.is(msg::SESSION_CONFLICT) => {
+                data.conflict = Some(cmd.get_unchecked(msg::SESSION_CONFLICT).to_owned());
+            }
+            Event::Command(cmd) if cmd.is(msg::KEEP_CHANGE) => {
+                let change = cmd.get_unchecked(msg::KEEP_CHANGE).to_owned();
+                sender
+                    .send(BackendCommand::KeepChange(data.content.clone(), change))
+                    .unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::RELOAD) => {
+                sender.send(BackendCommand::Reload).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::RESTORE_SNAPSHOT) => {
+                let snapshot = cmd.get_unchecked(msg::RESTORE_SNAPSHOT).to_owned();
+                sender
+                    .send(BackendCommand::RestoreSnapshot(snapshot))
+                    .unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::REPAIR) => {
+                sender.send(BackendCommand::Repair).unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::STOP_RUNNING) => {
+                sender
+                    .send(BackendCommand::StopRunning(data.content.clone()))
+                    .unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::LOAD_OLDER_SESSIONS) => {
+                let before = *cmd.get_unchecked(msg::LOAD_OLDER_SESSIONS);
+                sender
+                    .send(BackendCommand::LoadOlderSessions(
+                        data.content.clone(),
+                        before,
+                    ))
+                    .unwrap();
+            }
+            Event::Command(cmd) if cmd.is(msg::FIND_SESSIONS) => {
+                let query = *cmd.get_unchecked(msg::FIND_SESSIONS);
+                sender
+                    .send(BackendCommand::FindSessions(data.content.clone(), query))
+                    .unwrap();
+            }
             Event::Command(cmd) if cmd.is(msg::STOP) => {
                 sender.send(BackendCommand::Stop).unwrap();
             }
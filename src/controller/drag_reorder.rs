@@ -0,0 +1,57 @@
+use druid::{widget::Controller, Env, Event, EventCtx, Point, Widget};
+use std::any::Any;
+
+/// Distance the mouse has to move with the button held before it counts as dragging.
+const THRESHOLD: f64 = 5.0;
+
+/// Calls back with the number of rows a list row was dragged down, or up if
+/// negative. The rows are taken to be as high as this one. A drag does not
+/// count as a click on the row.
+pub struct DragReorder<D> {
+    callback: Box<dyn Fn(&mut EventCtx, &mut D, isize)>,
+    pressed: Option<Point>,
+    dragging: bool,
+}
+
+impl<D> DragReorder<D> {
+    pub fn new(callback: impl Fn(&mut EventCtx, &mut D, isize) + Any) -> Self {
+        Self {
+            callback: Box::new(callback),
+            pressed: None,
+            dragging: false,
+        }
+    }
+}
+
+impl<D, W: Widget<D>> Controller<D, W> for DragReorder<D> {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut D, env: &Env) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                self.pressed = Some(mouse.pos);
+                self.dragging = false;
+                ctx.set_active(true);
+            }
+            Event::MouseMove(mouse) => {
+                if let Some(pressed) = self.pressed {
+                    if (mouse.pos.y - pressed.y).abs() > THRESHOLD {
+                        self.dragging = true;
+                    }
+                }
+            }
+            Event::MouseUp(mouse) if mouse.button.is_left() && self.dragging => {
+                self.pressed = None;
+                self.dragging = false;
+                ctx.set_active(false);
+                ctx.set_handled();
+                let rows = (mouse.pos.y / ctx.size().height.max(1.0)).floor() as isize;
+                if rows != 0 {
+                    (self.callback)(ctx, data, rows);
+                }
+                return;
+            }
+            Event::MouseUp(_) => self.pressed = None,
+            _ => {}
+        }
+        child.event(ctx, event, data, env);
+    }
+}
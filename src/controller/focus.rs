@@ -0,0 +1,24 @@
+use druid::{widget::Controller, Env, Event, EventCtx, Selector, Widget};
+
+/// Takes the keyboard focus when the selector is received, for text boxes
+/// that should be typed into right away once shown.
+pub struct FocusOn {
+    selector: Selector,
+}
+
+impl FocusOn {
+    pub fn new(selector: Selector) -> Self {
+        Self { selector }
+    }
+}
+
+impl<D, W: Widget<D>> Controller<D, W> for FocusOn {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut D, env: &Env) {
+        if let Event::Command(cmd) = event {
+            if cmd.is(self.selector) {
+                ctx.request_focus();
+            }
+        }
+        child.event(ctx, event, data, env);
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{state::AppState, ui::tracker};
+use druid::{
+    widget::Controller, Env, Event, EventCtx, ExtEventSink, LifeCycle, LifeCycleCtx, Selector,
+    Target, UpdateCtx, Widget,
+};
+use global_hotkey::{hotkey::HotKey, GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
+
+/// Sent with the id of a system-wide shortcut once it has been pressed.
+const HOTKEY_PRESSED: Selector<u32> = Selector::new("zeitig.hotkey.pressed");
+
+/// Registers the shortcut configured in the settings, which starts or stops
+/// the session like the Start button, even while the window is unfocused.
+#[derive(Default)]
+pub struct HotkeyController {
+    manager: Option<GlobalHotKeyManager>,
+    registered: Option<HotKey>,
+}
+
+impl HotkeyController {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn init(&mut self, sink: ExtEventSink) {
+        match GlobalHotKeyManager::new() {
+            Ok(manager) => self.manager = Some(manager),
+            Err(err) => {
+                log::warn!("System-wide shortcuts are not available: {}", err);
+                return;
+            }
+        }
+        std::thread::spawn(move || {
+            for event in GlobalHotKeyEvent::receiver() {
+                if event.state != HotKeyState::Pressed {
+                    continue;
+                }
+                if sink
+                    .submit_command(HOTKEY_PRESSED, event.id, Target::Global)
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+    }
+
+    /// Replaces the registered shortcut, an empty one only removes it.
+    fn register(&mut self, shortcut: &str) {
+        let manager = match &self.manager {
+            Some(manager) => manager,
+            None => return,
+        };
+        if let Some(registered) = self.registered.take() {
+            if let Err(err) = manager.unregister(registered) {
+                log::warn!("Cannot remove the shortcut {}: {}", registered, err);
+            }
+        }
+        if shortcut.trim().is_empty() {
+            return;
+        }
+        let hotkey: HotKey = match shortcut.parse() {
+            Ok(hotkey) => hotkey,
+            Err(err) => {
+                log::warn!("Invalid shortcut {}: {}", shortcut, err);
+                return;
+            }
+        };
+        match manager.register(hotkey) {
+            Ok(()) => self.registered = Some(hotkey),
+            // Most likely taken by another application.
+            Err(err) => log::warn!("Cannot register the shortcut {}: {}", shortcut, err),
+        }
+    }
+}
+
+impl<W: Widget<AppState>> Controller<AppState, W> for HotkeyController {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut AppState,
+        env: &Env,
+    ) {
+        if let Event::Command(cmd) = event {
+            if let Some(id) = cmd.get(HOTKEY_PRESSED) {
+                if self.registered.map(|hotkey| hotkey.id()) == Some(*id) {
+                    ctx.submit_command(tracker::TOGGLE_SESSION);
+                }
+                ctx.set_handled();
+                return;
+            }
+        }
+        child.event(ctx, event, data, env)
+    }
+
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &AppState,
+        env: &Env,
+    ) {
+        if let (LifeCycle::WidgetAdded, false) = (event, data.safe_mode) {
+            self.init(ctx.get_external_handle());
+            self.register(&data.content.settings.hotkey);
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(
+        &mut self,
+        child: &mut W,
+        ctx: &mut UpdateCtx,
+        old_data: &AppState,
+        data: &AppState,
+        env: &Env,
+    ) {
+        if old_data.content.settings.hotkey != data.content.settings.hotkey {
+            self.register(&data.content.settings.hotkey);
+        }
+        child.update(ctx, old_data, data, env)
+    }
+}
@@ -0,0 +1,227 @@
+use crate::{
+    controller::backend_msg,
+    state::{
+        backup::{self, Backup},
+        collation, companion,
+        import::TopicLists,
+        template::Template,
+        toggl, AppState, Confirming, Creating,
+    },
+    ui::tracker,
+};
+use druid::{
+    commands, widget::Controller, Env, Event, EventCtx, FileDialogOptions, FileSpec, Selector,
+    Widget,
+};
+use std::{error::Error, path::Path, sync::Arc};
+
+pub const IMPORT: Selector<ImportKind> = Selector::new("zeitig.import");
+
+const CSV: FileSpec = FileSpec::new("CSV", &["csv", "txt"]);
+const JSON: FileSpec = FileSpec::new("JSON", &["json"]);
+const TEXT: FileSpec = FileSpec::new("Text", &["txt"]);
+
+#[derive(Clone, Copy)]
+pub enum ImportKind {
+    Topics,
+    Template,
+    Backup,
+    /// Sessions entered on a phone, in the compact companion format.
+    Companion,
+    /// Time entries from a detailed CSV export of Toggl.
+    Toggl,
+}
+
+impl ImportKind {
+    fn dialog_options(self) -> FileDialogOptions {
+        match self {
+            ImportKind::Topics => FileDialogOptions::new().allowed_types(vec![CSV, JSON]),
+            ImportKind::Toggl => FileDialogOptions::new().allowed_types(vec![CSV]),
+            ImportKind::Template | ImportKind::Backup => {
+                FileDialogOptions::new().allowed_types(vec![JSON])
+            }
+            ImportKind::Companion => FileDialogOptions::new().allowed_types(vec![TEXT]),
+        }
+    }
+}
+
+/// Asks for a file to import and processes it once the open panel returns.
+pub struct ImportController {
+    pending: Option<ImportKind>,
+    /// Read from the chosen file and waiting for the import to be confirmed.
+    confirming: Option<Backup>,
+}
+
+impl ImportController {
+    pub fn new() -> Self {
+        ImportController {
+            pending: None,
+            confirming: None,
+        }
+    }
+
+    fn import(
+        &mut self,
+        kind: ImportKind,
+        path: &Path,
+        ctx: &mut EventCtx,
+        data: &mut AppState,
+    ) -> Result<(), Box<dyn Error>> {
+        match kind {
+            ImportKind::Topics => {
+                let lists = TopicLists::read(path)?;
+                let content = &data.content;
+                create_missing(
+                    ctx,
+                    &content.actions,
+                    lists.actions,
+                    backend_msg::ADD_ACTION,
+                );
+                create_missing(
+                    ctx,
+                    &content.subjects,
+                    lists.subjects,
+                    backend_msg::ADD_SUBJECT,
+                );
+                create_missing(
+                    ctx,
+                    &content.contexts,
+                    lists.contexts,
+                    backend_msg::ADD_CONTEXT,
+                );
+            }
+            ImportKind::Template => {
+                let template = Template::read(path)?;
+                // Subjects that already exist keep what they have and only
+                // take from the template what is still missing.
+                for entry in &template.subjects {
+                    let existing = collation::find(&data.content.subjects, &entry.name);
+                    let mut subject = match existing {
+                        Some(subject) => subject.clone(),
+                        None => continue,
+                    };
+                    if let (Some(category), None) =
+                        (&entry.category, data.content.categories.get(&subject))
+                    {
+                        let category: Arc<str> = category.as_str().into();
+                        data.content
+                            .categories
+                            .set(&subject, Some(category.clone()));
+                        ctx.submit_command(
+                            backend_msg::SET_CATEGORY.with((subject.clone(), Some(category))),
+                        );
+                    }
+                    let color = entry.color.as_deref().and_then(backup::parse_color);
+                    if let (Some(color), None) = (color, subject.color) {
+                        subject.color = Some(color);
+                        data.rename_subject(&subject);
+                        ctx.submit_command(backend_msg::SET_SUBJECT_COLOR.with(subject.clone()));
+                    }
+                    let parent = entry
+                        .parent
+                        .as_ref()
+                        .and_then(|name| collation::find(&data.content.subjects, name))
+                        .filter(|parent| parent.id != subject.id)
+                        .map(|parent| parent.id);
+                    if let (Some(parent), None) = (parent, subject.parent) {
+                        subject.parent = Some(parent);
+                        data.rename_subject(&subject);
+                        ctx.submit_command(backend_msg::SET_PARENT.with(subject));
+                    }
+                }
+                let backup = template.into_backup(&data.content);
+                ctx.submit_command(backend_msg::IMPORT_TEMPLATE.with(backup));
+            }
+            ImportKind::Backup => {
+                let backup = Backup::read(path)?;
+                self.import_backup(path, backup, ctx, data);
+            }
+            ImportKind::Companion => {
+                let text = std::fs::read_to_string(path)?;
+                let backup = companion::parse(&data.content, &text)?;
+                self.import_backup(path, backup, ctx, data);
+            }
+            ImportKind::Toggl => {
+                let text = std::fs::read_to_string(path)?;
+                let backup = toggl::parse(&data.content, &text)?;
+                self.import_backup(path, backup, ctx, data);
+            }
+        }
+        Ok(())
+    }
+
+    /// Hands what was read to the backend, once confirmed if the settings ask for it.
+    fn import_backup(
+        &mut self,
+        path: &Path,
+        backup: Backup,
+        ctx: &mut EventCtx,
+        data: &mut AppState,
+    ) {
+        if !data.content.settings.confirm_import {
+            ctx.submit_command(backend_msg::IMPORT_BACKUP.with(backup));
+            return;
+        }
+        let file = path.file_name().unwrap_or_default().to_string_lossy();
+        data.setup.creating = Creating::Confirm(Confirming::Import {
+            file: file.as_ref().into(),
+            sessions: backup.sessions.len(),
+        });
+        self.confirming = Some(backup);
+    }
+}
+
+fn create_missing<'a, T: AsRef<str> + 'a>(
+    ctx: &mut EventCtx,
+    existing: impl IntoIterator<Item = &'a T>,
+    names: Vec<String>,
+    selector: Selector<String>,
+) {
+    let mut known: Vec<String> = existing
+        .into_iter()
+        .map(|item| item.as_ref().to_string())
+        .collect();
+    for name in names {
+        if !known.iter().any(|other| collation::same_name(other, &name)) {
+            known.push(name.clone());
+            ctx.submit_command(selector.with(name));
+        }
+    }
+}
+
+impl<W: Widget<AppState>> Controller<AppState, W> for ImportController {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut AppState,
+        env: &Env,
+    ) {
+        match event {
+            Event::Command(cmd) if cmd.is(IMPORT) => {
+                let kind = *cmd.get_unchecked(IMPORT);
+                self.pending = Some(kind);
+                ctx.submit_command(commands::SHOW_OPEN_PANEL.with(kind.dialog_options()));
+            }
+            Event::Command(cmd) if cmd.is(commands::OPEN_FILE) && self.pending.is_some() => {
+                let kind = self.pending.take().unwrap();
+                let path = cmd.get_unchecked(commands::OPEN_FILE).path();
+                if let Err(err) = self.import(kind, path, ctx, data) {
+                    log::error!("Failed to import {}: {}", path.display(), err);
+                }
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(tracker::CONFIRMED) => {
+                let confirmed = cmd.get_unchecked(tracker::CONFIRMED);
+                if let (Confirming::Import { .. }, Some(backup)) =
+                    (confirmed, self.confirming.take())
+                {
+                    ctx.submit_command(backend_msg::IMPORT_BACKUP.with(backup));
+                }
+            }
+            _ => {}
+        }
+        child.event(ctx, event, data, env)
+    }
+}
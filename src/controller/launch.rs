@@ -0,0 +1,116 @@
+//! Offers to start the topic of a launch rule once its application is
+//! launched, from a notification, as the window is likely out of sight then.
+
+use crate::{
+    platform,
+    state::{launch, AppState},
+    ui::tracker,
+};
+use druid::{
+    widget::Controller, Env, Event, EventCtx, LifeCycle, LifeCycleCtx, Selector, Target, UpdateCtx,
+    Widget,
+};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// Sent with the name of a process that was launched.
+const LAUNCHED: Selector<String> = Selector::new("zeitig.launched");
+
+pub struct LaunchController {
+    /// Whether the watcher looks at the processes, as set in the settings.
+    enabled: Arc<AtomicBool>,
+}
+
+impl LaunchController {
+    pub fn new() -> Self {
+        LaunchController {
+            enabled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+/// Waits for the answer on a thread of its own, as the notification stays until it is answered.
+fn offer(ctx: &mut EventCtx, data: &AppState, process: &str) {
+    let rule = match launch::rule_for(&data.content, process) {
+        Some(rule) => rule.clone(),
+        None => return,
+    };
+    let topic = &rule.topic;
+    let running = data.active.is_some()
+        && data.setup.selected_action.as_ref() == Some(&topic.action)
+        && data.setup.selected_subject.as_ref() == Some(&topic.subject);
+    if running {
+        return;
+    }
+    let sink = ctx.get_external_handle();
+    let body = format!(
+        "{} was launched, start {} {}?",
+        process, topic.action.name, topic.subject.name
+    );
+    std::thread::spawn(move || {
+        let chosen = platform::ask("Application launched", &body, &[("start", "Start")]);
+        if chosen.as_deref() == Some("start") {
+            if let Err(err) = sink.submit_command(tracker::START_TOPIC, rule.topic, Target::Auto) {
+                log::warn!("Cannot answer the notification: {}", err);
+            }
+        }
+    });
+}
+
+impl<W: Widget<AppState>> Controller<AppState, W> for LaunchController {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut AppState,
+        env: &Env,
+    ) {
+        if let Event::Command(cmd) = event {
+            if let Some(process) = cmd.get(LAUNCHED) {
+                offer(ctx, data, process);
+                ctx.set_handled();
+                return;
+            }
+        }
+        child.event(ctx, event, data, env)
+    }
+
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &AppState,
+        env: &Env,
+    ) {
+        if let (LifeCycle::WidgetAdded, false) = (event, data.safe_mode) {
+            let watch = data.content.settings.watch_launches;
+            self.enabled.store(watch, Ordering::Relaxed);
+            let sink = ctx.get_external_handle();
+            platform::watch_launches(self.enabled.clone(), move |process| {
+                if let Err(err) = sink.submit_command(LAUNCHED, process, Target::Auto) {
+                    log::warn!("Cannot tell about a launched application: {}", err);
+                }
+            });
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(
+        &mut self,
+        child: &mut W,
+        ctx: &mut UpdateCtx,
+        old_data: &AppState,
+        data: &AppState,
+        env: &Env,
+    ) {
+        let watch = data.content.settings.watch_launches;
+        if watch != old_data.content.settings.watch_launches {
+            self.enabled.store(watch, Ordering::Relaxed);
+        }
+        child.update(ctx, old_data, data, env)
+    }
+}
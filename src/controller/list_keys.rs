@@ -0,0 +1,37 @@
+use druid::{keyboard_types::Key, widget::Controller, Env, Event, EventCtx, Widget};
+use std::any::Any;
+
+/// Moves through a list with the arrow keys and chooses an entry with Enter,
+/// for the text box filtering the list.
+pub struct ListKeys<D> {
+    step: Box<dyn Fn(&mut D, isize)>,
+    choose: Box<dyn Fn(&mut EventCtx, &mut D)>,
+}
+
+impl<D> ListKeys<D> {
+    pub fn new(
+        step: impl Fn(&mut D, isize) + Any,
+        choose: impl Fn(&mut EventCtx, &mut D) + Any,
+    ) -> Self {
+        Self {
+            step: Box::new(step),
+            choose: Box::new(choose),
+        }
+    }
+}
+
+impl<D, W: Widget<D>> Controller<D, W> for ListKeys<D> {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut D, env: &Env) {
+        if let Event::KeyDown(key) = event {
+            match key.key {
+                Key::ArrowDown => (self.step)(data, 1),
+                Key::ArrowUp => (self.step)(data, -1),
+                Key::Enter => (self.choose)(ctx, data),
+                _ => return child.event(ctx, event, data, env),
+            }
+            ctx.set_handled();
+            return;
+        }
+        child.event(ctx, event, data, env);
+    }
+}
@@ -5,11 +5,41 @@ mod command_receiver;
 pub use command_receiver::CommandReceiver;
 
 mod ticker;
-pub use ticker::Ticker;
+pub use ticker::{Ticker, DAY_ENDED, INTERVAL_ENDED};
+
+mod presence;
+pub use presence::{PresenceController, CAME_BACK, WENT_AWAY};
+
+mod launch;
+pub use launch::LaunchController;
 
 mod enter;
 pub use enter::EnterController;
 
+mod focus;
+pub use focus::FocusOn;
+
+mod hotkey;
+pub use hotkey::HotkeyController;
+
+mod notify;
+pub use notify::NotifyController;
+
+mod reminder;
+pub use reminder::ReminderController;
+
+mod number_keys;
+pub use number_keys::NumberKeys;
+
+mod list_keys;
+pub use list_keys::ListKeys;
+
+mod right_click;
+pub use right_click::RightClick;
+
+mod drag_reorder;
+pub use drag_reorder::DragReorder;
+
 mod backend;
 
 pub use backend::msg as backend_msg;
@@ -17,3 +47,6 @@ pub use backend::BackendController;
 
 mod close;
 pub use close::CloseController;
+
+mod import;
+pub use import::{ImportController, ImportKind, IMPORT};
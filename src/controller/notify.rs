@@ -0,0 +1,149 @@
+//! Suggests a break once a session has been running for long, with the
+//! choice to be reminded again later or to stop it right from the notification.
+//! Also announces the time a session has been running at regular milestones,
+//! for those who cannot keep an eye on the window.
+
+use crate::{
+    platform,
+    state::{ActiveSession, AppState, DateTime},
+    ui::tracker,
+};
+use druid::{
+    widget::Controller, Data, Env, Event, EventCtx, ExtEventSink, Selector, Target, UpdateCtx,
+    Widget,
+};
+use std::time::Duration;
+
+/// Time until the suggestion is repeated when it is snoozed.
+const SNOOZE: Duration = Duration::from_secs(15 * 60);
+
+/// Repeats the suggestion later, for the session started at the given time.
+const SNOOZE_REMINDER: Selector<DateTime> = Selector::new("zeitig.notify.snooze");
+
+pub struct NotifyController {
+    /// Start of the watched session.
+    session: Option<DateTime>,
+    /// Duration at which to suggest a break, `None` once suggested.
+    due: Option<Duration>,
+}
+
+impl NotifyController {
+    pub fn new() -> Self {
+        NotifyController {
+            session: None,
+            due: None,
+        }
+    }
+
+    fn watches(&self, active: &ActiveSession) -> bool {
+        self.session
+            .as_ref()
+            .map_or(false, |started| started.same(&active.started))
+    }
+}
+
+/// Waits for the answer on a thread of its own, as the notification stays until it is answered.
+fn suggest_break(sink: ExtEventSink, label: String, started: DateTime, running: Duration) {
+    std::thread::spawn(move || {
+        let body = format!(
+            "{} has been running for {}, time for a break?",
+            label,
+            duration_label(running)
+        );
+        let chosen = platform::ask(
+            "Long session",
+            &body,
+            &[("snooze", "Remind me later"), ("stop", "Stop")],
+        );
+        let sent = match chosen.as_deref() {
+            Some("snooze") => sink.submit_command(SNOOZE_REMINDER, started, Target::Auto),
+            Some("stop") => sink.submit_command(tracker::END_SESSION, (), Target::Auto),
+            _ => Ok(()),
+        };
+        if let Err(err) = sent {
+            log::warn!("Cannot answer the notification: {}", err);
+        }
+    });
+}
+
+fn session_label(data: &AppState) -> String {
+    match (&data.setup.selected_action, &data.setup.selected_subject) {
+        (Some(action), Some(subject)) => format!("{} {}", action.as_ref(), subject.as_ref()),
+        _ => "The session".to_string(),
+    }
+}
+
+fn duration_label(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    match (minutes / 60, minutes % 60) {
+        (1, 0) => "an hour".to_string(),
+        (hours, 0) => format!("{} hours", hours),
+        _ => format!("{} minutes", minutes),
+    }
+}
+
+impl<W: Widget<AppState>> Controller<AppState, W> for NotifyController {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut AppState,
+        env: &Env,
+    ) {
+        if let Event::Command(cmd) = event {
+            if let Some(started) = cmd.get(SNOOZE_REMINDER) {
+                if let Some(active) = data.active.as_ref().filter(|a| a.started.same(started)) {
+                    self.due = Some(*active.duration + SNOOZE);
+                }
+                ctx.set_handled();
+                return;
+            }
+        }
+        child.event(ctx, event, data, env)
+    }
+
+    fn update(
+        &mut self,
+        child: &mut W,
+        ctx: &mut UpdateCtx,
+        old_data: &AppState,
+        data: &AppState,
+        env: &Env,
+    ) {
+        let step = (data.content.settings.milestone_minutes * 60.0) as u64;
+        if let (Some(old), Some(active)) = (&old_data.active, &data.active) {
+            let passed = |active: &ActiveSession| active.duration.as_secs() / step;
+            if step > 0 && old.started.same(&active.started) && passed(active) > passed(old) {
+                let running = Duration::from_secs(passed(active) * step);
+                platform::announce(
+                    &format!(
+                        "{} has been running for {}.",
+                        session_label(data),
+                        duration_label(running)
+                    ),
+                    match data.safe_mode {
+                        true => "",
+                        false => &data.content.settings.speech_command,
+                    },
+                );
+            }
+        }
+        let minutes = data.content.settings.long_session_minutes;
+        match &data.active {
+            Some(active) if minutes > 0.0 => {
+                if !self.watches(active) {
+                    self.session = Some(active.started);
+                    self.due = Some(Duration::from_secs_f64(minutes * 60.0));
+                }
+                if self.due.map_or(false, |due| *active.duration >= due) {
+                    self.due = None;
+                    let sink = ctx.get_external_handle();
+                    suggest_break(sink, session_label(data), active.started, *active.duration);
+                }
+            }
+            _ => self.session = None,
+        }
+        child.update(ctx, old_data, data, env)
+    }
+}
@@ -0,0 +1,35 @@
+use druid::{keyboard_types::Key, widget::Controller, Env, Event, EventCtx, Widget};
+use std::any::Any;
+
+/// Calls back with the number of the keys 1 to 9, unless a descendant such as
+/// a text box has focus and needs the keys for itself.
+pub struct NumberKeys<D> {
+    callback: Box<dyn Fn(&mut EventCtx, &mut D, usize)>,
+}
+
+impl<D> NumberKeys<D> {
+    pub fn new(callback: impl Fn(&mut EventCtx, &mut D, usize) + Any) -> Self {
+        Self {
+            callback: Box::new(callback),
+        }
+    }
+}
+
+impl<D, W: Widget<D>> Controller<D, W> for NumberKeys<D> {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut D, env: &Env) {
+        if let Event::KeyDown(key) = event {
+            // Lock keys are ignored, so the numeric keypad works with num lock on.
+            let shortcut = key.mods.ctrl() || key.mods.alt() || key.mods.meta();
+            let number = match &key.key {
+                Key::Character(c) if !shortcut && !ctx.has_focus() => c.parse().ok(),
+                _ => None,
+            };
+            if let Some(number @ 1..=9) = number {
+                (self.callback)(ctx, data, number);
+                ctx.set_handled();
+                return;
+            }
+        }
+        child.event(ctx, event, data, env);
+    }
+}
@@ -0,0 +1,47 @@
+use druid::{widget::Controller, Env, LifeCycle, LifeCycleCtx, Selector, Target, Widget};
+
+use crate::{
+    platform::{self, Presence},
+    state::{AppState, DateTime},
+};
+
+/// Sent when the screen was locked or the system went to sleep at the given time.
+pub const WENT_AWAY: Selector<DateTime> = Selector::new("zeitig.went_away");
+/// Sent when the screen is unlocked and the system awake again.
+pub const CAME_BACK: Selector = Selector::new("zeitig.came_back");
+
+/// Watches for the user going away from the computer and coming back.
+pub struct PresenceController;
+
+impl PresenceController {
+    pub fn new() -> Self {
+        PresenceController
+    }
+}
+
+impl<W: Widget<AppState>> Controller<AppState, W> for PresenceController {
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &AppState,
+        env: &Env,
+    ) {
+        if let (LifeCycle::WidgetAdded, false) = (event, data.safe_mode) {
+            let sink = ctx.get_external_handle();
+            platform::watch_presence(move |presence| {
+                let sent = match presence {
+                    Presence::Away(since) => {
+                        sink.submit_command(WENT_AWAY, DateTime::local(since), Target::Auto)
+                    }
+                    Presence::Back => sink.submit_command(CAME_BACK, (), Target::Auto),
+                };
+                if let Err(err) = sent {
+                    log::warn!("Cannot tell about the screen lock or sleep: {}", err);
+                }
+            });
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+}
@@ -0,0 +1,75 @@
+use druid::{
+    widget::Controller, Env, Event, EventCtx, LifeCycle, LifeCycleCtx, TimerToken, Widget,
+};
+use std::time::Duration;
+
+use crate::{
+    platform,
+    state::{reminder::Reminder, AppState, DateTime, Startup},
+};
+
+/// Time between two looks at whether a reminder is due.
+const INTERVAL: Duration = Duration::from_secs(60);
+
+/// Reminds to start tracking during the work hours given in the settings.
+pub struct ReminderController {
+    timer: Option<TimerToken>,
+    /// When the last reminder was shown.
+    reminded: Option<time::OffsetDateTime>,
+}
+
+impl ReminderController {
+    pub fn new() -> Self {
+        ReminderController {
+            timer: None,
+            reminded: None,
+        }
+    }
+
+    fn check(&mut self, data: &AppState) {
+        // Before loading, the history would look empty.
+        if data.active.is_some() || data.startup != Startup::Ready {
+            return;
+        }
+        let now = *DateTime::now();
+        let settings = &data.content.settings;
+        if let Some(reminder) = Reminder::due(settings, &data.history, now, self.reminded) {
+            let (summary, body) = reminder.message();
+            platform::notify(summary, &body);
+            self.reminded = Some(now);
+        }
+    }
+}
+
+impl<W: Widget<AppState>> Controller<AppState, W> for ReminderController {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut AppState,
+        env: &Env,
+    ) {
+        if let Event::Timer(token) = event {
+            if Some(*token) == self.timer {
+                self.check(data);
+                self.timer = Some(ctx.request_timer(INTERVAL));
+            }
+        }
+        child.event(ctx, event, data, env)
+    }
+
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &AppState,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.timer = Some(ctx.request_timer(INTERVAL));
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+}
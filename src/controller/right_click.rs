@@ -0,0 +1,29 @@
+use druid::{widget::Controller, Env, Event, EventCtx, Point, Widget};
+use std::any::Any;
+
+/// Calls back with the window position of a right click, which is not passed
+/// on to the child so it does not count as a regular click.
+pub struct RightClick<D> {
+    callback: Box<dyn Fn(&mut EventCtx, &mut D, Point)>,
+}
+
+impl<D> RightClick<D> {
+    pub fn new(callback: impl Fn(&mut EventCtx, &mut D, Point) + Any) -> Self {
+        Self {
+            callback: Box::new(callback),
+        }
+    }
+}
+
+impl<D, W: Widget<D>> Controller<D, W> for RightClick<D> {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut D, env: &Env) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_right() => {
+                (self.callback)(ctx, data, mouse.window_pos);
+                ctx.set_handled();
+            }
+            Event::MouseUp(mouse) if mouse.button.is_right() => ctx.set_handled(),
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+}
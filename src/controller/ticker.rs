@@ -1,20 +1,53 @@
-use druid::{widget::Controller, Env, Event, EventCtx, TimerToken, Widget};
+use druid::{widget::Controller, Env, Event, EventCtx, Selector, TimerToken, Widget};
 use std::time::Duration;
 
-use crate::{controller::SAVE_NOW, state::AppState};
+use crate::{
+    controller::SAVE_NOW,
+    platform,
+    state::{
+        idle::{IdleRule, Response},
+        AppState, DateTime, Settings, SpentTime, Subject,
+    },
+};
 
 const INTERVAL: Duration = Duration::from_secs(1);
+/// Ticks between two queries of the idle time.
+const IDLE_CHECK_TICKS: u32 = 10;
+
+/// Sent when the current pomodoro interval is over.
+pub const INTERVAL_ENDED: Selector = Selector::new("zeitig.pomodoro.interval_ended");
+/// Sent when the day changes while a session is running, so it can be split.
+pub const DAY_ENDED: Selector = Selector::new("zeitig.day_ended");
 
 pub struct Ticker {
     timer: Option<TimerToken>,
+    ticks: u32,
 }
 
 impl Ticker {
     pub fn new() -> Self {
-        Self { timer: None }
+        Self {
+            timer: None,
+            ticks: 0,
+        }
     }
 }
 
+/// The idle time and what to do about it, once it exceeds the interval
+/// configured for the selected subject.
+fn idle_time(subject: Option<&Subject>, settings: &Settings) -> Option<(Duration, Response)> {
+    let rule = subject.map_or(IdleRule::Default, |subject| subject.idle_rule);
+    let (threshold, response) = rule.threshold(settings)?;
+    let idle = platform::idle_time().filter(|idle| *idle >= threshold)?;
+    Some((idle, response))
+}
+
+fn is_idle(data: &AppState) -> bool {
+    data.active
+        .as_ref()
+        .map_or(false, |session| session.idle_since.is_some())
+}
+
 impl<W: Widget<AppState>> Controller<AppState, W> for Ticker {
     fn event(
         &mut self,
@@ -27,8 +60,64 @@ impl<W: Widget<AppState>> Controller<AppState, W> for Ticker {
         if let Event::Timer(token) = event {
             if Some(*token) == self.timer {
                 if let Some(session) = data.active.as_mut() {
-                    *session.duration += INTERVAL;
-                    self.timer = Some(ctx.request_timer(INTERVAL));
+                    let running = session.paused_since.is_none();
+                    if running {
+                        *session.duration += INTERVAL;
+                    }
+                    self.ticks += 1;
+                    let check = self.ticks % IDLE_CHECK_TICKS == 0;
+                    let next_day = *session.started.next_midnight() <= *DateTime::now();
+                    if check && next_day && data.content.settings.split_at_midnight {
+                        ctx.submit_command(DAY_ENDED);
+                    }
+                    // Breaks are spent paused, so they count down regardless.
+                    if let Some(pomodoro) = data.pomodoro.as_mut() {
+                        if (running || pomodoro.is_break()) && pomodoro.tick(INTERVAL) {
+                            ctx.submit_command(INTERVAL_ENDED);
+                        }
+                    }
+                    // Time spent away is handled by pausing, not by asking about it.
+                    let idle = if running && check && data.setup.away.is_none() {
+                        idle_time(data.setup.selected_subject.as_ref(), &data.content.settings)
+                    } else {
+                        None
+                    };
+                    match idle {
+                        // Paused since the idle time began, so it is left out.
+                        Some((idle, Response::Pause)) => {
+                            session.pause(DateTime::from(*DateTime::now() - idle));
+                            session.duration =
+                                session.duration.saturating_sub(SpentTime::from(idle));
+                            platform::notify(
+                                "Session paused",
+                                &format!(
+                                    "Nothing happened for {} minutes, resume it when you are back.",
+                                    idle.as_secs() / 60
+                                ),
+                            );
+                            self.timer = Some(ctx.request_timer(INTERVAL));
+                        }
+                        // Stop counting until the idle time is kept or discarded.
+                        Some((idle, Response::Ask)) => {
+                            session.idle_since = Some(DateTime::from(*DateTime::now() - idle));
+                            session.duration =
+                                session.duration.saturating_sub(SpentTime::from(idle));
+                            self.timer = None;
+                        }
+                        None => self.timer = Some(ctx.request_timer(INTERVAL)),
+                    }
+                }
+                for goal in data.reach_goals() {
+                    let topic = &goal.topic;
+                    platform::notify(
+                        "Goal reached",
+                        &format!(
+                            "{} {}: {}",
+                            topic.action.as_ref(),
+                            topic.subject.as_ref(),
+                            goal.label()
+                        ),
+                    );
                 }
             }
         }
@@ -49,6 +138,9 @@ impl<W: Widget<AppState>> Controller<AppState, W> for Ticker {
                 self.timer = None;
                 ctx.submit_command(SAVE_NOW)
             }
+            (true, true) if is_idle(old_data) && !is_idle(data) => {
+                self.timer = Some(ctx.request_timer(INTERVAL))
+            }
             _ => (),
         }
         child.update(ctx, old_data, data, env);
@@ -0,0 +1,370 @@
+//! Tracking without the window, for frontends embedding Zeitig.
+//!
+//! [`Tracker`] starts and stops sessions, keeps them in the data file and sums
+//! them up per topic. It works on the same file as the window and the command
+//! line, a session started by one of them can be stopped by any other.
+//!
+//! Everything here is made of plain types of the standard library, so that it
+//! does not change along with the window. Actions, subjects and contexts are
+//! referred to by their names.
+
+use crate::state::{
+    self,
+    backend::{Backend, Sqlite},
+    collation, legacy, paths, report, Content, Date, DateTime, SpentTime,
+};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+/// Sessions shorter than this are discarded, as in the window.
+pub const MIN_SESSION: Duration = Duration::from_secs(30);
+
+/// What is being worked on, by the names of its action and subject.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Topic {
+    pub action: String,
+    pub subject: String,
+}
+
+/// The session being tracked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Running {
+    pub topic: Topic,
+    pub context: Option<String>,
+    pub started: SystemTime,
+}
+
+impl Running {
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed().unwrap_or_default()
+    }
+}
+
+/// A session recorded in the history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub topic: Topic,
+    pub context: Option<String>,
+    pub started: SystemTime,
+    pub ended: SystemTime,
+    /// Time between start and end without the breaks taken.
+    pub worked: Duration,
+}
+
+/// What became of the running session when it was stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Stopped {
+    /// No session was running.
+    Nothing,
+    /// The session was recorded in the history.
+    Recorded(Session),
+    /// The session was shorter than [`MIN_SESSION`] and left out.
+    Discarded(Running),
+}
+
+/// Length of the period a [`Summary`] adds up, up to now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Day,
+    Week,
+    /// Starting on the day of the month set in the window.
+    Month,
+}
+
+/// Time spent per topic since the start of a period.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Summary {
+    /// The period as shown to the user, like "Week 2020-06-01".
+    pub label: String,
+    /// Sorted by the names of action and subject, including the running session.
+    pub totals: Vec<(Topic, Duration)>,
+}
+
+impl Summary {
+    pub fn total(&self) -> Duration {
+        self.totals.iter().map(|(_, time)| *time).sum()
+    }
+}
+
+/// Writes out time the way the window does, like `1h 30m 0s`.
+pub fn format_duration(time: Duration) -> String {
+    SpentTime::from(time).to_string()
+}
+
+fn topic(topic: &state::Topic) -> Topic {
+    Topic {
+        action: topic.action.name.to_string(),
+        subject: topic.subject.name.to_string(),
+    }
+}
+
+fn context(context: &Option<state::Context>) -> Option<String> {
+    context.as_ref().map(|context| context.name.to_string())
+}
+
+fn running(running: &state::Running) -> Running {
+    Running {
+        topic: topic(&running.topic),
+        context: context(&running.context),
+        started: SystemTime::from(*running.started),
+    }
+}
+
+fn session(session: &state::Session) -> Session {
+    Session {
+        topic: topic(&session.topic),
+        context: context(&session.context),
+        started: SystemTime::from(*session.started),
+        ended: SystemTime::from(*session.ended),
+        worked: *session.duration(),
+    }
+}
+
+/// A data file opened for tracking.
+pub struct Tracker {
+    backend: Box<dyn Backend>,
+    content: Content,
+    took_over: Option<usize>,
+}
+
+impl Tracker {
+    /// Opens the data file at `path`, creating it if missing.
+    pub fn open(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let mut backend = Sqlite::new(path)?;
+        backend.setup()?;
+        Tracker::with_backend(Box::new(backend))
+    }
+
+    /// Opens the data file the window uses. When it is created, the file of
+    /// older versions is taken over, see [`Tracker::took_over`].
+    pub fn open_default() -> Result<Self, Box<dyn Error>> {
+        let path = paths::data_file();
+        let fresh = !path.exists();
+        let mut backend = Sqlite::new(&path)?;
+        backend.setup()?;
+        let took_over = if fresh {
+            legacy::migrate(&mut backend, &paths::legacy_file())?
+        } else {
+            None
+        };
+        let mut tracker = Tracker::with_backend(Box::new(backend))?;
+        tracker.took_over = took_over;
+        Ok(tracker)
+    }
+
+    /// Opens the data file of a profile like [`Tracker::open_default`], `None`
+    /// for the default one. The profile is used for everything opened later on.
+    pub fn open_profile(name: Option<&str>) -> Result<Self, Box<dyn Error>> {
+        paths::set_profile(name)?;
+        Tracker::open_default()
+    }
+
+    fn with_backend(mut backend: Box<dyn Backend>) -> Result<Self, Box<dyn Error>> {
+        let content = backend.load_content()?;
+        Ok(Tracker {
+            backend,
+            content,
+            took_over: None,
+        })
+    }
+
+    /// Number of sessions taken over from the file of older versions on opening.
+    pub fn took_over(&self) -> Option<usize> {
+        self.took_over
+    }
+
+    /// Loads the actions, subjects and contexts again, to see changes made elsewhere.
+    pub fn reload(&mut self) -> Result<(), Box<dyn Error>> {
+        self.content = self.backend.load_content()?;
+        Ok(())
+    }
+
+    /// Names of the actions that are not archived, in the order of the window.
+    pub fn actions(&self) -> Vec<String> {
+        self.content
+            .actions
+            .iter()
+            .filter(|action| !action.archived)
+            .map(|action| action.name.to_string())
+            .collect()
+    }
+
+    /// Names of the subjects that are not archived, in the order of the window.
+    pub fn subjects(&self) -> Vec<String> {
+        self.content
+            .subjects
+            .iter()
+            .filter(|subject| !subject.archived)
+            .map(|subject| subject.name.to_string())
+            .collect()
+    }
+
+    /// Looks up a topic by the names or aliases of its action and subject,
+    /// ignoring case and accents. It is returned with the names as stored.
+    pub fn find_topic(&self, action: &str, subject: &str) -> Result<Topic, Box<dyn Error>> {
+        Ok(topic(&self.state_topic(action, subject)?))
+    }
+
+    /// Looks up a context by name, ignoring case and accents.
+    pub fn find_context(&self, name: &str) -> Result<String, Box<dyn Error>> {
+        Ok(self.state_context(name)?.name.to_string())
+    }
+
+    fn state_topic(&self, action: &str, subject: &str) -> Result<state::Topic, Box<dyn Error>> {
+        Ok(state::Topic {
+            action: self
+                .content
+                .action_named(action)
+                .cloned()
+                .ok_or_else(|| format!("No action is named {}.", action))?,
+            subject: self
+                .content
+                .subject_named(subject)
+                .cloned()
+                .ok_or_else(|| format!("No subject is named {}.", subject))?,
+        })
+    }
+
+    fn state_context(&self, name: &str) -> Result<state::Context, Box<dyn Error>> {
+        Ok(collation::find(&self.content.contexts, name)
+            .cloned()
+            .ok_or_else(|| format!("No context is named {}.", name))?)
+    }
+
+    /// Fails if an action of that name exists already.
+    pub fn create_action(&mut self, name: &str) -> Result<(), Box<dyn Error>> {
+        if let Some(action) = collation::find(&self.content.actions, name) {
+            return Err(format!("The action {} exists already.", action.as_ref()).into());
+        }
+        let action = self.backend.create_action(name)?;
+        self.content.actions.push_back(action);
+        Ok(())
+    }
+
+    /// Fails if a subject of that name exists already.
+    pub fn create_subject(&mut self, name: &str) -> Result<(), Box<dyn Error>> {
+        if let Some(subject) = collation::find(&self.content.subjects, name) {
+            return Err(format!("The subject {} exists already.", subject.as_ref()).into());
+        }
+        let subject = self.backend.create_subject(name)?;
+        self.content.subjects.push_back(subject);
+        Ok(())
+    }
+
+    /// The running session, which may have been started elsewhere.
+    pub fn running(&mut self) -> Result<Option<Running>, Box<dyn Error>> {
+        Ok(self.running_state()?.as_ref().map(running))
+    }
+
+    fn running_state(&mut self) -> Result<Option<state::Running>, Box<dyn Error>> {
+        self.backend.load_running(&self.content)
+    }
+
+    /// Starts a session now, stopping the running one first.
+    pub fn start(
+        &mut self,
+        topic: &Topic,
+        context: Option<&str>,
+    ) -> Result<Stopped, Box<dyn Error>> {
+        let topic = self.state_topic(&topic.action, &topic.subject)?;
+        let context = match context {
+            Some(name) => Some(self.state_context(name)?),
+            None => None,
+        };
+        let stopped = self.stop()?;
+        let running = state::Running {
+            topic,
+            context,
+            started: DateTime::now(),
+        };
+        self.backend.set_running(Some(&running))?;
+        Ok(stopped)
+    }
+
+    /// Stops the running session now, recording it unless it was too short.
+    pub fn stop(&mut self) -> Result<Stopped, Box<dyn Error>> {
+        let stopped = match self.running_state()? {
+            Some(stopped) => stopped,
+            None => return Ok(Stopped::Nothing),
+        };
+        let duration = stopped.elapsed();
+        let result = if *duration >= MIN_SESSION {
+            let recorded = stopped.session(DateTime::now());
+            self.backend.add_session(&recorded)?;
+            self.add_time(&recorded.topic, duration)?;
+            Stopped::Recorded(session(&recorded))
+        } else {
+            Stopped::Discarded(running(&stopped))
+        };
+        self.backend.set_running(None)?;
+        Ok(result)
+    }
+
+    fn history_state(&mut self) -> Result<state::History, Box<dyn Error>> {
+        self.backend.load_history(&self.content)
+    }
+
+    /// All recorded sessions, the oldest first.
+    pub fn history(&mut self) -> Result<Vec<Session>, Box<dyn Error>> {
+        Ok(self.history_state()?.iter().map(session).collect())
+    }
+
+    /// Records a session without breaks that was not tracked live.
+    pub fn record(
+        &mut self,
+        topic: &Topic,
+        context: Option<&str>,
+        started: SystemTime,
+        ended: SystemTime,
+    ) -> Result<(), Box<dyn Error>> {
+        let topic = self.state_topic(&topic.action, &topic.subject)?;
+        let context = match context {
+            Some(name) => Some(self.state_context(name)?),
+            None => None,
+        };
+        let recorded = state::Running {
+            topic,
+            context,
+            started: DateTime::local(started.into()),
+        }
+        .session(DateTime::local(ended.into()));
+        self.backend.add_session(&recorded)?;
+        self.add_time(&recorded.topic, recorded.duration())
+    }
+
+    fn add_time(&mut self, topic: &state::Topic, time: SpentTime) -> Result<(), Box<dyn Error>> {
+        *self.content.time_table.get_mut(topic.clone()) += time;
+        self.backend.add_time(topic, &time)
+    }
+
+    /// Time spent per topic in the current `period`, up to now.
+    pub fn summary(&mut self, period: Period) -> Result<Summary, Box<dyn Error>> {
+        let period = match period {
+            Period::Day => report::Period::Day,
+            Period::Week => report::Period::Week,
+            Period::Month => report::Period::Month,
+        };
+        let today = Date::from(time::OffsetDateTime::now_local());
+        let begin = period.begin(today, self.content.settings.month_start as u8);
+        let mut totals: BTreeMap<Topic, Duration> = BTreeMap::new();
+        let mut add = |spent: &state::Topic, time: SpentTime| {
+            *totals.entry(topic(spent)).or_default() += *time;
+        };
+        for session in self.history_state()?.iter() {
+            if Date::from(*session.started) >= begin {
+                add(&session.topic, session.duration());
+            }
+        }
+        if let Some(running) = self.running_state()? {
+            add(&running.topic, running.elapsed());
+        }
+        Ok(Summary {
+            label: period.label(begin),
+            totals: totals.into_iter().collect(),
+        })
+    }
+}
@@ -0,0 +1,45 @@
+//! Keeps a second window from opening the same data file, as both would
+//! overwrite the changes of the other. The second one shows the first instead.
+
+use crate::ipc;
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+};
+use zeitig::state::paths;
+
+/// Holds the lock on the data file until the window closes. The system
+/// releases it as well if the application crashes.
+pub struct Instance {
+    _file: File,
+}
+
+/// Locks the data file for this instance, or returns `None` after showing
+/// the window of the instance that has it.
+pub fn acquire() -> std::io::Result<Option<Instance>> {
+    let path = paths::lock_file();
+    let mut file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)?;
+    match file.try_lock() {
+        Ok(()) => {
+            // Only informative, the lock itself is what counts.
+            file.set_len(0)?;
+            write!(file, "{}", std::process::id())?;
+            Ok(Some(Instance { _file: file }))
+        }
+        Err(std::fs::TryLockError::WouldBlock) => {
+            if let Err(err) = ipc::show_running() {
+                log::warn!("Cannot show the running instance: {}", err);
+                eprintln!(
+                    "Zeitig is already running on {}.",
+                    paths::data_file().display()
+                );
+            }
+            Ok(None)
+        }
+        Err(std::fs::TryLockError::Error(err)) => Err(err),
+    }
+}
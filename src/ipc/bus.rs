@@ -0,0 +1,61 @@
+//! The endpoint on the session bus, for example:
+//!
+//! `busctl --user call de.leopoldluley.Zeitig /de/leopoldluley/Zeitig de.leopoldluley.Zeitig CurrentDuration`
+
+use super::Control;
+use dbus::{blocking::Connection, MethodErr};
+use dbus_crossroads::{Crossroads, IfaceBuilder};
+use std::{error::Error, time::Duration};
+
+static NAME: &str = "de.leopoldluley.Zeitig";
+static PATH: &str = "/de/leopoldluley/Zeitig";
+
+pub fn serve(control: Control) {
+    if let Err(err) = run(control) {
+        log::warn!("Cannot offer the D-Bus interface: {}", err);
+    }
+}
+
+fn run(control: Control) -> Result<(), Box<dyn Error>> {
+    let connection = Connection::new_session()?;
+    // Fails if another instance is running, which keeps the name.
+    connection.request_name(NAME, false, false, true)?;
+
+    let mut crossroads = Crossroads::new();
+    let interface = crossroads.register(NAME, |builder: &mut IfaceBuilder<Control>| {
+        builder.method(
+            "StartSession",
+            ("action", "subject"),
+            (),
+            |_, control, (action, subject): (String, String)| {
+                control
+                    .start(&action, &subject)
+                    .map_err(|err| MethodErr::failed(&err))
+            },
+        );
+        builder.method("StopSession", (), (), |_, control, (): ()| {
+            control.stop().map_err(|err| MethodErr::failed(&err))
+        });
+        builder.method("Show", (), (), |_, control, (): ()| {
+            control.show().map_err(|err| MethodErr::failed(&err))
+        });
+        builder.method("Status", (), ("running", "topic"), |_, control, (): ()| {
+            let status = control.status();
+            Ok((status.running.is_some(), status.label()))
+        });
+        builder.method("CurrentDuration", (), ("seconds",), |_, control, (): ()| {
+            Ok((control.status().duration.as_secs(),))
+        });
+    });
+    crossroads.insert(PATH, &[interface], control);
+    crossroads.serve(&connection)?;
+    Ok(())
+}
+
+/// Asks the instance owning the name to show its window.
+pub fn show_running() -> Result<(), Box<dyn Error>> {
+    let connection = Connection::new_session()?;
+    let proxy = connection.with_proxy(NAME, PATH, Duration::from_secs(1));
+    proxy.method_call::<(), _, _, _>(NAME, "Show", ())?;
+    Ok(())
+}
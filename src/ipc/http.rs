@@ -0,0 +1,124 @@
+//! A single read-only page showing today, this week and the running session,
+//! served to the local network to be glanced at from a phone.
+
+use super::{topic_label, Control, Status};
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+};
+
+/// Seconds after which the page reloads itself.
+const REFRESH: u32 = 30;
+
+pub fn serve(port: u16, control: Control) {
+    let listener = match TcpListener::bind(("0.0.0.0", port)) {
+        Ok(listener) => listener,
+        Err(err) => {
+            log::warn!("Cannot serve the dashboard on port {}: {}", port, err);
+            return;
+        }
+    };
+    log::info!("Serving the dashboard on port {}.", port);
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let control = control.clone();
+                std::thread::spawn(move || {
+                    if let Err(err) = answer(&control, stream) {
+                        log::warn!("Dashboard connection failed: {}", err);
+                    }
+                });
+            }
+            Err(err) => log::warn!("Cannot accept a dashboard connection: {}", err),
+        }
+    }
+}
+
+fn answer(control: &Control, stream: TcpStream) -> std::io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    // Headers are not needed, but have to be read before answering.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && !header.trim().is_empty() {
+        header.clear();
+    }
+
+    let mut parts = request.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/")) => ("200 OK", page(&control.status())),
+        (Some("GET"), _) => ("404 Not Found", "Not found".to_string()),
+        _ => (
+            "405 Method Not Allowed",
+            "Only reading is allowed".to_string(),
+        ),
+    };
+    write!(
+        writer,
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    writer.flush()
+}
+
+fn page(status: &Status) -> String {
+    let running = match &status.running {
+        Some(topic) => format!(
+            "<p class=\"running\">{} <strong>{}</strong></p>",
+            escape(&topic_label(topic)),
+            status.duration
+        ),
+        None => "<p>Not tracking</p>".to_string(),
+    };
+    let today: String = status
+        .today
+        .iter()
+        .map(|(topic, time)| {
+            format!(
+                "<tr><td>{}</td><td>{}</td></tr>",
+                escape(&topic_label(topic)),
+                time
+            )
+        })
+        .collect();
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<meta http-equiv="refresh" content="{refresh}">
+<title>Zeitig</title>
+<style>
+body {{ font-family: sans-serif; margin: 1em auto; max-width: 30em; padding: 0 1em; }}
+td:last-child {{ text-align: right; }}
+table {{ width: 100%; }}
+.running {{ font-size: 1.3em; }}
+</style>
+</head>
+<body>
+<h1>Zeitig</h1>
+{running}
+<h2>Today: {today_total}</h2>
+<table>{today}</table>
+<h2>This week: {week}</h2>
+</body>
+</html>
+"#,
+        refresh = REFRESH,
+        running = running,
+        today_total = status.today_total(),
+        today = today,
+        week = status.week,
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
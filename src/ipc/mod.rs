@@ -0,0 +1,316 @@
+//! Local endpoint through which status bars and scripts query and control
+//! the running application, over D-Bus on Linux, a named pipe on Windows and
+//! a Unix socket elsewhere.
+//! A read-only dashboard can additionally be served over HTTP.
+
+use crate::{
+    state::{collation, Action, AppState, Date, SpentTime, Subject, Topic},
+    tray,
+    ui::tracker,
+};
+use druid::{
+    widget::Controller, Data, Env, ExtEventSink, LifeCycle, LifeCycleCtx, Selector, Target,
+    UpdateCtx, Widget,
+};
+use std::sync::{Arc, Mutex};
+#[cfg(not(target_os = "linux"))]
+use std::{
+    error::Error,
+    io::{self, BufRead, Write},
+};
+
+#[cfg(target_os = "linux")]
+mod bus;
+mod http;
+#[cfg(windows)]
+mod pipe;
+#[cfg(all(unix, not(target_os = "linux")))]
+mod socket;
+
+/// What the endpoint reports, a copy of the relevant parts of the state.
+#[derive(Clone, Default)]
+pub struct Status {
+    /// The topic of the running session.
+    pub running: Option<Topic>,
+    pub duration: SpentTime,
+    /// Time per topic today, including the running session, the longest first.
+    pub today: Vec<(Topic, SpentTime)>,
+    /// Time spent since monday, including the running session.
+    pub week: SpentTime,
+    /// Offered for starting sessions by name.
+    actions: Vec<Action>,
+    subjects: Vec<Subject>,
+}
+
+impl Status {
+    fn of(data: &AppState) -> Self {
+        let running = match (
+            &data.active,
+            &data.setup.selected_action,
+            &data.setup.selected_subject,
+        ) {
+            (Some(_), Some(action), Some(subject)) => Some(Topic {
+                action: action.clone(),
+                subject: subject.clone(),
+            }),
+            _ => None,
+        };
+        let duration = data
+            .active
+            .as_ref()
+            .map_or_else(SpentTime::default, |active| active.duration);
+
+        let today_begin = Date::from(::time::OffsetDateTime::now_local());
+        let week_begin = today_begin.week_begin();
+        let mut today: Vec<(Topic, SpentTime)> = Vec::new();
+        let mut add_today = |topic: &Topic, time: SpentTime| match today
+            .iter_mut()
+            .find(|(other, _)| other == topic)
+        {
+            Some((_, sum)) => *sum += time,
+            None => today.push((topic.clone(), time)),
+        };
+        let mut week = duration;
+        if let Some(topic) = &running {
+            add_today(topic, duration);
+        }
+        for session in data.history.iter() {
+            let started = Date::from(*session.started);
+            if started >= week_begin {
+                week += session.duration();
+            }
+            if started == today_begin {
+                add_today(&session.topic, session.duration());
+            }
+        }
+        today.sort_by(|(_, a), (_, b)| b.as_secs().cmp(&a.as_secs()));
+
+        Status {
+            running,
+            duration,
+            today,
+            week,
+            actions: data.content.actions.iter().cloned().collect(),
+            subjects: data.content.subjects.iter().cloned().collect(),
+        }
+    }
+
+    /// The running topic as "action subject", empty if no session is running.
+    pub fn label(&self) -> String {
+        self.running.as_ref().map_or_else(String::new, topic_label)
+    }
+
+    pub fn today_total(&self) -> SpentTime {
+        self.today
+            .iter()
+            .fold(SpentTime::default(), |sum, (_, time)| sum + *time)
+    }
+}
+
+fn topic_label(topic: &Topic) -> String {
+    format!("{} {}", topic.action.name, topic.subject.name)
+}
+
+/// Shared between the window and the thread serving the endpoint.
+#[derive(Clone)]
+pub struct Control {
+    sink: ExtEventSink,
+    status: Arc<Mutex<Status>>,
+}
+
+impl Control {
+    pub fn status(&self) -> Status {
+        self.status.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    /// Starts a session on the named topic, ending the running one.
+    pub fn start(&self, action: &str, subject: &str) -> Result<(), String> {
+        let status = self.status();
+        let topic = Topic {
+            action: collation::find(&status.actions, action)
+                .cloned()
+                .ok_or_else(|| format!("No action is named {}.", action))?,
+            subject: collation::find(&status.subjects, subject)
+                .cloned()
+                .ok_or_else(|| format!("No subject is named {}.", subject))?,
+        };
+        self.submit(tracker::START_TOPIC, topic)
+    }
+
+    /// Brings the window to the front, for a second instance being launched.
+    pub fn show(&self) -> Result<(), String> {
+        self.submit(tray::SHOW, ())
+    }
+
+    /// Ends the running session, if any.
+    pub fn stop(&self) -> Result<(), String> {
+        if self.status().running.is_none() {
+            return Ok(());
+        }
+        self.submit(tracker::END_SESSION, ())
+    }
+
+    fn submit<T: Send + 'static>(&self, selector: Selector<T>, payload: T) -> Result<(), String> {
+        self.sink
+            .submit_command(selector, Box::new(payload), Target::Global)
+            .map_err(|err| format!("Cannot reach the window: {}", err))
+    }
+}
+
+#[cfg(target_os = "linux")]
+use self::bus::serve;
+#[cfg(target_os = "linux")]
+pub use self::bus::show_running;
+#[cfg(all(unix, not(target_os = "linux")))]
+use self::socket::serve;
+#[cfg(all(unix, not(target_os = "linux")))]
+pub use self::socket::show_running;
+
+#[cfg(windows)]
+use self::pipe::serve;
+#[cfg(windows)]
+pub use self::pipe::show_running;
+
+#[cfg(not(any(unix, windows)))]
+fn serve(_control: Control) {
+    log::info!("The control endpoint is not available on this platform.");
+}
+
+/// Asks the instance serving the endpoint to show its window.
+#[cfg(not(any(unix, windows)))]
+pub fn show_running() -> Result<(), Box<dyn std::error::Error>> {
+    Err("The control endpoint is not available on this platform.".into())
+}
+
+/// Answers the commands of a socket or pipe connection, one per line with
+/// its arguments separated by tabs, as listed for the `socket` module.
+#[cfg(not(target_os = "linux"))]
+fn answer(control: &Control, reader: impl BufRead, mut writer: impl Write) -> io::Result<()> {
+    for line in reader.lines() {
+        let line = line?;
+        let args: Vec<&str> = line.trim_end_matches('\r').split('\t').collect();
+        let result = match args.as_slice() {
+            ["start", action, subject] => control.start(action, subject).map(|_| "ok".into()),
+            ["stop"] => control.stop().map(|_| "ok".into()),
+            ["show"] => control.show().map(|_| "ok".into()),
+            ["status"] => {
+                let status = control.status();
+                Ok(match status.running {
+                    Some(_) => format!("running\t{}", status.label()),
+                    None => "idle".into(),
+                })
+            }
+            ["duration"] => Ok(control.status().duration.as_secs().to_string()),
+            _ => Err(format!("Unknown command {}.", line)),
+        };
+        match result {
+            Ok(reply) => writeln!(writer, "{}", reply)?,
+            Err(err) => writeln!(writer, "error\t{}", err)?,
+        }
+    }
+    Ok(())
+}
+
+/// Sends `show` over a socket or pipe connection and waits for the answer.
+#[cfg(not(target_os = "linux"))]
+fn request_show(mut reader: impl BufRead, mut writer: impl Write) -> Result<(), Box<dyn Error>> {
+    writeln!(writer, "show")?;
+    let mut reply = String::new();
+    reader.read_line(&mut reply)?;
+    match reply.trim_end() {
+        "ok" => Ok(()),
+        reply => Err(reply.trim_start_matches("error\t").to_string().into()),
+    }
+}
+
+/// Serves the endpoint on a thread of its own and keeps its status up to date.
+#[derive(Default)]
+pub struct IpcController {
+    status: Option<Arc<Mutex<Status>>>,
+    control: Option<Control>,
+    /// Port the dashboard is served on, it keeps it until the application is restarted.
+    dashboard: Option<u16>,
+}
+
+impl IpcController {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn serve_dashboard(&mut self, port: &str) {
+        let port: u16 = match port.trim() {
+            "" => {
+                if self.dashboard.is_some() {
+                    log::info!("The dashboard stops once restarted.");
+                }
+                return;
+            }
+            port => match port.parse() {
+                Ok(port) => port,
+                Err(_) => {
+                    log::warn!("Invalid dashboard port {}.", port);
+                    return;
+                }
+            },
+        };
+        match (self.dashboard, &self.control) {
+            (None, Some(control)) => {
+                let control = control.clone();
+                std::thread::spawn(move || http::serve(port, control));
+                self.dashboard = Some(port);
+            }
+            (Some(served), _) if served != port => {
+                log::info!("The dashboard moves to port {} once restarted.", port)
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<W: Widget<AppState>> Controller<AppState, W> for IpcController {
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &AppState,
+        env: &Env,
+    ) {
+        if let (LifeCycle::WidgetAdded, false) = (event, data.safe_mode) {
+            let status = Arc::new(Mutex::new(Status::of(data)));
+            let control = Control {
+                sink: ctx.get_external_handle(),
+                status: status.clone(),
+            };
+            let served = control.clone();
+            std::thread::spawn(move || serve(served));
+            self.status = Some(status);
+            self.control = Some(control);
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(
+        &mut self,
+        child: &mut W,
+        ctx: &mut UpdateCtx,
+        old_data: &AppState,
+        data: &AppState,
+        env: &Env,
+    ) {
+        let changed = !old_data.active.same(&data.active)
+            || !old_data.setup.same(&data.setup)
+            || !old_data.content.actions.same(&data.content.actions)
+            || !old_data.content.subjects.same(&data.content.subjects);
+        if let (Some(status), true) = (&self.status, changed) {
+            if let Ok(mut status) = status.lock() {
+                *status = Status::of(data);
+            }
+        }
+        let port = &data.content.settings.dashboard_port;
+        if old_data.content.settings.dashboard_port != *port && !data.safe_mode {
+            self.serve_dashboard(port);
+        }
+        child.update(ctx, old_data, data, env)
+    }
+}
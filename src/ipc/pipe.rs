@@ -0,0 +1,93 @@
+//! The endpoint as the named pipe `\\.\pipe\zeitig`, taking the same
+//! commands as the Unix socket offered on other platforms.
+
+use super::Control;
+use std::{
+    error::Error,
+    ffi::OsStr,
+    fs::{File, OpenOptions},
+    io::{self, BufReader},
+    iter,
+    os::windows::{ffi::OsStrExt, io::FromRawHandle},
+    ptr,
+};
+use winapi::{
+    shared::winerror::ERROR_PIPE_CONNECTED,
+    um::{
+        handleapi::INVALID_HANDLE_VALUE,
+        namedpipeapi::{ConnectNamedPipe, CreateNamedPipeW},
+        winbase::{
+            FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_DUPLEX, PIPE_READMODE_BYTE,
+            PIPE_REJECT_REMOTE_CLIENTS, PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+        },
+    },
+};
+
+const PIPE: &str = r"\\.\pipe\zeitig";
+const BUFFER_SIZE: u32 = 4096;
+
+/// Creates another instance of the pipe and waits for a client to connect.
+/// Only the `first` one fails if another application already owns the pipe.
+fn accept(first: bool) -> io::Result<File> {
+    let name: Vec<u16> = OsStr::new(PIPE)
+        .encode_wide()
+        .chain(iter::once(0))
+        .collect();
+    let open_mode = match first {
+        true => PIPE_ACCESS_DUPLEX | FILE_FLAG_FIRST_PIPE_INSTANCE,
+        false => PIPE_ACCESS_DUPLEX,
+    };
+    let handle = unsafe {
+        CreateNamedPipeW(
+            name.as_ptr(),
+            open_mode,
+            PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+            PIPE_UNLIMITED_INSTANCES,
+            BUFFER_SIZE,
+            BUFFER_SIZE,
+            0,
+            ptr::null_mut(),
+        )
+    };
+    if handle == INVALID_HANDLE_VALUE {
+        return Err(io::Error::last_os_error());
+    }
+    // Owns the handle from here on, so it is closed on failure as well.
+    let pipe = unsafe { File::from_raw_handle(handle as _) };
+    if unsafe { ConnectNamedPipe(handle, ptr::null_mut()) } == 0 {
+        let err = io::Error::last_os_error();
+        // The client was faster and connected before it was waited for.
+        if err.raw_os_error() != Some(ERROR_PIPE_CONNECTED as i32) {
+            return Err(err);
+        }
+    }
+    Ok(pipe)
+}
+
+pub fn serve(control: Control) {
+    let mut first = true;
+    loop {
+        match accept(first) {
+            Ok(pipe) => {
+                first = false;
+                let control = control.clone();
+                std::thread::spawn(move || {
+                    if let Err(err) = super::answer(&control, BufReader::new(&pipe), &pipe) {
+                        log::warn!("Control pipe connection failed: {}", err);
+                    }
+                });
+            }
+            Err(err) if first => {
+                log::warn!("Cannot offer the control pipe: {}", err);
+                return;
+            }
+            Err(err) => log::warn!("Cannot accept a control connection: {}", err),
+        }
+    }
+}
+
+/// Asks the instance listening on the pipe to show its window.
+pub fn show_running() -> Result<(), Box<dyn Error>> {
+    let pipe = OpenOptions::new().read(true).write(true).open(PIPE)?;
+    super::request_show(BufReader::new(&pipe), &pipe)
+}
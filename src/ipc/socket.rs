@@ -0,0 +1,60 @@
+//! The endpoint as a Unix socket next to the data file, taking one command
+//! per line with its arguments separated by tabs:
+//!
+//! - `start<TAB>action<TAB>subject` answers `ok`
+//! - `stop` answers `ok`
+//! - `show` brings the window to the front and answers `ok`
+//! - `status` answers `running<TAB>topic` or `idle`
+//! - `duration` answers the seconds of the running session
+//!
+//! Failures are answered with `error<TAB>message`.
+
+use super::Control;
+use crate::state::paths;
+use std::{
+    error::Error,
+    io::BufReader,
+    os::unix::net::{UnixListener, UnixStream},
+    path::PathBuf,
+};
+
+fn socket_file() -> PathBuf {
+    match paths::data_file().parent() {
+        Some(parent) => parent.join("zeitig.sock"),
+        None => PathBuf::from("zeitig.sock"),
+    }
+}
+
+pub fn serve(control: Control) {
+    let path = socket_file();
+    // Left behind if the application was not closed properly.
+    if UnixStream::connect(&path).is_err() {
+        let _ = std::fs::remove_file(&path);
+    }
+    let listener = match UnixListener::bind(&path) {
+        Ok(listener) => listener,
+        Err(err) => {
+            log::warn!("Cannot offer the control socket: {}", err);
+            return;
+        }
+    };
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let control = control.clone();
+                std::thread::spawn(move || {
+                    if let Err(err) = super::answer(&control, BufReader::new(&stream), &stream) {
+                        log::warn!("Control socket connection failed: {}", err);
+                    }
+                });
+            }
+            Err(err) => log::warn!("Cannot accept a control connection: {}", err),
+        }
+    }
+}
+
+/// Asks the instance listening on the socket to show its window.
+pub fn show_running() -> Result<(), Box<dyn Error>> {
+    let stream = UnixStream::connect(socket_file())?;
+    super::request_show(BufReader::new(&stream), &stream)
+}
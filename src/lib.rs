@@ -0,0 +1,13 @@
+//! State and persistence of Zeitig, shared by the application and its benchmarks.
+//!
+//! Other frontends track time through [`engine`]. It follows semantic
+//! versioning: while the version is below 1.0, a breaking change to it raises
+//! the minor version and everything else only the patch version. [`state`]
+//! is shaped by the window and may change in any release.
+//!
+//! The window is built with the default feature `gui`. Without it the library
+//! does not depend on druid, `cargo build --no-default-features --features tui`
+//! builds the terminal frontend alone.
+
+pub mod engine;
+pub mod state;
@@ -1,32 +1,97 @@
 use druid::{AppLauncher, WindowDesc};
-use std::error::Error;
+use std::{error::Error, sync::Arc};
+use zeitig::state;
 
+mod cli;
 mod controller;
-mod state;
+mod instance;
+mod ipc;
+mod platform;
+mod tray;
 mod ui;
 mod widgets;
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let window = WindowDesc::new(ui::tracker)
-        .title("Zeitig")
-        .window_size((300.0, 400.0));
+    let mut args = std::env::args().skip(1).peekable();
+    if args.peek().map(String::as_str) == Some("--profile") {
+        args.next();
+        let profile = args
+            .next()
+            .ok_or("Usage: zeitig --profile <name> [command]")?;
+        state::paths::set_profile(Some(&profile))?;
+    }
+    let safe_mode = args.peek().map(String::as_str) == Some("--safe-mode");
+    if safe_mode {
+        args.next();
+    }
+    if args.peek().map(String::as_str) == Some("--read-only") {
+        args.next();
+        let file = args
+            .next()
+            .map(std::path::PathBuf::from)
+            .unwrap_or_else(state::paths::data_file);
+        if !file.exists() {
+            return Err(format!("{} does not exist.", file.display()).into());
+        }
+        state::paths::set_read_only(file);
+        AppLauncher::with_window(ui::viewer::window())
+            .use_simple_logger()
+            .launch(state::AppState {
+                read_only: true,
+                ..state::AppState::default()
+            })?;
+        return Ok(());
+    }
+    match args.next().as_deref() {
+        Some("verify") => return verify(args.next()),
+        Some(command) => return cli::run(command, &args.collect::<Vec<_>>()),
+        None => {}
+    }
 
-    use state::backend::Backend;
-    let mut backend = state::backend::Sqlite::new(state::paths::data_file())?;
-    backend.setup()?;
-    let content = backend.load_content()?;
-    let history = backend.load_history(&content)?;
-    backend.close()?;
-    let state = state::AppState {
-        content,
-        history,
-        setup: state::Setup::default(),
-        active: None,
+    let _instance = match instance::acquire()? {
+        Some(instance) => instance,
+        None => return Ok(()),
     };
 
-    AppLauncher::with_window(window)
+    let title = match safe_mode {
+        true => format!("{} (safe mode)", ui::title()),
+        false => ui::title(),
+    };
+    let window = WindowDesc::new(ui::tracker)
+        .title(title)
+        .menu(ui::menu())
+        .window_size((300.0, 400.0));
+
+    // The backend loads content and history once the window is up.
+    let launcher = AppLauncher::with_window(window)
         .use_simple_logger()
-        .launch(state)?;
+        .configure_env(|env, _| {
+            let settings = state::Settings::default();
+            env.set(widgets::NAME_LENGTH, settings.name_length);
+            env.set(ui::MONEY_LOCALE, Arc::from(settings.money_locale.as_str()));
+            env.set(ui::CURRENCY, Arc::from(settings.currency.as_str()));
+        });
+    if safe_mode {
+        log::set_max_level(log::LevelFilter::Trace);
+        log::info!(
+            "Starting in safe mode without the tray, shortcut, control endpoint, dashboard, speech command and system watchers."
+        );
+    }
+    launcher.launch(state::AppState {
+        safe_mode,
+        ..state::AppState::default()
+    })?;
+
+    Ok(())
+}
 
+/// Checks an exported file against its detached signature.
+fn verify(path: Option<String>) -> Result<(), Box<dyn Error>> {
+    let path = path.ok_or("Usage: zeitig verify <file>")?;
+    let verified = state::signing::verify_file(std::path::Path::new(&path))?;
+    println!("Valid signature by {}", verified.public_key);
+    if verified.own_key {
+        println!("The file was signed with the key of this profile.");
+    }
     Ok(())
 }
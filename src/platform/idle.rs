@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+/// Time since the last keyboard or mouse input, if the platform can tell.
+#[cfg(target_os = "linux")]
+pub fn idle_time() -> Option<Duration> {
+    // Provided by the xprintidle package, reports milliseconds.
+    let output = std::process::Command::new("xprintidle").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let millis = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .ok()?;
+    Some(Duration::from_millis(millis))
+}
+
+/// Time since the last keyboard or mouse input, if the platform can tell.
+#[cfg(target_os = "macos")]
+pub fn idle_time() -> Option<Duration> {
+    // The HID system reports nanoseconds as `"HIDIdleTime" = 1234`.
+    let output = std::process::Command::new("ioreg")
+        .args(&["-c", "IOHIDSystem", "-d", "4"])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let line = text.lines().find(|line| line.contains("\"HIDIdleTime\""))?;
+    let nanos = line.rsplit('=').next()?.trim().parse().ok()?;
+    Some(Duration::from_nanos(nanos))
+}
+
+/// Time since the last keyboard or mouse input, if the platform can tell.
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn idle_time() -> Option<Duration> {
+    None
+}
@@ -0,0 +1,43 @@
+/// Desktops implement either the freedesktop or the GNOME interface.
+#[cfg(target_os = "linux")]
+pub(super) const SCREENSAVERS: &[(&str, &str)] = &[
+    (
+        "org.freedesktop.ScreenSaver",
+        "/org/freedesktop/ScreenSaver",
+    ),
+    ("org.gnome.ScreenSaver", "/org/gnome/ScreenSaver"),
+];
+
+/// Whether the screen is locked, if the platform can tell.
+#[cfg(target_os = "linux")]
+pub fn screen_locked() -> Option<bool> {
+    use dbus::blocking::Connection;
+    use std::time::Duration;
+
+    let connection = Connection::new_session().ok()?;
+    SCREENSAVERS.iter().find_map(|(service, path)| {
+        connection
+            .with_proxy(*service, *path, Duration::from_millis(500))
+            .method_call(*service, "GetActive", ())
+            .ok()
+            .map(|(active,): (bool,)| active)
+    })
+}
+
+/// Whether the screen is locked, if the platform can tell.
+#[cfg(target_os = "macos")]
+pub fn screen_locked() -> Option<bool> {
+    // The session dictionary only has this key while the screen is locked.
+    let output = std::process::Command::new("ioreg")
+        .args(&["-n", "Root", "-d", "1"])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    Some(text.contains("\"CGSSessionScreenIsLocked\"=Yes"))
+}
+
+/// Whether the screen is locked, if the platform can tell.
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn screen_locked() -> Option<bool> {
+    None
+}
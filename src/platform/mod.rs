@@ -0,0 +1,17 @@
+mod idle;
+pub use idle::idle_time;
+
+mod lock;
+pub use lock::screen_locked;
+
+mod processes;
+pub use processes::watch as watch_launches;
+
+mod system_events;
+pub use system_events::{watch as watch_presence, Presence};
+
+mod notify;
+pub use notify::{announce, ask, notify};
+
+mod open;
+pub use open::open_url;
@@ -0,0 +1,97 @@
+use std::io;
+
+/// Shows a desktop notification, failures are only logged.
+pub fn notify(summary: &str, body: &str) {
+    if let Err(err) = send(summary, body) {
+        log::warn!("Cannot show notification: {}", err);
+    }
+}
+
+/// Shows `text` in a notification, which screen readers read out, and speaks
+/// it with `command` too if one is configured, like `espeak` or `say`.
+pub fn announce(text: &str, command: &str) {
+    notify("Zeitig", text);
+    let mut args = command.split_whitespace();
+    if let Some(program) = args.next() {
+        let spawned = std::process::Command::new(program)
+            .args(args)
+            .arg(text)
+            .spawn();
+        if let Err(err) = spawned {
+            log::warn!("Cannot announce with {}: {}", program, err);
+        }
+    }
+}
+
+/// Shows a notification offering `actions`, given as id and label, and waits
+/// for one to be chosen. Without support for actions it is shown without them.
+pub fn ask(summary: &str, body: &str, actions: &[(&str, &str)]) -> Option<String> {
+    match send_actions(summary, body, actions) {
+        Ok(chosen) => chosen,
+        Err(err) => {
+            log::info!("Cannot offer actions in notifications: {}", err);
+            notify(summary, body);
+            None
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn send_actions(summary: &str, body: &str, actions: &[(&str, &str)]) -> io::Result<Option<String>> {
+    // Older versions of notify-send do not know --action and fail.
+    let mut command = std::process::Command::new("notify-send");
+    command.args(&["--app-name", "Zeitig"]);
+    for (id, label) in actions {
+        command.arg(format!("--action={}={}", id, label));
+    }
+    let output = command.args(&[summary, body]).output()?;
+    if !output.status.success() {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    // Prints the id of the chosen action, nothing if dismissed.
+    let chosen = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok(Some(chosen).filter(|chosen| !chosen.is_empty()))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn send_actions(
+    _summary: &str,
+    _body: &str,
+    _actions: &[(&str, &str)],
+) -> io::Result<Option<String>> {
+    Err(io::Error::new(
+        io::ErrorKind::Other,
+        "not supported on this platform",
+    ))
+}
+
+#[cfg(target_os = "linux")]
+fn send(summary: &str, body: &str) -> io::Result<()> {
+    // Provided by libnotify, spawned so the interface does not wait for it.
+    std::process::Command::new("notify-send")
+        .args(&["--app-name", "Zeitig", summary, body])
+        .spawn()
+        .map(|_| ())
+}
+
+#[cfg(target_os = "macos")]
+fn send(summary: &str, body: &str) -> io::Result<()> {
+    let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+    let script = format!(
+        "display notification {} with title {}",
+        quote(body),
+        quote(summary)
+    );
+    std::process::Command::new("osascript")
+        .args(&["-e", &script])
+        .spawn()
+        .map(|_| ())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn send(_summary: &str, _body: &str) -> io::Result<()> {
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use std::{io, process::Command};
+
+/// Opens the link in the default browser, failures are only logged.
+pub fn open_url(url: &str) {
+    if let Err(err) = spawn(url) {
+        log::warn!("Cannot open {}: {}", url, err);
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn spawn(url: &str) -> io::Result<()> {
+    Command::new("xdg-open").arg(url).spawn().map(|_| ())
+}
+
+#[cfg(target_os = "macos")]
+fn spawn(url: &str) -> io::Result<()> {
+    Command::new("open").arg(url).spawn().map(|_| ())
+}
+
+#[cfg(target_os = "windows")]
+fn spawn(url: &str) -> io::Result<()> {
+    // The empty argument is the window title `start` would take the url for.
+    Command::new("cmd")
+        .args(&["/C", "start", "", url])
+        .spawn()
+        .map(|_| ())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn spawn(_url: &str) -> io::Result<()> {
+    Ok(())
+}
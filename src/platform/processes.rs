@@ -0,0 +1,76 @@
+//! Notices applications being launched by comparing the running processes
+//! from time to time, as there is no common way to be told about them.
+
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+/// Time between two looks at the running processes.
+const POLL: Duration = Duration::from_secs(5);
+
+/// Names of the running processes, if the platform can tell.
+#[cfg(target_os = "linux")]
+fn running() -> Option<HashSet<String>> {
+    let entries = std::fs::read_dir("/proc").ok()?;
+    let names = entries.filter_map(|entry| {
+        let path = entry.ok()?.path();
+        // Only the numbered folders are processes.
+        path.file_name()?.to_str()?.parse::<u32>().ok()?;
+        let name = std::fs::read_to_string(path.join("comm")).ok()?;
+        Some(name.trim().to_string())
+    });
+    Some(names.collect())
+}
+
+/// Names of the running processes, if the platform can tell.
+#[cfg(target_os = "macos")]
+fn running() -> Option<HashSet<String>> {
+    // Only the name of the executable, without its path and arguments.
+    let output = std::process::Command::new("ps")
+        .args(&["-axco", "comm="])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    Some(text.lines().map(|line| line.trim().to_string()).collect())
+}
+
+/// Names of the running processes, if the platform can tell.
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn running() -> Option<HashSet<String>> {
+    None
+}
+
+/// Calls `report` on a thread of its own with the name of every process
+/// launched while `enabled` is set. Those already running when it is set are
+/// not reported, and neither are further instances of a running one.
+pub fn watch(enabled: Arc<AtomicBool>, mut report: impl FnMut(String) + Send + 'static) {
+    thread::spawn(move || {
+        let mut known: Option<HashSet<String>> = None;
+        loop {
+            thread::sleep(POLL);
+            if !enabled.load(Ordering::Relaxed) {
+                known = None;
+                continue;
+            }
+            let running = match running() {
+                Some(running) => running,
+                None => return log::warn!("Cannot list the running processes."),
+            };
+            if let Some(known) = &known {
+                for name in running.difference(known) {
+                    report(name.clone());
+                }
+            }
+            known = Some(running);
+        }
+    });
+}
@@ -0,0 +1,109 @@
+//! Notices the screen being locked and the system going to sleep, which both
+//! mean the user is away from the computer. Linux tells about both over
+//! D-Bus, elsewhere the lock is looked at regularly and sleeping is noticed
+//! by the clock jumping ahead.
+
+use std::{thread, time::Duration};
+use time::OffsetDateTime;
+
+/// Time between two looks when the system does not tell about changes.
+const POLL: Duration = Duration::from_secs(10);
+/// A look this late means the system was asleep in between.
+const SLEEP_GAP: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Copy)]
+pub enum Presence {
+    /// Locked or asleep since the given time.
+    Away(OffsetDateTime),
+    /// Unlocked and awake again.
+    Back,
+}
+
+enum Change {
+    Locked(bool),
+    Asleep(bool),
+}
+
+struct State<F> {
+    locked: bool,
+    asleep: bool,
+    report: F,
+}
+
+impl<F: FnMut(Presence)> State<F> {
+    /// Reports only going away and coming back, not locking while asleep and the like.
+    fn change(&mut self, change: Change, at: OffsetDateTime) {
+        let was_away = self.locked || self.asleep;
+        match change {
+            Change::Locked(locked) => self.locked = locked,
+            Change::Asleep(asleep) => self.asleep = asleep,
+        }
+        match (was_away, self.locked || self.asleep) {
+            (false, true) => (self.report)(Presence::Away(at)),
+            (true, false) => (self.report)(Presence::Back),
+            _ => {}
+        }
+    }
+}
+
+/// Calls `report` on a thread of its own whenever the user goes away or comes back.
+pub fn watch(report: impl FnMut(Presence) + Send + 'static) {
+    thread::spawn(move || {
+        let mut state = State {
+            locked: false,
+            asleep: false,
+            report,
+        };
+        #[cfg(target_os = "linux")]
+        if let Err(err) = listen(&mut state) {
+            log::warn!("Cannot listen for the screen lock and sleep: {}", err);
+        }
+        poll(&mut state);
+    });
+}
+
+fn poll<F: FnMut(Presence)>(state: &mut State<F>) {
+    let mut last = OffsetDateTime::now_utc();
+    loop {
+        thread::sleep(POLL);
+        let now = OffsetDateTime::now_utc();
+        if now - last > POLL + SLEEP_GAP {
+            state.change(Change::Asleep(true), last);
+            state.change(Change::Asleep(false), now);
+        }
+        if let Some(locked) = super::screen_locked() {
+            state.change(Change::Locked(locked), now);
+        }
+        last = now;
+    }
+}
+
+/// Follows the signals of logind and the screensaver, returning only once they fail.
+#[cfg(target_os = "linux")]
+fn listen<F: FnMut(Presence)>(state: &mut State<F>) -> Result<(), Box<dyn std::error::Error>> {
+    use dbus::{blocking::Connection, message::MatchRule};
+    use std::sync::mpsc;
+
+    let (sender, changes) = mpsc::channel();
+    let system = Connection::new_system()?;
+    let sleeping = sender.clone();
+    system.add_match(
+        MatchRule::new_signal("org.freedesktop.login1.Manager", "PrepareForSleep"),
+        move |(asleep,): (bool,), _, _| sleeping.send(Change::Asleep(asleep)).is_ok(),
+    )?;
+    let session = Connection::new_session()?;
+    for (interface, _) in super::lock::SCREENSAVERS {
+        let locking = sender.clone();
+        session.add_match(
+            MatchRule::new_signal(*interface, "ActiveChanged"),
+            move |(locked,): (bool,), _, _| locking.send(Change::Locked(locked)).is_ok(),
+        )?;
+    }
+    loop {
+        system.process(POLL / 20)?;
+        session.process(POLL / 20)?;
+        for change in changes.try_iter() {
+            state.change(change, OffsetDateTime::now_utc());
+        }
+    }
+}
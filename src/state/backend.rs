@@ -1,6 +1,17 @@
-use super::{Action, Content, History, Session, Subject, Topic};
-use crate::state::SpentTime;
-use std::error::Error;
+use super::{
+    backup::Backup,
+    cleanup::Cleanup,
+    goal::Goal,
+    history::{Merge, SessionQuery},
+    launch::LaunchRule,
+    ledger::{Adjustment, DayOff},
+    order::ListOrder,
+    Action, Alias, ChecklistItem, Content, Context, History, Running, Session, Settings, Subject,
+    Tag, Topic,
+};
+use crate::state::{Date, DateTime, SpentTime};
+use im::Vector;
+use std::{error::Error, path::Path, sync::Arc};
 
 mod sqlite;
 pub use sqlite::Sqlite;
@@ -11,10 +22,199 @@ pub trait Backend {
 
     fn load_content(&mut self) -> Result<Content, Box<dyn Error>>;
     fn load_history(&mut self, content: &Content) -> Result<History, Box<dyn Error>>;
+    /// Loads up to `limit` of the latest sessions that started before `before`.
+    fn load_sessions(
+        &mut self,
+        content: &Content,
+        before: Option<&DateTime>,
+        limit: usize,
+    ) -> Result<History, Box<dyn Error>>;
+    /// Loads every session matching `query`.
+    fn find_sessions(
+        &mut self,
+        content: &Content,
+        query: &SessionQuery,
+    ) -> Result<History, Box<dyn Error>>;
 
     fn create_action(&mut self, name: &str) -> Result<Action, Box<dyn Error>>;
     fn create_subject(&mut self, name: &str) -> Result<Subject, Box<dyn Error>>;
+    fn create_context(&mut self, name: &str) -> Result<Context, Box<dyn Error>>;
+    fn create_tag(&mut self, name: &str) -> Result<Tag, Box<dyn Error>>;
+
+    fn set_action_archived(
+        &mut self,
+        action: &Action,
+        archived: bool,
+    ) -> Result<(), Box<dyn Error>>;
+    fn set_subject_archived(
+        &mut self,
+        subject: &Subject,
+        archived: bool,
+    ) -> Result<(), Box<dyn Error>>;
+    /// Temporary entries are archived once they have not been used for a while.
+    fn set_action_temporary(
+        &mut self,
+        action: &Action,
+        temporary: bool,
+    ) -> Result<(), Box<dyn Error>>;
+    fn set_subject_temporary(
+        &mut self,
+        subject: &Subject,
+        temporary: bool,
+    ) -> Result<(), Box<dyn Error>>;
+    /// Listed actions and subjects whose latest session ended before `before`,
+    /// entries that were never used are left to be deleted instead.
+    fn unused_since(
+        &mut self,
+        content: &Content,
+        before: &DateTime,
+    ) -> Result<Cleanup, Box<dyn Error>>;
+    /// Stores the name of `action`, identifying it by its id.
+    fn rename_action(&mut self, action: &Action) -> Result<(), Box<dyn Error>>;
+    /// Stores the name of `subject`, identifying it by its id.
+    fn rename_subject(&mut self, subject: &Subject) -> Result<(), Box<dyn Error>>;
+    /// Stores the issue link template of `subject`, identifying it by its id.
+    fn set_issue_url(&mut self, subject: &Subject) -> Result<(), Box<dyn Error>>;
+    /// Stores the estimate of `subject`, identifying it by its id.
+    fn set_estimate(&mut self, subject: &Subject) -> Result<(), Box<dyn Error>>;
+    /// Stores the hourly rate of `subject`, identifying it by its id.
+    fn set_rate(&mut self, subject: &Subject) -> Result<(), Box<dyn Error>>;
+    /// Stores the subject `subject` is nested in, identifying it by its id.
+    fn set_parent(&mut self, subject: &Subject) -> Result<(), Box<dyn Error>>;
+    /// Stores the final report of `subject`, identifying it by its id.
+    fn set_final_report(&mut self, subject: &Subject) -> Result<(), Box<dyn Error>>;
+    /// Stores how idle time is handled for `subject`, identifying it by its id.
+    fn set_idle_rule(&mut self, subject: &Subject) -> Result<(), Box<dyn Error>>;
+    /// Stores the color of `action`, identifying it by its id.
+    fn set_action_color(&mut self, action: &Action) -> Result<(), Box<dyn Error>>;
+    /// Stores the color of `subject`, identifying it by its id.
+    fn set_subject_color(&mut self, subject: &Subject) -> Result<(), Box<dyn Error>>;
+    /// Replaces the arranged order and the favorites of the actions.
+    fn set_action_order(&mut self, order: &ListOrder) -> Result<(), Box<dyn Error>>;
+    fn set_subject_order(&mut self, order: &ListOrder) -> Result<(), Box<dyn Error>>;
+    /// Replaces the pinned topics, keeping their order.
+    fn set_pinned(&mut self, pinned: &Vector<Topic>) -> Result<(), Box<dyn Error>>;
+    /// Replaces the stored launch rules.
+    fn set_launch_rules(&mut self, rules: &Vector<LaunchRule>) -> Result<(), Box<dyn Error>>;
+    /// Locks the sessions starting before `before` against changes, or unlocks
+    /// all of them for `None`. Both are recorded in the audit log.
+    fn set_locked_before(&mut self, before: Option<Date>) -> Result<(), Box<dyn Error>>;
+    /// Replaces the goals of all topics.
+    fn set_goals(&mut self, goals: &Vector<Goal>) -> Result<(), Box<dyn Error>>;
+    /// Replaces the days off counted in the overtime balance.
+    fn set_days_off(&mut self, days_off: &Vector<DayOff>) -> Result<(), Box<dyn Error>>;
+    /// Replaces the adjustments of the overtime balance.
+    fn set_adjustments(&mut self, adjustments: &Vector<Adjustment>) -> Result<(), Box<dyn Error>>;
+    /// Adds the entries and sessions of `backup` that are missing, matching them by name.
+    /// Returns the number of sessions added.
+    fn import_backup(&mut self, backup: &Backup) -> Result<usize, Box<dyn Error>>;
+    /// Fails if the action is referenced by recorded sessions.
+    fn delete_action(&mut self, action: &Action) -> Result<(), Box<dyn Error>>;
+    /// Fails if the subject is referenced by recorded sessions.
+    fn delete_subject(&mut self, subject: &Subject) -> Result<(), Box<dyn Error>>;
+    /// Moves the sessions, time, pins and goals of `merge.from` over to
+    /// `merge.into` and deletes it.
+    fn merge_actions(&mut self, merge: &Merge<Action>) -> Result<(), Box<dyn Error>>;
+    /// Like `merge_actions`, checklists and nested subjects move along.
+    fn merge_subjects(&mut self, merge: &Merge<Subject>) -> Result<(), Box<dyn Error>>;
+    /// Creates `merge.from` again by name and moves its sessions and time back.
+    fn split_actions(&mut self, merge: &Merge<Action>) -> Result<(), Box<dyn Error>>;
+    fn split_subjects(&mut self, merge: &Merge<Subject>) -> Result<(), Box<dyn Error>>;
+
+    fn set_category(
+        &mut self,
+        subject: &Subject,
+        category: Option<&str>,
+    ) -> Result<(), Box<dyn Error>>;
+
+    fn create_checklist_item(
+        &mut self,
+        subject: &Subject,
+        text: &str,
+    ) -> Result<ChecklistItem, Box<dyn Error>>;
+    fn set_checklist_item_done(&mut self, id: usize, done: bool) -> Result<(), Box<dyn Error>>;
+
+    fn add_alias(&mut self, alias: &Alias) -> Result<(), Box<dyn Error>>;
+    fn delete_alias(&mut self, alias: &Alias) -> Result<(), Box<dyn Error>>;
 
     fn update_time(&mut self, topic: &Topic, time: &SpentTime) -> Result<(), Box<dyn Error>>;
+    /// Adds to the stored total rather than replacing it, so that time another
+    /// process recorded in the meantime is kept.
+    fn add_time(&mut self, topic: &Topic, time: &SpentTime) -> Result<(), Box<dyn Error>>;
     fn add_session(&mut self, session: &Session) -> Result<(), Box<dyn Error>>;
+    fn update_session(
+        &mut self,
+        original: &Session,
+        updated: &Session,
+    ) -> Result<(), Box<dyn Error>>;
+    fn delete_session(&mut self, session: &Session) -> Result<(), Box<dyn Error>>;
+    /// Stores the note of a session, which is not treated as an amendment.
+    fn set_note(&mut self, session: &Session) -> Result<(), Box<dyn Error>>;
+    /// Replaces the tags of a session, which is not treated as an amendment either.
+    fn set_tags(&mut self, session: &Session) -> Result<(), Box<dyn Error>>;
+    /// Brings back a deleted session, or drops the amendment that removed it.
+    fn restore_session(&mut self, session: &Session) -> Result<(), Box<dyn Error>>;
+    /// Records a correction of a session, `None` removes it.
+    fn amend_session(
+        &mut self,
+        original: &Session,
+        amended: Option<&Session>,
+    ) -> Result<(), Box<dyn Error>>;
+    /// Replaces each session by its changed version, recording amendments
+    /// if `strict`. Either all of them are replaced or none is.
+    fn replace_sessions(
+        &mut self,
+        replacements: &Vector<(Session, Session)>,
+        strict: bool,
+    ) -> Result<(), Box<dyn Error>>;
+    /// Like `replace_sessions`, moving each session to the subject of the
+    /// given name, which is created if there is none yet.
+    fn move_sessions(
+        &mut self,
+        content: &Content,
+        moves: &Vector<(Session, Arc<str>)>,
+        strict: bool,
+    ) -> Result<(), Box<dyn Error>>;
+
+    fn save_settings(&mut self, settings: &Settings) -> Result<(), Box<dyn Error>>;
+
+    /// The session as it is stored now, `None` if it was deleted.
+    fn reload_session(
+        &mut self,
+        content: &Content,
+        key: &DateTime,
+    ) -> Result<Option<Session>, Box<dyn Error>>;
+
+    /// The session started from the command line, if one is running.
+    fn load_running(&mut self, content: &Content) -> Result<Option<Running>, Box<dyn Error>>;
+    /// Replaces the session started from the command line, `None` clears it.
+    fn set_running(&mut self, running: Option<&Running>) -> Result<(), Box<dyn Error>>;
+
+    /// Damage the storage finds in itself, empty if it is intact.
+    fn check_integrity(&mut self) -> Result<Vec<String>, Box<dyn Error>>;
+    /// Rebuilds what can be derived from the stored data, like indices.
+    fn repair(&mut self) -> Result<(), Box<dyn Error>>;
+
+    /// Writes a consistent copy of all data to `path`.
+    fn snapshot(&mut self, path: &Path) -> Result<(), Box<dyn Error>>;
+    /// Replaces all data with a copy written by `snapshot`.
+    fn restore_snapshot(&mut self, path: &Path) -> Result<(), Box<dyn Error>>;
+}
+
+/// A session was changed or deleted by another instance since it was loaded,
+/// so changing it here would silently undo that.
+#[derive(Debug)]
+pub struct ChangedElsewhere {
+    pub deleted: bool,
 }
+
+impl std::fmt::Display for ChangedElsewhere {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.deleted {
+            true => write!(f, "The session was deleted elsewhere."),
+            false => write!(f, "The session was changed elsewhere."),
+        }
+    }
+}
+
+impl Error for ChangedElsewhere {}
@@ -1,31 +1,192 @@
-use super::{Action, Backend, Content, History, Session, Subject};
-use crate::state::{DateTime, SpentTime, Topic};
-use rusqlite::{params, Connection, NO_PARAMS};
-use std::{error::Error, path::Path, time::Duration};
+use super::{
+    Action, Backend, ChangedElsewhere, ChecklistItem, Content, Context, History, Running, Session,
+    Settings, Subject,
+};
+use crate::state::{
+    backup::{self, Backup},
+    cleanup::Cleanup,
+    goal::Goal,
+    history::{Merge, SessionQuery},
+    idle::IdleRule,
+    launch::LaunchRule,
+    ledger::{Adjustment, DayOff, DayOffKind},
+    order::ListOrder,
+    report::Period,
+    totals::{GroupKey, Grouping},
+    Alias, AliasTarget, Date, DateTime, Interruption, Interval, SpentTime, Tag, Topic,
+};
+use im::Vector;
+use rusqlite::{params, Connection, DatabaseName, OpenFlags, OptionalExtension, NO_PARAMS};
+use std::{collections::HashMap, error::Error, path::Path, sync::Arc, time::Duration};
 
 static SCHEMA: &str = include_str!("sqlite/schema.sql");
 
+/// Migrations from version `n` to `n + 1`, starting with version 1.
+static MIGRATIONS: &[&str] = &[
+    include_str!("sqlite/migrations/v2.sql"),
+    include_str!("sqlite/migrations/v3.sql"),
+    include_str!("sqlite/migrations/v4.sql"),
+    include_str!("sqlite/migrations/v5.sql"),
+    include_str!("sqlite/migrations/v6.sql"),
+    include_str!("sqlite/migrations/v7.sql"),
+    include_str!("sqlite/migrations/v8.sql"),
+    include_str!("sqlite/migrations/v9.sql"),
+    include_str!("sqlite/migrations/v10.sql"),
+    include_str!("sqlite/migrations/v11.sql"),
+    include_str!("sqlite/migrations/v12.sql"),
+    include_str!("sqlite/migrations/v13.sql"),
+    include_str!("sqlite/migrations/v14.sql"),
+    include_str!("sqlite/migrations/v15.sql"),
+    include_str!("sqlite/migrations/v16.sql"),
+    include_str!("sqlite/migrations/v17.sql"),
+    include_str!("sqlite/migrations/v18.sql"),
+    include_str!("sqlite/migrations/v19.sql"),
+    include_str!("sqlite/migrations/v20.sql"),
+    include_str!("sqlite/migrations/v21.sql"),
+    include_str!("sqlite/migrations/v22.sql"),
+    include_str!("sqlite/migrations/v23.sql"),
+    include_str!("sqlite/migrations/v24.sql"),
+    include_str!("sqlite/migrations/v25.sql"),
+    include_str!("sqlite/migrations/v26.sql"),
+    include_str!("sqlite/migrations/v27.sql"),
+    include_str!("sqlite/migrations/v28.sql"),
+    include_str!("sqlite/migrations/v29.sql"),
+    include_str!("sqlite/migrations/v30.sql"),
+];
+
+/// Version that introduced the `Totals` table, which is filled from the history.
+const TOTALS_VERSION: u32 = 6;
+
+/// Version that replaced the time paused in sessions with the intervals worked.
+const INTERVALS_VERSION: u32 = 22;
+
+/// Format of the locked billing period's end in the settings.
+static DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Days a temporary action or subject stays listed without being used.
+const TEMPORARY_DAYS: i64 = 30;
+
 pub struct Sqlite {
     connection: Connection,
+    /// Versions of the sessions as they were loaded or saved here, to notice
+    /// changes made by other instances in between.
+    versions: HashMap<time::OffsetDateTime, u32>,
 }
 
 impl Sqlite {
     pub fn new(path: impl AsRef<Path>) -> Result<Self, rusqlite::Error> {
         let connection = Connection::open(path)?;
+        // Changes are appended to a log and only then moved into the file, so a
+        // crash while writing loses at most the last transaction.
+        connection.query_row("pragma journal_mode = wal", NO_PARAMS, |_| Ok(()))?;
+        connection.execute_batch("pragma synchronous = normal")?;
+
+        Ok(Sqlite {
+            connection,
+            versions: HashMap::new(),
+        })
+    }
+
+    /// Opens a file only to look at it, without locking it or migrating it,
+    /// so it has to be of the current version. Changes made through it fail.
+    /// Changes still waiting in the write-ahead log of a file in use are not seen.
+    pub fn open_read_only(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref().to_string_lossy().replace('\\', "/");
+        let uri = format!(
+            "file:{}?immutable=1",
+            path.replace('%', "%25")
+                .replace('?', "%3f")
+                .replace('#', "%23")
+        );
+        let connection = Connection::open_with_flags(
+            uri,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_URI,
+        )?;
+        let mut sqlite = Sqlite {
+            connection,
+            versions: HashMap::new(),
+        };
+        let latest = MIGRATIONS.len() as u32 + 1;
+        match sqlite.query_version() {
+            Some(version) if version == latest => Ok(sqlite),
+            Some(version) if version < latest => Err("The file is from an older version of \
+                 Zeitig and has to be opened normally once to be upgraded."
+                .into()),
+            Some(_) => Err("The file is from a newer version of Zeitig.".into()),
+            None => Err("The file does not hold any data of Zeitig.".into()),
+        }
+    }
 
-        Ok(Sqlite { connection })
+    /// Fails with `ChangedElsewhere` if the session was changed or deleted
+    /// since this instance last saw it.
+    fn check_version(&self, key: &DateTime) -> Result<(), Box<dyn Error>> {
+        let known = match self.versions.get(&**key) {
+            Some(known) => *known,
+            None => return Ok(()),
+        };
+        match stored_version(&self.connection, key)? {
+            Some(stored) if stored == known => Ok(()),
+            stored => Err(ChangedElsewhere {
+                deleted: stored.is_none(),
+            }
+            .into()),
+        }
+    }
+
+    /// Takes note of the versions of replaced sessions, which are kept under
+    /// their new start unless they were amended.
+    fn remember_replaced(
+        &mut self,
+        replacements: &Vector<(Session, Session)>,
+        strict: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        for (original, changed) in replacements {
+            if strict {
+                self.remember_version(&original.key())?;
+            } else {
+                self.versions.remove(&*original.key());
+                self.remember_version(&changed.started)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn remember_version(&mut self, key: &DateTime) -> Result<(), Box<dyn Error>> {
+        match stored_version(&self.connection, key)? {
+            Some(version) => self.versions.insert(**key, version),
+            None => self.versions.remove(&**key),
+        };
+        Ok(())
     }
 
     pub fn setup(&mut self) -> Result<(), rusqlite::Error> {
-        let version = self.query_version();
+        let mut version = match self.query_version() {
+            Some(version) => version,
+            None => {
+                self.connection.execute_batch(SCHEMA)?;
+                1
+            }
+        };
 
-        if version.is_none() {
-            return self.connection.execute_batch(SCHEMA);
+        let latest = MIGRATIONS.len() as u32 + 1;
+        if version > latest {
+            log::error!("Database is using unknown version {}.", version);
+            return Ok(());
         }
 
-        match version.unwrap() {
-            1 => {}
-            v @ _ => log::error!("Database is using unknown version {}.", v),
+        while version < latest {
+            log::info!("Migrating database from version {}.", version);
+            let transaction = self.connection.transaction()?;
+            transaction.execute_batch(MIGRATIONS[version as usize - 1])?;
+            version += 1;
+            if version == TOTALS_VERSION {
+                rebuild_totals(&transaction)?;
+            }
+            if version == INTERVALS_VERSION {
+                convert_pauses(&transaction, "History")?;
+                convert_pauses(&transaction, "Amendments")?;
+            }
+            transaction.commit()?;
         }
 
         Ok(())
@@ -59,15 +220,600 @@ impl Sqlite {
 fn create_action(connection: &Connection, name: &str) -> Result<Action, Box<dyn Error>> {
     connection.execute("insert into Actions (name) values (?)", &[&name])?;
     let id = connection.last_insert_rowid() as usize;
-    let name = name.into();
-    Ok(Action { id, name })
+    Ok(Action::new(id, name))
 }
 
 fn create_subject(connection: &Connection, name: &str) -> Result<Subject, Box<dyn Error>> {
     connection.execute("insert into Subjects (name) values (?)", &[&name])?;
     let id = connection.last_insert_rowid() as usize;
+    Ok(Subject::new(id, name))
+}
+
+/// Reads a stored idle rule, unreadable ones fall back to the default.
+fn idle_rule(stored: Option<String>) -> IdleRule {
+    let stored = stored.unwrap_or_default();
+    IdleRule::parse(&stored).unwrap_or_else(|err| {
+        log::warn!("Ignoring the idle rule '{}': {}", stored, err);
+        IdleRule::Default
+    })
+}
+
+/// The default rule is stored as null.
+fn stored_idle_rule(rule: IdleRule) -> Option<String> {
+    match rule {
+        IdleRule::Default => None,
+        rule => Some(rule.to_string()),
+    }
+}
+
+/// Sets the archived flag of an action or subject in `table`.
+fn set_archived(
+    connection: &Connection,
+    table: &str,
+    id: usize,
+    archived: bool,
+) -> Result<(), Box<dyn Error>> {
+    connection.execute(
+        &format!("update {} set archived = ? where id = ?", table),
+        params![archived, id as u32],
+    )?;
+    if !archived {
+        // Restored temporary entries get another grace period.
+        connection.execute(
+            &format!(
+                "update {} set temporary_since = ? where id = ? and temporary_since is not null",
+                table
+            ),
+            params![time::OffsetDateTime::now_local(), id as u32],
+        )?;
+    }
+    Ok(())
+}
+
+/// Sets the color of the action or subject `id` in `table`, `None` removes it.
+fn set_color(
+    connection: &Connection,
+    table: &str,
+    id: usize,
+    color: Option<u32>,
+) -> Result<(), Box<dyn Error>> {
+    connection.execute(
+        &format!("update {} set color = ? where id = ?", table),
+        params![color, id as u32],
+    )?;
+    Ok(())
+}
+
+/// Stores the arranged order and the favorites of the actions or subjects in `table`.
+fn set_order(
+    connection: &Connection,
+    table: &str,
+    order: &ListOrder,
+) -> Result<(), Box<dyn Error>> {
+    connection.execute(
+        &format!("update {} set position = null, favorite = 0", table),
+        NO_PARAMS,
+    )?;
+    for (position, id) in order.arranged.iter().enumerate() {
+        connection.execute(
+            &format!("update {} set position = ? where id = ?", table),
+            params![position as u32, *id as u32],
+        )?;
+    }
+    for id in &order.favorites {
+        connection.execute(
+            &format!("update {} set favorite = 1 where id = ?", table),
+            params![*id as u32],
+        )?;
+    }
+    Ok(())
+}
+
+/// Reads the arranged order and the favorites of the actions or subjects in `table`.
+fn load_order(connection: &Connection, table: &str) -> Result<ListOrder, Box<dyn Error>> {
+    let mut query = connection.prepare_cached(&format!(
+        "select id, favorite from {} where position is not null or favorite = 1 \
+         order by position",
+        table
+    ))?;
+    let mut order = ListOrder::default();
+    let mut rows = query.query(NO_PARAMS)?;
+    while let Some(row) = rows.next()? {
+        let id = row.get::<_, u32>("id")? as usize;
+        if row.get("favorite")? {
+            order.favorites.push_back(id);
+        }
+        order.arranged.push_back(id);
+    }
+    Ok(order)
+}
+
+/// Marks the action or subject `id` in `table` as temporary, starting its grace period now.
+fn set_temporary(
+    connection: &Connection,
+    table: &str,
+    id: usize,
+    temporary: bool,
+) -> Result<(), Box<dyn Error>> {
+    let since = match temporary {
+        true => Some(time::OffsetDateTime::now_local()),
+        false => None,
+    };
+    connection.execute(
+        &format!("update {} set temporary_since = ? where id = ?", table),
+        params![since, id as u32],
+    )?;
+    Ok(())
+}
+
+/// Archives the temporary entries of `table` that were neither marked nor
+/// used in a session referring to them by `column` for `TEMPORARY_DAYS`.
+fn archive_stale(connection: &Connection, table: &str, column: &str) -> Result<(), Box<dyn Error>> {
+    let cutoff = time::OffsetDateTime::now_local() - time::Duration::days(TEMPORARY_DAYS);
+    let mut stale = Vec::new();
+    {
+        let mut query = connection.prepare(&format!(
+            "select id, temporary_since, \
+                 (select max(ended) from History where {1} = {0}.id) as used \
+             from {0} where temporary_since is not null and archived = 0",
+            table, column
+        ))?;
+        let mut rows = query.query(NO_PARAMS)?;
+        while let Some(row) = rows.next()? {
+            let since: time::OffsetDateTime = row.get("temporary_since")?;
+            let used: Option<time::OffsetDateTime> = row.get("used")?;
+            if used.map_or(since, |used| used.max(since)) < cutoff {
+                stale.push(row.get::<_, u32>("id")? as usize);
+            }
+        }
+    }
+    for id in &stale {
+        set_archived(connection, table, *id, true)?;
+    }
+    if !stale.is_empty() {
+        log::info!(
+            "Archived {} unused temporary entries of {}.",
+            stale.len(),
+            table
+        );
+    }
+    Ok(())
+}
+
+/// Renames the action or subject `id` in `table`, sessions refer to it by id.
+fn rename(
+    connection: &Connection,
+    table: &str,
+    id: usize,
+    name: &str,
+) -> Result<(), Box<dyn Error>> {
+    connection.execute(
+        &format!("update {} set name = ? where id = ?", table),
+        params![name, id as u32],
+    )?;
+    Ok(())
+}
+
+/// End of the latest session per id in `column` of the history.
+fn last_used(
+    connection: &Connection,
+    column: &str,
+) -> Result<HashMap<usize, time::OffsetDateTime>, Box<dyn Error>> {
+    let mut last_used = HashMap::new();
+    let mut query = connection.prepare(&format!(
+        "select {0} as id, max(ended) as used from History where {0} is not null group by {0}",
+        column
+    ))?;
+    let mut rows = query.query(NO_PARAMS)?;
+    while let Some(row) = rows.next()? {
+        last_used.insert(row.get::<_, u32>("id")? as usize, row.get("used")?);
+    }
+    Ok(last_used)
+}
+
+/// Whether any recorded session or amendment refers to the row `id` in `column`.
+fn has_sessions(connection: &Connection, column: &str, id: usize) -> Result<bool, Box<dyn Error>> {
+    let count: u32 = connection.query_row(
+        &format!(
+            "select (select count(*) from History where {0} = ?1) \
+                  + (select count(*) from Amendments where {0} = ?1)",
+            column
+        ),
+        params![id as u32],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+/// Deletes an action that was never used, used ones have to be archived.
+fn delete_action(connection: &Connection, action: &Action) -> Result<(), Box<dyn Error>> {
+    if has_sessions(connection, "action", action.id)? {
+        return Err(format!("{} has recorded sessions, archive it instead.", action.name).into());
+    }
+    let id = action.id as u32;
+    connection.execute("delete from TimeTable where action = ?", params![id])?;
+    connection.execute("delete from Pins where action = ?", params![id])?;
+    connection.execute("delete from LaunchRules where action = ?", params![id])?;
+    connection.execute("delete from Goals where action = ?", params![id])?;
+    connection.execute("delete from Totals where action = ?", params![id])?;
+    connection.execute("delete from Aliases where action = ?", params![id])?;
+    connection.execute("delete from Actions where id = ?", params![id])?;
+    Ok(())
+}
+
+/// Deletes a subject that was never used, used ones have to be archived.
+fn delete_subject(connection: &Connection, subject: &Subject) -> Result<(), Box<dyn Error>> {
+    if has_sessions(connection, "subject", subject.id)? {
+        return Err(format!(
+            "{} has recorded sessions, archive it instead.",
+            subject.name
+        )
+        .into());
+    }
+    let id = subject.id as u32;
+    connection.execute("delete from TimeTable where subject = ?", params![id])?;
+    connection.execute("delete from Pins where subject = ?", params![id])?;
+    connection.execute("delete from LaunchRules where subject = ?", params![id])?;
+    connection.execute("delete from Goals where subject = ?", params![id])?;
+    connection.execute("delete from Totals where subject = ?", params![id])?;
+    connection.execute("delete from Categories where subject = ?", params![id])?;
+    connection.execute("delete from Checklists where subject = ?", params![id])?;
+    connection.execute("delete from Aliases where subject = ?", params![id])?;
+    // Its sub-projects move up to the top level.
+    connection.execute(
+        "update Subjects set parent = null where parent = ?",
+        params![id],
+    )?;
+    connection.execute("delete from Subjects where id = ?", params![id])?;
+    Ok(())
+}
+
+/// Keys of the sessions recorded on the entry `id`, amended ones included.
+fn sessions_on(
+    connection: &Connection,
+    column: &str,
+    id: usize,
+) -> Result<Vec<DateTime>, Box<dyn Error>> {
+    let mut query = connection.prepare(&format!(
+        "\
+        select started from History where {0} = ?1 \
+        union select session from Amendments where {0} = ?1 \
+        union select started from Amendments where {0} = ?1",
+        column
+    ))?;
+    let keys = query
+        .query_map(params![id as u32], |row| {
+            row.get::<_, time::OffsetDateTime>(0).map(DateTime::from)
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(keys)
+}
+
+/// Moves everything recorded on the entry `from` over to `into` and deletes
+/// it. `column` names the kind of entry, `action` or `subject`. Returns the
+/// keys of the sessions moved, and fails if any of them is locked.
+fn merge_entries(
+    connection: &Connection,
+    column: &str,
+    from: usize,
+    into: usize,
+) -> Result<Vec<DateTime>, Box<dyn Error>> {
+    let (table, other) = match column {
+        "action" => ("Actions", "subject"),
+        _ => ("Subjects", "action"),
+    };
+    let moved = sessions_on(connection, column, from)?;
+    for key in &moved {
+        check_unlocked(connection, key)?;
+    }
+    let counted = counted_sessions(connection, &moved)?;
+    let ids = params![from as u32, into as u32];
+    for moved in &[
+        "History",
+        "Amendments",
+        "Running",
+        "Pins",
+        "Aliases",
+        "LaunchRules",
+    ] {
+        connection.execute(
+            &format!("update {0} set {1} = ?2 where {1} = ?1", moved, column),
+            ids,
+        )?;
+    }
+    // The time kept for both is added up.
+    connection.execute(
+        &format!(
+            "\
+            insert into TimeTable ({0}, {1}, duration) \
+            select ?2, {1}, duration from TimeTable where {0} = ?1 \
+            on conflict (action, subject) \
+            do update set duration = duration + excluded.duration",
+            column, other
+        ),
+        ids,
+    )?;
+    connection.execute(
+        &format!("delete from TimeTable where {} = ?", column),
+        params![from as u32],
+    )?;
+    // A goal set for both keeps the one of `into`.
+    connection.execute(
+        &format!("update or ignore Goals set {0} = ?2 where {0} = ?1", column),
+        ids,
+    )?;
+    connection.execute(
+        &format!("delete from Goals where {} = ?", column),
+        params![from as u32],
+    )?;
+    if column == "subject" {
+        connection.execute("update Checklists set subject = ?2 where subject = ?1", ids)?;
+        connection.execute(
+            "delete from Categories where subject = ?1",
+            params![from as u32],
+        )?;
+        // Nested in the merged subject, `into` takes its place.
+        connection.execute(
+            "\
+            update Subjects set parent = (select parent from Subjects where id = ?1) \
+            where id = ?2 and parent = ?1",
+            ids,
+        )?;
+        connection.execute("update Subjects set parent = ?2 where parent = ?1", ids)?;
+    }
+    // Other instances showing the sessions see they changed.
+    for key in &moved {
+        bump_version(connection, key)?;
+    }
+    recount_totals(connection, counted, &moved)?;
+    connection.execute(
+        &format!("delete from {} where id = ?", table),
+        params![from as u32],
+    )?;
+    Ok(moved)
+}
+
+/// Moves the sessions and time of a merged entry from `into` back to
+/// `created`, the entry created again in its place.
+fn split_entries<T>(
+    connection: &Connection,
+    column: &str,
+    merge: &Merge<T>,
+    into: usize,
+    created: usize,
+) -> Result<(), Box<dyn Error>> {
+    let other = match column {
+        "action" => "subject",
+        _ => "action",
+    };
+    for key in &merge.sessions {
+        check_unlocked(connection, key)?;
+    }
+    let counted = counted_sessions(connection, &merge.sessions)?;
+    for key in &merge.sessions {
+        bump_version(connection, key)?;
+        let ids = params![**key, into as u32, created as u32];
+        connection.execute(
+            &format!(
+                "update History set {0} = ?3 where started = ?1 and {0} = ?2",
+                column
+            ),
+            ids,
+        )?;
+        connection.execute(
+            &format!(
+                "update Amendments set {0} = ?3 where session = ?1 and {0} = ?2",
+                column
+            ),
+            ids,
+        )?;
+    }
+    for (id, time) in &merge.time {
+        let seconds = time.as_secs() as u32;
+        connection.execute(
+            &format!(
+                "update TimeTable set duration = max(duration - ?3, 0) where {} = ?1 and {} = ?2",
+                column, other
+            ),
+            params![into as u32, *id as u32, seconds],
+        )?;
+        connection.execute(
+            &format!(
+                "delete from TimeTable where {} = ?1 and {} = ?2 and duration = 0",
+                column, other
+            ),
+            params![into as u32, *id as u32],
+        )?;
+        connection.execute(
+            &format!(
+                "insert into TimeTable ({}, {}, duration) values (?1, ?2, ?3)",
+                column, other
+            ),
+            params![created as u32, *id as u32, seconds],
+        )?;
+    }
+    let moved = |table: &str, key: &str, value: &dyn rusqlite::ToSql| {
+        connection.execute(
+            &format!(
+                "update {0} set {1} = ?3 where {2} = ?1 and {1} = ?2",
+                table, column, key
+            ),
+            params![value, into as u32, created as u32],
+        )
+    };
+    for slot in &merge.pins {
+        moved("Pins", "slot", &(*slot as u32))?;
+    }
+    for name in &merge.aliases {
+        moved("Aliases", "name", &name.as_ref())?;
+    }
+    for process in &merge.launch_rules {
+        moved("LaunchRules", "process", &process.as_ref())?;
+    }
+    for (goal, taken) in &merge.goals {
+        let id = match column {
+            "action" => goal.topic.subject.id,
+            _ => goal.topic.action.id,
+        };
+        if *taken {
+            connection.execute(
+                &format!("delete from Goals where {} = ?1 and {} = ?2", column, other),
+                params![into as u32, id as u32],
+            )?;
+        }
+        connection.execute(
+            &format!(
+                "insert into Goals ({}, {}, period, seconds) values (?, ?, ?, ?)",
+                column, other
+            ),
+            params![
+                created as u32,
+                id as u32,
+                stored_period(goal.period),
+                goal.target.as_secs() as u32
+            ],
+        )?;
+    }
+    recount_totals(connection, counted, &merge.sessions)?;
+    Ok(())
+}
+
+fn stored_period(period: Period) -> u32 {
+    match period {
+        Period::Day => 0,
+        Period::Week => 1,
+        Period::Month => 2,
+    }
+}
+
+/// Gives the action created again by an undone merge the attributes it had.
+fn restore_action(
+    connection: &Connection,
+    action: &Action,
+    id: usize,
+) -> Result<(), Box<dyn Error>> {
+    set_archived(connection, "Actions", id, action.archived)?;
+    set_temporary(connection, "Actions", id, action.temporary)?;
+    set_color(connection, "Actions", id, action.color)
+}
+
+/// Like `restore_action`, the checklist, category and nested subjects of the
+/// merged subject return to it as well.
+fn restore_subject(
+    connection: &Connection,
+    merge: &Merge<Subject>,
+    created: &Subject,
+) -> Result<(), Box<dyn Error>> {
+    let subject = &merge.from;
+    let id = created.id as u32;
+    set_archived(connection, "Subjects", created.id, subject.archived)?;
+    set_temporary(connection, "Subjects", created.id, subject.temporary)?;
+    set_color(connection, "Subjects", created.id, subject.color)?;
+    connection.execute(
+        "\
+        update Subjects set issue_url = ?, estimate = ?, rate = ?, idle_rule = ?, \
+        parent = ?, final_report = ? where id = ?",
+        params![
+            subject.issue_url.as_deref(),
+            subject.estimate.map(|estimate| estimate.as_secs() as u32),
+            subject.rate,
+            stored_idle_rule(subject.idle_rule.clone()),
+            subject.parent.map(|parent| parent as u32),
+            subject.final_report.as_deref(),
+            id
+        ],
+    )?;
+    set_category(connection, created, merge.category.as_deref())?;
+    for item in &merge.checklist {
+        connection.execute(
+            "update Checklists set subject = ? where id = ?",
+            params![id, *item as u32],
+        )?;
+    }
+    for child in &merge.children {
+        connection.execute(
+            "update Subjects set parent = ? where id = ?",
+            params![id, *child as u32],
+        )?;
+    }
+    Ok(())
+}
+
+fn create_context(connection: &Connection, name: &str) -> Result<Context, Box<dyn Error>> {
+    connection.execute("insert into Contexts (name) values (?)", &[&name])?;
+    let id = connection.last_insert_rowid() as usize;
     let name = name.into();
-    Ok(Subject { id, name })
+    Ok(Context { id, name })
+}
+
+fn create_tag(connection: &Connection, name: &str) -> Result<Tag, Box<dyn Error>> {
+    connection.execute("insert into Tags (name) values (?)", &[&name])?;
+    let id = connection.last_insert_rowid() as usize;
+    let name = name.into();
+    Ok(Tag { id, name })
+}
+
+/// Replaces the tags stored for the session recorded at `key`.
+fn set_tags(
+    connection: &Connection,
+    key: &DateTime,
+    tags: &Vector<Tag>,
+) -> Result<(), Box<dyn Error>> {
+    connection.execute("delete from SessionTags where session = ?", params![**key])?;
+    for tag in tags {
+        connection.execute(
+            "insert into SessionTags (session, tag) values (?, ?)",
+            params![**key, tag.id as u32],
+        )?;
+    }
+    Ok(())
+}
+
+fn set_category(
+    connection: &Connection,
+    subject: &Subject,
+    category: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    match category {
+        Some(category) => connection.execute(
+            "\
+            insert into Categories (subject, category) \
+            values (?1, ?2) \
+            on conflict (subject) \
+            do update set category = ?2",
+            params![subject.id as u32, category],
+        )?,
+        None => connection.execute(
+            "delete from Categories where subject = ?",
+            params![subject.id as u32],
+        )?,
+    };
+    Ok(())
+}
+
+/// The action and subject columns of an alias, only one of them is set.
+fn alias_target(alias: &Alias) -> (Option<u32>, Option<u32>) {
+    match alias.target {
+        AliasTarget::Action(id) => (Some(id as u32), None),
+        AliasTarget::Subject(id) => (None, Some(id as u32)),
+    }
+}
+
+fn create_checklist_item(
+    connection: &Connection,
+    subject: &Subject,
+    text: &str,
+) -> Result<ChecklistItem, Box<dyn Error>> {
+    connection.execute(
+        "insert into Checklists (subject, text) values (?, ?)",
+        params![subject.id as u32, text],
+    )?;
+    Ok(ChecklistItem {
+        id: connection.last_insert_rowid() as usize,
+        subject: subject.id,
+        text: text.into(),
+        done: false,
+    })
 }
 
 fn update_time(
@@ -90,29 +836,902 @@ fn update_time(
     Ok(())
 }
 
+/// Ids are stored as 0 for dimensions outside of the grouping, as `null`
+/// would never conflict in the primary key.
+fn change_totals(
+    connection: &Connection,
+    session: &Session,
+    seconds: i64,
+) -> Result<(), rusqlite::Error> {
+    for grouping in Grouping::all() {
+        let key = grouping.key_of(session);
+        connection.execute(
+            "\
+            insert into Totals (grouping, action, subject, context, duration) \
+            values (?1, ?2, ?3, ?4, ?5) \
+            on conflict (grouping, action, subject, context) \
+            do update set duration = duration + ?5",
+            params![
+                grouping.bits(),
+                key.action.map(|a| a.id as u32).unwrap_or(0),
+                key.subject.map(|s| s.id as u32).unwrap_or(0),
+                key.context.map(|c| c.id as u32).unwrap_or(0),
+                seconds,
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+fn rebuild_totals(connection: &Connection) -> Result<(), rusqlite::Error> {
+    connection.execute("delete from Totals", NO_PARAMS)?;
+    let mut query =
+        connection.prepare("select started, ended, action, subject, context from History")?;
+    let mut rows = query.query(NO_PARAMS)?;
+    while let Some(row) = rows.next()? {
+        let id = |column| -> Result<usize, rusqlite::Error> {
+            Ok(row.get::<_, Option<u32>>(column)?.unwrap_or(0) as usize)
+        };
+        let context = match id("context")? {
+            0 => None,
+            id => Some(id),
+        };
+        let session = session_with_ids(
+            DateTime::from(row.get::<_, time::OffsetDateTime>("started")?),
+            DateTime::from(row.get::<_, time::OffsetDateTime>("ended")?),
+            (id("action")?, id("subject")?, context),
+            Vector::new(),
+        );
+        change_totals(connection, &session, session.duration().as_secs() as i64)?;
+    }
+    Ok(())
+}
+
+/// A session referring to its action, subject and context by id only, which
+/// is all the totals need. Names are resolved when loading.
+fn session_with_ids(
+    started: DateTime,
+    ended: DateTime,
+    (action, subject, context): (usize, usize, Option<usize>),
+    intervals: Vector<Interval>,
+) -> Session {
+    Session {
+        started,
+        ended,
+        topic: Topic {
+            action: Action::new(action, ""),
+            subject: Subject::new(subject, ""),
+        },
+        context: context.map(|id| Context {
+            id,
+            name: "".into(),
+        }),
+        interruptions: Vector::new(),
+        original_start: None,
+        intervals,
+        note: None,
+        tags: Vector::new(),
+    }
+}
+
+/// The session recorded at `key` as it counts now, with its latest amendment
+/// applied. `None` if there is none or it was removed.
+fn effective_session(
+    connection: &Connection,
+    key: &DateTime,
+) -> Result<Option<Session>, Box<dyn Error>> {
+    let amended = connection
+        .query_row(
+            "\
+            select started, ended, action, subject, context, intervals, note, removed, \
+            utc_offset \
+            from Amendments where session = ? order by id desc limit 1",
+            params![**key],
+            |row| match row.get("removed")? {
+                true => Ok(None),
+                false => SessionRow::read(row).map(Some),
+            },
+        )
+        .optional()?;
+    let record = match amended {
+        Some(Some(record)) => record,
+        Some(None) => return Ok(None),
+        None => {
+            let recorded = connection
+                .query_row(
+                    "\
+                    select started, ended, action, subject, context, intervals, note, \
+                    utc_offset \
+                    from History where started = ?",
+                    params![**key],
+                    SessionRow::read,
+                )
+                .optional()?;
+            match recorded {
+                Some(record) => record,
+                None => return Ok(None),
+            }
+        }
+    };
+    let intervals = read_intervals(record.intervals.as_deref(), record.utc_offset)?;
+    Ok(Some(session_with_ids(
+        DateTime::from(record.started),
+        DateTime::from(record.ended),
+        (record.action, record.subject, record.context),
+        intervals,
+    )))
+}
+
+/// The sessions `keys` as they count in the totals now.
+fn counted_sessions<'a>(
+    connection: &Connection,
+    keys: impl IntoIterator<Item = &'a DateTime>,
+) -> Result<Vec<Session>, Box<dyn Error>> {
+    let mut sessions = Vec::new();
+    for key in keys {
+        sessions.extend(effective_session(connection, key)?);
+    }
+    Ok(sessions)
+}
+
+/// Takes the `counted` sessions out of the totals and adds the sessions
+/// `keys` back as they are stored now, once they moved to other entries.
+fn recount_totals<'a>(
+    connection: &Connection,
+    counted: Vec<Session>,
+    keys: impl IntoIterator<Item = &'a DateTime>,
+) -> Result<(), Box<dyn Error>> {
+    for session in counted {
+        change_totals(connection, &session, -(session.duration().as_secs() as i64))?;
+    }
+    for session in counted_sessions(connection, keys)? {
+        change_totals(connection, &session, session.duration().as_secs() as i64)?;
+    }
+    // Nothing counts for the entry the sessions left, which may be deleted.
+    connection.execute("delete from Totals where duration = 0", NO_PARAMS)?;
+    Ok(())
+}
+
+/// Turns the time paused of the sessions in `table` into the intervals worked.
+fn convert_pauses(connection: &Connection, table: &str) -> Result<(), rusqlite::Error> {
+    let mut paused = Vec::new();
+    {
+        let mut query = connection.prepare(&format!(
+            "select rowid, started, ended, paused from {} where paused > 0",
+            table
+        ))?;
+        let mut rows = query.query(NO_PARAMS)?;
+        while let Some(row) = rows.next()? {
+            let intervals = Interval::with_pause(
+                DateTime::from(row.get::<_, time::OffsetDateTime>("started")?),
+                DateTime::from(row.get::<_, time::OffsetDateTime>("ended")?),
+                SpentTime::from(Duration::from_secs(row.get::<_, u32>("paused")? as u64)),
+            );
+            paused.push((row.get::<_, i64>("rowid")?, stored_intervals(&intervals)));
+        }
+    }
+    for (rowid, intervals) in paused {
+        connection.execute(
+            &format!(
+                "update {} set intervals = ?, paused = 0 where rowid = ?",
+                table
+            ),
+            params![intervals, rowid],
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes the intervals of a session like `start/end;start/end`, none as null.
+fn stored_intervals(intervals: &Vector<Interval>) -> Option<String> {
+    if intervals.is_empty() {
+        return None;
+    }
+    let stored: Vec<String> = intervals
+        .iter()
+        .map(|interval| {
+            format!(
+                "{}/{}",
+                interval.started.format(backup::TIMESTAMP_FORMAT),
+                interval.ended.format(backup::TIMESTAMP_FORMAT)
+            )
+        })
+        .collect();
+    Some(stored.join(";"))
+}
+
+/// Reads the intervals written by `stored_intervals`, in the offset of their session.
+fn read_intervals(
+    stored: Option<&str>,
+    utc_offset: Option<i32>,
+) -> Result<Vector<Interval>, Box<dyn Error>> {
+    let mut intervals = Vector::new();
+    for interval in stored.into_iter().flat_map(|stored| stored.split(';')) {
+        let mut times = interval.splitn(2, '/');
+        let mut next = || -> Result<DateTime, Box<dyn Error>> {
+            let time = times
+                .next()
+                .ok_or_else(|| format!("Invalid interval '{}'.", interval))?;
+            Ok(DateTime::in_offset(
+                backup::parse_timestamp(time)?,
+                utc_offset,
+            ))
+        };
+        intervals.push_back(Interval::new(next()?, next()?));
+    }
+    Ok(intervals)
+}
+
+fn set_setting(connection: &Connection, key: &str, value: &str) -> Result<(), Box<dyn Error>> {
+    connection.execute(
+        "\
+        insert into Settings (key, value) \
+        values (?1, ?2) \
+        on conflict (key) \
+        do update set value = ?2",
+        params![key, value],
+    )?;
+    Ok(())
+}
+
+fn set_flag(connection: &Connection, key: &str, value: bool) -> Result<(), Box<dyn Error>> {
+    set_setting(connection, key, if value { "1" } else { "0" })
+}
+
+/// Day before which sessions are locked, `None` if no period is locked.
+fn locked_before(connection: &Connection) -> Result<Option<Date>, Box<dyn Error>> {
+    let mut query =
+        connection.prepare_cached("select value from Settings where key = 'locked_before'")?;
+    let mut rows = query.query(NO_PARAMS)?;
+    match rows.next()? {
+        Some(row) => {
+            let value: String = row.get("value")?;
+            Ok(Some(Date::from(time::Date::parse(value, DATE_FORMAT)?)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Fails if a session starting at `started` belongs to a locked billing period.
+fn check_unlocked(connection: &Connection, started: &DateTime) -> Result<(), Box<dyn Error>> {
+    match locked_before(connection)? {
+        Some(before) if Date::from(**started) < before => {
+            Err(format!("Sessions before {} are locked, unlock them first.", *before).into())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Locks the sessions before `before`, or unlocks all of them, and records it in the audit log.
+fn set_locked_before(connection: &Connection, before: Option<Date>) -> Result<(), Box<dyn Error>> {
+    let event = match (before, locked_before(connection)?) {
+        (Some(before), _) => {
+            set_setting(connection, "locked_before", &before.format(DATE_FORMAT))?;
+            format!("Locked sessions before {}", *before)
+        }
+        (None, Some(previous)) => {
+            connection.execute(
+                "delete from Settings where key = 'locked_before'",
+                NO_PARAMS,
+            )?;
+            format!("Unlocked sessions before {}", *previous)
+        }
+        (None, None) => return Ok(()),
+    };
+    connection.execute(
+        "insert into AuditLog (at, event) values (?, ?)",
+        params![*DateTime::now(), event],
+    )?;
+    Ok(())
+}
+
+fn change_time(connection: &Connection, topic: &Topic, seconds: i64) -> Result<(), Box<dyn Error>> {
+    connection.execute(
+        "\
+        insert into TimeTable (action, subject, duration) \
+        values (?1, ?2, max(?3, 0)) \
+        on conflict (action, subject) \
+        do update set duration = max(duration + ?3, 0)",
+        params![topic.action.id as u32, topic.subject.id as u32, seconds],
+    )?;
+    Ok(())
+}
+
+fn stored_version(connection: &Connection, key: &DateTime) -> Result<Option<u32>, Box<dyn Error>> {
+    let mut query = connection.prepare_cached("select version from History where started = ?")?;
+    let mut rows = query.query(params![**key])?;
+    match rows.next()? {
+        Some(row) => Ok(Some(row.get(0)?)),
+        None => Ok(None),
+    }
+}
+
+fn bump_version(connection: &Connection, key: &DateTime) -> Result<(), Box<dyn Error>> {
+    connection.execute(
+        "update History set version = version + 1 where started = ?",
+        params![**key],
+    )?;
+    Ok(())
+}
+
+/// Replaces each session by its changed version, as an amendment if `strict`.
+fn replace_sessions(
+    connection: &Connection,
+    replacements: &Vector<(Session, Session)>,
+    strict: bool,
+) -> Result<(), Box<dyn Error>> {
+    for (original, changed) in replacements {
+        if strict {
+            let mut amended = changed.clone();
+            amended.original_start = Some(original.key());
+            amend_session(connection, original, Some(&amended))?;
+        } else {
+            update_session(connection, original, changed)?;
+        }
+    }
+    Ok(())
+}
+
+fn amend_session(
+    connection: &Connection,
+    original: &Session,
+    amended: Option<&Session>,
+) -> Result<(), Box<dyn Error>> {
+    check_unlocked(connection, &original.key())?;
+    if let Some(amended) = amended {
+        check_unlocked(connection, &amended.started)?;
+    }
+    connection.execute(
+        "\
+        insert into Amendments \
+        (session, amended_at, started, ended, action, subject, context, intervals, note, removed, \
+        utc_offset) \
+        values (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        params![
+            *original.key(),
+            *DateTime::now(),
+            amended.map(|s| *s.started),
+            amended.map(|s| *s.ended),
+            amended.map(|s| s.topic.action.id as u32),
+            amended.map(|s| s.topic.subject.id as u32),
+            amended.and_then(|s| s.context.as_ref().map(|c| c.id as u32)),
+            amended.and_then(|s| stored_intervals(&s.intervals)),
+            amended.and_then(|s| s.note.as_deref()),
+            amended.is_none(),
+            amended.map(|s| s.started.offset().as_seconds()),
+        ],
+    )?;
+    bump_version(connection, &original.key())?;
+    let seconds = original.duration().as_secs() as i64;
+    change_time(connection, &original.topic, -seconds)?;
+    change_totals(connection, original, -seconds)?;
+    if let Some(amended) = amended {
+        let seconds = amended.duration().as_secs() as i64;
+        change_time(connection, &amended.topic, seconds)?;
+        change_totals(connection, amended, seconds)?;
+    }
+    Ok(())
+}
+
+/// Rewrites a session in place, dropping amendments recorded for it.
+fn update_session(
+    connection: &Connection,
+    original: &Session,
+    updated: &Session,
+) -> Result<(), Box<dyn Error>> {
+    check_unlocked(connection, &original.key())?;
+    check_unlocked(connection, &updated.started)?;
+    let key = *original.key();
+    connection.execute("delete from Amendments where session = ?", params![key])?;
+    connection.execute(
+        "update Interruptions set session = ?1 where session = ?2",
+        params![*updated.started, key],
+    )?;
+    connection.execute("delete from SessionTags where session = ?", params![key])?;
+    set_tags(connection, &updated.started, &updated.tags)?;
+    connection.execute(
+        "\
+        update History \
+        set started = ?1, ended = ?2, action = ?3, subject = ?4, context = ?5, intervals = ?6, \
+        note = ?7, utc_offset = ?8, version = version + 1 \
+        where started = ?9",
+        params![
+            *updated.started,
+            *updated.ended,
+            updated.topic.action.id as u32,
+            updated.topic.subject.id as u32,
+            updated.context.as_ref().map(|c| c.id as u32),
+            stored_intervals(&updated.intervals),
+            updated.note.as_deref(),
+            updated.started.offset().as_seconds(),
+            key,
+        ],
+    )?;
+    let seconds = original.duration().as_secs() as i64;
+    change_time(connection, &original.topic, -seconds)?;
+    change_totals(connection, original, -seconds)?;
+    let seconds = updated.duration().as_secs() as i64;
+    change_time(connection, &updated.topic, seconds)?;
+    change_totals(connection, updated, seconds)?;
+    Ok(())
+}
+
+fn delete_session(connection: &Connection, session: &Session) -> Result<(), Box<dyn Error>> {
+    check_unlocked(connection, &session.key())?;
+    let key = *session.key();
+    connection.execute("delete from Amendments where session = ?", params![key])?;
+    connection.execute("delete from Interruptions where session = ?", params![key])?;
+    connection.execute("delete from SessionTags where session = ?", params![key])?;
+    connection.execute("delete from History where started = ?", params![key])?;
+    let seconds = session.duration().as_secs() as i64;
+    change_time(connection, &session.topic, -seconds)?;
+    change_totals(connection, session, -seconds)?;
+    Ok(())
+}
+
+fn restore_session(connection: &Connection, session: &Session) -> Result<(), Box<dyn Error>> {
+    check_unlocked(connection, &session.key())?;
+    let removals = connection.execute(
+        "delete from Amendments where session = ? and removed = 1",
+        params![*session.key()],
+    )?;
+    let seconds = session.duration().as_secs() as i64;
+    if removals == 0 {
+        add_session(connection, session)?;
+    } else {
+        bump_version(connection, &session.key())?;
+        change_totals(connection, session, seconds)?;
+    }
+    change_time(connection, &session.topic, seconds)?;
+    Ok(())
+}
+
+/// The raw values of a session as stored in `History` or `Amendments`.
+struct SessionRow {
+    started: time::OffsetDateTime,
+    ended: time::OffsetDateTime,
+    action: usize,
+    subject: usize,
+    context: Option<usize>,
+    intervals: Option<String>,
+    note: Option<String>,
+    utc_offset: Option<i32>,
+}
+
+impl SessionRow {
+    fn read(row: &rusqlite::Row) -> Result<Self, rusqlite::Error> {
+        Ok(SessionRow {
+            started: row.get("started")?,
+            ended: row.get("ended")?,
+            action: row.get::<_, u32>("action")? as usize,
+            subject: row.get::<_, u32>("subject")? as usize,
+            context: row.get::<_, Option<u32>>("context")?.map(|id| id as usize),
+            intervals: row.get("intervals")?,
+            note: row.get("note")?,
+            utc_offset: row.get("utc_offset")?,
+        })
+    }
+}
+
+/// Loads the latest sessions that started before `before`, as amended. The
+/// `bounds` apply to them after the `limit` of recorded sessions was taken.
+fn load_sessions(
+    connection: &Connection,
+    content: &Content,
+    before: Option<&DateTime>,
+    limit: Option<usize>,
+    bounds: &SessionQuery,
+) -> Result<History, Box<dyn Error>> {
+    let before = before.map(|dt| **dt);
+    let mut records = Vec::new();
+    {
+        let mut query = connection.prepare_cached(
+            "\
+            select started, ended, action, subject, context, intervals, note, utc_offset \
+            from History \
+            where (?1 is null or started < ?1) \
+            order by started desc \
+            limit ?2",
+        )?;
+        let mut rows = query.query(params![before, limit.map_or(-1, |limit| limit as i64)])?;
+        while let Some(row) = rows.next()? {
+            records.push(SessionRow::read(row)?);
+        }
+    }
+    records.reverse();
+    let complete = limit.map_or(true, |limit| records.len() < limit);
+    let oldest = match records.first() {
+        Some(record) => record.started,
+        None => return Ok(History::new(Vector::new(), true)),
+    };
+
+    // Interruptions are shown in the offset of their session.
+    let offsets: HashMap<time::OffsetDateTime, Option<i32>> = records
+        .iter()
+        .map(|record| (record.started, record.utc_offset))
+        .collect();
+    let mut interruptions: HashMap<time::OffsetDateTime, Vector<Interruption>> = HashMap::new();
+    {
+        let mut query = connection.prepare_cached(
+            "\
+            select session, at, cause from Interruptions \
+            where session >= ?1 and (?2 is null or session < ?2) \
+            order by at",
+        )?;
+        let mut rows = query.query(params![oldest, before])?;
+        while let Some(row) = rows.next()? {
+            let session: time::OffsetDateTime = row.get("session")?;
+            let at: time::OffsetDateTime = row.get("at")?;
+            let cause: Option<String> = row.get("cause")?;
+            interruptions
+                .entry(session)
+                .or_default()
+                .push_back(Interruption {
+                    at: DateTime::in_offset(at, offsets.get(&session).copied().flatten()),
+                    cause: cause.map(Into::into),
+                });
+        }
+    }
+
+    let mut tags: HashMap<time::OffsetDateTime, Vector<Tag>> = HashMap::new();
+    {
+        let mut query = connection.prepare_cached(
+            "\
+            select session, tag from SessionTags \
+            where session >= ?1 and (?2 is null or session < ?2)",
+        )?;
+        let mut rows = query.query(params![oldest, before])?;
+        while let Some(row) = rows.next()? {
+            let session: time::OffsetDateTime = row.get("session")?;
+            let id = row.get::<_, u32>("tag")? as usize;
+            if let Some(tag) = content.find_tag(id) {
+                tags.entry(session).or_default().insert_ord(tag);
+            }
+        }
+    }
+
+    // Only the latest amendment of a session counts, `None` marks removed sessions.
+    let mut amendments: HashMap<time::OffsetDateTime, Option<SessionRow>> = HashMap::new();
+    {
+        let mut query = connection.prepare_cached(
+            "\
+            select session, started, ended, action, subject, context, intervals, note, removed, \
+            utc_offset \
+            from Amendments \
+            where session >= ?1 and (?2 is null or session < ?2) \
+            order by id",
+        )?;
+        let mut rows = query.query(params![oldest, before])?;
+        while let Some(row) = rows.next()? {
+            let session: time::OffsetDateTime = row.get("session")?;
+            let amended = if row.get("removed")? {
+                None
+            } else {
+                Some(SessionRow::read(row)?)
+            };
+            amendments.insert(session, amended);
+        }
+    }
+
+    let mut sessions = Vector::new();
+    for mut record in records {
+        let recorded_start = record.started;
+        let original_start = match amendments.remove(&recorded_start) {
+            Some(None) => continue,
+            Some(Some(amended)) => {
+                record = amended;
+                Some(DateTime::from(recorded_start))
+            }
+            None => None,
+        };
+
+        let action = content.find_action(record.action).ok_or_else(|| {
+            NoneError::new(format!(
+                "An action with id {} has a session entry but does not exist.",
+                record.action
+            ))
+        })?;
+        let subject = content.find_subject(record.subject).ok_or_else(|| {
+            NoneError::new(format!(
+                "A subject with id {} has a session entry but does not exist.",
+                record.subject
+            ))
+        })?;
+
+        let context = match record.context {
+            Some(id) => Some(content.find_context(id).ok_or_else(|| {
+                NoneError::new(format!(
+                    "A context with id {} has a session entry but does not exist.",
+                    id
+                ))
+            })?),
+            None => None,
+        };
+
+        let in_offset = |at| DateTime::in_offset(at, record.utc_offset);
+        let session = Session {
+            started: in_offset(record.started),
+            ended: in_offset(record.ended),
+            topic: Topic { action, subject },
+            context,
+            interruptions: interruptions.remove(&recorded_start).unwrap_or_default(),
+            original_start,
+            intervals: read_intervals(record.intervals.as_deref(), record.utc_offset)?,
+            note: record.note.map(Into::into),
+            tags: tags.remove(&recorded_start).unwrap_or_default(),
+        };
+        // Amendments and breaks change how long a session took.
+        if bounds.matches(&session) {
+            sessions.push_back(session);
+        }
+    }
+    Ok(History::new(sessions, complete))
+}
+
 fn add_session(connection: &Connection, session: &Session) -> Result<(), Box<dyn Error>> {
+    check_unlocked(connection, &session.started)?;
     connection.execute(
-        "insert into History (started, ended, action, subject) values (?, ?, ?, ?)",
+        "\
+        insert into History (started, ended, action, subject, context, intervals, note, utc_offset) \
+        values (?, ?, ?, ?, ?, ?, ?, ?)",
         params![
             *session.started,
             *session.ended,
             session.topic.action.id as u32,
             session.topic.subject.id as u32,
+            session.context.as_ref().map(|c| c.id as u32),
+            stored_intervals(&session.intervals),
+            session.note.as_deref(),
+            session.started.offset().as_seconds(),
         ],
     )?;
+    for interruption in &session.interruptions {
+        connection.execute(
+            "insert into Interruptions (session, at, cause) values (?, ?, ?)",
+            params![
+                *session.started,
+                *interruption.at,
+                interruption.cause.as_deref()
+            ],
+        )?;
+    }
+    set_tags(connection, &session.started, &session.tags)?;
+    change_totals(connection, session, session.duration().as_secs() as i64)?;
     Ok(())
 }
 
+/// Adds what `backup` has but `content` lacks, returning the number of sessions added.
+/// Sessions are identified by their start, goals and pins are only taken over
+/// for topics without a goal and if nothing is pinned yet.
+fn import_backup(
+    connection: &Connection,
+    content: &Content,
+    backup: &Backup,
+) -> Result<usize, Box<dyn Error>> {
+    let mut actions: HashMap<&str, Action> = content
+        .actions
+        .iter()
+        .map(|action| (action.name.as_ref(), action.clone()))
+        .collect();
+    let mut subjects: HashMap<&str, Subject> = content
+        .subjects
+        .iter()
+        .map(|subject| (subject.name.as_ref(), subject.clone()))
+        .collect();
+    let mut contexts: HashMap<&str, Context> = content
+        .contexts
+        .iter()
+        .map(|context| (context.name.as_ref(), context.clone()))
+        .collect();
+
+    for entry in &backup.actions {
+        if !actions.contains_key(entry.name.as_str()) {
+            let mut action = create_action(connection, &entry.name)?;
+            if entry.archived {
+                set_archived(connection, "Actions", action.id, true)?;
+            }
+            action.color = entry.color.as_deref().and_then(backup::parse_color);
+            set_color(connection, "Actions", action.id, action.color)?;
+            actions.insert(&entry.name, action);
+        }
+    }
+    let mut created = Vec::new();
+    for entry in &backup.subjects {
+        if !subjects.contains_key(entry.name.as_str()) {
+            created.push(entry);
+            let mut subject = create_subject(connection, &entry.name)?;
+            if entry.archived {
+                set_archived(connection, "Subjects", subject.id, true)?;
+            }
+            set_category(connection, &subject, entry.category.as_deref())?;
+            subject.issue_url = entry.issue_url.as_deref().map(Into::into);
+            subject.estimate = entry
+                .estimate
+                .map(|seconds| SpentTime::from(Duration::from_secs(seconds)));
+            subject.rate = entry.rate;
+            subject.idle_rule = idle_rule(entry.idle_rule.clone());
+            subject.color = entry.color.as_deref().and_then(backup::parse_color);
+            set_color(connection, "Subjects", subject.id, subject.color)?;
+            subject.final_report = entry.final_report.as_deref().map(Into::into);
+            connection.execute(
+                "\
+                update Subjects set issue_url = ?, estimate = ?, rate = ?, idle_rule = ?, \
+                final_report = ? where id = ?",
+                params![
+                    entry.issue_url,
+                    entry.estimate.map(|seconds| seconds as u32),
+                    entry.rate,
+                    stored_idle_rule(subject.idle_rule),
+                    entry.final_report,
+                    subject.id as u32
+                ],
+            )?;
+            subjects.insert(&entry.name, subject);
+        }
+    }
+    // Parents can only be found once all subjects exist.
+    for entry in created {
+        let parent = entry.parent.as_deref().and_then(|name| subjects.get(name));
+        if let (Some(subject), Some(parent)) = (subjects.get(entry.name.as_str()), parent) {
+            connection.execute(
+                "update Subjects set parent = ? where id = ?",
+                params![parent.id as u32, subject.id as u32],
+            )?;
+        }
+    }
+    for name in &backup.contexts {
+        if !contexts.contains_key(name.as_str()) {
+            contexts.insert(name, create_context(connection, name)?);
+        }
+    }
+    let mut tag_ids: HashMap<&str, Tag> = content
+        .tags
+        .iter()
+        .map(|tag| (tag.name.as_ref(), tag.clone()))
+        .collect();
+    for name in &backup.tags {
+        if !tag_ids.contains_key(name.as_str()) {
+            tag_ids.insert(name, create_tag(connection, name)?);
+        }
+    }
+
+    let topic = |action: &str, subject: &str| -> Result<Topic, Box<dyn Error>> {
+        Ok(Topic {
+            action: actions
+                .get(action)
+                .cloned()
+                .ok_or_else(|| format!("The backup lacks the action '{}'.", action))?,
+            subject: subjects
+                .get(subject)
+                .cloned()
+                .ok_or_else(|| format!("The backup lacks the subject '{}'.", subject))?,
+        })
+    };
+
+    let mut added = 0;
+    for entry in &backup.sessions {
+        let started = DateTime::from(backup::parse_timestamp(&entry.started)?);
+        let exists: bool = connection.query_row(
+            "select exists (select 1 from History where started = ?)",
+            params![*started],
+            |row| row.get(0),
+        )?;
+        if exists {
+            continue;
+        }
+        let context = match &entry.context {
+            Some(name) => Some(
+                contexts
+                    .get(name.as_str())
+                    .cloned()
+                    .ok_or_else(|| format!("The backup lacks the context '{}'.", name))?,
+            ),
+            None => None,
+        };
+        let mut tags = Vector::new();
+        for name in &entry.tags {
+            let tag = match tag_ids.get(name.as_str()) {
+                Some(tag) => tag.clone(),
+                None => create_tag(connection, name)?,
+            };
+            tag_ids.insert(name, tag.clone());
+            tags.insert_ord(tag);
+        }
+        let mut interruptions = Vector::new();
+        for interruption in &entry.interruptions {
+            interruptions.push_back(Interruption {
+                at: DateTime::from(backup::parse_timestamp(&interruption.at)?),
+                cause: interruption.cause.as_deref().map(Into::into),
+            });
+        }
+        let ended = DateTime::from(backup::parse_timestamp(&entry.ended)?);
+        let mut intervals = Vector::new();
+        for interval in &entry.intervals {
+            intervals.push_back(Interval::new(
+                DateTime::from(backup::parse_timestamp(&interval.started)?),
+                DateTime::from(backup::parse_timestamp(&interval.ended)?),
+            ));
+        }
+        if intervals.is_empty() {
+            let paused = SpentTime::from(Duration::from_secs(entry.paused));
+            intervals = Interval::with_pause(started, ended, paused);
+        }
+        let session = Session {
+            topic: topic(&entry.action, &entry.subject)?,
+            started,
+            ended,
+            context,
+            interruptions,
+            original_start: None,
+            intervals,
+            note: entry.note.as_deref().map(Into::into),
+            tags,
+        };
+        add_session(connection, &session)?;
+        change_time(
+            connection,
+            &session.topic,
+            session.duration().as_secs() as i64,
+        )?;
+        added += 1;
+    }
+
+    for goal in &backup.goals {
+        let topic = topic(&goal.action, &goal.subject)?;
+        connection.execute(
+            "insert or ignore into Goals (action, subject, period, seconds) values (?, ?, ?, ?)",
+            params![
+                topic.action.id as u32,
+                topic.subject.id as u32,
+                stored_period(goal.period()?),
+                goal.target as u32,
+            ],
+        )?;
+    }
+
+    if content.pinned.is_empty() {
+        for (slot, pin) in backup.pinned.iter().enumerate() {
+            let topic = topic(&pin.action, &pin.subject)?;
+            connection.execute(
+                "insert into Pins (slot, action, subject) values (?, ?, ?)",
+                params![slot as u32, topic.action.id as u32, topic.subject.id as u32],
+            )?;
+        }
+    }
+    Ok(added)
+}
+
 impl Backend for Sqlite {
     fn transfer_content(&mut self, content: &Content) -> Result<(), Box<dyn Error>> {
         let transaction = self.connection.transaction()?;
 
         for action in &content.actions {
-            create_action(&transaction, &action.name)?;
+            let created = create_action(&transaction, &action.name)?;
+            if action.archived {
+                set_archived(&transaction, "Actions", created.id, true)?;
+            }
+            set_color(&transaction, "Actions", created.id, action.color)?;
+        }
+
+        for subject in &content.subjects {
+            let created = create_subject(&transaction, &subject.name)?;
+            if subject.archived {
+                set_archived(&transaction, "Subjects", created.id, true)?;
+            }
+            set_color(&transaction, "Subjects", created.id, subject.color)?;
+        }
+
+        for context in &content.contexts {
+            transaction.execute(
+                "insert or ignore into Contexts (name) values (?)",
+                &[&context.name.as_ref()],
+            )?;
         }
 
         for subject in &content.subjects {
-            create_subject(&transaction, &subject.name)?;
+            if let Some(category) = content.categories.get(subject) {
+                set_category(&transaction, subject, Some(&category))?;
+            }
         }
 
         for (topic, time) in &content.time_table {
@@ -133,29 +1752,228 @@ impl Backend for Sqlite {
         Ok(())
     }
     fn load_content(&mut self) -> Result<Content, Box<dyn Error>> {
+        let transaction = self.connection.transaction()?;
+        archive_stale(&transaction, "Actions", "action")?;
+        archive_stale(&transaction, "Subjects", "subject")?;
+        transaction.commit()?;
         let mut content = Content::default();
 
         {
             let mut action_query = self
                 .connection
-                .prepare_cached("select id, name from Actions")?;
+                .prepare_cached("select id, name, archived, temporary_since, color from Actions")?;
             let mut rows = action_query.query(NO_PARAMS)?;
             while let Some(row) = rows.next()? {
                 let id = row.get::<_, u32>("id")? as usize;
                 let name = row.get("name")?;
-                content.actions.insert_ord(Action { id, name });
+                let archived = row.get("archived")?;
+                let temporary = row.get::<_, Option<time::OffsetDateTime>>("temporary_since")?;
+                content.actions.insert_ord(Action {
+                    id,
+                    name,
+                    archived,
+                    temporary: temporary.is_some(),
+                    color: row.get("color")?,
+                });
             }
         }
 
         {
-            let mut subject_query = self
-                .connection
-                .prepare_cached("select id, name from Subjects")?;
+            let mut subject_query = self.connection.prepare_cached(
+                "select id, name, archived, temporary_since, issue_url, estimate, rate, idle_rule, \
+                 color, parent, final_report from Subjects",
+            )?;
             let mut rows = subject_query.query(NO_PARAMS)?;
             while let Some(row) = rows.next()? {
                 let id = row.get::<_, u32>("id")? as usize;
                 let name = row.get("name")?;
-                content.subjects.insert_ord(Subject { id, name });
+                let archived = row.get("archived")?;
+                let temporary = row.get::<_, Option<time::OffsetDateTime>>("temporary_since")?;
+                content.subjects.insert_ord(Subject {
+                    id,
+                    name,
+                    archived,
+                    temporary: temporary.is_some(),
+                    issue_url: row.get::<_, Option<String>>("issue_url")?.map(Into::into),
+                    estimate: row
+                        .get::<_, Option<u32>>("estimate")?
+                        .map(|seconds| SpentTime::from(Duration::from_secs(seconds as u64))),
+                    rate: row.get("rate")?,
+                    idle_rule: idle_rule(row.get("idle_rule")?),
+                    color: row.get("color")?,
+                    parent: row
+                        .get::<_, Option<u32>>("parent")?
+                        .map(|parent| parent as usize),
+                    final_report: row
+                        .get::<_, Option<String>>("final_report")?
+                        .map(Into::into),
+                });
+            }
+        }
+
+        {
+            let mut context_query = self
+                .connection
+                .prepare_cached("select id, name from Contexts")?;
+            let mut rows = context_query.query(NO_PARAMS)?;
+            while let Some(row) = rows.next()? {
+                let id = row.get::<_, u32>("id")? as usize;
+                let name = row.get("name")?;
+                content.contexts.insert_ord(Context { id, name });
+            }
+        }
+
+        content.action_order = load_order(&self.connection, "Actions")?;
+        content.subject_order = load_order(&self.connection, "Subjects")?;
+
+        {
+            let mut day_off_query = self
+                .connection
+                .prepare_cached("select day, kind from DaysOff order by day")?;
+            let mut rows = day_off_query.query(NO_PARAMS)?;
+            while let Some(row) = rows.next()? {
+                let day: String = row.get("day")?;
+                let kind = match row.get::<_, u32>("kind")? {
+                    0 => DayOffKind::Holiday,
+                    _ => DayOffKind::Vacation,
+                };
+                content.days_off.push_back(DayOff {
+                    day: Date::from(time::Date::parse(day, DATE_FORMAT)?),
+                    kind,
+                });
+            }
+        }
+
+        {
+            let mut adjustment_query = self
+                .connection
+                .prepare_cached("select day, seconds, note from Adjustments order by day, id")?;
+            let mut rows = adjustment_query.query(NO_PARAMS)?;
+            while let Some(row) = rows.next()? {
+                let day: String = row.get("day")?;
+                let note: String = row.get("note")?;
+                content.adjustments.push_back(Adjustment {
+                    day: Date::from(time::Date::parse(day, DATE_FORMAT)?),
+                    seconds: row.get("seconds")?,
+                    note: note.into(),
+                });
+            }
+        }
+
+        {
+            let mut tag_query = self
+                .connection
+                .prepare_cached("select id, name from Tags")?;
+            let mut rows = tag_query.query(NO_PARAMS)?;
+            while let Some(row) = rows.next()? {
+                let id = row.get::<_, u32>("id")? as usize;
+                let name = row.get("name")?;
+                content.tags.insert_ord(Tag { id, name });
+            }
+        }
+
+        {
+            let mut category_query = self
+                .connection
+                .prepare_cached("select subject, category from Categories")?;
+            let mut rows = category_query.query(NO_PARAMS)?;
+            while let Some(row) = rows.next()? {
+                let subject_id = row.get::<_, u32>("subject")? as usize;
+                let category: String = row.get("category")?;
+                if let Some(subject) = content.find_subject(subject_id) {
+                    content.categories.set(&subject, Some(category.into()));
+                }
+            }
+        }
+
+        {
+            let mut checklist_query = self
+                .connection
+                .prepare_cached("select id, subject, text, done from Checklists order by id")?;
+            let mut rows = checklist_query.query(NO_PARAMS)?;
+            while let Some(row) = rows.next()? {
+                content.checklist.push_back(ChecklistItem {
+                    id: row.get::<_, u32>("id")? as usize,
+                    subject: row.get::<_, u32>("subject")? as usize,
+                    text: row.get("text")?,
+                    done: row.get("done")?,
+                });
+            }
+        }
+
+        {
+            let mut alias_query = self
+                .connection
+                .prepare_cached("select name, action, subject from Aliases order by name")?;
+            let mut rows = alias_query.query(NO_PARAMS)?;
+            while let Some(row) = rows.next()? {
+                let action: Option<u32> = row.get("action")?;
+                let subject: Option<u32> = row.get("subject")?;
+                let target = match (action, subject) {
+                    (Some(action), None) => AliasTarget::Action(action as usize),
+                    (None, Some(subject)) => AliasTarget::Subject(subject as usize),
+                    _ => continue,
+                };
+                content.aliases.push_back(Alias {
+                    name: row.get::<_, String>("name")?.into(),
+                    target,
+                });
+            }
+        }
+
+        {
+            let mut pin_query = self
+                .connection
+                .prepare_cached("select action, subject from Pins order by slot")?;
+            let mut rows = pin_query.query(NO_PARAMS)?;
+            while let Some(row) = rows.next()? {
+                let action = content.find_action(row.get::<_, u32>("action")? as usize);
+                let subject = content.find_subject(row.get::<_, u32>("subject")? as usize);
+                if let (Some(action), Some(subject)) = (action, subject) {
+                    content.pinned.push_back(Topic { action, subject });
+                }
+            }
+        }
+
+        {
+            let mut rule_query = self
+                .connection
+                .prepare_cached("select process, action, subject from LaunchRules")?;
+            let mut rows = rule_query.query(NO_PARAMS)?;
+            while let Some(row) = rows.next()? {
+                let action = content.find_action(row.get::<_, u32>("action")? as usize);
+                let subject = content.find_subject(row.get::<_, u32>("subject")? as usize);
+                if let (Some(action), Some(subject)) = (action, subject) {
+                    content.launch_rules.push_back(LaunchRule {
+                        process: row.get::<_, String>("process")?.into(),
+                        topic: Topic { action, subject },
+                    });
+                }
+            }
+        }
+
+        {
+            let mut goal_query = self
+                .connection
+                .prepare_cached("select action, subject, period, seconds from Goals")?;
+            let mut rows = goal_query.query(NO_PARAMS)?;
+            while let Some(row) = rows.next()? {
+                let action = content.find_action(row.get::<_, u32>("action")? as usize);
+                let subject = content.find_subject(row.get::<_, u32>("subject")? as usize);
+                let period = match row.get::<_, u32>("period")? {
+                    0 => Period::Day,
+                    1 => Period::Week,
+                    _ => Period::Month,
+                };
+                let seconds: u32 = row.get("seconds")?;
+                if let (Some(action), Some(subject)) = (action, subject) {
+                    content.goals.push_back(Goal {
+                        topic: Topic { action, subject },
+                        period,
+                        target: SpentTime::from(Duration::from_secs(seconds as u64)),
+                        reached: None,
+                    });
+                }
             }
         }
 
@@ -185,54 +2003,777 @@ impl Backend for Sqlite {
             }
         }
 
+        {
+            let mut totals_query = self.connection.prepare_cached(
+                "select grouping, action, subject, context, duration from Totals",
+            )?;
+            let mut rows = totals_query.query(NO_PARAMS)?;
+            while let Some(row) = rows.next()? {
+                let grouping = match Grouping::from_bits(row.get("grouping")?) {
+                    Some(grouping) => grouping,
+                    None => continue,
+                };
+                let action_id = row.get::<_, u32>("action")? as usize;
+                let subject_id = row.get::<_, u32>("subject")? as usize;
+                let context_id = row.get::<_, u32>("context")? as usize;
+                let key = GroupKey {
+                    action: content.find_action(action_id),
+                    subject: content.find_subject(subject_id),
+                    context: content.find_context(context_id),
+                };
+                let duration_secs: u32 = row.get("duration")?;
+                let duration = SpentTime::from(Duration::from_secs(duration_secs as u64));
+                content.totals.set(grouping, key, duration);
+            }
+        }
+
+        {
+            let mut settings_query = self
+                .connection
+                .prepare_cached("select key, value from Settings")?;
+            let mut rows = settings_query.query(NO_PARAMS)?;
+            while let Some(row) = rows.next()? {
+                let key: String = row.get("key")?;
+                let value: String = row.get("value")?;
+                match key.as_str() {
+                    "strict_sessions" => content.settings.strict_sessions = value == "1",
+                    "split_at_midnight" => content.settings.split_at_midnight = value == "1",
+                    "sign_exports" => content.settings.sign_exports = value == "1",
+                    "idle_minutes" => content.settings.idle_minutes = value.parse().unwrap_or(0.0),
+                    "pause_on_lock" => content.settings.pause_on_lock = value == "1",
+                    "end_when_away" => content.settings.end_when_away = value == "1",
+                    "resume_on_unlock" => content.settings.resume_on_unlock = value == "1",
+                    "long_session_minutes" => {
+                        content.settings.long_session_minutes = value.parse().unwrap_or(0.0)
+                    }
+                    "confirm_delete_session" => {
+                        content.settings.confirm_delete_session = value == "1"
+                    }
+                    "confirm_delete_topic" => content.settings.confirm_delete_topic = value == "1",
+                    "confirm_stop_minutes" => {
+                        content.settings.confirm_stop_minutes = value.parse().unwrap_or(0.0)
+                    }
+                    "confirm_import" => content.settings.confirm_import = value == "1",
+                    "watch_launches" => content.settings.watch_launches = value == "1",
+                    "milestone_minutes" => {
+                        content.settings.milestone_minutes = value.parse().unwrap_or(0.0)
+                    }
+                    "speech_command" => content.settings.speech_command = value,
+                    "work_hours" => content.settings.work_hours = value,
+                    "remind_idle_minutes" => {
+                        content.settings.remind_idle_minutes = value.parse().unwrap_or(0.0)
+                    }
+                    "weekly_hours" => content.settings.weekly_hours = value.parse().unwrap_or(0.0),
+                    "ledger_start" => content.settings.ledger_start = value,
+                    "name_length" => content.settings.name_length = value.parse().unwrap_or(0.0),
+                    "pomodoro" => content.settings.pomodoro = value == "1",
+                    "work_minutes" => content.settings.work_minutes = value.parse().unwrap_or(25.0),
+                    "break_minutes" => {
+                        content.settings.break_minutes = value.parse().unwrap_or(5.0)
+                    }
+                    "hotkey" => content.settings.hotkey = value,
+                    "dashboard_port" => content.settings.dashboard_port = value,
+                    "rounding_minutes" => {
+                        content.settings.rounding_minutes = value.parse().unwrap_or(0.0)
+                    }
+                    "round_up" => content.settings.round_up = value == "1",
+                    "round_per_day" => content.settings.round_per_day = value == "1",
+                    "money_locale" => content.settings.money_locale = value,
+                    "currency" => content.settings.currency = value,
+                    "locked_before" => {
+                        content.locked_before =
+                            time::Date::parse(value, DATE_FORMAT).ok().map(Date::from)
+                    }
+                    "close_to_tray" => content.settings.close_to_tray = value == "1",
+                    "color_by_action" => content.settings.color_by_action = value == "1",
+                    "hide_seconds" => content.settings.hide_seconds = value == "1",
+                    "stale_months" => content.settings.stale_months = value.parse().unwrap_or(0.0),
+                    "backups_kept" => content.settings.backups_kept = value.parse().unwrap_or(0.0),
+                    "month_start" => content.settings.month_start = value.parse().unwrap_or(1.0),
+                    "streak_minutes" => {
+                        content.settings.streak_minutes = value.parse().unwrap_or(15.0)
+                    }
+                    _ => log::warn!("Ignoring unknown setting {}.", key),
+                }
+            }
+        }
+
         Ok(content)
     }
     fn load_history(&mut self, content: &Content) -> Result<History, Box<dyn Error>> {
-        let mut history = History::default();
-        let mut query = self
-            .connection
-            .prepare_cached("select started, ended, action, subject from History")?;
-        let mut rows = query.query(NO_PARAMS)?;
-        while let Some(row) = rows.next()? {
-            let started_dt: time::OffsetDateTime = row.get("started")?;
-            let ended_dt: time::OffsetDateTime = row.get("ended")?;
-            let started = DateTime::from(started_dt);
-            let ended = DateTime::from(ended_dt);
-
-            let action_id: usize = row.get::<_, u32>("action")? as usize;
-            let subject_id: usize = row.get::<_, u32>("subject")? as usize;
-            let action = content.find_action(action_id).ok_or_else(|| {
-                NoneError::new(format!(
-                    "An action with id {} has a session entry but does not exist.",
-                    action_id
-                ))
-            })?;
-            let subject = content.find_subject(subject_id).ok_or_else(|| {
-                NoneError::new(format!(
-                    "A subject with id {} has a session entry but does not exist.",
-                    action_id
-                ))
-            })?;
-
-            history.add(Session {
-                started,
-                ended,
-                topic: Topic { action, subject },
-            })
+        load_sessions(
+            &self.connection,
+            content,
+            None,
+            None,
+            &SessionQuery::default(),
+        )
+    }
+    fn load_sessions(
+        &mut self,
+        content: &Content,
+        before: Option<&DateTime>,
+        limit: usize,
+    ) -> Result<History, Box<dyn Error>> {
+        let unbounded = SessionQuery::default();
+        let history = load_sessions(&self.connection, content, before, Some(limit), &unbounded)?;
+        for session in history.iter() {
+            self.remember_version(&session.key())?;
+        }
+        Ok(history)
+    }
+    fn find_sessions(
+        &mut self,
+        content: &Content,
+        query: &SessionQuery,
+    ) -> Result<History, Box<dyn Error>> {
+        let history = load_sessions(&self.connection, content, None, None, query)?;
+        for session in history.iter() {
+            self.remember_version(&session.key())?;
         }
         Ok(history)
     }
+    fn reload_session(
+        &mut self,
+        content: &Content,
+        key: &DateTime,
+    ) -> Result<Option<Session>, Box<dyn Error>> {
+        // The latest session starting up to the key is the one, unless it is gone.
+        let before = DateTime::from(**key + time::Duration::nanoseconds(1));
+        let unbounded = SessionQuery::default();
+        let history = load_sessions(
+            &self.connection,
+            content,
+            Some(&before),
+            Some(1),
+            &unbounded,
+        )?;
+        self.remember_version(key)?;
+        Ok(history
+            .iter()
+            .find(|session| *session.key() == **key)
+            .cloned())
+    }
     fn create_action(&mut self, name: &str) -> Result<Action, Box<dyn Error>> {
         create_action(&self.connection, name)
     }
     fn create_subject(&mut self, name: &str) -> Result<Subject, Box<dyn Error>> {
         create_subject(&self.connection, name)
     }
+    fn create_context(&mut self, name: &str) -> Result<Context, Box<dyn Error>> {
+        create_context(&self.connection, name)
+    }
+    fn create_tag(&mut self, name: &str) -> Result<Tag, Box<dyn Error>> {
+        create_tag(&self.connection, name)
+    }
+    fn set_action_archived(
+        &mut self,
+        action: &Action,
+        archived: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        set_archived(&self.connection, "Actions", action.id, archived)
+    }
+    fn set_subject_archived(
+        &mut self,
+        subject: &Subject,
+        archived: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        set_archived(&self.connection, "Subjects", subject.id, archived)
+    }
+    fn set_action_temporary(
+        &mut self,
+        action: &Action,
+        temporary: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        set_temporary(&self.connection, "Actions", action.id, temporary)
+    }
+    fn set_subject_temporary(
+        &mut self,
+        subject: &Subject,
+        temporary: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        set_temporary(&self.connection, "Subjects", subject.id, temporary)
+    }
+    fn unused_since(
+        &mut self,
+        content: &Content,
+        before: &DateTime,
+    ) -> Result<Cleanup, Box<dyn Error>> {
+        let actions = last_used(&self.connection, "action")?;
+        let subjects = last_used(&self.connection, "subject")?;
+        let stale =
+            |used: Option<&time::OffsetDateTime>| used.map_or(false, |used| *used < **before);
+        Ok(Cleanup {
+            actions: content
+                .actions
+                .iter()
+                .filter(|a| !a.archived && stale(actions.get(&a.id)))
+                .cloned()
+                .collect(),
+            subjects: content
+                .subjects
+                .iter()
+                .filter(|s| !s.archived && stale(subjects.get(&s.id)))
+                .cloned()
+                .collect(),
+        })
+    }
+    fn rename_action(&mut self, action: &Action) -> Result<(), Box<dyn Error>> {
+        rename(&self.connection, "Actions", action.id, &action.name)
+    }
+    fn rename_subject(&mut self, subject: &Subject) -> Result<(), Box<dyn Error>> {
+        rename(&self.connection, "Subjects", subject.id, &subject.name)
+    }
+    fn set_issue_url(&mut self, subject: &Subject) -> Result<(), Box<dyn Error>> {
+        self.connection.execute(
+            "update Subjects set issue_url = ? where id = ?",
+            params![subject.issue_url.as_deref(), subject.id as u32],
+        )?;
+        Ok(())
+    }
+    fn set_note(&mut self, session: &Session) -> Result<(), Box<dyn Error>> {
+        check_unlocked(&self.connection, &session.key())?;
+        self.connection.execute(
+            "update History set note = ?, version = version + 1 where started = ?",
+            params![session.note.as_deref(), *session.key()],
+        )?;
+        self.remember_version(&session.key())
+    }
+    fn set_tags(&mut self, session: &Session) -> Result<(), Box<dyn Error>> {
+        check_unlocked(&self.connection, &session.key())?;
+        let transaction = self.connection.transaction()?;
+        set_tags(&transaction, &session.key(), &session.tags)?;
+        bump_version(&transaction, &session.key())?;
+        transaction.commit()?;
+        self.remember_version(&session.key())
+    }
+    fn set_estimate(&mut self, subject: &Subject) -> Result<(), Box<dyn Error>> {
+        self.connection.execute(
+            "update Subjects set estimate = ? where id = ?",
+            params![
+                subject.estimate.map(|estimate| estimate.as_secs() as u32),
+                subject.id as u32
+            ],
+        )?;
+        Ok(())
+    }
+    fn set_rate(&mut self, subject: &Subject) -> Result<(), Box<dyn Error>> {
+        self.connection.execute(
+            "update Subjects set rate = ? where id = ?",
+            params![subject.rate, subject.id as u32],
+        )?;
+        Ok(())
+    }
+    fn set_parent(&mut self, subject: &Subject) -> Result<(), Box<dyn Error>> {
+        self.connection.execute(
+            "update Subjects set parent = ? where id = ?",
+            params![
+                subject.parent.map(|parent| parent as u32),
+                subject.id as u32
+            ],
+        )?;
+        Ok(())
+    }
+    fn set_final_report(&mut self, subject: &Subject) -> Result<(), Box<dyn Error>> {
+        self.connection.execute(
+            "update Subjects set final_report = ? where id = ?",
+            params![subject.final_report.as_deref(), subject.id as u32],
+        )?;
+        Ok(())
+    }
+    fn set_idle_rule(&mut self, subject: &Subject) -> Result<(), Box<dyn Error>> {
+        self.connection.execute(
+            "update Subjects set idle_rule = ? where id = ?",
+            params![stored_idle_rule(subject.idle_rule), subject.id as u32],
+        )?;
+        Ok(())
+    }
+    fn set_action_color(&mut self, action: &Action) -> Result<(), Box<dyn Error>> {
+        set_color(&self.connection, "Actions", action.id, action.color)
+    }
+    fn set_subject_color(&mut self, subject: &Subject) -> Result<(), Box<dyn Error>> {
+        set_color(&self.connection, "Subjects", subject.id, subject.color)
+    }
+    fn set_action_order(&mut self, order: &ListOrder) -> Result<(), Box<dyn Error>> {
+        let transaction = self.connection.transaction()?;
+        set_order(&transaction, "Actions", order)?;
+        transaction.commit()?;
+        Ok(())
+    }
+    fn set_subject_order(&mut self, order: &ListOrder) -> Result<(), Box<dyn Error>> {
+        let transaction = self.connection.transaction()?;
+        set_order(&transaction, "Subjects", order)?;
+        transaction.commit()?;
+        Ok(())
+    }
+    fn set_pinned(&mut self, pinned: &Vector<Topic>) -> Result<(), Box<dyn Error>> {
+        let transaction = self.connection.transaction()?;
+        transaction.execute("delete from Pins", NO_PARAMS)?;
+        for (slot, topic) in pinned.iter().enumerate() {
+            transaction.execute(
+                "insert into Pins (slot, action, subject) values (?, ?, ?)",
+                params![slot as u32, topic.action.id as u32, topic.subject.id as u32],
+            )?;
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+    fn set_launch_rules(&mut self, rules: &Vector<LaunchRule>) -> Result<(), Box<dyn Error>> {
+        let transaction = self.connection.transaction()?;
+        transaction.execute("delete from LaunchRules", NO_PARAMS)?;
+        for rule in rules {
+            transaction.execute(
+                "insert into LaunchRules (process, action, subject) values (?, ?, ?)",
+                params![
+                    rule.process.as_ref(),
+                    rule.topic.action.id as u32,
+                    rule.topic.subject.id as u32
+                ],
+            )?;
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+    fn set_locked_before(&mut self, before: Option<Date>) -> Result<(), Box<dyn Error>> {
+        let transaction = self.connection.transaction()?;
+        set_locked_before(&transaction, before)?;
+        transaction.commit()?;
+        Ok(())
+    }
+    fn set_goals(&mut self, goals: &Vector<Goal>) -> Result<(), Box<dyn Error>> {
+        let transaction = self.connection.transaction()?;
+        transaction.execute("delete from Goals", NO_PARAMS)?;
+        for goal in goals {
+            transaction.execute(
+                "insert into Goals (action, subject, period, seconds) values (?, ?, ?, ?)",
+                params![
+                    goal.topic.action.id as u32,
+                    goal.topic.subject.id as u32,
+                    stored_period(goal.period),
+                    goal.target.as_secs() as u32,
+                ],
+            )?;
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+    fn set_days_off(&mut self, days_off: &Vector<DayOff>) -> Result<(), Box<dyn Error>> {
+        let transaction = self.connection.transaction()?;
+        transaction.execute("delete from DaysOff", NO_PARAMS)?;
+        for day_off in days_off {
+            let kind = match day_off.kind {
+                DayOffKind::Holiday => 0,
+                DayOffKind::Vacation => 1,
+            };
+            transaction.execute(
+                "insert into DaysOff (day, kind) values (?, ?)",
+                params![day_off.day.format(DATE_FORMAT), kind as u32],
+            )?;
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+    fn set_adjustments(&mut self, adjustments: &Vector<Adjustment>) -> Result<(), Box<dyn Error>> {
+        let transaction = self.connection.transaction()?;
+        transaction.execute("delete from Adjustments", NO_PARAMS)?;
+        for adjustment in adjustments {
+            transaction.execute(
+                "insert into Adjustments (day, seconds, note) values (?, ?, ?)",
+                params![
+                    adjustment.day.format(DATE_FORMAT),
+                    adjustment.seconds,
+                    adjustment.note.as_ref()
+                ],
+            )?;
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+    fn import_backup(&mut self, backup: &Backup) -> Result<usize, Box<dyn Error>> {
+        let content = self.load_content()?;
+        let transaction = self.connection.transaction()?;
+        let added = import_backup(&transaction, &content, backup)?;
+        transaction.commit()?;
+        Ok(added)
+    }
+    fn delete_action(&mut self, action: &Action) -> Result<(), Box<dyn Error>> {
+        let transaction = self.connection.transaction()?;
+        delete_action(&transaction, action)?;
+        transaction.commit()?;
+        Ok(())
+    }
+    fn delete_subject(&mut self, subject: &Subject) -> Result<(), Box<dyn Error>> {
+        let transaction = self.connection.transaction()?;
+        delete_subject(&transaction, subject)?;
+        transaction.commit()?;
+        Ok(())
+    }
+    fn merge_actions(&mut self, merge: &Merge<Action>) -> Result<(), Box<dyn Error>> {
+        let transaction = self.connection.transaction()?;
+        let moved = merge_entries(&transaction, "action", merge.from.id, merge.into.id)?;
+        transaction.commit()?;
+        for key in &moved {
+            self.remember_version(key)?;
+        }
+        Ok(())
+    }
+    fn merge_subjects(&mut self, merge: &Merge<Subject>) -> Result<(), Box<dyn Error>> {
+        let transaction = self.connection.transaction()?;
+        let moved = merge_entries(&transaction, "subject", merge.from.id, merge.into.id)?;
+        transaction.commit()?;
+        for key in &moved {
+            self.remember_version(key)?;
+        }
+        Ok(())
+    }
+    fn split_actions(&mut self, merge: &Merge<Action>) -> Result<(), Box<dyn Error>> {
+        let transaction = self.connection.transaction()?;
+        let created = create_action(&transaction, &merge.from.name)?;
+        split_entries(&transaction, "action", merge, merge.into.id, created.id)?;
+        restore_action(&transaction, &merge.from, created.id)?;
+        transaction.commit()?;
+        for key in &merge.sessions {
+            self.remember_version(key)?;
+        }
+        Ok(())
+    }
+    fn split_subjects(&mut self, merge: &Merge<Subject>) -> Result<(), Box<dyn Error>> {
+        let transaction = self.connection.transaction()?;
+        let created = create_subject(&transaction, &merge.from.name)?;
+        split_entries(&transaction, "subject", merge, merge.into.id, created.id)?;
+        restore_subject(&transaction, merge, &created)?;
+        transaction.commit()?;
+        for key in &merge.sessions {
+            self.remember_version(key)?;
+        }
+        Ok(())
+    }
+    fn set_category(
+        &mut self,
+        subject: &Subject,
+        category: Option<&str>,
+    ) -> Result<(), Box<dyn Error>> {
+        set_category(&self.connection, subject, category)
+    }
+    fn create_checklist_item(
+        &mut self,
+        subject: &Subject,
+        text: &str,
+    ) -> Result<ChecklistItem, Box<dyn Error>> {
+        create_checklist_item(&self.connection, subject, text)
+    }
+    fn set_checklist_item_done(&mut self, id: usize, done: bool) -> Result<(), Box<dyn Error>> {
+        self.connection.execute(
+            "update Checklists set done = ? where id = ?",
+            params![done, id as u32],
+        )?;
+        Ok(())
+    }
+    fn add_alias(&mut self, alias: &Alias) -> Result<(), Box<dyn Error>> {
+        let (action, subject) = alias_target(alias);
+        self.connection.execute(
+            "insert into Aliases (name, action, subject) values (?, ?, ?)",
+            params![alias.name.as_ref(), action, subject],
+        )?;
+        Ok(())
+    }
+    fn delete_alias(&mut self, alias: &Alias) -> Result<(), Box<dyn Error>> {
+        let (action, subject) = alias_target(alias);
+        self.connection.execute(
+            "delete from Aliases where name = ? and action is ? and subject is ?",
+            params![alias.name.as_ref(), action, subject],
+        )?;
+        Ok(())
+    }
     fn update_time(&mut self, topic: &Topic, time: &SpentTime) -> Result<(), Box<dyn Error>> {
         update_time(&self.connection, topic, time)
     }
+    fn add_time(&mut self, topic: &Topic, time: &SpentTime) -> Result<(), Box<dyn Error>> {
+        change_time(&self.connection, topic, time.as_secs() as i64)
+    }
     fn add_session(&mut self, session: &Session) -> Result<(), Box<dyn Error>> {
-        add_session(&self.connection, session)
+        let transaction = self.connection.transaction()?;
+        add_session(&transaction, session)?;
+        transaction.commit()?;
+        self.remember_version(&session.key())
+    }
+    fn amend_session(
+        &mut self,
+        original: &Session,
+        amended: Option<&Session>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.check_version(&original.key())?;
+        let transaction = self.connection.transaction()?;
+        amend_session(&transaction, original, amended)?;
+        transaction.commit()?;
+        self.remember_version(&original.key())
+    }
+    fn update_session(
+        &mut self,
+        original: &Session,
+        updated: &Session,
+    ) -> Result<(), Box<dyn Error>> {
+        self.check_version(&original.key())?;
+        let transaction = self.connection.transaction()?;
+        update_session(&transaction, original, updated)?;
+        transaction.commit()?;
+        self.versions.remove(&*original.key());
+        self.remember_version(&updated.started)
+    }
+    fn replace_sessions(
+        &mut self,
+        replacements: &Vector<(Session, Session)>,
+        strict: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        for (original, _) in replacements {
+            self.check_version(&original.key())?;
+        }
+        let transaction = self.connection.transaction()?;
+        replace_sessions(&transaction, replacements, strict)?;
+        transaction.commit()?;
+        self.remember_replaced(replacements, strict)
+    }
+    fn move_sessions(
+        &mut self,
+        content: &Content,
+        moves: &Vector<(Session, Arc<str>)>,
+        strict: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        for (original, _) in moves {
+            self.check_version(&original.key())?;
+        }
+        let transaction = self.connection.transaction()?;
+        let mut content = content.clone();
+        let mut replacements = Vector::new();
+        for (original, name) in moves {
+            let subject = match content.subject_named(name) {
+                Some(subject) => subject.clone(),
+                None => {
+                    let subject = create_subject(&transaction, name)?;
+                    content.subjects.push_back(subject.clone());
+                    subject
+                }
+            };
+            let mut moved = original.clone();
+            moved.topic.subject = subject;
+            replacements.push_back((original.clone(), moved));
+        }
+        replace_sessions(&transaction, &replacements, strict)?;
+        transaction.commit()?;
+        self.remember_replaced(&replacements, strict)
+    }
+    fn delete_session(&mut self, session: &Session) -> Result<(), Box<dyn Error>> {
+        self.check_version(&session.key())?;
+        let transaction = self.connection.transaction()?;
+        delete_session(&transaction, session)?;
+        transaction.commit()?;
+        self.versions.remove(&*session.key());
+        Ok(())
+    }
+    fn restore_session(&mut self, session: &Session) -> Result<(), Box<dyn Error>> {
+        let transaction = self.connection.transaction()?;
+        restore_session(&transaction, session)?;
+        transaction.commit()?;
+        self.remember_version(&session.key())
+    }
+    fn save_settings(&mut self, settings: &Settings) -> Result<(), Box<dyn Error>> {
+        let transaction = self.connection.transaction()?;
+        set_flag(&transaction, "strict_sessions", settings.strict_sessions)?;
+        set_flag(
+            &transaction,
+            "split_at_midnight",
+            settings.split_at_midnight,
+        )?;
+        set_flag(&transaction, "sign_exports", settings.sign_exports)?;
+        set_setting(
+            &transaction,
+            "idle_minutes",
+            &settings.idle_minutes.to_string(),
+        )?;
+        set_flag(&transaction, "pause_on_lock", settings.pause_on_lock)?;
+        set_flag(&transaction, "end_when_away", settings.end_when_away)?;
+        set_flag(&transaction, "resume_on_unlock", settings.resume_on_unlock)?;
+        set_setting(
+            &transaction,
+            "long_session_minutes",
+            &settings.long_session_minutes.to_string(),
+        )?;
+        set_flag(
+            &transaction,
+            "confirm_delete_session",
+            settings.confirm_delete_session,
+        )?;
+        set_flag(
+            &transaction,
+            "confirm_delete_topic",
+            settings.confirm_delete_topic,
+        )?;
+        set_setting(
+            &transaction,
+            "confirm_stop_minutes",
+            &settings.confirm_stop_minutes.to_string(),
+        )?;
+        set_flag(&transaction, "confirm_import", settings.confirm_import)?;
+        set_flag(&transaction, "watch_launches", settings.watch_launches)?;
+        set_setting(
+            &transaction,
+            "milestone_minutes",
+            &settings.milestone_minutes.to_string(),
+        )?;
+        set_setting(&transaction, "speech_command", &settings.speech_command)?;
+        set_setting(&transaction, "work_hours", &settings.work_hours)?;
+        set_setting(
+            &transaction,
+            "remind_idle_minutes",
+            &settings.remind_idle_minutes.to_string(),
+        )?;
+        set_setting(
+            &transaction,
+            "weekly_hours",
+            &settings.weekly_hours.to_string(),
+        )?;
+        set_setting(&transaction, "ledger_start", &settings.ledger_start)?;
+        set_setting(
+            &transaction,
+            "name_length",
+            &settings.name_length.to_string(),
+        )?;
+        set_flag(&transaction, "pomodoro", settings.pomodoro)?;
+        set_setting(
+            &transaction,
+            "work_minutes",
+            &settings.work_minutes.to_string(),
+        )?;
+        set_setting(
+            &transaction,
+            "break_minutes",
+            &settings.break_minutes.to_string(),
+        )?;
+        set_setting(
+            &transaction,
+            "stale_months",
+            &settings.stale_months.to_string(),
+        )?;
+        set_setting(
+            &transaction,
+            "backups_kept",
+            &settings.backups_kept.to_string(),
+        )?;
+        set_setting(
+            &transaction,
+            "month_start",
+            &settings.month_start.to_string(),
+        )?;
+        set_setting(
+            &transaction,
+            "streak_minutes",
+            &settings.streak_minutes.to_string(),
+        )?;
+        set_flag(&transaction, "close_to_tray", settings.close_to_tray)?;
+        set_flag(&transaction, "color_by_action", settings.color_by_action)?;
+        set_flag(&transaction, "hide_seconds", settings.hide_seconds)?;
+        set_setting(&transaction, "hotkey", &settings.hotkey)?;
+        set_setting(&transaction, "dashboard_port", &settings.dashboard_port)?;
+        set_setting(
+            &transaction,
+            "rounding_minutes",
+            &settings.rounding_minutes.to_string(),
+        )?;
+        set_flag(&transaction, "round_up", settings.round_up)?;
+        set_flag(&transaction, "round_per_day", settings.round_per_day)?;
+        set_setting(&transaction, "money_locale", &settings.money_locale)?;
+        set_setting(&transaction, "currency", &settings.currency)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn load_running(&mut self, content: &Content) -> Result<Option<Running>, Box<dyn Error>> {
+        let mut query = self
+            .connection
+            .prepare_cached("select started, action, subject, context, utc_offset from Running")?;
+        let mut rows = query.query(NO_PARAMS)?;
+        let row = match rows.next()? {
+            Some(row) => row,
+            None => return Ok(None),
+        };
+        let missing = |kind: &str, id: usize| {
+            NoneError::new(format!(
+                "A {} with id {} has a running session but does not exist.",
+                kind, id
+            ))
+        };
+        let action = row.get::<_, u32>("action")? as usize;
+        let subject = row.get::<_, u32>("subject")? as usize;
+        let context = row.get::<_, Option<u32>>("context")?;
+        Ok(Some(Running {
+            topic: Topic {
+                action: content
+                    .find_action(action)
+                    .ok_or_else(|| missing("action", action))?,
+                subject: content
+                    .find_subject(subject)
+                    .ok_or_else(|| missing("subject", subject))?,
+            },
+            context: match context {
+                Some(id) => Some(
+                    content
+                        .find_context(id as usize)
+                        .ok_or_else(|| missing("context", id as usize))?,
+                ),
+                None => None,
+            },
+            started: DateTime::in_offset(row.get("started")?, row.get("utc_offset")?),
+        }))
+    }
+    fn set_running(&mut self, running: Option<&Running>) -> Result<(), Box<dyn Error>> {
+        let transaction = self.connection.transaction()?;
+        transaction.execute("delete from Running", NO_PARAMS)?;
+        if let Some(running) = running {
+            transaction.execute(
+                "\
+                insert into Running (started, action, subject, context, utc_offset) \
+                values (?, ?, ?, ?, ?)",
+                params![
+                    *running.started,
+                    running.topic.action.id as u32,
+                    running.topic.subject.id as u32,
+                    running.context.as_ref().map(|c| c.id as u32),
+                    running.started.offset().as_seconds(),
+                ],
+            )?;
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn check_integrity(&mut self) -> Result<Vec<String>, Box<dyn Error>> {
+        let mut query = self.connection.prepare("pragma quick_check")?;
+        let problems = query
+            .query_map(NO_PARAMS, |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(problems.into_iter().filter(|p| p != "ok").collect())
+    }
+
+    fn repair(&mut self) -> Result<(), Box<dyn Error>> {
+        self.connection.execute_batch("reindex; vacuum;")?;
+        Ok(())
+    }
+
+    fn snapshot(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        self.connection.backup(DatabaseName::Main, path, None)?;
+        Ok(())
+    }
+
+    fn restore_snapshot(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        self.connection.restore(
+            DatabaseName::Main,
+            path,
+            None::<fn(rusqlite::backup::Progress)>,
+        )?;
+        // Every session may differ now, and the copy may be of an older version.
+        self.versions.clear();
+        self.setup()?;
+        Ok(())
     }
 }
 
@@ -0,0 +1,13 @@
+create table Pins (
+    slot integer,
+    action integer not null,
+    subject integer not null,
+
+    primary key (slot),
+    foreign key (action)
+        references Actions (id),
+    foreign key (subject)
+        references Subjects (id)
+);
+
+update Meta set value = '10' where key = 'version';
@@ -0,0 +1,4 @@
+alter table Actions add column temporary_since timestamp;
+alter table Subjects add column temporary_since timestamp;
+
+update Meta set value = '11' where key = 'version';
@@ -0,0 +1,5 @@
+alter table History add column note text;
+
+alter table Amendments add column note text;
+
+update Meta set value = '12' where key = 'version';
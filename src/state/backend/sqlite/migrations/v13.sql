@@ -0,0 +1,3 @@
+alter table Subjects add column issue_url text;
+
+update Meta set value = '13' where key = 'version';
@@ -0,0 +1,14 @@
+create table Goals (
+    action integer not null,
+    subject integer not null,
+    period integer not null,
+    seconds integer not null,
+
+    primary key (action, subject),
+    foreign key (action)
+        references Actions (id),
+    foreign key (subject)
+        references Subjects (id)
+);
+
+update Meta set value = '14' where key = 'version';
@@ -0,0 +1,9 @@
+create table AuditLog (
+    id integer,
+    at timestamp not null,
+    event text not null,
+
+    primary key (id)
+);
+
+update Meta set value = '15' where key = 'version';
@@ -0,0 +1,16 @@
+create table Running (
+    started timestamp not null,
+    action integer not null,
+    subject integer not null,
+    context integer,
+
+    primary key (started),
+    foreign key (action)
+        references Actions (id),
+    foreign key (subject)
+        references Subjects (id),
+    foreign key (context)
+        references Contexts (id)
+);
+
+update Meta set value = '16' where key = 'version';
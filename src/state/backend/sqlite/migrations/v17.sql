@@ -0,0 +1,3 @@
+alter table Subjects add column estimate integer;
+
+update Meta set value = '17' where key = 'version';
@@ -0,0 +1,19 @@
+create table Tags (
+    id integer,
+    name text not null unique,
+
+    primary key (id)
+);
+
+create table SessionTags (
+    session text not null,
+    tag integer not null,
+
+    primary key (session, tag),
+    foreign key (session)
+        references History (started),
+    foreign key (tag)
+        references Tags (id)
+);
+
+update Meta set value = '18' where key = 'version';
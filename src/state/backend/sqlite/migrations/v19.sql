@@ -0,0 +1,3 @@
+alter table History add column version integer not null default 0;
+
+update Meta set value = '19' where key = 'version';
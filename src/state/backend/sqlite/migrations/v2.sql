@@ -0,0 +1,11 @@
+create table Interruptions (
+    session text not null,
+    at text not null,
+    cause text,
+
+    primary key (session, at),
+    foreign key (session)
+        references History (started)
+);
+
+update Meta set value = '2' where key = 'version';
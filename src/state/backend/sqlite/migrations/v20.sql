@@ -0,0 +1,3 @@
+alter table Subjects add column idle_rule text;
+
+update Meta set value = '20' where key = 'version';
@@ -0,0 +1,4 @@
+alter table Actions add column color integer;
+alter table Subjects add column color integer;
+
+update Meta set value = '21' where key = 'version';
@@ -0,0 +1,6 @@
+-- Sessions keep the stretches worked instead of the time paused, null if
+-- worked throughout. Earlier pauses are converted when migrating.
+alter table History add column intervals text;
+alter table Amendments add column intervals text;
+
+update Meta set value = '22' where key = 'version';
@@ -0,0 +1,13 @@
+-- Other names actions and subjects are found by, each standing for either.
+create table Aliases (
+    name text not null,
+    action integer,
+    subject integer,
+
+    foreign key (action)
+        references Actions (id),
+    foreign key (subject)
+        references Subjects (id)
+);
+
+update Meta set value = '23' where key = 'version';
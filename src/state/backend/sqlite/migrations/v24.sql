@@ -0,0 +1,7 @@
+-- Offset east of UTC in seconds the session was recorded in, timestamps are
+-- stored in UTC. Sessions recorded before are shown in the local zone.
+alter table History add column utc_offset integer;
+alter table Amendments add column utc_offset integer;
+alter table Running add column utc_offset integer;
+
+update Meta set value = '24' where key = 'version';
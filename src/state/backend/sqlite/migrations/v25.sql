@@ -0,0 +1,5 @@
+-- Hourly rate in cents the time spent on a subject is billed at, null if it
+-- is not billable.
+alter table Subjects add column rate integer;
+
+update Meta set value = '25' where key = 'version';
@@ -0,0 +1,9 @@
+-- Place of an action or subject in its selection list once arranged by
+-- dragging, null before. Favorites are listed first.
+alter table Actions add column position integer;
+alter table Actions add column favorite integer not null default 0;
+
+alter table Subjects add column position integer;
+alter table Subjects add column favorite integer not null default 0;
+
+update Meta set value = '26' where key = 'version';
@@ -0,0 +1,20 @@
+-- Days no work is expected on for the overtime balance. Kind 0 is a
+-- holiday, 1 a day of vacation.
+create table DaysOff (
+    day text,
+    kind integer not null,
+
+    primary key (day)
+);
+
+-- Time added to the overtime balance by hand, or taken from it if negative.
+create table Adjustments (
+    id integer,
+    day text not null,
+    seconds integer not null,
+    note text not null,
+
+    primary key (id)
+);
+
+update Meta set value = '27' where key = 'version';
@@ -0,0 +1,5 @@
+-- Subject a subject is nested in, like a sub-project in its project. Null
+-- for top-level subjects.
+alter table Subjects add column parent integer references Subjects (id);
+
+update Meta set value = '28' where key = 'version';
@@ -0,0 +1,4 @@
+-- Summary written when a subject was archived as a closed project.
+alter table Subjects add column final_report text;
+
+update Meta set value = '29' where key = 'version';
@@ -0,0 +1,12 @@
+create table Contexts (
+    id integer,
+    name text not null unique,
+
+    primary key (id)
+);
+
+insert into Contexts (name) values ('Home'), ('Office'), ('Train');
+
+alter table History add column context integer references Contexts (id);
+
+update Meta set value = '3' where key = 'version';
@@ -0,0 +1,14 @@
+-- Topics offered to start when the process of the given name is launched.
+create table LaunchRules (
+    process text not null,
+    action integer not null,
+    subject integer not null,
+
+    primary key (process),
+    foreign key (action)
+        references Actions (id),
+    foreign key (subject)
+        references Subjects (id)
+);
+
+update Meta set value = '30' where key = 'version';
@@ -0,0 +1,10 @@
+create table Categories (
+    subject integer,
+    category text not null,
+
+    primary key (subject),
+    foreign key (subject)
+        references Subjects (id)
+);
+
+update Meta set value = '4' where key = 'version';
@@ -0,0 +1,12 @@
+create table Checklists (
+    id integer,
+    subject integer not null,
+    text text not null,
+    done integer not null default 0,
+
+    primary key (id),
+    foreign key (subject)
+        references Subjects (id)
+);
+
+update Meta set value = '5' where key = 'version';
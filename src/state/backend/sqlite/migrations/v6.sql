@@ -0,0 +1,11 @@
+create table Totals (
+    grouping integer not null,
+    action integer not null,
+    subject integer not null,
+    context integer not null,
+    duration integer not null,
+
+    primary key (grouping, action, subject, context)
+);
+
+update Meta set value = '6' where key = 'version';
@@ -0,0 +1,30 @@
+create table Settings (
+    key text,
+    value text not null,
+
+    primary key (key)
+);
+
+create table Amendments (
+    id integer,
+    session text not null,
+    amended_at text not null,
+    started text,
+    ended text,
+    action integer,
+    subject integer,
+    context integer,
+    removed integer not null default 0,
+
+    primary key (id),
+    foreign key (session)
+        references History (started),
+    foreign key (action)
+        references Actions (id),
+    foreign key (subject)
+        references Subjects (id),
+    foreign key (context)
+        references Contexts (id)
+);
+
+update Meta set value = '7' where key = 'version';
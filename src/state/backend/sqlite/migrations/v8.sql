@@ -0,0 +1,5 @@
+alter table History add column paused integer not null default 0;
+
+alter table Amendments add column paused integer not null default 0;
+
+update Meta set value = '8' where key = 'version';
@@ -0,0 +1,5 @@
+alter table Actions add column archived integer not null default 0;
+
+alter table Subjects add column archived integer not null default 0;
+
+update Meta set value = '9' where key = 'version';
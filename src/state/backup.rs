@@ -0,0 +1,337 @@
+use super::{goal::Goal, hierarchy, idle::IdleRule, report::Period, Content, History};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, error::Error, io::Write, path::Path};
+
+pub const VERSION: u32 = 1;
+
+/// Format of the timestamps in a backup, like `2020-06-01T09:30:00.000000000+0200`.
+pub static TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S.%N%z";
+
+/// All recorded data, to be kept as a backup or moved to another machine.
+///
+/// Entries refer to each other by name, so a backup can be imported into
+/// existing data. Settings are not included as they belong to the machine.
+/// Durations are given in whole seconds.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Backup {
+    pub version: u32,
+    #[serde(default)]
+    pub actions: Vec<BackupAction>,
+    #[serde(default)]
+    pub subjects: Vec<BackupSubject>,
+    #[serde(default)]
+    pub contexts: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub sessions: Vec<BackupSession>,
+    #[serde(default)]
+    pub goals: Vec<BackupGoal>,
+    /// Topics started with the number keys, in the order of their keys.
+    #[serde(default)]
+    pub pinned: Vec<BackupTopic>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BackupAction {
+    pub name: String,
+    #[serde(default)]
+    pub archived: bool,
+    /// Written like `#3a7bd5`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BackupSubject {
+    pub name: String,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<u64>,
+    /// Hourly rate in cents.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle_rule: Option<String>,
+    /// Written like `#3a7bd5`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Name of the subject it is nested in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub final_report: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BackupTopic {
+    pub action: String,
+    pub subject: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BackupSession {
+    pub action: String,
+    pub subject: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    pub started: String,
+    pub ended: String,
+    /// Time between the start and the end that was not worked.
+    #[serde(default)]
+    pub paused: u64,
+    /// Stretches worked, only given if there were breaks.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub intervals: Vec<BackupInterval>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interruptions: Vec<BackupInterruption>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BackupInterval {
+    pub started: String,
+    pub ended: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BackupInterruption {
+    pub at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cause: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BackupGoal {
+    pub action: String,
+    pub subject: String,
+    /// One of `day`, `week` or `month`.
+    pub period: String,
+    pub target: u64,
+}
+
+impl BackupGoal {
+    pub fn new(goal: &Goal) -> Self {
+        BackupGoal {
+            action: goal.topic.action.name.to_string(),
+            subject: goal.topic.subject.name.to_string(),
+            period: match goal.period {
+                Period::Day => "day",
+                Period::Week => "week",
+                Period::Month => "month",
+            }
+            .to_string(),
+            target: goal.target.as_secs(),
+        }
+    }
+
+    pub fn period(&self) -> Result<Period, Box<dyn Error>> {
+        match self.period.as_str() {
+            "day" => Ok(Period::Day),
+            "week" => Ok(Period::Week),
+            "month" => Ok(Period::Month),
+            other => Err(format!("Unknown goal period '{}'.", other).into()),
+        }
+    }
+}
+
+impl Backup {
+    pub fn new(content: &Content, history: &History) -> Self {
+        let timestamp = |dt: &time::OffsetDateTime| dt.format(TIMESTAMP_FORMAT);
+        let topic = |topic: &super::Topic| BackupTopic {
+            action: topic.action.name.to_string(),
+            subject: topic.subject.name.to_string(),
+        };
+        Backup {
+            version: VERSION,
+            actions: content
+                .actions
+                .iter()
+                .map(|action| BackupAction {
+                    name: action.name.to_string(),
+                    archived: action.archived,
+                    color: action.color.map(color_hex),
+                })
+                .collect(),
+            subjects: content
+                .subjects
+                .iter()
+                .map(|subject| BackupSubject {
+                    name: subject.name.to_string(),
+                    archived: subject.archived,
+                    category: content.categories.get(subject).map(|c| c.to_string()),
+                    issue_url: subject.issue_url.as_ref().map(|url| url.to_string()),
+                    estimate: subject.estimate.map(|estimate| estimate.as_secs()),
+                    rate: subject.rate,
+                    idle_rule: match subject.idle_rule {
+                        IdleRule::Default => None,
+                        rule => Some(rule.to_string()),
+                    },
+                    color: subject.color.map(color_hex),
+                    parent: hierarchy::parent(&content.subjects, subject)
+                        .map(|parent| parent.name.to_string()),
+                    final_report: subject.final_report.as_ref().map(|r| r.to_string()),
+                })
+                .collect(),
+            contexts: content
+                .contexts
+                .iter()
+                .map(|c| c.name.to_string())
+                .collect(),
+            tags: content.tags.iter().map(|t| t.name.to_string()).collect(),
+            sessions: history
+                .iter()
+                .map(|session| BackupSession {
+                    action: session.topic.action.name.to_string(),
+                    subject: session.topic.subject.name.to_string(),
+                    context: session.context.as_ref().map(|c| c.name.to_string()),
+                    started: timestamp(&session.started),
+                    ended: timestamp(&session.ended),
+                    paused: session.breaks().as_secs(),
+                    intervals: session
+                        .intervals
+                        .iter()
+                        .map(|interval| BackupInterval {
+                            started: timestamp(&interval.started),
+                            ended: timestamp(&interval.ended),
+                        })
+                        .collect(),
+                    note: session.note.as_ref().map(|note| note.to_string()),
+                    interruptions: session
+                        .interruptions
+                        .iter()
+                        .map(|interruption| BackupInterruption {
+                            at: timestamp(&interruption.at),
+                            cause: interruption.cause.as_ref().map(|c| c.to_string()),
+                        })
+                        .collect(),
+                    tags: session.tags.iter().map(|t| t.name.to_string()).collect(),
+                })
+                .collect(),
+            goals: content.goals.iter().map(BackupGoal::new).collect(),
+            pinned: content.pinned.iter().map(topic).collect(),
+        }
+    }
+
+    pub fn read(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let backup: Backup = serde_json::from_slice(&std::fs::read(path)?)?;
+        if backup.version > VERSION {
+            return Err(format!(
+                "The backup uses version {} but only {} is supported.",
+                backup.version, VERSION
+            )
+            .into());
+        }
+        Ok(backup)
+    }
+
+    /// The same backup with every name replaced by a numbered one like
+    /// `Subject 3`, and without notes, causes of interruptions, issue links,
+    /// rates and final reports. What was recorded when stays as it is, to
+    /// share it without giving away anything about the work.
+    pub fn anonymized(mut self) -> Self {
+        let actions = pseudonyms("Action", self.actions.iter().map(|a| &a.name));
+        let subjects = pseudonyms("Subject", self.subjects.iter().map(|s| &s.name));
+        let categories = pseudonyms(
+            "Category",
+            self.subjects.iter().filter_map(|s| s.category.as_ref()),
+        );
+        let contexts = pseudonyms("Context", self.contexts.iter());
+        let tags = pseudonyms("Tag", self.tags.iter());
+        for action in &mut self.actions {
+            rename(&actions, &mut action.name);
+        }
+        for subject in &mut self.subjects {
+            rename(&subjects, &mut subject.name);
+            if let Some(parent) = &mut subject.parent {
+                rename(&subjects, parent);
+            }
+            if let Some(category) = &mut subject.category {
+                rename(&categories, category);
+            }
+            subject.issue_url = None;
+            subject.rate = None;
+            subject.final_report = None;
+        }
+        for context in &mut self.contexts {
+            rename(&contexts, context);
+        }
+        for tag in &mut self.tags {
+            rename(&tags, tag);
+        }
+        for session in &mut self.sessions {
+            rename(&actions, &mut session.action);
+            rename(&subjects, &mut session.subject);
+            if let Some(context) = &mut session.context {
+                rename(&contexts, context);
+            }
+            for tag in &mut session.tags {
+                rename(&tags, tag);
+            }
+            session.note = None;
+            for interruption in &mut session.interruptions {
+                interruption.cause = None;
+            }
+        }
+        for goal in &mut self.goals {
+            rename(&actions, &mut goal.action);
+            rename(&subjects, &mut goal.subject);
+        }
+        for topic in &mut self.pinned {
+            rename(&actions, &mut topic.action);
+            rename(&subjects, &mut topic.subject);
+        }
+        self
+    }
+
+    pub fn write(&self, out: &mut impl Write) -> std::io::Result<()> {
+        serde_json::to_writer_pretty(out, self)?;
+        Ok(())
+    }
+}
+
+/// Numbers the `names` in their order, like `Tag 1`, `Tag 2`.
+fn pseudonyms<'a>(kind: &str, names: impl Iterator<Item = &'a String>) -> HashMap<String, String> {
+    let mut pseudonyms = HashMap::new();
+    for name in names {
+        let number = pseudonyms.len() + 1;
+        pseudonyms
+            .entry(name.clone())
+            .or_insert_with(|| format!("{} {}", kind, number));
+    }
+    pseudonyms
+}
+
+fn rename(pseudonyms: &HashMap<String, String>, name: &mut String) {
+    if let Some(pseudonym) = pseudonyms.get(name) {
+        *name = pseudonym.clone();
+    }
+}
+
+/// Parses a timestamp written in the `TIMESTAMP_FORMAT`.
+pub fn parse_timestamp(text: &str) -> Result<time::OffsetDateTime, Box<dyn Error>> {
+    time::OffsetDateTime::parse(text, TIMESTAMP_FORMAT)
+        .map_err(|err| format!("Invalid timestamp '{}': {}", text, err).into())
+}
+
+/// Writes a color as `#rrggbb`.
+pub fn color_hex(color: u32) -> String {
+    format!("#{:06x}", color & 0xff_ff_ff)
+}
+
+/// Reads a color written like `#rrggbb`, `None` if it is not one.
+pub fn parse_color(text: &str) -> Option<u32> {
+    let hex = text.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok()
+}
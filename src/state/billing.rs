@@ -0,0 +1,94 @@
+//! Hourly rates of billable subjects and the amounts earned with them.
+//! Amounts are kept in cents, so that adding them up does not drift.
+
+use super::{Settings, SpentTime, Subject};
+
+/// How amounts are written in a locale, which is chosen apart from the
+/// language of the interface as invoices often go elsewhere.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MoneyFormat {
+    /// Between groups of thousands.
+    thousands: char,
+    decimal: char,
+    /// Whether the currency comes before the amount.
+    currency_first: bool,
+    /// Whether a space separates the currency from the amount.
+    spaced: bool,
+}
+
+impl MoneyFormat {
+    /// The format of a locale like `de-CH` or `fr`, unknown ones are written
+    /// like in English.
+    pub fn for_locale(locale: &str) -> Self {
+        let locale = locale.trim().replace('_', "-").to_lowercase();
+        let language = locale.split('-').next().unwrap_or_default();
+        let swiss = locale.ends_with("-ch") || locale.ends_with("-li");
+        let (thousands, decimal, currency_first, spaced) = match language {
+            "de" | "fr" | "it" if swiss => ('’', '.', true, true),
+            "de" | "es" | "it" | "pt" | "da" | "tr" | "id" => ('.', ',', false, true),
+            "nl" => ('.', ',', true, true),
+            "fr" | "fi" | "sv" | "nb" | "no" | "cs" | "sk" | "pl" | "ru" | "uk" => {
+                ('\u{202f}', ',', false, true)
+            }
+            _ => (',', '.', true, false),
+        };
+        MoneyFormat {
+            thousands,
+            decimal,
+            currency_first,
+            spaced,
+        }
+    }
+
+    /// Writes an amount in cents with the currency, if there is one.
+    pub fn format(&self, cents: u64, currency: &str) -> String {
+        let units = (cents / 100).to_string();
+        let mut number = String::new();
+        for (index, digit) in units.chars().enumerate() {
+            if index > 0 && (units.len() - index) % 3 == 0 {
+                number.push(self.thousands);
+            }
+            number.push(digit);
+        }
+        number.push(self.decimal);
+        number.push_str(&format!("{:02}", cents % 100));
+        // Kept on one line with the amount.
+        let space = if self.spaced { "\u{a0}" } else { "" };
+        match currency.trim() {
+            "" => number,
+            currency if self.currency_first => format!("{}{}{}", currency, space, number),
+            currency => format!("{}{}{}", number, space, currency),
+        }
+    }
+}
+
+/// Writes an amount in cents as configured in the settings.
+pub fn money(settings: &Settings, cents: u64) -> String {
+    MoneyFormat::for_locale(&settings.money_locale).format(cents, &settings.currency)
+}
+
+/// The amount earned with `spent` on `subject`, `None` if it is not billable.
+pub fn earned(subject: &Subject, spent: SpentTime) -> Option<u64> {
+    let rate = subject.rate?;
+    Some((rate as f64 * spent.as_secs_f64() / 3600.0).round() as u64)
+}
+
+/// Writes an amount in cents like `1234.50`, to be read back by `parse_rate`.
+pub fn amount(cents: u64) -> String {
+    format!("{}.{:02}", cents / 100, cents % 100)
+}
+
+/// Reads an hourly rate like `80` or `92.50` into cents, an empty one
+/// makes the subject not billable.
+pub fn parse_rate(text: &str) -> Result<Option<u32>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    match text.replace(',', ".").parse::<f64>() {
+        Ok(rate) if rate >= 0.0 && rate < u32::MAX as f64 / 100.0 => {
+            Ok(Some((rate * 100.0).round() as u32))
+        }
+        _ => Err(format!("Expected a rate like 80 or 92.50, not '{}'.", text)),
+    }
+}
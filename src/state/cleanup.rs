@@ -0,0 +1,24 @@
+use super::{Action, DateTime, Subject};
+#[cfg(feature = "gui")]
+use druid::{Data, Lens};
+use im::Vector;
+
+/// Actions and subjects not used for a while, suggested for archiving.
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct Cleanup {
+    pub actions: Vector<Action>,
+    pub subjects: Vector<Subject>,
+}
+
+impl Cleanup {
+    /// Entries whose latest session ended before this are considered stale.
+    pub fn cutoff(months: f64) -> DateTime {
+        let days = (months * 30.0) as i64;
+        DateTime::from(*DateTime::now() - time::Duration::days(days))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty() && self.subjects.is_empty()
+    }
+}
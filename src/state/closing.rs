@@ -0,0 +1,64 @@
+//! The summary a subject keeps once it is archived as a closed project.
+
+use super::{billing, hierarchy, Content, Date, History, SpentTime, Subject, TimeFormat};
+use std::{collections::BTreeMap, sync::Arc};
+
+/// Sums up the time spent on `subject` and the subjects nested in it: the
+/// total, the days it spans, the time per action and the amount earned.
+pub fn final_report(content: &Content, sessions: &History, subject: &Subject) -> String {
+    let mut total = SpentTime::default();
+    let mut count = 0;
+    let mut span: Option<(Date, Date)> = None;
+    let mut actions: BTreeMap<Arc<str>, SpentTime> = BTreeMap::new();
+    let mut earned: Option<u64> = None;
+    let sessions = sessions
+        .iter()
+        .filter(|s| hierarchy::within(&content.subjects, &s.topic.subject, subject.id));
+    for session in sessions {
+        let duration = session.duration();
+        total += duration;
+        count += 1;
+        let day = Date::from(*session.started);
+        span = Some(match span {
+            Some((first, last)) => (first.min(day), last.max(day)),
+            None => (day, day),
+        });
+        *actions
+            .entry(session.topic.action.name.clone())
+            .or_insert_with(SpentTime::default) += duration;
+        // Nested subjects may be billed at their own rate.
+        if let Some(cents) = billing::earned(&session.topic.subject, duration) {
+            *earned.get_or_insert(0) += cents;
+        }
+    }
+    let (first, last) = match span {
+        Some(span) => span,
+        None => return "No time was recorded.".to_string(),
+    };
+    let mut lines = vec![
+        format!(
+            "Total: {} in {} sessions",
+            total.format(TimeFormat::Short),
+            count
+        ),
+        format!(
+            "From {} to {}",
+            first.format("%Y-%m-%d"),
+            last.format("%Y-%m-%d")
+        ),
+    ];
+    let mut actions: Vec<_> = actions.into_iter().collect();
+    actions.sort_by(|a, b| b.1.cmp(&a.1));
+    lines.extend(
+        actions
+            .into_iter()
+            .map(|(action, spent)| format!("{}: {}", action, spent.format(TimeFormat::Short))),
+    );
+    if let Some(cents) = earned {
+        lines.push(format!(
+            "Earned: {}",
+            billing::money(&content.settings, cents)
+        ));
+    }
+    lines.join("\n")
+}
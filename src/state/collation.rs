@@ -0,0 +1,113 @@
+//! Language aware ordering and matching of names, exact and fuzzy.
+
+use icu_collator::{Collator, CollatorOptions, Strength};
+use icu_normalizer::DecomposingNormalizer;
+use std::cmp::Ordering;
+
+thread_local! {
+    /// Orders accented letters next to their base letter and case variants next to each other.
+    static SORTING: Option<Collator> = collator(Strength::Tertiary);
+    /// Treats names differing only in case as the same.
+    static MATCHING: Option<Collator> = collator(Strength::Secondary);
+}
+
+fn collator(strength: Strength) -> Option<Collator> {
+    let mut options = CollatorOptions::new();
+    options.strength = Some(strength);
+    match Collator::try_new(&Default::default(), options) {
+        Ok(collator) => Some(collator),
+        Err(err) => {
+            log::error!(
+                "Cannot create collator, falling back to byte order: {}",
+                err
+            );
+            None
+        }
+    }
+}
+
+/// Orders names for display, so "Übung" sorts before "Zeichnen".
+pub fn compare(a: &str, b: &str) -> Ordering {
+    SORTING.with(|collator| match collator {
+        // Fall back to the bytes so only identical names are equal.
+        Some(collator) => collator.compare(a, b).then_with(|| a.cmp(b)),
+        None => a.cmp(b),
+    })
+}
+
+/// Whether two names denote the same entry, ignoring case.
+pub fn same_name(a: &str, b: &str) -> bool {
+    MATCHING.with(|collator| match collator {
+        Some(collator) => collator.compare(a.trim(), b.trim()) == Ordering::Equal,
+        None => a.trim().to_lowercase() == b.trim().to_lowercase(),
+    })
+}
+
+/// The first of `items` with the same name as `name`, ignoring case.
+pub fn find<'a, T: AsRef<str>>(
+    items: impl IntoIterator<Item = &'a T>,
+    name: &str,
+) -> Option<&'a T> {
+    items
+        .into_iter()
+        .find(|item| same_name(item.as_ref(), name))
+}
+
+/// Lowercases `text` and strips accents, so "Übung" is matched by "ubung".
+pub fn fold(text: &str) -> String {
+    DecomposingNormalizer::new_nfd()
+        .normalize(text)
+        .chars()
+        .filter(|c| !matches!(c, '\u{300}'..='\u{36f}'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// How well `pattern` matches `text` when its letters are typed in order but
+/// not necessarily next to each other, so "ths" finds "Thesis". Higher is
+/// better, `None` if it does not match at all.
+pub fn fuzzy_score(pattern: &str, text: &str) -> Option<i64> {
+    let pattern: Vec<char> = fold(pattern)
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    let text: Vec<char> = fold(text).chars().collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+    for wanted in pattern {
+        let found = (next..text.len()).find(|&i| text[i] == wanted)?;
+        score += 1;
+        match previous {
+            // Runs of consecutive letters count the most.
+            Some(previous) if previous + 1 == found => score += 4,
+            // Skipped letters cost a little, so tighter matches win.
+            Some(previous) => score -= (found - previous - 1).min(3) as i64,
+            None => score -= found.min(3) as i64,
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(found);
+        next = found + 1;
+    }
+    Some(score)
+}
+
+/// The `items` matched by `pattern`, the best matches first, all of them in
+/// their order if `pattern` is empty.
+pub fn fuzzy_filter<T: AsRef<str> + Clone>(
+    items: impl IntoIterator<Item = T>,
+    pattern: &str,
+) -> Vec<T> {
+    if pattern.trim().is_empty() {
+        return items.into_iter().collect();
+    }
+    let mut matches: Vec<(i64, T)> = items
+        .into_iter()
+        .filter_map(|item| Some((fuzzy_score(pattern, item.as_ref())?, item)))
+        .collect();
+    // Stable, so equally good matches keep their order.
+    matches.sort_by(|(a, _), (b, _)| b.cmp(a));
+    matches.into_iter().map(|(_, item)| item).collect()
+}
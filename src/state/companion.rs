@@ -0,0 +1,212 @@
+//! A compact text format for exchanging data with a phone, small enough for
+//! a QR code and simple enough to be typed into a note:
+//!
+//! ```text
+//! zeitig 1
+//! actions;Coding;Writing
+//! subjects;Zeitig;Thesis
+//! contexts;Home;Office
+//! 2020-06-01
+//! 09:30;1:15;Coding;Zeitig;Home;Fixed the menu
+//! 14:00;45;Writing;Thesis
+//! ```
+//!
+//! The desktop exports the names, the phone sends back sessions. A date line
+//! applies to the sessions below it, each given by its start, its duration as
+//! `H:MM` or minutes, its action and subject, and optionally context and note.
+//! Actions and subjects may be given by an alias, like "DL" for "Deep Learning".
+
+use super::{
+    backup::{Backup, BackupAction, BackupSession, BackupSubject, TIMESTAMP_FORMAT},
+    collation, Content, DateTime, History, SpentTime,
+};
+use qrcode::{render::svg, QrCode};
+use std::{error::Error, io::Write};
+
+const HEADER: &str = "zeitig 1";
+const SEPARATOR: char = ';';
+/// Seconds in a day, longer sessions are taken for typing mistakes.
+const LONGEST: u64 = 24 * 60 * 60;
+
+/// Writes the names of everything that is not archived, for the phone to offer.
+pub fn write_names(content: &Content, out: &mut impl Write) -> std::io::Result<()> {
+    writeln!(out, "{}", HEADER)?;
+    let line = |label: &str, names: Vec<&str>| {
+        let mut line = label.to_string();
+        for name in names {
+            line.push(SEPARATOR);
+            line.push_str(&name.replace(SEPARATOR, ","));
+        }
+        line
+    };
+    let actions = content.actions.iter().filter(|a| !a.archived);
+    writeln!(
+        out,
+        "{}",
+        line("actions", actions.map(|a| a.as_ref()).collect())
+    )?;
+    let subjects = content.subjects.iter().filter(|s| !s.archived);
+    writeln!(
+        out,
+        "{}",
+        line("subjects", subjects.map(|s| s.as_ref()).collect())
+    )?;
+    let contexts = content.contexts.iter().map(|c| c.as_ref());
+    writeln!(out, "{}", line("contexts", contexts.collect()))?;
+    Ok(())
+}
+
+/// A QR code holding `text`, as an SVG image.
+pub fn qr_code(text: &str) -> Result<String, Box<dyn Error>> {
+    let code = QrCode::new(text.as_bytes())
+        .map_err(|err| format!("The names do not fit into a QR code: {}", err))?;
+    Ok(code.render::<svg::Color>().min_dimensions(300, 300).build())
+}
+
+/// Reads sessions entered on the phone into a backup, which can be imported
+/// like any other. Names are matched to the existing ones ignoring case,
+/// unknown ones are created.
+pub fn parse(content: &Content, text: &str) -> Result<Backup, Box<dyn Error>> {
+    let mut backup = Backup::new(&Content::default(), &History::default());
+
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty());
+    match lines.next() {
+        Some((_, HEADER)) => {}
+        _ => return Err(format!("The file does not start with '{}'.", HEADER).into()),
+    }
+
+    let mut date: Option<time::Date> = None;
+    for (number, line) in lines {
+        // Notes are last, so they may contain the separator themselves.
+        let fields: Vec<&str> = line.splitn(6, SEPARATOR).map(str::trim).collect();
+        let result = match fields.as_slice() {
+            ["actions", ..] | ["subjects", ..] | ["contexts", ..] => Ok(()),
+            [day] => time::Date::parse(*day, "%Y-%m-%d")
+                .map(|day| date = Some(day))
+                .map_err(|_| "Expected a date like 2020-06-01.".to_string()),
+            [start, duration, action, subject, rest @ ..] => match date {
+                Some(date) => session(
+                    &mut backup,
+                    content,
+                    date,
+                    start,
+                    duration,
+                    action,
+                    subject,
+                    rest,
+                ),
+                None => Err("A date line has to come before the sessions.".to_string()),
+            },
+            _ => Err("Expected start;duration;action;subject.".to_string()),
+        };
+        result.map_err(|err| format!("Line {}: {}", number, err))?;
+    }
+    Ok(backup)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn session(
+    backup: &mut Backup,
+    content: &Content,
+    date: time::Date,
+    start: &str,
+    duration: &str,
+    action: &str,
+    subject: &str,
+    rest: &[&str],
+) -> Result<(), String> {
+    let start = time::Time::parse(start, "%H:%M")
+        .map_err(|_| format!("Expected a start like 09:30, not '{}'.", start))?;
+    let duration = duration
+        .parse::<SpentTime>()
+        .ok()
+        .filter(|duration| duration.as_secs() > 0)
+        .ok_or_else(|| format!("Expected a duration like 1:15 or 75, not '{}'.", duration))?;
+    if duration.as_secs() > LONGEST {
+        return Err("A session can not be longer than a day.".to_string());
+    }
+    let started = *DateTime::from_local(time::PrimitiveDateTime::new(date, start));
+    let ended = started + *duration;
+
+    let action = match content.action_named(action) {
+        Some(action) => action.as_ref().to_string(),
+        None => add_name(
+            &mut backup.actions,
+            action,
+            |entry| &entry.name,
+            |name| BackupAction {
+                name,
+                archived: false,
+                color: None,
+            },
+        ),
+    };
+    let subject = match content.subject_named(subject) {
+        Some(subject) => subject.as_ref().to_string(),
+        None => add_name(
+            &mut backup.subjects,
+            subject,
+            |entry| &entry.name,
+            |name| BackupSubject {
+                name,
+                archived: false,
+                category: None,
+                issue_url: None,
+                estimate: None,
+                rate: None,
+                idle_rule: None,
+                color: None,
+                parent: None,
+                final_report: None,
+            },
+        ),
+    };
+    let context = match rest.first().filter(|name| !name.is_empty()) {
+        Some(name) => Some(match collation::find(&content.contexts, name) {
+            Some(context) => context.as_ref().to_string(),
+            None => {
+                if !backup.contexts.iter().any(|c| c == name) {
+                    backup.contexts.push(name.to_string());
+                }
+                name.to_string()
+            }
+        }),
+        None => None,
+    };
+    let note = rest.get(1).filter(|note| !note.is_empty());
+
+    backup.sessions.push(BackupSession {
+        action,
+        subject,
+        context,
+        started: started.format(TIMESTAMP_FORMAT),
+        ended: ended.format(TIMESTAMP_FORMAT),
+        paused: 0,
+        intervals: Vec::new(),
+        note: note.map(|note| note.to_string()),
+        interruptions: Vec::new(),
+        tags: Vec::new(),
+    });
+    Ok(())
+}
+
+/// Adds a name that is not known yet to the backup, unless an earlier line did.
+pub fn add_name<T>(
+    entries: &mut Vec<T>,
+    name: &str,
+    name_of: impl Fn(&T) -> &String,
+    entry: impl FnOnce(String) -> T,
+) -> String {
+    if let Some(existing) = entries
+        .iter()
+        .find(|existing| collation::same_name(name_of(existing), name))
+    {
+        return name_of(existing).clone();
+    }
+    entries.push(entry(name.to_string()));
+    name.to_string()
+}
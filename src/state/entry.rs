@@ -0,0 +1,99 @@
+use super::{Action, AppState, DateTime, Session, SpentTime, Subject, Topic};
+#[cfg(feature = "gui")]
+use druid::{Data, Lens};
+use im::Vector;
+
+static FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Input of the dialog for adding a session after the fact or editing one.
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct ManualEntry {
+    pub action: Option<Action>,
+    pub subject: Option<Subject>,
+    pub start: String,
+    pub minutes: String,
+    pub note: String,
+    pub status: String,
+    /// The recorded session being edited, `None` when adding a new one.
+    pub editing: Option<Session>,
+}
+
+impl ManualEntry {
+    pub fn generate(data: &AppState) -> Self {
+        let hour_ago = DateTime::from(*DateTime::now() - time::Duration::hour());
+        ManualEntry {
+            action: data.setup.selected_action.clone(),
+            subject: data.setup.selected_subject.clone(),
+            start: hour_ago.format(FORMAT),
+            minutes: "60".to_string(),
+            note: String::new(),
+            status: String::new(),
+            editing: None,
+        }
+    }
+
+    pub fn edit(session: &Session) -> Self {
+        ManualEntry {
+            action: Some(session.topic.action.clone()),
+            subject: Some(session.topic.subject.clone()),
+            start: session.started.format(FORMAT),
+            minutes: minutes_of(session),
+            note: session.note.as_deref().unwrap_or_default().to_string(),
+            status: String::new(),
+            editing: Some(session.clone()),
+        }
+    }
+
+    /// Builds the session, or explains what is missing.
+    pub fn to_session(&self) -> Result<Session, String> {
+        let action = self.action.clone().ok_or("Choose an action.")?;
+        let subject = self.subject.clone().ok_or("Choose a subject.")?;
+        let started = time::PrimitiveDateTime::parse(self.start.trim(), FORMAT)
+            .map(|at| *DateTime::from_local(at))
+            .map_err(|_| "Enter the start as YYYY-MM-DD HH:MM.")?;
+        let duration = match self.minutes.parse::<SpentTime>() {
+            Ok(duration) if duration.as_secs() > 0 => duration,
+            _ => return Err("Enter the duration like 90, 1:30 or 1h30m.".to_string()),
+        };
+        let ended = started + *duration;
+        if ended > *DateTime::now() {
+            return Err("The session would end in the future.".to_string());
+        }
+        let editing = self.editing.as_ref();
+        // Keep the exact times unless they were changed, the fields only show minutes.
+        // Changed times describe the whole session, so earlier breaks are dropped.
+        let (started, ended, intervals) = match editing {
+            Some(session)
+                if self.start.trim() == session.started.format(FORMAT)
+                    && self.minutes.trim() == minutes_of(session) =>
+            {
+                (session.started, session.ended, session.intervals.clone())
+            }
+            _ => (started.into(), ended.into(), Vector::new()),
+        };
+        Ok(Session {
+            topic: Topic { action, subject },
+            started,
+            ended,
+            context: editing.and_then(|session| session.context.clone()),
+            interruptions: editing
+                .map(|session| session.interruptions.clone())
+                .unwrap_or_else(Vector::new),
+            original_start: editing.and_then(|session| session.original_start),
+            tags: editing
+                .map(|session| session.tags.clone())
+                .unwrap_or_else(Vector::new),
+            intervals,
+            note: if self.note.trim().is_empty() {
+                None
+            } else {
+                Some(self.note.as_str().into())
+            },
+        })
+    }
+}
+
+fn minutes_of(session: &Session) -> String {
+    (session.duration().as_secs() / 60).to_string()
+}
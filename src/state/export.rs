@@ -0,0 +1,202 @@
+use super::{
+    backend::{Backend, Sqlite},
+    billing,
+    rounding::Rounding,
+    Content, Date, DateTime, History, Settings, SpentTime, Subject,
+};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+    sync::Arc,
+};
+
+static UNCATEGORIZED: &str = "Uncategorized";
+
+/// Loads every session, the history in memory only holds the recent ones.
+pub fn full_history(content: &Content) -> io::Result<History> {
+    let load = || -> Result<History, Box<dyn Error>> {
+        let mut backend = match super::paths::read_only() {
+            Some(file) => Sqlite::open_read_only(file)?,
+            None => Sqlite::new(super::paths::data_file())?,
+        };
+        backend.load_history(content)
+    };
+    load().map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()))
+}
+
+/// Every session like [`full_history`], falling back to the `recent` ones if
+/// the others cannot be loaded. The status says so, it is empty otherwise.
+pub fn full_or_recent_history(content: &Content, recent: &History) -> (History, String) {
+    match full_history(content) {
+        Ok(history) => (history, String::new()),
+        Err(err) => {
+            log::error!("Failed to load the full history: {}", err);
+            let status = "Only the recent sessions could be loaded.".to_string();
+            (recent.clone(), status)
+        }
+    }
+}
+
+/// Writes the weekly totals per reporting category as CSV, rounded as
+/// configured in the settings.
+pub fn categories_csv(
+    content: &Content,
+    history: &History,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let sessions = history.iter().map(|session| {
+        let category = content
+            .categories
+            .get(&session.topic.subject)
+            .unwrap_or_else(|| UNCATEGORIZED.into());
+        (Date::from(*session.started), category, session.duration())
+    });
+    let rounding = Rounding::from_settings(&content.settings);
+    let mut totals: BTreeMap<(Date, Arc<str>), SpentTime> = BTreeMap::new();
+    for ((day, category), time) in rounding.add_up(sessions) {
+        *totals.entry((day.week_begin(), category)).or_default() += time;
+    }
+
+    writeln!(out, "week,category,seconds")?;
+    for ((week, category), time) in totals {
+        writeln!(out, "{},{},{}", *week, field(&category), time.as_secs())?;
+    }
+    Ok(())
+}
+
+/// Writes the time per day and topic as CSV, one row for each combination
+/// with time recorded, to be pivoted in a spreadsheet. Sessions count for
+/// the day they started on. The time is rounded and the amount earned
+/// written as configured in `settings`, which is left empty for subjects
+/// that are not billable.
+pub fn daily_csv(history: &History, settings: &Settings, out: &mut impl Write) -> io::Result<()> {
+    let sessions = history.iter().map(|session| {
+        let day = Date::from(*session.started);
+        (day, session.topic.clone(), session.duration())
+    });
+    let totals: BTreeMap<(Date, Arc<str>, Arc<str>), (SpentTime, Subject)> =
+        Rounding::from_settings(settings)
+            .add_up(sessions)
+            .into_iter()
+            .map(|((day, topic), time)| {
+                let key = (day, topic.action.name.clone(), topic.subject.name.clone());
+                (key, (time, topic.subject))
+            })
+            .collect();
+
+    writeln!(out, "day,action,subject,seconds,earned")?;
+    for ((day, action, subject), (time, billed)) in totals {
+        let earned = billing::earned(&billed, time)
+            .map_or_else(String::new, |cents| billing::money(settings, cents));
+        writeln!(
+            out,
+            "{},{},{},{},{}",
+            *day,
+            field(&action),
+            field(&subject),
+            time.as_secs(),
+            field(&earned)
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes every session as an iCalendar event, to be overlaid on a calendar.
+/// The summary names the topic and the description holds the note.
+pub fn calendar_ics(history: &History, out: &mut impl Write) -> io::Result<()> {
+    let stamp = ics_time(&DateTime::now());
+    let mut line = |text: String| -> io::Result<()> { write!(out, "{}\r\n", fold(&text)) };
+    line("BEGIN:VCALENDAR".into())?;
+    line("VERSION:2.0".into())?;
+    line("PRODID:-//Zeitig//Zeitig//EN".into())?;
+    for session in history {
+        let topic = &session.topic;
+        line("BEGIN:VEVENT".into())?;
+        line(format!(
+            "UID:{}-{}-{}@zeitig",
+            session.started.timestamp(),
+            topic.action.id,
+            topic.subject.id
+        ))?;
+        line(format!("DTSTAMP:{}", stamp))?;
+        line(format!("DTSTART:{}", ics_time(&session.started)))?;
+        line(format!("DTEND:{}", ics_time(&session.ended)))?;
+        line(format!(
+            "SUMMARY:{}",
+            text(&format!(
+                "{} {}",
+                topic.action.as_ref(),
+                topic.subject.as_ref()
+            ))
+        ))?;
+        if let Some(context) = &session.context {
+            line(format!("LOCATION:{}", text(context.as_ref())))?;
+        }
+        if let Some(note) = &session.note {
+            line(format!("DESCRIPTION:{}", text(note)))?;
+        }
+        line("END:VEVENT".into())?;
+    }
+    line("END:VCALENDAR".into())
+}
+
+fn ics_time(time: &DateTime) -> String {
+    time.to_offset(time::UtcOffset::UTC)
+        .format("%Y%m%dT%H%M%SZ")
+}
+
+/// Escapes an iCalendar text value.
+fn text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Breaks lines longer than 75 bytes, continuing them with a space.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
+
+/// Creates a timestamped file in the export directory and fills it using `write`.
+/// With `sign` set, a detached signature is written next to it.
+pub fn to_file(
+    prefix: &str,
+    extension: &str,
+    sign: bool,
+    write: impl FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+) -> io::Result<PathBuf> {
+    let now = time::OffsetDateTime::now_local();
+    let name = format!("{}-{}.{}", prefix, now.format("%Y%m%d-%H%M%S"), extension);
+    let path = super::paths::export_file(&name);
+    super::paths::write_atomic(&path, write)?;
+    if sign {
+        super::signing::sign_file(&path)
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()))?;
+    }
+    Ok(path)
+}
+
+/// Quotes a CSV field if necessary.
+pub fn field(value: &str) -> String {
+    if value.contains(|c| c == ',' || c == '"' || c == '\n') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,83 @@
+use super::{report::Period, Date, History, SpentTime, TimeFormat, Topic, Unit};
+#[cfg(feature = "gui")]
+use druid::{Data, Lens};
+
+/// Time to spend on a topic each day, week or month.
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct Goal {
+    pub topic: Topic,
+    pub period: Period,
+    pub target: SpentTime,
+    /// First day of the period the goal was last reached in, so it is only announced once.
+    pub reached: Option<Date>,
+}
+
+impl Goal {
+    pub fn label(&self) -> String {
+        let per = match self.period {
+            Period::Day => "day",
+            Period::Week => "week",
+            Period::Month => "month",
+        };
+        format!("{} per {}", self.target, per)
+    }
+
+    /// Time spent on the topic in the current period, the sessions of
+    /// earlier periods are in the recent history.
+    pub fn progress(&self, history: &History, running: SpentTime, month_start: u8) -> SpentTime {
+        let today = Date::from(time::OffsetDateTime::now_local());
+        let begin = self.period.begin(today, month_start);
+        history
+            .iter()
+            .filter(|session| session.topic == self.topic)
+            .filter(|session| Date::from(*session.started) >= begin)
+            .fold(running, |sum, session| sum + session.duration())
+    }
+
+    /// Share of the target reached, between 0 and 1.
+    pub fn fraction(&self, progress: SpentTime) -> f64 {
+        if self.target.as_secs() == 0 {
+            return 1.0;
+        }
+        (progress.as_secs_f64() / self.target.as_secs_f64()).min(1.0)
+    }
+}
+
+/// Input of the dialog for setting the goal of the selected topic.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct GoalInput {
+    pub topic: Topic,
+    pub period: Period,
+    pub hours: String,
+    pub status: String,
+}
+
+impl GoalInput {
+    pub fn new(topic: Topic, goal: Option<&Goal>) -> Self {
+        GoalInput {
+            topic,
+            period: goal.map_or(Period::Week, |goal| goal.period),
+            hours: goal.map_or_else(String::new, |goal| goal.target.format(TimeFormat::Hours)),
+            status: String::new(),
+        }
+    }
+
+    /// The goal typed in, `Ok(None)` if the field was emptied to remove it.
+    pub fn to_goal(&self) -> Result<Option<Goal>, String> {
+        let hours = self.hours.trim();
+        if hours.is_empty() {
+            return Ok(None);
+        }
+        match SpentTime::parse_in(hours, Unit::Hours) {
+            Ok(target) if target.as_secs() > 0 => Ok(Some(Goal {
+                topic: self.topic.clone(),
+                period: self.period,
+                target,
+                reached: None,
+            })),
+            _ => Err("Enter the goal in hours, like 1.5 or 1h30m.".to_string()),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! Checks run once the data is loaded, so problems are pointed out with a way
+//! to fix them instead of surfacing later as failing saves or odd totals.
+
+use super::{backend::Backend, Content, DateTime, History, Topic};
+#[cfg(feature = "gui")]
+use druid::Data;
+use im::Vector;
+use std::{path::Path, sync::Arc};
+
+/// Sessions may end this far in the future before the clock is suspected.
+const CLOCK_TOLERANCE: time::Duration = time::Duration::minutes(5);
+
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub enum Finding {
+    /// Changes cannot be saved into the folder holding the data file.
+    ReadOnly(Arc<str>),
+    /// The data file is damaged, with what the database reported.
+    Damaged(Arc<str>),
+    /// The latest session ended after now, so the clock is probably behind.
+    ClockBehind(DateTime),
+    /// A session started from the command line is still running.
+    Running(Topic, DateTime),
+}
+
+impl Finding {
+    pub fn message(&self) -> String {
+        match self {
+            Finding::ReadOnly(folder) => format!(
+                "Changes cannot be saved, {} is not writable: check its permissions and free space.",
+                folder
+            ),
+            Finding::Damaged(report) => format!("The data file is damaged: {}", report),
+            Finding::ClockBehind(ended) => format!(
+                "A session ended on {}, which is later than now. Is the clock set correctly?",
+                ended.format("%Y-%m-%d %H:%M")
+            ),
+            Finding::Running(topic, started) => format!(
+                "{} {} has been running since {}, started from the command line.",
+                topic.action.as_ref(),
+                topic.subject.as_ref(),
+                started.format("%Y-%m-%d %H:%M")
+            ),
+        }
+    }
+
+    pub fn fix_label(&self) -> &'static str {
+        match self {
+            Finding::ReadOnly(_) => "Open folder",
+            Finding::Damaged(_) => "Repair",
+            Finding::ClockBehind(_) => "Check again",
+            Finding::Running(..) => "Stop it",
+        }
+    }
+}
+
+/// Runs all checks, failing checks are logged and skipped.
+pub fn check(
+    backend: &mut dyn Backend,
+    folder: &Path,
+    content: &Content,
+    history: &History,
+) -> Vector<Finding> {
+    let mut findings = Vector::new();
+    if let Err(err) = writable(folder) {
+        log::warn!("Cannot write to {}: {}", folder.display(), err);
+        findings.push_back(Finding::ReadOnly(folder.display().to_string().into()));
+    }
+    match backend.check_integrity() {
+        Ok(problems) if !problems.is_empty() => {
+            findings.push_back(Finding::Damaged(problems.join(" ").into()))
+        }
+        Ok(_) => {}
+        Err(err) => log::warn!("Cannot check the data file: {}", err),
+    }
+    if let Some(ended) = clock_behind(history) {
+        findings.push_back(Finding::ClockBehind(ended));
+    }
+    match backend.load_running(content) {
+        Ok(Some(running)) => findings.push_back(Finding::Running(running.topic, running.started)),
+        Ok(None) => {}
+        Err(err) => log::warn!("Cannot look for a running session: {}", err),
+    }
+    findings
+}
+
+/// The end of the latest session if it lies in the future.
+pub fn clock_behind(history: &History) -> Option<DateTime> {
+    let latest = history
+        .iter()
+        .map(|session| session.ended)
+        .max_by_key(|ended| **ended)?;
+    match *latest > *DateTime::now() + CLOCK_TOLERANCE {
+        true => Some(latest),
+        false => None,
+    }
+}
+
+/// Writes and removes a file, as the database would only fail on saving.
+fn writable(folder: &Path) -> std::io::Result<()> {
+    let probe = folder.join(".zeitig-probe");
+    std::fs::write(&probe, b"")?;
+    std::fs::remove_file(probe)
+}
@@ -0,0 +1,91 @@
+//! Subjects nested into projects and sub-projects, like
+//! "Client A / Website / Backend".
+
+use super::Subject;
+use im::Vector;
+
+/// The subject `subject` is nested in, if it is among `subjects`.
+pub fn parent<'a>(subjects: &'a Vector<Subject>, subject: &Subject) -> Option<&'a Subject> {
+    let parent = subject.parent?;
+    subjects.iter().find(|other| other.id == parent)
+}
+
+/// The subjects `subject` is nested in, the top-level one first. Stops at
+/// a cycle, which choosing parents does not allow but stored data might hold.
+pub fn ancestors<'a>(subjects: &'a Vector<Subject>, subject: &Subject) -> Vec<&'a Subject> {
+    let mut ancestors: Vec<&Subject> = Vec::new();
+    let mut current = parent(subjects, subject);
+    while let Some(next) = current {
+        if next.id == subject.id || ancestors.iter().any(|known| known.id == next.id) {
+            break;
+        }
+        ancestors.push(next);
+        current = parent(subjects, next);
+    }
+    ancestors.reverse();
+    ancestors
+}
+
+/// The top-level subject `subject` belongs to, itself if it is not nested.
+pub fn root(subjects: &Vector<Subject>, subject: &Subject) -> Subject {
+    ancestors(subjects, subject)
+        .first()
+        .map_or_else(|| subject.clone(), |root| (*root).clone())
+}
+
+/// The names from the top-level subject down to `subject`, like `Client A / Website`.
+pub fn path(subjects: &Vector<Subject>, subject: &Subject) -> String {
+    ancestors(subjects, subject)
+        .iter()
+        .map(|ancestor| ancestor.name.as_ref())
+        .chain(std::iter::once(subject.name.as_ref()))
+        .collect::<Vec<_>>()
+        .join(" / ")
+}
+
+/// Whether `subject` is the subject `id` or nested in it at any depth.
+pub fn within(subjects: &Vector<Subject>, subject: &Subject, id: usize) -> bool {
+    subject.id == id || ancestors(subjects, subject).iter().any(|a| a.id == id)
+}
+
+/// A subject placed in the tree, with the number of subjects it is nested in.
+pub struct Node {
+    pub subject: Subject,
+    pub depth: usize,
+    /// Other listed subjects are nested in it.
+    pub nested: bool,
+}
+
+/// The `listed` subjects in tree order, each followed by the ones nested in
+/// it and keeping their order otherwise. Subjects nested in one that is not
+/// listed, like an archived project, are placed at the top level. The
+/// subjects nested in one of the `collapsed` ones are left out.
+pub fn tree(listed: &Vector<Subject>, collapsed: &Vector<usize>) -> Vec<Node> {
+    // Subjects in a cycle are placed at the top level too.
+    let placed_in = |subject: &Subject| match parent(listed, subject) {
+        Some(parent) if !within(listed, parent, subject.id) => Some(parent.id),
+        _ => None,
+    };
+    let children = |parent: Option<usize>| -> Vec<&Subject> {
+        listed
+            .iter()
+            .filter(|subject| placed_in(subject) == parent)
+            .collect()
+    };
+    let mut nodes = Vec::new();
+    // Subjects waiting to be placed, the next one last.
+    let mut pending: Vec<(&Subject, usize)> =
+        children(None).into_iter().rev().map(|s| (s, 0)).collect();
+    while let Some((subject, depth)) = pending.pop() {
+        let nested = children(Some(subject.id));
+        nodes.push(Node {
+            subject: subject.clone(),
+            depth,
+            nested: !nested.is_empty(),
+        });
+        if !collapsed.contains(&subject.id) {
+            pending.extend(nested.into_iter().rev().map(|child| (child, depth + 1)));
+        }
+    }
+    nodes
+}
@@ -0,0 +1,172 @@
+use super::{goal::Goal, Action, DateTime, Session, SpentTime, Subject, Unit};
+#[cfg(feature = "gui")]
+use druid::Data;
+use im::Vector;
+use std::sync::Arc;
+
+/// Number of edits that can be undone.
+const LIMIT: usize = 100;
+
+/// A destructive change that can be taken back.
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub enum Edit {
+    /// Stopping the running session, holding the session it recorded.
+    StopSession(Session),
+    DeleteSession(Session),
+    DeleteAction(Action),
+    DeleteSubject(Subject),
+    RenameAction {
+        from: Action,
+        to: Action,
+    },
+    RenameSubject {
+        from: Subject,
+        to: Subject,
+    },
+    MergeActions(Merge<Action>),
+    MergeSubjects(Merge<Subject>),
+}
+
+/// An action or subject merged into another one, with what it held to take
+/// it apart again. `from` keeps its attributes, like its color or rate.
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub struct Merge<T> {
+    pub from: T,
+    pub into: T,
+    /// Keys of the sessions recorded on `from`.
+    pub sessions: Vector<DateTime>,
+    /// Time kept for `from` in the time table, by the id of the other part of the topic.
+    pub time: Vector<(usize, SpentTime)>,
+    /// Slots of the pinned topics with `from`.
+    pub pins: Vector<usize>,
+    /// Names of the aliases of `from`.
+    pub aliases: Vector<Arc<str>>,
+    /// Processes of the launch rules starting `from`.
+    pub launch_rules: Vector<Arc<str>>,
+    /// Goals set with `from`, and whether `into` took them over as it had
+    /// none for the same topic. Otherwise they were dropped.
+    pub goals: Vector<(Goal, bool)>,
+    /// Ids of the checklist items of a merged subject.
+    pub checklist: Vector<usize>,
+    pub category: Option<Arc<str>>,
+    /// Ids of the subjects nested in a merged subject.
+    pub children: Vector<usize>,
+}
+
+/// The edits made since starting, to be undone and redone in order.
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub struct UndoManager {
+    done: Vector<Edit>,
+    undone: Vector<Edit>,
+}
+
+impl UndoManager {
+    /// Records a new edit, after which the undone ones can no longer be redone.
+    pub fn push(&mut self, edit: Edit) {
+        self.undone.clear();
+        self.redone(edit);
+    }
+
+    /// The edit `undo` would return.
+    pub fn next_undo(&self) -> Option<&Edit> {
+        self.done.back()
+    }
+
+    /// Takes the latest edit, which should be passed to `undone` once reverted.
+    pub fn undo(&mut self) -> Option<Edit> {
+        self.done.pop_back()
+    }
+
+    pub fn undone(&mut self, edit: Edit) {
+        self.undone.push_back(edit);
+    }
+
+    /// Takes the latest undone edit, which should be passed to `redone` once applied again.
+    pub fn redo(&mut self) -> Option<Edit> {
+        self.undone.pop_back()
+    }
+
+    pub fn redone(&mut self, edit: Edit) {
+        self.done.push_back(edit);
+        if self.done.len() > LIMIT {
+            self.done.pop_front();
+        }
+    }
+}
+
+/// A change to a recorded session, as it was sent to the backend.
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub enum Change {
+    Update(Session, Session),
+    Delete(Session),
+    Amend(Session, Option<Session>),
+}
+
+impl Change {
+    /// The session as it was before the change.
+    pub fn original(&self) -> &Session {
+        match self {
+            Change::Update(original, _) | Change::Delete(original) | Change::Amend(original, _) => {
+                original
+            }
+        }
+    }
+
+    /// The session as it is after the change, `None` if it was removed.
+    pub fn changed(&self) -> Option<&Session> {
+        match self {
+            Change::Update(_, changed) => Some(changed),
+            Change::Delete(_) => None,
+            Change::Amend(_, changed) => changed.as_ref(),
+        }
+    }
+}
+
+/// A change the backend refused, as another instance changed the session first.
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub struct Conflict {
+    pub change: Change,
+    /// The other instance deleted the session.
+    pub deleted: bool,
+}
+
+/// Bounds on the duration of sessions to look for in the whole history,
+/// to find forgotten timers and sessions started by accident.
+#[derive(Clone, Copy, Default)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub struct SessionQuery {
+    pub longer_than: Option<SpentTime>,
+    pub shorter_than: Option<SpentTime>,
+}
+
+impl SessionQuery {
+    /// The bounds typed like `3h` or `5m`, each left empty to not bound the duration.
+    pub fn parse(longer_than: &str, shorter_than: &str) -> Result<Self, String> {
+        let bound = |text: &str| match text.trim() {
+            "" => Ok(None),
+            text => SpentTime::parse_in(text, Unit::Minutes)
+                .map(Some)
+                .map_err(|err| err.to_string()),
+        };
+        Ok(SessionQuery {
+            longer_than: bound(longer_than)?,
+            shorter_than: bound(shorter_than)?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.longer_than.is_none() && self.shorter_than.is_none()
+    }
+
+    /// Whether the time worked in `session`, without its breaks, is within the bounds.
+    pub fn matches(&self, session: &Session) -> bool {
+        let worked = session.duration();
+        self.longer_than.iter().all(|bound| *worked > **bound)
+            && self.shorter_than.iter().all(|bound| *worked < **bound)
+    }
+}
@@ -0,0 +1,84 @@
+//! How idle time is handled while working on a subject, as some work like
+//! reading hardly involves the keyboard.
+
+use super::Settings;
+#[cfg(feature = "gui")]
+use druid::Data;
+use std::{fmt, time::Duration};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub enum IdleRule {
+    /// Ask after the minutes from the settings.
+    Default,
+    /// Never ask, the idle time is kept.
+    Ignore,
+    /// Ask after these minutes instead.
+    Ask(u32),
+    /// Pause without asking after these minutes, leaving out the idle time.
+    Pause(u32),
+}
+
+/// What to do once the idle time reached the threshold.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Response {
+    Ask,
+    Pause,
+}
+
+impl Default for IdleRule {
+    fn default() -> Self {
+        IdleRule::Default
+    }
+}
+
+impl IdleRule {
+    /// Idle time after which to respond and how, `None` if it is ignored.
+    pub fn threshold(self, settings: &Settings) -> Option<(Duration, Response)> {
+        let (minutes, response) = match self {
+            IdleRule::Default if settings.idle_minutes > 0.0 => {
+                (settings.idle_minutes, Response::Ask)
+            }
+            IdleRule::Default | IdleRule::Ignore => return None,
+            IdleRule::Ask(minutes) => (minutes as f64, Response::Ask),
+            IdleRule::Pause(minutes) => (minutes as f64, Response::Pause),
+        };
+        Some((Duration::from_secs_f64(minutes * 60.0), response))
+    }
+
+    /// Reads a rule as written by `Display`, like "ignore", "ask 10" or "pause 5".
+    /// Empty text is the default.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut words = text.split_whitespace();
+        let rule = words.next().map(str::to_lowercase);
+        let minutes = match words.next().map(str::parse::<u32>) {
+            Some(Ok(minutes)) if minutes > 0 => Some(minutes),
+            Some(_) => return Err("Expected the minutes as a whole number.".to_string()),
+            None => None,
+        };
+        if words.next().is_some() {
+            return Err("Expected a rule and at most the minutes.".to_string());
+        }
+        match (rule.as_deref(), minutes) {
+            (None, _) => Ok(IdleRule::Default),
+            (Some("ignore"), None) => Ok(IdleRule::Ignore),
+            (Some("ask"), Some(minutes)) => Ok(IdleRule::Ask(minutes)),
+            (Some("pause"), Some(minutes)) => Ok(IdleRule::Pause(minutes)),
+            (Some(rule), _) => Err(format!(
+                "Expected ignore, ask or pause with minutes, not '{}'.",
+                rule
+            )),
+        }
+    }
+}
+
+impl fmt::Display for IdleRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdleRule::Default => Ok(()),
+            IdleRule::Ignore => write!(f, "ignore"),
+            IdleRule::Ask(minutes) => write!(f, "ask {}", minutes),
+            IdleRule::Pause(minutes) => write!(f, "pause {}", minutes),
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use serde::Deserialize;
+use std::{error::Error, path::Path};
+
+/// Names of actions, subjects and contexts to create in bulk.
+///
+/// Accepts either JSON of the form `{"actions": [..], "subjects": [..], "contexts": [..]}`
+/// or CSV / plain text with one `kind,name` pair per line, where kind is one of
+/// `action`, `subject` or `context`.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct TopicLists {
+    pub actions: Vec<String>,
+    pub subjects: Vec<String>,
+    pub contexts: Vec<String>,
+}
+
+impl TopicLists {
+    pub fn read(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let text = std::fs::read_to_string(path)?;
+        let is_json = path
+            .extension()
+            .map(|ext| ext.eq_ignore_ascii_case("json"))
+            .unwrap_or(false);
+        if is_json {
+            Ok(serde_json::from_str(&text)?)
+        } else {
+            Self::parse_csv(&text)
+        }
+    }
+
+    fn parse_csv(text: &str) -> Result<Self, Box<dyn Error>> {
+        let mut lists = TopicLists::default();
+        for (number, line) in text.lines().enumerate() {
+            let fields = csv_line(line);
+            let (kind, name) = match fields.as_slice() {
+                [] => continue,
+                [kind, name, ..] => (kind.trim().to_lowercase(), name.trim().to_string()),
+                [_] => {
+                    return Err(format!("Line {} is missing a name.", number + 1).into());
+                }
+            };
+            if name.is_empty() {
+                continue;
+            }
+            match kind.as_str() {
+                "action" => lists.actions.push(name),
+                "subject" => lists.subjects.push(name),
+                "context" => lists.contexts.push(name),
+                "kind" if number == 0 => {}
+                _ => {
+                    return Err(format!("Line {} has unknown kind '{}'.", number + 1, kind).into());
+                }
+            }
+        }
+        Ok(lists)
+    }
+}
+
+/// Splits a single CSV line into its fields, honoring double quotes.
+pub fn csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    if line.trim().is_empty() {
+        return fields;
+    }
+
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
@@ -0,0 +1,234 @@
+use super::{
+    billing, collation, export,
+    totals::{Dimension, Grouping, Totals},
+    AppState, Date, History, SpentTime, Subject, Topic,
+};
+#[cfg(feature = "gui")]
+use druid::{Data, Lens};
+use im::Vector;
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryFrom,
+    sync::Arc,
+};
+
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct Insights {
+    pub weeks: Vector<Week>,
+    /// Minutes tracked per day over the last year, starting on a monday, with
+    /// the color of the topic most of them were spent on.
+    pub days: Vector<(u32, Option<u32>)>,
+    pub interruptions: Vector<InterruptionSummary>,
+    pub checklists: Vector<ChecklistSummary>,
+    pub totals: Totals,
+    pub grouping: Grouping,
+    pub status: String,
+    /// Monday of the week shown on the timeline.
+    pub timeline_week: Date,
+    /// Every recorded session, for the timeline of any week.
+    pub sessions: History,
+    /// Sessions are colored by their action rather than their subject.
+    pub color_by_action: bool,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct Week {
+    pub begin: Date,
+    pub entries: Vector<Summary>,
+    pub contexts: Vector<ContextSummary>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct Summary {
+    pub topic: Topic,
+    pub spent_time: SpentTime,
+    /// Amount earned in cents, if the subject is billable.
+    pub earned: Option<u64>,
+    pub color: Option<u32>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct ContextSummary {
+    pub context: Arc<str>,
+    pub spent_time: SpentTime,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct ChecklistSummary {
+    pub subject: Subject,
+    pub done: usize,
+    pub total: usize,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct GroupTotal {
+    pub label: Arc<str>,
+    pub spent_time: SpentTime,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct InterruptionSummary {
+    pub date: Date,
+    pub subject: Subject,
+    pub count: usize,
+}
+
+impl Insights {
+    pub fn generate(data: &AppState) -> Self {
+        let (sessions, status) = export::full_or_recent_history(&data.content, &data.history);
+        let today = Date::from(time::OffsetDateTime::now_local());
+        let by_action = data.content.settings.color_by_action;
+        Insights {
+            weeks: weeks(&sessions, by_action),
+            days: days(&sessions, by_action),
+            interruptions: interruptions(&sessions),
+            checklists: checklists(data),
+            totals: data.content.totals.clone(),
+            grouping: Grouping::of(&[Dimension::Subject]),
+            status,
+            timeline_week: today.week_begin(),
+            sessions,
+            color_by_action: by_action,
+        }
+    }
+
+    /// All time totals for the selected grouping.
+    pub fn grouped_totals(&self) -> Vector<GroupTotal> {
+        let mut totals: Vec<GroupTotal> = self
+            .totals
+            .group_by(self.grouping)
+            .map(|(key, spent_time)| GroupTotal {
+                label: key.label().into(),
+                spent_time,
+            })
+            .collect();
+        totals.sort_by(|a, b| collation::compare(&a.label, &b.label));
+        totals.into()
+    }
+}
+
+#[derive(Default)]
+struct WeekTotals {
+    topics: HashMap<Topic, SpentTime>,
+    contexts: BTreeMap<Arc<str>, SpentTime>,
+}
+
+fn weeks(history: &History, by_action: bool) -> Vector<Week> {
+    let mut weeks: BTreeMap<Date, WeekTotals> = BTreeMap::new();
+    for session in history {
+        let begin = Date::from(*session.started).week_begin();
+        let totals = weeks.entry(begin).or_default();
+        *totals.topics.entry(session.topic.clone()).or_default() += session.duration();
+        let context = match &session.context {
+            Some(context) => context.name.clone(),
+            None => "No context".into(),
+        };
+        *totals.contexts.entry(context).or_default() += session.duration();
+    }
+
+    weeks
+        .into_iter()
+        .rev()
+        .map(|(begin, totals)| {
+            let mut entries: Vec<Summary> = totals
+                .topics
+                .into_iter()
+                .map(|(topic, spent_time)| Summary {
+                    color: topic.color(by_action),
+                    earned: billing::earned(&topic.subject, spent_time),
+                    topic,
+                    spent_time,
+                })
+                .collect();
+            entries.sort_by(|a, b| {
+                (&a.topic.action, &a.topic.subject).cmp(&(&b.topic.action, &b.topic.subject))
+            });
+            let contexts = totals
+                .contexts
+                .into_iter()
+                .map(|(context, spent_time)| ContextSummary {
+                    context,
+                    spent_time,
+                })
+                .collect();
+            Week {
+                begin,
+                entries: entries.into(),
+                contexts,
+            }
+        })
+        .collect()
+}
+
+/// Minutes per day from the monday 52 weeks ago up to today. Sessions count
+/// for the day they started on.
+fn days(history: &History, by_action: bool) -> Vector<(u32, Option<u32>)> {
+    let today = Date::from(time::OffsetDateTime::now_local());
+    let begin = Date::from(*today - time::Duration::weeks(52)).week_begin();
+    let mut days: Vec<HashMap<Option<u32>, u64>> =
+        vec![HashMap::new(); (*today - *begin).whole_days() as usize + 1];
+    for session in history {
+        let day = (*Date::from(*session.started) - *begin).whole_days();
+        if let Some(colors) = usize::try_from(day).ok().and_then(|day| days.get_mut(day)) {
+            *colors.entry(session.topic.color(by_action)).or_default() +=
+                session.duration().as_secs();
+        }
+    }
+    days.into_iter()
+        .map(|colors| {
+            let total: u64 = colors.values().sum();
+            let color = colors
+                .into_iter()
+                .max_by_key(|(_, seconds)| *seconds)
+                .and_then(|(color, _)| color);
+            ((total / 60) as u32, color)
+        })
+        .collect()
+}
+
+fn interruptions(history: &History) -> Vector<InterruptionSummary> {
+    let mut counts: BTreeMap<(Date, Subject), usize> = BTreeMap::new();
+    for session in history {
+        for interruption in &session.interruptions {
+            let date = Date::from(*interruption.at);
+            *counts
+                .entry((date, session.topic.subject.clone()))
+                .or_default() += 1;
+        }
+    }
+
+    counts
+        .into_iter()
+        .rev()
+        .map(|((date, subject), count)| InterruptionSummary {
+            date,
+            subject,
+            count,
+        })
+        .collect()
+}
+
+fn checklists(data: &AppState) -> Vector<ChecklistSummary> {
+    data.content
+        .subjects
+        .iter()
+        .filter_map(|subject| {
+            let items = data.content.checklist_of(subject);
+            if items.is_empty() {
+                return None;
+            }
+            Some(ChecklistSummary {
+                subject: subject.clone(),
+                done: items.iter().filter(|item| item.done).count(),
+                total: items.len(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,36 @@
+//! Topics offered to start when an application is launched, for work bound
+//! to a tool like an IDE.
+
+use super::{Content, Topic};
+#[cfg(feature = "gui")]
+use druid::{Data, Lens};
+use std::sync::Arc;
+
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct LaunchRule {
+    /// Name of the process as the system lists it, like `idea` or `Code.exe`.
+    pub process: Arc<str>,
+    pub topic: Topic,
+}
+
+/// Process names are compared ignoring case and the `.exe` of Windows.
+fn normalized(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    match name.strip_suffix(".exe") {
+        Some(stem) => stem.to_string(),
+        None => name,
+    }
+}
+
+pub fn same_process(a: &str, b: &str) -> bool {
+    normalized(a) == normalized(b)
+}
+
+/// The rule for the process named `name`, if there is one.
+pub fn rule_for<'a>(content: &'a Content, name: &str) -> Option<&'a LaunchRule> {
+    content
+        .launch_rules
+        .iter()
+        .find(|rule| same_process(&rule.process, name))
+}
@@ -0,0 +1,252 @@
+//! A flexitime account: time worked beyond the weekly hours, or short of
+//! them, is carried over from week to week.
+
+use super::{export, AppState, Content, Date, History, Session, SpentTime, TimeFormat, Unit};
+#[cfg(feature = "gui")]
+use druid::{Data, Lens};
+use im::Vector;
+use std::{collections::BTreeMap, sync::Arc};
+
+const FORMAT: &str = "%Y-%m-%d";
+
+/// Why no work is expected on a day.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub enum DayOffKind {
+    Holiday,
+    Vacation,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct DayOff {
+    pub day: Date,
+    pub kind: DayOffKind,
+}
+
+/// Time added to the balance by hand, or taken from it if negative, like
+/// overtime that was paid out.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct Adjustment {
+    pub day: Date,
+    pub seconds: i64,
+    pub note: Arc<str>,
+}
+
+/// Writes a balance with its sign, like `+1h 30m` or `-0h 45m`.
+pub fn format_balance(seconds: i64) -> String {
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let time = SpentTime::from_secs(seconds.unsigned_abs());
+    format!("{}{}", sign, time.format(TimeFormat::Short))
+}
+
+/// Time expected on `day`, the weekly hours spread over Monday to Friday.
+/// Nothing is expected on weekends and days off.
+pub fn target(content: &Content, day: Date) -> i64 {
+    let weekly = content.settings.weekly_hours * 3600.0;
+    let weekend = day.weekday().number_days_from_monday() >= 5;
+    if weekly <= 0.0 || weekend || content.days_off.iter().any(|off| off.day == day) {
+        return 0;
+    }
+    (weekly / 5.0).round() as i64
+}
+
+/// The day the account starts on, from the settings or else the week of
+/// the first session. `None` while no weekly hours are set.
+fn start(content: &Content, first: Option<Date>) -> Option<Date> {
+    if content.settings.weekly_hours <= 0.0 {
+        return None;
+    }
+    let configured = time::Date::parse(content.settings.ledger_start.trim(), FORMAT);
+    match configured {
+        Ok(day) => Some(Date::from(day)),
+        Err(_) => first.map(|day| day.week_begin()),
+    }
+}
+
+/// Time worked on each day, sessions count for the day they started on.
+fn worked_per_day<'a>(sessions: impl Iterator<Item = &'a Session>) -> BTreeMap<Date, SpentTime> {
+    let mut worked = BTreeMap::new();
+    for session in sessions {
+        *worked
+            .entry(Date::from(*session.started))
+            .or_insert_with(SpentTime::default) += session.duration();
+    }
+    worked
+}
+
+/// Balance of the days from `from` up to but not including `to`.
+fn balance(content: &Content, worked: &BTreeMap<Date, SpentTime>, from: Date, to: Date) -> i64 {
+    let mut balance = 0;
+    let mut day = from;
+    while day < to {
+        let done = worked.get(&day).map_or(0, |time| time.as_secs() as i64);
+        balance += done - target(content, day);
+        day = Date::from(*day + time::Duration::days(1));
+    }
+    let adjusted: i64 = content
+        .adjustments
+        .iter()
+        .filter(|adjustment| adjustment.day >= from && adjustment.day < to)
+        .map(|adjustment| adjustment.seconds)
+        .sum();
+    balance + adjusted
+}
+
+/// Balance carried into the current week, `None` while no weekly hours are set.
+pub fn carryover(content: &Content, sessions: &History) -> Option<i64> {
+    let first = sessions
+        .iter()
+        .next()
+        .map(|session| Date::from(*session.started));
+    let start = start(content, first)?;
+    let today = Date::from(time::OffsetDateTime::now_local());
+    let week = today.week_begin();
+    let worked = worked_per_day(sessions.iter().filter(|s| Date::from(*s.started) < week));
+    Some(match start < week {
+        true => balance(content, &worked, start, week),
+        false => 0,
+    })
+}
+
+impl AppState {
+    /// The carried balance with this week up to today added, including the
+    /// running session. The recent history holds at least this week.
+    pub fn overtime_balance(&self) -> Option<i64> {
+        let carried = self.setup.carryover?;
+        let today = Date::from(time::OffsetDateTime::now_local());
+        let week = today.week_begin();
+        let mut worked = worked_per_day(
+            self.history
+                .iter()
+                .filter(|session| Date::from(*session.started) >= week),
+        );
+        if let Some(active) = &self.active {
+            *worked.entry(today).or_insert_with(SpentTime::default) += active.duration;
+        }
+        let tomorrow = Date::from(*today + time::Duration::days(1));
+        let from = match start(&self.content, None) {
+            Some(start) if start > week => start,
+            _ => week,
+        };
+        Some(carried + balance(&self.content, &worked, from, tomorrow))
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct LedgerWeek {
+    /// Monday of the week.
+    pub begin: Date,
+    pub worked: SpentTime,
+    pub target: SpentTime,
+    /// Worked minus expected time plus adjustments.
+    pub change: i64,
+    /// Balance at the end of the week.
+    pub balance: i64,
+}
+
+/// Input of the window listing the account week by week.
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct Ledger {
+    pub weeks: Vector<LedgerWeek>,
+    /// Day of a day off or an adjustment being added.
+    pub day: String,
+    /// Time of an adjustment, like `+2h` or `-30m`.
+    pub amount: String,
+    pub note: String,
+    pub status: String,
+    /// All of them, the balance adds up every week since the start.
+    sessions: History,
+}
+
+impl Ledger {
+    pub fn generate(data: &AppState) -> Self {
+        let (sessions, status) = export::full_or_recent_history(&data.content, &data.history);
+        let today = Date::from(time::OffsetDateTime::now_local());
+        let mut ledger = Ledger {
+            weeks: Vector::new(),
+            day: today.format(FORMAT),
+            amount: String::new(),
+            note: String::new(),
+            status,
+            sessions,
+        };
+        ledger.refresh(&data.content);
+        ledger
+    }
+
+    /// Adds up the weeks again after days off or adjustments changed, newest first.
+    pub fn refresh(&mut self, content: &Content) {
+        self.weeks.clear();
+        let first = self.sessions.iter().next().map(|s| Date::from(*s.started));
+        let start = match start(content, first) {
+            Some(start) => start,
+            None => {
+                self.status = "Set the weekly hours in Manage to keep an account.".to_string();
+                return;
+            }
+        };
+        let worked = worked_per_day(self.sessions.iter());
+        let today = Date::from(time::OffsetDateTime::now_local());
+        let tomorrow = Date::from(*today + time::Duration::days(1));
+        let mut running = 0;
+        let mut week = start;
+        while week < tomorrow {
+            let next = Date::from(*week.week_begin() + time::Duration::weeks(1)).min(tomorrow);
+            let mut done = SpentTime::default();
+            let mut expected = 0;
+            let mut day = week;
+            while day < next {
+                done += worked.get(&day).copied().unwrap_or_default();
+                expected += target(content, day);
+                day = Date::from(*day + time::Duration::days(1));
+            }
+            let change = balance(content, &worked, week, next);
+            running += change;
+            self.weeks.push_front(LedgerWeek {
+                begin: week.week_begin(),
+                worked: done,
+                target: SpentTime::from_secs(expected as u64),
+                change,
+                balance: running,
+            });
+            week = next;
+        }
+    }
+
+    /// Balance carried into the current week, as listed.
+    pub fn carryover(&self, content: &Content) -> Option<i64> {
+        if content.settings.weekly_hours <= 0.0 {
+            return None;
+        }
+        let week = Date::from(time::OffsetDateTime::now_local()).week_begin();
+        let carried = self.weeks.iter().find(|listed| listed.begin < week);
+        Some(carried.map_or(0, |listed| listed.balance))
+    }
+
+    pub fn parse_day(&self) -> Result<Date, String> {
+        time::Date::parse(self.day.trim(), FORMAT)
+            .map(Date::from)
+            .map_err(|_| "Enter the day as YYYY-MM-DD.".to_string())
+    }
+
+    /// The adjustment typed in, a time with an optional sign.
+    pub fn to_adjustment(&self) -> Result<Adjustment, String> {
+        let day = self.parse_day()?;
+        let amount = self.amount.trim();
+        let (sign, time) = match amount.strip_prefix('-') {
+            Some(rest) => (-1, rest),
+            None => (1, amount.strip_prefix('+').unwrap_or(amount)),
+        };
+        let time = SpentTime::parse_in(time, Unit::Hours)
+            .map_err(|_| "Enter the time like +2h or -30m.".to_string())?;
+        Ok(Adjustment {
+            day,
+            seconds: sign * time.as_secs() as i64,
+            note: self.note.trim().into(),
+        })
+    }
+}
@@ -0,0 +1,93 @@
+//! The MessagePack file older versions kept everything in, moved into the
+//! database when it is created so their users do not start over.
+
+use super::{
+    backend::Backend, Action, Content, DateTime, History, Session, SpentTime, Subject, Topic,
+};
+use serde::Deserialize;
+use std::{collections::HashMap, error::Error, path::Path, time::Duration};
+
+/// Layout of the old file, with timestamps in seconds since the epoch.
+#[derive(Deserialize)]
+struct LegacyState {
+    actions: Vec<String>,
+    subjects: Vec<String>,
+    time_table: HashMap<LegacyTopic, Duration>,
+    history: Vec<LegacySession>,
+}
+
+#[derive(Deserialize, PartialEq, Eq, Hash)]
+struct LegacyTopic {
+    action: String,
+    subject: String,
+}
+
+#[derive(Deserialize)]
+struct LegacySession {
+    topic: LegacyTopic,
+    started: i64,
+    ended: i64,
+}
+
+/// Moves the old file into the freshly created database and renames it to
+/// `zeitig.mp.bak`. Returns the number of sessions moved, `None` without an old file.
+pub fn migrate(backend: &mut dyn Backend, path: &Path) -> Result<Option<usize>, Box<dyn Error>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let bytes = std::fs::read(path)?;
+    let legacy: LegacyState = rmp_serde::from_slice(&bytes)
+        .map_err(|err| format!("Cannot read {}: {}", path.display(), err))?;
+
+    let mut content = Content::default();
+    for name in &legacy.actions {
+        content.actions.push_back(Action::new(0, name));
+    }
+    for name in &legacy.subjects {
+        content.subjects.push_back(Subject::new(0, name));
+    }
+    backend.transfer_content(&content)?;
+
+    // Ids are assigned by the database, so topics are resolved against what it stored.
+    let content = backend.load_content()?;
+    let topic = |legacy: &LegacyTopic| -> Result<Topic, String> {
+        let action = content.actions.iter().find(|a| *a.name == *legacy.action);
+        let subject = content.subjects.iter().find(|s| *s.name == *legacy.subject);
+        match (action, subject) {
+            (Some(action), Some(subject)) => Ok(Topic {
+                action: action.clone(),
+                subject: subject.clone(),
+            }),
+            _ => Err(format!(
+                "The old file refers to the unknown topic {} {}.",
+                legacy.action, legacy.subject
+            )),
+        }
+    };
+    let timestamp =
+        |seconds: i64| DateTime::from(time::OffsetDateTime::from_unix_timestamp(seconds));
+
+    let mut sessions = im::Vector::new();
+    for session in &legacy.history {
+        sessions.push_back(Session {
+            topic: topic(&session.topic)?,
+            started: timestamp(session.started),
+            ended: timestamp(session.ended),
+            context: None,
+            interruptions: Default::default(),
+            original_start: None,
+            intervals: Default::default(),
+            note: None,
+            tags: Default::default(),
+        });
+    }
+    let moved = sessions.len();
+    backend.transfer_history(&History::new(sessions, true))?;
+    // The old time table also holds time recorded before sessions were kept.
+    for (legacy, time) in &legacy.time_table {
+        backend.update_time(&topic(legacy)?, &SpentTime::from(*time))?;
+    }
+
+    std::fs::rename(path, path.with_extension("mp.bak"))?;
+    Ok(Some(moved))
+}
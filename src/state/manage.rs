@@ -0,0 +1,456 @@
+use super::{
+    collation, launch::LaunchRule, time::Unit, Alias, AliasTarget, AppState, Content, Date,
+    DateTime, History, Interval, Session, Settings, SpentTime, Subject,
+};
+#[cfg(feature = "gui")]
+use druid::{Data, Lens};
+use im::Vector;
+use regex::Regex;
+use std::sync::Arc;
+
+pub static LOCK_FORMAT: &str = "%Y-%m-%d";
+
+/// Editable snapshot of the per-topic settings shown in the manage window.
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct Manage {
+    pub subjects: Vector<SubjectEntry>,
+    pub settings: Settings,
+    pub locked_before: Option<Date>,
+    /// First day after the billing period to lock, as typed.
+    pub lock_before: String,
+    pub bulk_rename: BulkRename,
+    pub split: SplitSubject,
+    pub trim: TrimSessions,
+    pub aliases: Vector<AliasEntry>,
+    /// Alias being added, as typed.
+    pub alias_name: String,
+    /// Name of the action or subject the alias is added for.
+    pub alias_target: String,
+    pub launch_rules: Vector<LaunchRule>,
+    /// Launch rule being added, as typed.
+    pub launch_process: String,
+    pub launch_action: String,
+    pub launch_subject: String,
+    pub status: String,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct SubjectEntry {
+    pub subject: Subject,
+    pub category: String,
+}
+
+/// An alias with the name of the action or subject it stands for.
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct AliasEntry {
+    pub alias: Alias,
+    pub target: Arc<str>,
+}
+
+impl AliasEntry {
+    /// Entries of every alias whose action or subject still exists, sorted by alias.
+    pub fn all(content: &Content) -> Vector<AliasEntry> {
+        let mut entries: Vec<AliasEntry> = content
+            .aliases
+            .iter()
+            .filter_map(|alias| {
+                let target = match alias.target {
+                    AliasTarget::Action(id) => content.find_action(id)?.name,
+                    AliasTarget::Subject(id) => content.find_subject(id)?.name,
+                };
+                Some(AliasEntry {
+                    alias: alias.clone(),
+                    target,
+                })
+            })
+            .collect();
+        entries.sort_by(|a, b| collation::compare(&a.alias.name, &b.alias.name));
+        entries.into()
+    }
+}
+
+/// Find and replace over the names of actions and subjects, for cleaning up
+/// imported ones.
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct BulkRename {
+    pub pattern: String,
+    /// May refer to groups of the pattern as `$1` or `$name`.
+    pub replacement: String,
+    pub actions: bool,
+    pub subjects: bool,
+    pub preview: Vector<Renamed>,
+}
+
+/// A name the bulk rename changes.
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct Renamed {
+    /// Whether it names an action rather than a subject.
+    pub action: bool,
+    pub id: usize,
+    pub from: Arc<str>,
+    pub to: Arc<str>,
+    /// The new name is empty or taken, so the rename cannot be applied.
+    pub conflict: bool,
+}
+
+impl BulkRename {
+    /// The names the pattern changes, actions first.
+    pub fn renames(&self, content: &Content) -> Result<Vector<Renamed>, String> {
+        if self.pattern.is_empty() {
+            return Err("Enter a pattern to search for.".to_string());
+        }
+        let regex = Regex::new(&self.pattern).map_err(|err| err.to_string())?;
+        let mut renames = Vector::new();
+        if self.actions {
+            let names = content.actions.iter().map(|a| (a.id, a.name.clone()));
+            renames.append(self.rename(&regex, true, names.collect()));
+        }
+        if self.subjects {
+            let names = content.subjects.iter().map(|s| (s.id, s.name.clone()));
+            renames.append(self.rename(&regex, false, names.collect()));
+        }
+        Ok(renames)
+    }
+
+    fn rename(
+        &self,
+        regex: &Regex,
+        action: bool,
+        names: Vec<(usize, Arc<str>)>,
+    ) -> Vector<Renamed> {
+        let renamed: Vec<(usize, Arc<str>)> = names
+            .iter()
+            .map(|(id, name)| {
+                let to = regex.replace_all(name, self.replacement.as_str());
+                (*id, to.trim().into())
+            })
+            .collect();
+        names
+            .iter()
+            .zip(&renamed)
+            .filter(|((_, from), (_, to))| from != to)
+            .map(|((id, from), (_, to))| Renamed {
+                action,
+                id: *id,
+                from: from.clone(),
+                to: to.clone(),
+                conflict: to.is_empty()
+                    || renamed
+                        .iter()
+                        .any(|(other, name)| other != id && collation::same_name(name, to)),
+            })
+            .collect()
+    }
+}
+
+/// Moves the sessions of a subject that grew to cover several things to new
+/// subjects, each taking the sessions its rule matches.
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct SplitSubject {
+    /// Name of the subject to split, as typed.
+    pub subject: String,
+    pub rules: Vector<SplitRule>,
+    pub preview: Vector<SplitPart>,
+}
+
+/// Sessions matching every condition filled in move to `into`, the first
+/// matching rule wins and sessions matching none stay.
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct SplitRule {
+    /// Name of the subject to move to, created if there is none.
+    pub into: String,
+    /// Text the note has to contain.
+    pub note: String,
+    pub tag: String,
+    /// First day as YYYY-MM-DD.
+    pub from: String,
+    /// Last day as YYYY-MM-DD.
+    pub until: String,
+}
+
+/// What a split moves to one subject.
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct SplitPart {
+    pub into: Arc<str>,
+    pub sessions: usize,
+    pub spent_time: SpentTime,
+    /// Whether the subject has to be created.
+    pub new: bool,
+}
+
+impl SplitRule {
+    pub fn new() -> Self {
+        SplitRule {
+            into: String::new(),
+            note: String::new(),
+            tag: String::new(),
+            from: String::new(),
+            until: String::new(),
+        }
+    }
+
+    fn matches(&self, session: &Session, from: Option<Date>, until: Option<Date>) -> bool {
+        let note = self.note.trim();
+        let tag = self.tag.trim();
+        let day = Date::from(*session.started);
+        (note.is_empty()
+            || collation::fold(session.note.as_deref().unwrap_or_default())
+                .contains(&collation::fold(note)))
+            && (tag.is_empty() || collation::find(&session.tags, tag).is_some())
+            && from.map_or(true, |from| day >= from)
+            && until.map_or(true, |until| day <= until)
+    }
+}
+
+impl SplitSubject {
+    /// The sessions of the subject to split with the name of the subject each
+    /// moves to. Sessions in the locked billing period stay.
+    pub fn moves(
+        &self,
+        content: &Content,
+        history: &History,
+    ) -> Result<Vector<(Session, Arc<str>)>, String> {
+        let subject = content
+            .subject_named(self.subject.trim())
+            .ok_or_else(|| format!("No subject is named {}.", self.subject.trim()))?;
+        if self.rules.is_empty() {
+            return Err("Add a rule for the sessions to move.".to_string());
+        }
+        let day = |text: &str| -> Result<Option<Date>, String> {
+            match text.trim() {
+                "" => Ok(None),
+                text => time::Date::parse(text, LOCK_FORMAT)
+                    .map(|date| Some(Date::from(date)))
+                    .map_err(|_| "Enter the days as YYYY-MM-DD.".to_string()),
+            }
+        };
+        let mut rules = Vec::new();
+        for rule in &self.rules {
+            let into = rule.into.trim();
+            if into.is_empty() {
+                return Err("Every rule needs a subject to move to.".to_string());
+            }
+            let into: Arc<str> = match content.subject_named(into) {
+                Some(existing) if existing == subject => {
+                    return Err(format!("Sessions cannot move to {} itself.", into));
+                }
+                Some(existing) => existing.name.clone(),
+                None => into.into(),
+            };
+            rules.push((rule, into, day(&rule.from)?, day(&rule.until)?));
+        }
+        Ok(history
+            .iter()
+            .filter(|session| session.topic.subject == *subject)
+            .filter(|session| !content.is_locked(&session.key()))
+            .filter_map(|session| {
+                rules
+                    .iter()
+                    .find(|(rule, _, from, until)| rule.matches(session, *from, *until))
+                    .map(|(_, into, _, _)| (session.clone(), into.clone()))
+            })
+            .collect())
+    }
+
+    /// The sessions and time each subject would take over, in the order of the rules.
+    pub fn parts(content: &Content, moves: &Vector<(Session, Arc<str>)>) -> Vector<SplitPart> {
+        let mut parts: Vector<SplitPart> = Vector::new();
+        for (session, into) in moves {
+            match parts.iter_mut().find(|part| part.into == *into) {
+                Some(part) => {
+                    part.sessions += 1;
+                    part.spent_time += session.duration();
+                }
+                None => parts.push_back(SplitPart {
+                    into: into.clone(),
+                    sessions: 1,
+                    spent_time: session.duration(),
+                    new: content.subject_named(into).is_none(),
+                }),
+            }
+        }
+        parts
+    }
+}
+
+/// Sessions longer than a threshold, likely left running by accident, each
+/// with an end to trim it to.
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct TrimSessions {
+    /// Sessions longer than this are listed, as typed.
+    pub longer_than: String,
+    pub candidates: Vector<TrimCandidate>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct TrimCandidate {
+    pub session: Session,
+    pub suggested: DateTime,
+    /// What the suggested end is based on.
+    pub basis: Arc<str>,
+    /// Whether it is trimmed when applying.
+    pub chosen: bool,
+}
+
+impl TrimSessions {
+    /// The sessions longer than the threshold, oldest first. Sessions in the
+    /// locked billing period are left out.
+    pub fn long_sessions(
+        &self,
+        content: &Content,
+        history: &History,
+    ) -> Result<Vector<TrimCandidate>, String> {
+        let threshold =
+            SpentTime::parse_in(&self.longer_than, Unit::Hours).map_err(|err| err.to_string())?;
+        if threshold.as_secs() == 0 {
+            return Err("Enter how long a session has to be to be listed.".to_string());
+        }
+        let grace = match content.settings.idle_minutes {
+            minutes if minutes > 0.0 => time::Duration::minutes(minutes as i64),
+            _ => time::Duration::minutes(15),
+        };
+        let mut starts: Vec<DateTime> = history.iter().map(|session| session.started).collect();
+        starts.sort_by_key(|started| **started);
+        Ok(history
+            .iter()
+            .filter(|session| *session.duration() > *threshold)
+            .filter(|session| !content.is_locked(&session.key()))
+            .map(|session| {
+                let next = starts
+                    .iter()
+                    .find(|started| ***started > *session.started)
+                    .copied();
+                TrimCandidate::new(session, threshold, grace, next)
+            })
+            .collect())
+    }
+}
+
+impl TrimCandidate {
+    /// Suggests ending the session a little after the last sign of activity,
+    /// a break taken or an interruption noted, or else once it reached the
+    /// threshold. It never runs into the `next` session.
+    fn new(
+        session: &Session,
+        threshold: SpentTime,
+        grace: time::Duration,
+        next: Option<DateTime>,
+    ) -> Self {
+        let ended = *session.ended;
+        let resumed = session
+            .intervals
+            .iter()
+            .skip(1)
+            .map(|i| (*i.started, "break"));
+        let interrupted = session
+            .interruptions
+            .iter()
+            .map(|i| (*i.at, "interruption"));
+        let activity = resumed
+            .chain(interrupted)
+            .filter(|(at, _)| *at > *session.started && *at < ended)
+            .max_by_key(|(at, _)| *at);
+        let (mut suggested, mut basis) = match activity {
+            Some((at, kind)) if at + grace < ended => (
+                at + grace,
+                format!("last {} at {}", kind, at.format("%H:%M")),
+            ),
+            _ => (
+                *session.started + time::Duration::seconds(threshold.as_secs() as i64),
+                "no activity recorded".to_string(),
+            ),
+        };
+        if let Some(next) = next.filter(|next| **next < suggested) {
+            suggested = *next;
+            basis = format!("next session at {}", next.format("%H:%M"));
+        }
+        TrimCandidate {
+            session: session.clone(),
+            suggested: DateTime::from(suggested.min(ended)),
+            basis: basis.into(),
+            chosen: true,
+        }
+    }
+
+    /// The session ending at the suggested time, without the breaks and
+    /// interruptions after it.
+    pub fn trimmed(&self) -> Session {
+        let end = *self.suggested;
+        let mut session = self.session.clone();
+        session.ended = self.suggested;
+        session
+            .interruptions
+            .retain(|interruption| *interruption.at <= end);
+        session.intervals = session
+            .intervals
+            .iter()
+            .filter(|interval| *interval.started < end)
+            .map(|interval| {
+                Interval::new(interval.started, DateTime::from((*interval.ended).min(end)))
+            })
+            .collect();
+        session
+    }
+}
+
+impl Manage {
+    pub fn generate(data: &AppState) -> Self {
+        let subjects = data
+            .content
+            .subjects
+            .iter()
+            .map(|subject| SubjectEntry {
+                subject: subject.clone(),
+                category: data
+                    .content
+                    .categories
+                    .get(subject)
+                    .map(|c| c.to_string())
+                    .unwrap_or_default(),
+            })
+            .collect();
+        let today = Date::from(time::OffsetDateTime::now_local());
+        Manage {
+            subjects,
+            settings: data.content.settings.clone(),
+            locked_before: data.content.locked_before,
+            lock_before: today
+                .month_begin_on(data.content.settings.month_start as u8)
+                .format(LOCK_FORMAT),
+            bulk_rename: BulkRename {
+                pattern: String::new(),
+                replacement: String::new(),
+                actions: true,
+                subjects: true,
+                preview: Vector::new(),
+            },
+            split: SplitSubject {
+                subject: String::new(),
+                rules: Vector::unit(SplitRule::new()),
+                preview: Vector::new(),
+            },
+            trim: TrimSessions {
+                longer_than: "10h".to_string(),
+                candidates: Vector::new(),
+            },
+            aliases: AliasEntry::all(&data.content),
+            alias_name: String::new(),
+            alias_target: String::new(),
+            launch_rules: data.content.launch_rules.clone(),
+            launch_process: String::new(),
+            launch_action: String::new(),
+            launch_subject: String::new(),
+            status: String::new(),
+        }
+    }
+}
@@ -1,20 +1,93 @@
+#[cfg(feature = "gui")]
 use druid::{Data, Lens};
+#[cfg(feature = "gui")]
 use druid_enums::Matcher;
 use im::{HashMap, Vector};
-use std::sync::Arc;
+use std::{
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
 
 pub mod backend;
+pub mod backup;
+pub mod billing;
+pub mod cleanup;
+pub mod closing;
+pub mod collation;
+pub mod companion;
+pub mod entry;
+pub mod export;
+pub mod goal;
+pub mod health;
+pub mod hierarchy;
+pub mod history;
+pub mod idle;
+pub mod import;
+pub mod insights;
+pub mod launch;
+pub mod ledger;
+pub mod legacy;
+pub mod manage;
+pub mod order;
+pub mod palette;
 pub mod paths;
+pub mod pomodoro;
+pub mod reminder;
+pub mod report;
+pub mod rounding;
+pub mod signing;
+pub mod snapshot;
+pub mod stats;
+pub mod streak;
+pub mod template;
 pub mod time;
+pub mod toggl;
+pub mod totals;
 
-pub use self::time::{Date, DateTime, SpentTime};
+pub use self::time::{Date, DateTime, SpentTime, TimeFormat, Unit};
 
-#[derive(Clone, Default, Data, Lens)]
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
 pub struct AppState {
     pub content: Content,
     pub history: History,
     pub setup: Setup,
     pub active: Option<ActiveSession>,
+    pub insights: Option<insights::Insights>,
+    pub manage: Option<manage::Manage>,
+    pub entry: Option<entry::ManualEntry>,
+    pub startup: Startup,
+    pub pomodoro: Option<pomodoro::Pomodoro>,
+    pub report: Option<report::Report>,
+    pub ledger: Option<ledger::Ledger>,
+    pub stats: Option<stats::Stats>,
+    pub cleanup: Option<cleanup::Cleanup>,
+    /// Problems found when starting, until they are fixed or dismissed.
+    pub findings: Vector<health::Finding>,
+    /// A change to a session refused as another instance changed it first.
+    pub conflict: Option<history::Conflict>,
+    /// Copies of the data file offered for restoring, while choosing one.
+    pub snapshots: Option<Vector<snapshot::Snapshot>>,
+    pub undo: history::UndoManager,
+    /// Only looking at a file opened with `--read-only`, nothing can be changed.
+    pub read_only: bool,
+    /// Started with `--safe-mode`, the optional integrations stay off.
+    pub safe_mode: bool,
+}
+
+/// Content and history are loaded by the backend after the window opened.
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub enum Startup {
+    Loading,
+    Ready,
+    Failed(Arc<str>),
+}
+
+impl Default for Startup {
+    fn default() -> Self {
+        Startup::Loading
+    }
 }
 
 #[allow(non_upper_case_globals)]
@@ -36,29 +109,216 @@ impl AppState {
             _ => SpentTime::default(),
         }
     }
+
+    /// Time spent today across all topics, including the running session.
+    pub fn today_duration(&self) -> SpentTime {
+        let today = Date::from(::time::OffsetDateTime::now_local());
+        let running = self
+            .active
+            .as_ref()
+            .map_or_else(SpentTime::default, |active| active.duration);
+        self.history
+            .iter()
+            .filter(|session| Date::from(*session.started) == today)
+            .fold(running, |sum, session| sum + session.duration())
+    }
+
+    /// Time spent on `subject` in total, including the running session.
+    pub fn subject_progress(&self, subject: &Subject) -> SpentTime {
+        let running = match (&self.active, &self.setup.selected_subject) {
+            (Some(active), Some(selected)) if selected == subject => active.duration,
+            _ => SpentTime::default(),
+        };
+        self.content
+            .time_table
+            .iter()
+            .filter(|(topic, _)| topic.subject == *subject)
+            .fold(running, |sum, (_, time)| sum + *time)
+    }
+
+    /// The goal of the selected topic.
+    pub fn selected_goal(&self) -> Option<&goal::Goal> {
+        match (&self.setup.selected_action, &self.setup.selected_subject) {
+            (Some(action), Some(subject)) => self
+                .content
+                .goals
+                .iter()
+                .find(|goal| goal.topic.action == *action && goal.topic.subject == *subject),
+            _ => None,
+        }
+    }
+
+    /// Time spent towards `goal` in its current period, including the running session.
+    pub fn goal_progress(&self, goal: &goal::Goal) -> SpentTime {
+        let running = match &self.active {
+            Some(active) if self.selected_goal().map(|g| &g.topic) == Some(&goal.topic) => {
+                active.duration
+            }
+            _ => SpentTime::default(),
+        };
+        goal.progress(
+            &self.history,
+            running,
+            self.content.settings.month_start as u8,
+        )
+    }
+
+    /// Marks the goals reached in their current period, returning the ones
+    /// that were not reached before.
+    pub fn reach_goals(&mut self) -> Vec<goal::Goal> {
+        let today = Date::from(::time::OffsetDateTime::now_local());
+        let mut reached = Vec::new();
+        for index in 0..self.content.goals.len() {
+            let goal = &self.content.goals[index];
+            let begin = goal
+                .period
+                .begin(today, self.content.settings.month_start as u8);
+            if goal.reached == Some(begin) || *self.goal_progress(goal) < *goal.target {
+                continue;
+            }
+            let goal = &mut self.content.goals[index];
+            goal.reached = Some(begin);
+            reached.push(goal.clone());
+        }
+        reached
+    }
+
+    /// Brings back a deleted session, keeping the precomputed totals in sync.
+    pub fn restore_session(&mut self, session: Session) {
+        *self.content.time_table.get_mut(session.topic.clone()) += session.duration();
+        self.content.totals.add(&session);
+        self.history.insert(session);
+    }
+
+    /// Swaps a recorded session for its amended version, or drops it if
+    /// `amended` is `None`, keeping the precomputed totals in sync.
+    pub fn amend_session(&mut self, original: &Session, amended: Option<Session>) {
+        let spent = self.content.time_table.get_mut(original.topic.clone());
+        *spent = spent.saturating_sub(original.duration());
+        self.content.totals.remove(original);
+        if let Some(amended) = &amended {
+            *self.content.time_table.get_mut(amended.topic.clone()) += amended.duration();
+            self.content.totals.add(amended);
+        }
+        self.history.replace(original, amended);
+    }
+
+    /// Takes over the name of `renamed` wherever the action with its id is referenced.
+    pub fn rename_action(&mut self, renamed: &Action) {
+        let rename = |action: &Action| renamed_as(action, renamed);
+        let content = &mut self.content;
+        content.actions = content.actions.iter().map(rename).collect();
+        content.actions.sort();
+        content
+            .time_table
+            .update_topics(|topic| topic.action = rename(&topic.action));
+        content
+            .totals
+            .update_keys(|key| key.action = key.action.as_ref().map(rename));
+        for topic in content.pinned.iter_mut() {
+            topic.action = rename(&topic.action);
+        }
+        for goal in content.goals.iter_mut() {
+            goal.topic.action = rename(&goal.topic.action);
+        }
+        self.history
+            .update_topics(|topic| topic.action = rename(&topic.action));
+        self.setup.selected_action = self.setup.selected_action.as_ref().map(rename);
+        if let Some(entry) = &mut self.entry {
+            entry.action = entry.action.as_ref().map(rename);
+        }
+    }
+
+    /// Takes over `renamed` wherever the subject with its id is referenced.
+    pub fn rename_subject(&mut self, renamed: &Subject) {
+        let rename = |subject: &Subject| renamed_as(subject, renamed);
+        let content = &mut self.content;
+        content.subjects = content.subjects.iter().map(rename).collect();
+        content.subjects.sort();
+        content
+            .time_table
+            .update_topics(|topic| topic.subject = rename(&topic.subject));
+        content
+            .totals
+            .update_keys(|key| key.subject = key.subject.as_ref().map(rename));
+        for topic in content.pinned.iter_mut() {
+            topic.subject = rename(&topic.subject);
+        }
+        for goal in content.goals.iter_mut() {
+            goal.topic.subject = rename(&goal.topic.subject);
+        }
+        self.history
+            .update_topics(|topic| topic.subject = rename(&topic.subject));
+        self.setup.selected_subject = self.setup.selected_subject.as_ref().map(rename);
+        if let Some(entry) = &mut self.entry {
+            entry.subject = entry.subject.as_ref().map(rename);
+        }
+    }
 }
 
-#[derive(Clone, Data, Lens, PartialEq, Eq, Hash)]
+/// `item`, or `renamed` if both are the same entry, as they compare by id.
+fn renamed_as<T: PartialEq + Clone>(item: &T, renamed: &T) -> T {
+    if item == renamed {
+        renamed.clone()
+    } else {
+        item.clone()
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
 pub struct Topic {
     pub action: Action,
     pub subject: Subject,
 }
 
-#[derive(Clone, Data, Lens, Eq, Hash)]
+impl Topic {
+    /// The color of the subject, or of the action if the subject has none.
+    /// With `by_action` the action's color comes first instead.
+    pub fn color(&self, by_action: bool) -> Option<u32> {
+        if by_action {
+            self.action.color.or(self.subject.color)
+        } else {
+            self.subject.color.or(self.action.color)
+        }
+    }
+}
+
+#[derive(Clone, Eq)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
 pub struct Action {
     pub id: usize,
     pub name: Arc<str>,
+    /// Hidden from the selection lists, but kept for the recorded sessions.
+    pub archived: bool,
+    /// Created for a one-off, archived once it has not been used for a while.
+    pub temporary: bool,
+    /// Shown next to the name to tell topics apart, as `0xRRGGBB`.
+    pub color: Option<u32>,
+}
+
+impl Action {
+    /// An action as it is created, with nothing set but its name.
+    pub fn new(id: usize, name: &str) -> Self {
+        Action {
+            id,
+            name: name.into(),
+            archived: false,
+            temporary: false,
+            color: None,
+        }
+    }
 }
 
 impl PartialOrd for Action {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.name.cmp(&other.name))
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for Action {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.name.cmp(&other.name)
+        collation::compare(&self.name, &other.name)
     }
 }
 
@@ -74,21 +334,64 @@ impl PartialEq for Action {
     }
 }
 
-#[derive(Clone, Data, Lens, Eq, Hash)]
+impl Hash for Action {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+#[derive(Clone, Eq)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
 pub struct Subject {
     pub id: usize,
     pub name: Arc<str>,
+    /// Hidden from the selection lists, but kept for the recorded sessions.
+    pub archived: bool,
+    /// Created for a one-off, archived once it has not been used for a while.
+    pub temporary: bool,
+    /// Where issue references in notes link to, `{id}` stands for the reference.
+    pub issue_url: Option<Arc<str>>,
+    /// Time the subject is expected to take in total.
+    pub estimate: Option<SpentTime>,
+    /// Hourly rate in cents the time spent is billed at, `None` if it is not billable.
+    pub rate: Option<u32>,
+    pub idle_rule: idle::IdleRule,
+    /// Shown next to the name to tell topics apart, as `0xRRGGBB`.
+    pub color: Option<u32>,
+    /// Id of the subject this one is nested in, like a sub-project in its project.
+    pub parent: Option<usize>,
+    /// Summary kept from when the subject was archived as a closed project.
+    pub final_report: Option<Arc<str>>,
+}
+
+impl Subject {
+    /// A subject as it is created, with nothing set but its name.
+    pub fn new(id: usize, name: &str) -> Self {
+        Subject {
+            id,
+            name: name.into(),
+            archived: false,
+            temporary: false,
+            issue_url: None,
+            estimate: None,
+            rate: None,
+            idle_rule: idle::IdleRule::Default,
+            color: None,
+            parent: None,
+            final_report: None,
+        }
+    }
 }
 
 impl PartialOrd for Subject {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.name.cmp(&other.name))
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for Subject {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.name.cmp(&other.name)
+        collation::compare(&self.name, &other.name)
     }
 }
 
@@ -104,11 +407,248 @@ impl PartialEq for Subject {
     }
 }
 
-#[derive(Clone, Default, Data, Lens)]
+impl Hash for Subject {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+/// Where a session took place, e.g. at home or in the office.
+#[derive(Clone, Eq)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct Context {
+    pub id: usize,
+    pub name: Arc<str>,
+}
+
+impl PartialOrd for Context {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Context {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        collation::compare(&self.name, &other.name)
+    }
+}
+
+impl AsRef<str> for Context {
+    fn as_ref(&self) -> &str {
+        &self.name
+    }
+}
+
+impl PartialEq for Context {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Hash for Context {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+/// A label for sessions that cuts across topics, e.g. client or internal work.
+#[derive(Clone, Eq)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct Tag {
+    pub id: usize,
+    pub name: Arc<str>,
+}
+
+impl PartialOrd for Tag {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Tag {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        collation::compare(&self.name, &other.name)
+    }
+}
+
+impl AsRef<str> for Tag {
+    fn as_ref(&self) -> &str {
+        &self.name
+    }
+}
+
+impl PartialEq for Tag {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Hash for Tag {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
 pub struct Content {
     pub actions: Vector<Action>,
     pub subjects: Vector<Subject>,
+    pub contexts: Vector<Context>,
+    pub tags: Vector<Tag>,
+    pub categories: Categories,
+    pub checklist: Vector<ChecklistItem>,
+    pub aliases: Vector<Alias>,
+    /// Topics started with the number keys, in the order of their keys.
+    pub pinned: Vector<Topic>,
+    pub action_order: order::ListOrder,
+    pub subject_order: order::ListOrder,
+    /// At most one goal per topic.
+    pub goals: Vector<goal::Goal>,
+    /// Sessions starting before this day belong to a closed billing period
+    /// and can only be changed after unlocking it.
+    pub locked_before: Option<Date>,
+    /// Days no work is expected on, for the overtime balance.
+    pub days_off: Vector<ledger::DayOff>,
+    pub adjustments: Vector<ledger::Adjustment>,
+    /// Topics offered to start when an application is launched.
+    pub launch_rules: Vector<launch::LaunchRule>,
     pub time_table: TimeTable,
+    pub totals: totals::Totals,
+    pub settings: Settings,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct Settings {
+    /// Never modify recorded sessions, edits are stored as amendments instead.
+    pub strict_sessions: bool,
+    /// Record sessions running past midnight as one session per day.
+    pub split_at_midnight: bool,
+    /// Write a detached ed25519 signature next to every export.
+    pub sign_exports: bool,
+    /// Minutes without input before asking about the idle time, 0 to never ask.
+    pub idle_minutes: f64,
+    /// Pause the running session while the screen is locked or the system asleep.
+    pub pause_on_lock: bool,
+    /// End it instead, so it is recorded up to the time the user went away.
+    pub end_when_away: bool,
+    /// Go on without the time away once back, instead of asking how to count it.
+    pub resume_on_unlock: bool,
+    /// Minutes a session runs before suggesting a break, 0 to never suggest one.
+    pub long_session_minutes: f64,
+    /// Ask before deleting a session in the history.
+    pub confirm_delete_session: bool,
+    /// Ask before deleting an action or a subject.
+    pub confirm_delete_topic: bool,
+    /// Minutes a session runs before stopping it with the button has to be confirmed, 0 to never ask.
+    pub confirm_stop_minutes: f64,
+    /// Ask before importing a file or restoring a copy of the data.
+    pub confirm_import: bool,
+    /// Look at the processes started, to offer the topic of their launch rule.
+    pub watch_launches: bool,
+    /// Work hours on weekdays typed as `09:00-17:00`, to remind of tracking during them.
+    /// Empty to never remind.
+    pub work_hours: String,
+    /// Minutes without a session during the work hours before reminding again, 0 to remind only once.
+    pub remind_idle_minutes: f64,
+    /// Hours expected per week, spread over Monday to Friday. Time worked beyond
+    /// them or short of them is carried over. 0 to not keep an overtime balance.
+    pub weekly_hours: f64,
+    /// Day the overtime balance starts on as `YYYY-MM-DD`, empty to start with the first session.
+    pub ledger_start: String,
+    /// Minutes between announcements of the time a session has been running, 0 to not announce it.
+    pub milestone_minutes: f64,
+    /// Program reading announcements out loud with the text as last argument, like "espeak".
+    pub speech_command: String,
+    /// Characters of action and subject names shown before shortening them, 0 to never shorten.
+    pub name_length: f64,
+    /// Split sessions into work intervals separated by breaks.
+    pub pomodoro: bool,
+    pub work_minutes: f64,
+    pub break_minutes: f64,
+    /// Months without use after which archiving is suggested, 0 to never suggest it.
+    pub stale_months: f64,
+    /// Copies of the data file kept in the backup folder, 0 to not take any.
+    pub backups_kept: f64,
+    /// Day of the month months start on in reports and goals, to match a billing cycle.
+    pub month_start: f64,
+    /// Minutes a subject needs on a day for the day to count towards its streak.
+    pub streak_minutes: f64,
+    /// Hide the window in the tray when it is closed, if a tray is available.
+    pub close_to_tray: bool,
+    /// Color sessions by their action rather than their subject.
+    pub color_by_action: bool,
+    /// Count the running time in minutes only, sessions are still recorded to the second.
+    pub hide_seconds: bool,
+    /// System-wide shortcut starting or stopping the session, like "Ctrl+Alt+Space".
+    pub hotkey: String,
+    /// Minutes reported and exported time is rounded to, 0 to keep it exact.
+    pub rounding_minutes: f64,
+    /// Round up to the next increment rather than to the nearest one.
+    pub round_up: bool,
+    /// Round the time of each day rather than of each session.
+    pub round_per_day: bool,
+    /// Locale amounts of money are written in, like "de-CH", empty for English.
+    pub money_locale: String,
+    /// Written next to amounts of money, like "€" or "CHF".
+    pub currency: String,
+    /// Port of the read-only dashboard served to the local network, empty to not serve it.
+    pub dashboard_port: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            strict_sessions: false,
+            split_at_midnight: true,
+            sign_exports: false,
+            idle_minutes: 0.0,
+            pause_on_lock: false,
+            end_when_away: false,
+            resume_on_unlock: true,
+            long_session_minutes: 0.0,
+            confirm_delete_session: false,
+            confirm_delete_topic: false,
+            confirm_stop_minutes: 0.0,
+            confirm_import: false,
+            watch_launches: false,
+            milestone_minutes: 0.0,
+            speech_command: String::new(),
+            work_hours: String::new(),
+            remind_idle_minutes: 0.0,
+            weekly_hours: 0.0,
+            ledger_start: String::new(),
+            name_length: 32.0,
+            pomodoro: false,
+            work_minutes: 25.0,
+            break_minutes: 5.0,
+            stale_months: 6.0,
+            backups_kept: 10.0,
+            month_start: 1.0,
+            streak_minutes: 15.0,
+            close_to_tray: false,
+            color_by_action: false,
+            hide_seconds: false,
+            hotkey: String::new(),
+            rounding_minutes: 0.0,
+            round_up: false,
+            round_per_day: false,
+            money_locale: String::new(),
+            currency: String::new(),
+            dashboard_port: String::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// How the running time is shown while it counts.
+    pub fn live_format(&self) -> TimeFormat {
+        match self.hide_seconds {
+            true => TimeFormat::Short,
+            false => TimeFormat::Long,
+        }
+    }
 }
 
 impl Content {
@@ -119,9 +659,122 @@ impl Content {
     pub fn find_subject(&self, id: usize) -> Option<Subject> {
         self.subjects.iter().find(|s| s.id == id).cloned()
     }
+
+    pub fn find_context(&self, id: usize) -> Option<Context> {
+        self.contexts.iter().find(|c| c.id == id).cloned()
+    }
+
+    pub fn find_tag(&self, id: usize) -> Option<Tag> {
+        self.tags.iter().find(|t| t.id == id).cloned()
+    }
+
+    /// Actions offered for selection in their arranged order, archived ones
+    /// only if requested.
+    pub fn listed_actions(&self, archived: bool) -> Vector<Action> {
+        let actions = self.actions.iter().filter(|a| archived || !a.archived);
+        self.action_order.arrange(actions.cloned(), |a| a.id)
+    }
+
+    /// Subjects offered for selection in their arranged order, archived ones
+    /// only if requested.
+    pub fn listed_subjects(&self, archived: bool) -> Vector<Subject> {
+        let subjects = self.subjects.iter().filter(|s| archived || !s.archived);
+        self.subject_order.arrange(subjects.cloned(), |s| s.id)
+    }
+
+    /// The most topics that can be pinned, one for each of the keys 1 to 9.
+    pub const PINS: usize = 9;
+
+    /// Whether a session starting at `started` belongs to the locked billing period.
+    pub fn is_locked(&self, started: &DateTime) -> bool {
+        self.locked_before
+            .map_or(false, |before| Date::from(**started) < before)
+    }
+
+    /// Whether any time has been recorded on a topic matching `filter`.
+    pub fn has_recorded_time(&self, filter: impl Fn(&Topic) -> bool) -> bool {
+        self.time_table
+            .iter()
+            .any(|(topic, time)| filter(topic) && time.as_secs() > 0)
+    }
+
+    pub fn checklist_of(&self, subject: &Subject) -> Vector<ChecklistItem> {
+        self.checklist
+            .iter()
+            .filter(|item| item.subject == subject.id)
+            .cloned()
+            .collect()
+    }
+
+    /// The action called `name` or known by it as an alias, ignoring case.
+    pub fn action_named(&self, name: &str) -> Option<&Action> {
+        collation::find(&self.actions, name).or_else(|| {
+            let id = self.aliases.iter().find_map(|alias| match alias.target {
+                AliasTarget::Action(id) if collation::same_name(&alias.name, name) => Some(id),
+                _ => None,
+            })?;
+            self.actions.iter().find(|action| action.id == id)
+        })
+    }
+
+    /// The subject called `name` or known by it as an alias, ignoring case.
+    pub fn subject_named(&self, name: &str) -> Option<&Subject> {
+        collation::find(&self.subjects, name).or_else(|| {
+            let id = self.aliases.iter().find_map(|alias| match alias.target {
+                AliasTarget::Subject(id) if collation::same_name(&alias.name, name) => Some(id),
+                _ => None,
+            })?;
+            self.subjects.iter().find(|subject| subject.id == id)
+        })
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct ChecklistItem {
+    pub id: usize,
+    pub subject: usize,
+    pub text: Arc<str>,
+    pub done: bool,
+}
+
+/// Another name an action or subject is found by, like "DL" for "Deep Learning".
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct Alias {
+    pub name: Arc<str>,
+    pub target: AliasTarget,
 }
 
-#[derive(Clone, Default, Data)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub enum AliasTarget {
+    /// The id of an action.
+    Action(usize),
+    /// The id of a subject.
+    Subject(usize),
+}
+
+/// Maps subjects to external reporting categories like cost centers.
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub struct Categories(HashMap<usize, Arc<str>>);
+
+impl Categories {
+    pub fn get(&self, subject: &Subject) -> Option<Arc<str>> {
+        self.0.get(&subject.id).cloned()
+    }
+
+    pub fn set(&mut self, subject: &Subject, category: Option<Arc<str>>) {
+        match category {
+            Some(category) => self.0.insert(subject.id, category),
+            None => self.0.remove(&subject.id),
+        };
+    }
+}
+
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "gui", derive(Data))]
 pub struct TimeTable(HashMap<Topic, SpentTime>);
 
 impl TimeTable {
@@ -140,6 +793,19 @@ impl TimeTable {
     pub fn iter_mut(&mut self) -> im::hashmap::IterMut<Topic, SpentTime> {
         self.0.iter_mut()
     }
+
+    /// Changes the topics in place, for example to take over a new name.
+    pub fn update_topics(&mut self, update: impl Fn(&mut Topic)) {
+        self.0 = self
+            .0
+            .iter()
+            .map(|(topic, time)| {
+                let mut topic = topic.clone();
+                update(&mut topic);
+                (topic, *time)
+            })
+            .collect();
+    }
 }
 
 impl<'a> IntoIterator for &'a TimeTable {
@@ -160,18 +826,107 @@ impl<'a> IntoIterator for &'a mut TimeTable {
     }
 }
 
-#[derive(Clone, Default, Data)]
+/// The most recent sessions, older ones are fetched from the backend on demand.
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "gui", derive(Data))]
 pub struct History {
     entries: Vector<Session>,
+    /// Whether no older sessions exist beyond the loaded ones.
+    complete: bool,
 }
 
 impl History {
+    /// Number of sessions kept in memory, comparing the state scales with it.
+    pub const RECENT: usize = 500;
+    /// Number of older sessions fetched at once.
+    pub const PAGE: usize = 100;
+
+    pub fn new(entries: Vector<Session>, complete: bool) -> Self {
+        History { entries, complete }
+    }
+
     pub fn iter(&self) -> im::vector::Iter<Session> {
         self.entries.iter()
     }
 
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    pub fn oldest(&self) -> Option<&Session> {
+        self.entries.front()
+    }
+
+    /// Up to `count` distinct topics worked on last, the newest first.
+    pub fn recent_topics(&self, count: usize) -> Vector<Topic> {
+        let mut recent = Vector::new();
+        for session in self.entries.iter().rev() {
+            if recent.len() == count {
+                break;
+            }
+            if !recent.contains(&session.topic) {
+                recent.push_back(session.topic.clone());
+            }
+        }
+        recent
+    }
+
+    /// Adds a new session, evicting the oldest ones beyond `RECENT`.
     pub fn add(&mut self, session: Session) {
         self.entries.push_back(session);
+        self.evict();
+    }
+
+    /// Puts sessions fetched from the backend in front of the loaded ones.
+    pub fn prepend(&mut self, older: History) {
+        let mut entries = older.entries;
+        entries.append(self.entries.clone());
+        self.entries = entries;
+        self.complete = older.complete;
+    }
+
+    /// Adds a session that may have started before the latest ones.
+    pub fn insert(&mut self, session: Session) {
+        let index = self
+            .entries
+            .iter()
+            .position(|s| *s.started > *session.started)
+            .unwrap_or_else(|| self.entries.len());
+        // Older than everything loaded, it will be fetched with the rest.
+        if index == 0 && !self.complete {
+            return;
+        }
+        self.entries.insert(index, session);
+        self.evict();
+    }
+
+    fn evict(&mut self) {
+        while self.entries.len() > Self::RECENT {
+            self.entries.pop_front();
+            self.complete = false;
+        }
+    }
+
+    /// Replaces the session with the same key, or removes it if `amended` is `None`.
+    pub fn replace(&mut self, original: &Session, amended: Option<Session>) {
+        let key = original.key();
+        if let Some(index) = self.entries.iter().position(|s| *s.key() == *key) {
+            match amended {
+                Some(amended) => {
+                    self.entries.set(index, amended);
+                }
+                None => {
+                    self.entries.remove(index);
+                }
+            }
+        }
+    }
+
+    /// Changes the topics of all loaded sessions, for example to take over a new name.
+    pub fn update_topics(&mut self, update: impl Fn(&mut Topic)) {
+        for session in self.entries.iter_mut() {
+            update(&mut session.topic);
+        }
     }
 }
 
@@ -184,28 +939,176 @@ impl<'a> IntoIterator for &'a History {
     }
 }
 
-#[derive(Clone, Data, Lens)]
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
 pub struct Session {
     pub topic: Topic,
     pub started: DateTime,
     pub ended: DateTime,
+    pub context: Option<Context>,
+    pub interruptions: Vector<Interruption>,
+    /// Start as originally recorded, if the session has been amended since.
+    pub original_start: Option<DateTime>,
+    /// Stretches worked between the start and the end, in order. Empty if
+    /// the session was worked throughout, breaks in between are not part of its duration.
+    pub intervals: Vector<Interval>,
+    /// Free text written in Markdown, stored as entered.
+    pub note: Option<Arc<str>>,
+    pub tags: Vector<Tag>,
+}
+
+impl PartialEq for Session {
+    fn eq(&self, other: &Self) -> bool {
+        *self.key() == *other.key()
+    }
 }
 
+impl Eq for Session {}
+
 impl Session {
+    /// Identifies the session as it was recorded.
+    pub fn key(&self) -> DateTime {
+        self.original_start.unwrap_or(self.started)
+    }
+
+    pub fn duration(&self) -> SpentTime {
+        if self.intervals.is_empty() {
+            Interval::new(self.started, self.ended).duration()
+        } else {
+            self.intervals.iter().map(Interval::duration).sum()
+        }
+    }
+
+    /// The stretches worked, a single one if the session had no breaks.
+    pub fn worked(&self) -> Vector<Interval> {
+        if self.intervals.is_empty() {
+            Vector::unit(Interval::new(self.started, self.ended))
+        } else {
+            self.intervals.clone()
+        }
+    }
+
+    /// Time between the start and the end that was not worked.
+    pub fn breaks(&self) -> SpentTime {
+        Interval::new(self.started, self.ended)
+            .duration()
+            .saturating_sub(self.duration())
+    }
+}
+
+/// A stretch of time worked without a break.
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct Interval {
+    pub started: DateTime,
+    pub ended: DateTime,
+}
+
+impl Interval {
+    pub fn new(started: DateTime, ended: DateTime) -> Self {
+        Interval { started, ended }
+    }
+
+    /// The stretches of a session that was `paused` for some time in total,
+    /// as older versions recorded it. The break is taken to be at the end.
+    pub fn with_pause(started: DateTime, ended: DateTime, paused: SpentTime) -> Vector<Interval> {
+        if paused.as_secs() == 0 {
+            return Vector::new();
+        }
+        let worked = Interval::new(started, ended)
+            .duration()
+            .saturating_sub(paused);
+        Vector::unit(Interval::new(started, DateTime::from(*started + *worked)))
+    }
+
     pub fn duration(&self) -> SpentTime {
         use std::convert::TryFrom;
-        SpentTime::from(std::time::Duration::try_from(*self.ended - *self.started).unwrap())
+        let elapsed = std::time::Duration::try_from(*self.ended - *self.started);
+        SpentTime::from(elapsed.unwrap_or_default())
+    }
+}
+
+/// Narrows a list down to fuzzy matches, with an entry highlighted to be
+/// chosen from the keyboard.
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct ListFilter {
+    pub text: String,
+    /// Position in the filtered list, `None` until an arrow key is pressed.
+    pub cursor: Option<usize>,
+}
+
+impl ListFilter {
+    /// Moves the highlight by `step` within a list of `len` entries, stopping at its ends.
+    pub fn move_cursor(&mut self, step: isize, len: usize) {
+        if len == 0 {
+            self.cursor = None;
+            return;
+        }
+        let moved = match self.cursor {
+            Some(cursor) => cursor as isize + step,
+            None if step > 0 => 0,
+            None => len as isize - 1,
+        };
+        self.cursor = Some(moved.max(0).min(len as isize - 1) as usize);
+    }
+
+    /// The entry to choose, the highlighted one or else the best match.
+    pub fn chosen(&self, len: usize) -> Option<usize> {
+        match self.cursor {
+            Some(cursor) if cursor < len => Some(cursor),
+            None if len > 0 && !self.text.trim().is_empty() => Some(0),
+            _ => None,
+        }
     }
 }
 
-#[derive(Clone, Default, Data, Lens)]
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
 pub struct Setup {
     pub selected_action: Option<Action>,
     pub selected_subject: Option<Subject>,
+    pub selected_context: Option<Context>,
     pub creating: Creating,
+    pub interruption_cause: String,
+    pub new_checklist_item: String,
+    pub countdown_minutes: f64,
+    /// List archived actions and subjects too, so they can be restored.
+    pub show_archived: bool,
+    /// The topics worked on last are listed to switch to.
+    pub show_recent: bool,
+    /// Only sessions with this in their note, topic or tags are shown in the history.
+    pub history_search: String,
+    /// Bounds on the duration of the sessions looked for in the whole history, like `3h`.
+    pub longer_than: String,
+    pub shorter_than: String,
+    /// The sessions found within those bounds, `None` to show the recent history.
+    pub found: Option<Vector<Session>>,
+    pub action_filter: ListFilter,
+    pub subject_filter: ListFilter,
+    /// Subjects whose sub-projects are hidden in the list.
+    pub collapsed: Vector<usize>,
+    /// The session was paused or ended because the user went away.
+    pub away: Option<Away>,
+    /// Session in the history waiting for its deletion to be confirmed.
+    pub deleting: Option<Session>,
+    /// Overtime balance carried into this week, once the whole history was added up.
+    pub carryover: Option<i64>,
+}
+
+/// Time away from the computer, the screen being locked or the system asleep.
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub struct Away {
+    pub since: DateTime,
+    /// The session recorded up to `since`, if it was ended rather than paused.
+    pub ended: Option<Session>,
+    /// Back again, so how to count the time away can be chosen.
+    pub back: bool,
 }
 
 impl Setup {
+    #[cfg(feature = "gui")]
     pub fn new_item_label(&self, _: &druid::Env) -> String {
         if self.creating == Creating::Nothing {
             "New Item"
@@ -216,19 +1119,316 @@ impl Setup {
     }
 }
 
-#[derive(Clone, Data, Lens)]
+/// A session started from the command line, kept in the data file until it is stopped there.
+#[derive(Clone)]
+pub struct Running {
+    pub topic: Topic,
+    pub context: Option<Context>,
+    pub started: DateTime,
+}
+
+impl Running {
+    /// The session this becomes when stopped at `ended`.
+    pub fn session(&self, ended: DateTime) -> Session {
+        Session {
+            topic: self.topic.clone(),
+            started: self.started,
+            ended,
+            context: self.context.clone(),
+            interruptions: Default::default(),
+            original_start: None,
+            intervals: Vector::new(),
+            note: None,
+            tags: Default::default(),
+        }
+    }
+
+    /// Time since it was started.
+    pub fn elapsed(&self) -> SpentTime {
+        use std::convert::TryFrom;
+        let elapsed = std::time::Duration::try_from(*DateTime::now() - *self.started);
+        SpentTime::from(elapsed.unwrap_or_default())
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
 pub struct ActiveSession {
     pub started: DateTime,
     pub duration: SpentTime,
+    pub interruptions: Vector<Interruption>,
+    /// Start of the idle time waiting to be kept or discarded, the
+    /// duration stops counting meanwhile.
+    pub idle_since: Option<DateTime>,
+    pub paused_since: Option<DateTime>,
+    /// Stretches worked before the latest pause.
+    pub intervals: Vector<Interval>,
+    /// Start of the stretch worked since, unless paused.
+    pub resumed: DateTime,
+}
+
+impl ActiveSession {
+    pub fn new(started: DateTime) -> Self {
+        ActiveSession {
+            started,
+            duration: SpentTime::default(),
+            interruptions: Vector::new(),
+            idle_since: None,
+            paused_since: None,
+            intervals: Vector::new(),
+            resumed: started,
+        }
+    }
+
+    /// Ends the current stretch at `at`, unless already paused.
+    pub fn pause(&mut self, at: DateTime) {
+        if self.paused_since.is_some() {
+            return;
+        }
+        if *at > *self.resumed {
+            self.intervals.push_back(Interval::new(self.resumed, at));
+        }
+        self.paused_since = Some(at);
+    }
+
+    /// Goes on as if the latest pause never happened, counting the time paused too.
+    pub fn undo_pause(&mut self) {
+        let paused = match self.paused_since.take() {
+            Some(paused) => paused,
+            None => return,
+        };
+        // The stretch the pause ended goes on.
+        match self.intervals.back() {
+            Some(last) if *last.ended == *paused => {
+                self.resumed = last.started;
+                self.intervals.pop_back();
+            }
+            _ => self.resumed = paused,
+        }
+        use std::convert::TryFrom;
+        let paused_for = std::time::Duration::try_from(*DateTime::now() - *paused);
+        self.duration += SpentTime::from(paused_for.unwrap_or_default());
+    }
+
+    /// Starts the next stretch now, if paused.
+    pub fn resume(&mut self) {
+        if self.paused_since.take().is_some() {
+            self.resumed = DateTime::now();
+        }
+    }
+
+    /// Splits off the part before `at`, as a session paused at `at` to be
+    /// recorded as ended there. This one goes on as if it was started at `at`.
+    pub fn split_at(&mut self, at: DateTime) -> ActiveSession {
+        let mut before = ActiveSession::new(self.started);
+        let mut after = Vector::new();
+        for interval in &self.intervals {
+            if *interval.ended <= *at {
+                before.intervals.push_back(*interval);
+            } else if *interval.started >= *at {
+                after.push_back(*interval);
+            } else {
+                before
+                    .intervals
+                    .push_back(Interval::new(interval.started, at));
+                after.push_back(Interval::new(at, interval.ended));
+            }
+        }
+        if self.paused_since.is_none() && *self.resumed < *at {
+            before.intervals.push_back(Interval::new(self.resumed, at));
+            self.resumed = at;
+        }
+        if let Some(paused) = self.paused_since.as_mut() {
+            if **paused < *at {
+                *paused = at;
+            }
+        }
+        before.paused_since = Some(at);
+        let worked: SpentTime = before.intervals.iter().map(Interval::duration).sum();
+        before.duration = SpentTime::from((*worked).min(*self.duration));
+        let (earlier, later) = self
+            .interruptions
+            .iter()
+            .cloned()
+            .partition(|interruption| *interruption.at < *at);
+        before.interruptions = earlier;
+
+        self.started = at;
+        self.duration = self.duration.saturating_sub(before.duration);
+        self.intervals = after;
+        self.interruptions = later;
+        before
+    }
+
+    /// The stretches worked when stopping at `ended`, empty if there was no break.
+    pub fn intervals_until(&self, ended: DateTime) -> Vector<Interval> {
+        let mut intervals = self.intervals.clone();
+        if self.paused_since.is_none() && *ended > *self.resumed {
+            intervals.push_back(Interval::new(self.resumed, ended));
+        }
+        match intervals.front() {
+            Some(only) if intervals.len() == 1 && *only.started == *self.started => Vector::new(),
+            _ => intervals,
+        }
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct Interruption {
+    pub at: DateTime,
+    pub cause: Option<Arc<str>>,
 }
 
-#[derive(Clone, Data, Matcher, PartialEq, Eq)]
-#[matcher(matcher_name = Creator)]
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "gui", derive(Data, Matcher))]
+#[cfg_attr(feature = "gui", matcher(matcher_name = Creator))]
 pub enum Creating {
     Nothing,
     Choosing,
     Action(String),
     Subject(String),
+    Context(String),
+    Tag(String),
+    RenameAction(Renaming<Action>),
+    RenameSubject(Renaming<Subject>),
+    /// Editing the issue link template of a subject, kept in `name`.
+    IssueUrl(Renaming<Subject>),
+    /// Hours expected for a subject, typed in place of a name.
+    Estimate(Renaming<Subject>),
+    /// Hourly rate of a subject, typed in place of a name.
+    Rate(Renaming<Subject>),
+    /// The idle rule of a subject, typed in place of a name.
+    IdleRule(Renaming<Subject>),
+    /// Archiving a subject, which may keep a final report of it.
+    Archive(Subject),
+    /// Reading the final report an archived subject keeps.
+    FinalReport(Subject),
+    /// What was done in a session that has just been stopped.
+    Note(Annotation),
+    Goal(goal::GoalInput),
+    Confirm(Confirming),
+    /// Looking for a command, action or subject to run or select.
+    Palette(palette::Palette),
+}
+
+/// An operation waiting to be confirmed, as asked for in the settings.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub enum Confirming {
+    DeleteAction(Action),
+    DeleteSubject(Subject),
+    /// Moving everything recorded on `from` over to `into` and deleting it.
+    MergeActions {
+        from: Action,
+        into: Action,
+    },
+    MergeSubjects {
+        from: Subject,
+        into: Subject,
+    },
+    /// Stopping a session that has been running for long.
+    StopSession,
+    RestoreSnapshot(snapshot::Snapshot),
+    /// Adding what a file holds, `sessions` of which were read from it.
+    Import {
+        file: Arc<str>,
+        sessions: usize,
+    },
+}
+
+impl Confirming {
+    pub fn question(&self) -> String {
+        match self {
+            Confirming::DeleteAction(action) => format!("Delete the action {}?", action.name),
+            Confirming::DeleteSubject(subject) => {
+                format!("Delete the subject {}?", subject.name)
+            }
+            Confirming::MergeActions { from, into } => {
+                format!("Merge the action {} into {}?", from.name, into.name)
+            }
+            Confirming::MergeSubjects { from, into } => {
+                format!("Merge the subject {} into {}?", from.name, into.name)
+            }
+            Confirming::StopSession => "Stop the running session?".to_string(),
+            Confirming::RestoreSnapshot(snapshot) => {
+                format!("Replace all data with the copy taken {}?", snapshot.taken)
+            }
+            Confirming::Import { file, sessions } => {
+                format!(
+                    "Import {} sessions and their topics from {}?",
+                    sessions, file
+                )
+            }
+        }
+    }
+}
+
+/// An entry being renamed and the name typed so far.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub struct Renaming<T> {
+    pub item: T,
+    pub name: String,
+}
+
+/// The note and tags given to a session that has just been stopped.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct Annotation {
+    pub session: Session,
+    pub note: String,
+    /// Every defined tag and whether it is attached to the session.
+    pub tags: Vector<TagChoice>,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct TagChoice {
+    pub tag: Tag,
+    pub chosen: bool,
+}
+
+impl Annotation {
+    pub fn new(session: Session, tags: &Vector<Tag>) -> Self {
+        let tags = tags
+            .iter()
+            .map(|tag| TagChoice {
+                tag: tag.clone(),
+                chosen: session.tags.contains(tag),
+            })
+            .collect();
+        Annotation {
+            session,
+            note: String::new(),
+            tags,
+        }
+    }
+
+    /// The session with the note and tags given.
+    pub fn annotated(&self) -> Session {
+        let note = self.note.trim();
+        Session {
+            note: match note.is_empty() {
+                true => self.session.note.clone(),
+                false => Some(note.into()),
+            },
+            tags: self
+                .tags
+                .iter()
+                .filter(|choice| choice.chosen)
+                .map(|choice| choice.tag.clone())
+                .collect(),
+            ..self.session.clone()
+        }
+    }
+}
+
+impl<T: AsRef<str>> Renaming<T> {
+    pub fn new(item: T) -> Self {
+        let name = item.as_ref().to_string();
+        Renaming { item, name }
+    }
 }
 
 impl Default for Creating {
@@ -0,0 +1,85 @@
+//! The order actions and subjects are listed in for selection, which can be
+//! arranged by dragging them and by pinning favorites to the top.
+
+#[cfg(feature = "gui")]
+use druid::{Data, Lens};
+use im::Vector;
+
+/// Favorites come first, then the entries in the order they were dragged
+/// into, then the rest by name.
+#[derive(Clone, Default, PartialEq)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct ListOrder {
+    /// Ids of the arranged entries, in their order.
+    pub arranged: Vector<usize>,
+    pub favorites: Vector<usize>,
+}
+
+impl ListOrder {
+    /// Sorts entries given by name, keeping that order where nothing else decides.
+    pub fn arrange<T: Clone>(
+        &self,
+        items: impl IntoIterator<Item = T>,
+        id: impl Fn(&T) -> usize,
+    ) -> Vector<T> {
+        let mut items: Vec<T> = items.into_iter().collect();
+        items.sort_by_key(|item| {
+            let id = id(item);
+            let position = self.arranged.index_of(&id).unwrap_or(usize::MAX);
+            (!self.is_favorite(id), position)
+        });
+        items.into()
+    }
+
+    pub fn is_favorite(&self, id: usize) -> bool {
+        self.favorites.contains(&id)
+    }
+
+    pub fn toggle_favorite(&mut self, id: usize) {
+        match self.favorites.index_of(&id) {
+            Some(index) => {
+                self.favorites.remove(index);
+            }
+            None => self.favorites.push_back(id),
+        }
+    }
+
+    /// Moves the entry `id` by `shift` places within the `listed` ids, as
+    /// currently arranged. Favorites stay among each other, and everything
+    /// listed keeps its place from now on.
+    pub fn shift(&mut self, listed: &[usize], id: usize, shift: isize) {
+        let favorite = self.is_favorite(id);
+        // Only the entries of the same group take part.
+        let mut group: Vec<usize> = listed
+            .iter()
+            .copied()
+            .filter(|other| self.is_favorite(*other) == favorite)
+            .collect();
+        let from = match group.iter().position(|other| *other == id) {
+            Some(from) => from,
+            None => return,
+        };
+        let to = (from as isize + shift).max(0).min(group.len() as isize - 1) as usize;
+        if from == to {
+            return;
+        }
+        let moved = group.remove(from);
+        group.insert(to, moved);
+        let others: Vec<usize> = listed
+            .iter()
+            .copied()
+            .filter(|other| self.is_favorite(*other) != favorite)
+            .collect();
+        let (favorites, rest) = match favorite {
+            true => (group, others),
+            false => (others, group),
+        };
+        // Entries not listed right now, like archived ones, keep their place behind.
+        let hidden = self
+            .arranged
+            .iter()
+            .copied()
+            .filter(|i| !listed.contains(i));
+        self.arranged = favorites.into_iter().chain(rest).chain(hidden).collect();
+    }
+}
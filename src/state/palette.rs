@@ -0,0 +1,107 @@
+//! Everything the window offers, found by typing a few letters of it.
+
+use super::{collation, Action, Content, Subject};
+#[cfg(feature = "gui")]
+use druid::{Data, Lens};
+use im::Vector;
+use std::sync::Arc;
+
+/// Entries shown at once, the best matches first.
+const SHOWN: usize = 8;
+
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub enum PaletteCommand {
+    SelectAction(Action),
+    SelectSubject(Subject),
+    StartStop,
+    ResumeLast,
+    Pause,
+    NewAction,
+    NewSubject,
+    Insights,
+    Reports,
+    Ledger,
+    Stats,
+    History,
+    Manage,
+    Focus,
+    Undo,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct PaletteEntry {
+    pub label: Arc<str>,
+    pub command: PaletteCommand,
+}
+
+impl AsRef<str> for PaletteEntry {
+    fn as_ref(&self) -> &str {
+        &self.label
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct Palette {
+    pub query: String,
+    entries: Vector<PaletteEntry>,
+}
+
+impl Palette {
+    /// Offers the commands followed by the actions and subjects not archived.
+    pub fn new(content: &Content) -> Self {
+        let command = |label: &str, command| PaletteEntry {
+            label: label.into(),
+            command,
+        };
+        let mut entries: Vector<PaletteEntry> = vec![
+            command("Start or stop the session", PaletteCommand::StartStop),
+            command("Resume the last topic", PaletteCommand::ResumeLast),
+            command("Pause or resume the session", PaletteCommand::Pause),
+            command("New action", PaletteCommand::NewAction),
+            command("New subject", PaletteCommand::NewSubject),
+            command("Open insights", PaletteCommand::Insights),
+            command("Open reports", PaletteCommand::Reports),
+            command("Open overtime", PaletteCommand::Ledger),
+            command("Open stats", PaletteCommand::Stats),
+            command("Open history", PaletteCommand::History),
+            command("Open manage", PaletteCommand::Manage),
+            command("Focus mode", PaletteCommand::Focus),
+            command("Undo", PaletteCommand::Undo),
+        ]
+        .into();
+        entries.extend(
+            content
+                .listed_actions(false)
+                .into_iter()
+                .map(|action| PaletteEntry {
+                    label: format!("Action: {}", action.name).into(),
+                    command: PaletteCommand::SelectAction(action),
+                }),
+        );
+        entries.extend(
+            content
+                .listed_subjects(false)
+                .into_iter()
+                .map(|subject| PaletteEntry {
+                    label: format!("Subject: {}", subject.name).into(),
+                    command: PaletteCommand::SelectSubject(subject),
+                }),
+        );
+        Palette {
+            query: String::new(),
+            entries,
+        }
+    }
+
+    /// The entries matching the query, the best first and the commands in
+    /// their order while nothing is typed.
+    pub fn matches(&self) -> Vector<PaletteEntry> {
+        collation::fuzzy_filter(self.entries.iter().cloned(), &self.query)
+            .into_iter()
+            .take(SHOWN)
+            .collect()
+    }
+}
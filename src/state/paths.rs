@@ -1,18 +1,144 @@
 use directories::ProjectDirs;
-use std::path::{Path, PathBuf};
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::RwLock,
+};
 
 static FILE_NAME: &str = "zeitig.db";
+/// Folder next to the default data file holding one folder per named profile.
+static PROFILES: &str = "profiles";
 
+static PROFILE: RwLock<Option<String>> = RwLock::new(None);
+static READ_ONLY: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Chooses the profile whose files are used from now on, `None` for the default one.
+pub fn set_profile(name: Option<&str>) -> Result<(), String> {
+    if let Some(name) = name {
+        let valid = !name.trim().is_empty()
+            && name != "."
+            && name != ".."
+            && !name.contains(|c| c == '/' || c == '\\');
+        if !valid {
+            return Err(format!("'{}' cannot be used as profile name.", name));
+        }
+    }
+    *PROFILE.write().unwrap() = name.map(str::to_string);
+    Ok(())
+}
+
+/// The chosen profile, `None` for the default one.
+pub fn profile() -> Option<String> {
+    PROFILE.read().unwrap().clone()
+}
+
+/// Only looks at `file` instead of the data file, as chosen with `--read-only`.
+pub fn set_read_only(file: PathBuf) {
+    *READ_ONLY.write().unwrap() = Some(file);
+}
+
+/// The file looked at in read-only mode.
+pub fn read_only() -> Option<PathBuf> {
+    READ_ONLY.read().unwrap().clone()
+}
+
+/// The named profiles that have been used, sorted by name.
+pub fn profiles() -> Vec<String> {
+    let entries = match fs::read_dir(base_dir().join(PROFILES)) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let mut profiles: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    profiles.sort();
+    profiles
+}
+
+/// Where the default profile keeps its files.
+fn base_dir() -> PathBuf {
+    if cfg!(debug_assertions) {
+        return PathBuf::new();
+    }
+    ProjectDirs::from("", "", "Zeitig")
+        .map(|pd| pd.data_dir().to_owned())
+        .unwrap_or_default()
+}
+
+/// The database of the chosen profile.
 pub fn data_file() -> PathBuf {
     if cfg!(debug_assertions) {
         log::info!("Accessing debug data file.");
-        return PathBuf::from(FILE_NAME);
     }
-    if let Some(pd) = ProjectDirs::from("", "", "Zeitig") {
-        let data = pd.data_dir();
-        if std::fs::create_dir_all(data).is_ok() {
-            return data.join(FILE_NAME);
-        }
+    let dir = match profile() {
+        Some(profile) => base_dir().join(PROFILES).join(profile),
+        None => base_dir(),
+    };
+    if dir.as_os_str().is_empty() || fs::create_dir_all(&dir).is_ok() {
+        return dir.join(FILE_NAME);
     }
     Path::new(FILE_NAME).to_owned()
 }
+
+/// The file older versions kept all data in, next to the data file.
+pub fn legacy_file() -> PathBuf {
+    data_file().with_file_name("zeitig.mp")
+}
+
+/// Held by the running window, so a second one does not open the same data file.
+pub fn lock_file() -> PathBuf {
+    data_file().with_file_name("zeitig.lock")
+}
+
+/// The ed25519 key used to sign exports, kept next to the data file.
+pub fn signing_key_file() -> PathBuf {
+    match data_file().parent() {
+        Some(parent) => parent.join("signing.key"),
+        None => PathBuf::from("signing.key"),
+    }
+}
+
+/// Where copies of the data file are kept, next to the data file.
+pub fn snapshot_dir() -> PathBuf {
+    match data_file().parent() {
+        Some(parent) => parent.join("backups"),
+        None => PathBuf::from("backups"),
+    }
+}
+
+pub fn export_file(name: &str) -> PathBuf {
+    let dir = match data_file().parent() {
+        Some(parent) => parent.join("exports"),
+        None => PathBuf::from("exports"),
+    };
+    if let Err(err) = std::fs::create_dir_all(&dir) {
+        log::error!("Failed to create export directory: {}", err);
+    }
+    dir.join(name)
+}
+
+/// Fills a temporary file next to `path` using `write` and moves it over `path`
+/// once it is complete, so a crash leaves the old file rather than half of the new one.
+pub fn write_atomic(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut name = path.file_name().unwrap_or_default().to_owned();
+    name.push(".tmp");
+    let temporary = path.with_file_name(name);
+    let mut out = BufWriter::new(File::create(&temporary)?);
+    let written = write(&mut out)
+        .and_then(|_| out.flush())
+        .and_then(|_| out.get_ref().sync_all());
+    drop(out);
+    match written {
+        Ok(()) => fs::rename(&temporary, path),
+        Err(err) => {
+            let _ = fs::remove_file(&temporary);
+            Err(err)
+        }
+    }
+}
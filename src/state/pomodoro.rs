@@ -0,0 +1,64 @@
+use super::{Settings, SpentTime};
+#[cfg(feature = "gui")]
+use druid::Data;
+use std::time::Duration;
+
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub enum Phase {
+    Work,
+    Break,
+}
+
+/// Countdown of the current pomodoro interval.
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub struct Pomodoro {
+    pub phase: Phase,
+    pub remaining: SpentTime,
+    /// Work intervals finished since the session was started.
+    pub completed: u32,
+}
+
+fn minutes(minutes: f64) -> SpentTime {
+    SpentTime::from(Duration::from_secs_f64(minutes.max(1.0) * 60.0))
+}
+
+impl Pomodoro {
+    /// The first work interval, if pomodoro mode is enabled.
+    pub fn start(settings: &Settings) -> Option<Self> {
+        if !settings.pomodoro {
+            return None;
+        }
+        Some(Pomodoro {
+            phase: Phase::Work,
+            remaining: minutes(settings.work_minutes),
+            completed: 0,
+        })
+    }
+
+    /// Counts down by `step`, returns whether the interval is over.
+    pub fn tick(&mut self, step: Duration) -> bool {
+        self.remaining = self.remaining.saturating_sub(SpentTime::from(step));
+        *self.remaining == Duration::from_secs(0)
+    }
+
+    /// Continues with the next interval.
+    pub fn advance(&mut self, settings: &Settings) {
+        match self.phase {
+            Phase::Work => {
+                self.completed += 1;
+                self.phase = Phase::Break;
+                self.remaining = minutes(settings.break_minutes);
+            }
+            Phase::Break => {
+                self.phase = Phase::Work;
+                self.remaining = minutes(settings.work_minutes);
+            }
+        }
+    }
+
+    pub fn is_break(&self) -> bool {
+        self.phase == Phase::Break
+    }
+}
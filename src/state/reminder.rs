@@ -0,0 +1,62 @@
+//! Reminders to start tracking on workdays, once the work hours begin with
+//! nothing tracked yet and again after a while without a session.
+
+use super::{report, History, Settings};
+use time::{Duration, OffsetDateTime, Weekday};
+
+pub enum Reminder {
+    /// The work hours began and nothing was tracked today.
+    NothingToday,
+    /// Nothing was tracked for these minutes during the work hours.
+    Idle(i64),
+}
+
+impl Reminder {
+    pub fn message(&self) -> (&'static str, String) {
+        match self {
+            Reminder::NothingToday => (
+                "Nothing tracked today",
+                "Work has begun, start a session?".to_string(),
+            ),
+            Reminder::Idle(minutes) => (
+                "Nothing tracked",
+                format!(
+                    "Nothing was tracked for {} minutes, start a session?",
+                    minutes
+                ),
+            ),
+        }
+    }
+
+    /// The reminder due `now` while no session is running, `reminded` being
+    /// the last time one was shown.
+    pub fn due(
+        settings: &Settings,
+        history: &History,
+        now: OffsetDateTime,
+        reminded: Option<OffsetDateTime>,
+    ) -> Option<Self> {
+        let (begin, end) = report::parse_hours(&settings.work_hours).flatten()?;
+        let weekend = matches!(now.weekday(), Weekday::Saturday | Weekday::Sunday);
+        if weekend || now.time() < begin || now.time() >= end {
+            return None;
+        }
+        let today = now.date();
+        let reminded = reminded.filter(|at| at.to_offset(now.offset()).date() == today);
+        let tracked = history
+            .iter()
+            .map(|session| session.ended.to_offset(now.offset()))
+            .filter(|ended| ended.date() == today)
+            .max();
+        if tracked.is_none() && reminded.is_none() {
+            return Some(Reminder::NothingToday);
+        }
+        let minutes = settings.remind_idle_minutes as i64;
+        let began = today.with_time(begin).assume_offset(now.offset());
+        let since = tracked.max(reminded).map_or(began, |at| at.max(began));
+        match minutes > 0 && now - since >= Duration::minutes(minutes) {
+            true => Some(Reminder::Idle((now - since).whole_minutes())),
+            false => None,
+        }
+    }
+}
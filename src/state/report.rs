@@ -0,0 +1,321 @@
+use super::{
+    billing, collation, export, hierarchy, insights::Summary, rounding::Rounding, AppState, Date,
+    History, Session, SpentTime, Subject, Topic,
+};
+#[cfg(feature = "gui")]
+use druid::{Data, Lens};
+use im::Vector;
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryFrom,
+    sync::Arc,
+    time::Duration,
+};
+
+static FORMAT: &str = "%Y-%m-%d";
+static HOURS_FORMAT: &str = "%H:%M";
+
+/// Length of the periods a report adds the spent time up by.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub enum Period {
+    Day,
+    Week,
+    Month,
+}
+
+impl Period {
+    /// The first day of the period `date` belongs to, with months starting
+    /// on the day `month_start`.
+    pub fn begin(self, date: Date, month_start: u8) -> Date {
+        match self {
+            Period::Day => date,
+            Period::Week => date.week_begin(),
+            Period::Month => date.month_begin_on(month_start),
+        }
+    }
+
+    pub fn label(self, begin: Date) -> String {
+        match self {
+            Period::Day => begin.format("%a %Y-%m-%d"),
+            Period::Week => format!("Week {}", *begin),
+            Period::Month if begin.day() == 1 => begin.format("%B %Y"),
+            Period::Month => {
+                let end = *begin.next_month() - time::Duration::day();
+                format!("{} to {}", begin.format(FORMAT), end.format(FORMAT))
+            }
+        }
+    }
+}
+
+/// Days of the week a report counts the spent time of.
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub enum Days {
+    All,
+    Weekdays,
+    Weekends,
+}
+
+impl Days {
+    fn contains(self, weekday: time::Weekday) -> bool {
+        let weekend = matches!(weekday, time::Weekday::Saturday | time::Weekday::Sunday);
+        match self {
+            Days::All => true,
+            Days::Weekdays => !weekend,
+            Days::Weekends => weekend,
+        }
+    }
+}
+
+/// Spent time per day, week or month within a range of dates.
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct Report {
+    pub period: Period,
+    /// First day of the range, as typed.
+    pub from: String,
+    /// Last day of the range, as typed.
+    pub to: String,
+    pub days: Days,
+    /// Time of day to count like `09:00-17:00`, empty for the whole day.
+    pub hours: String,
+    /// Name of the tag sessions need to be counted, empty to count all.
+    pub tag: String,
+    /// Add the time of sub-projects to their top-level project.
+    pub roll_up: bool,
+    pub periods: Vector<PeriodTotal>,
+    pub status: String,
+    /// Day of the month months start on, from the settings.
+    month_start: u8,
+    /// Sessions are colored by their action rather than their subject.
+    color_by_action: bool,
+    /// How the time is rounded, from the settings.
+    rounding: Rounding,
+    /// All of them, as any range can be chosen.
+    sessions: History,
+    /// All subjects, to find the projects sub-projects are nested in.
+    subjects: Vector<Subject>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct PeriodTotal {
+    pub label: Arc<str>,
+    pub spent_time: SpentTime,
+    /// Amount earned in cents, if any of the subjects is billable.
+    pub earned: Option<u64>,
+    pub entries: Vector<Summary>,
+}
+
+impl Report {
+    /// A weekly report of the last four weeks.
+    pub fn generate(data: &AppState) -> Self {
+        let (sessions, status) = export::full_or_recent_history(&data.content, &data.history);
+        let today = Date::from(time::OffsetDateTime::now_local());
+        let from = Date::from(*today.week_begin() - time::Duration::weeks(3));
+        let mut report = Report {
+            period: Period::Week,
+            from: from.format(FORMAT),
+            to: today.format(FORMAT),
+            days: Days::All,
+            hours: String::new(),
+            tag: String::new(),
+            roll_up: false,
+            periods: Vector::new(),
+            status,
+            month_start: data.content.settings.month_start as u8,
+            color_by_action: data.content.settings.color_by_action,
+            rounding: Rounding::from_settings(&data.content.settings),
+            sessions,
+            subjects: data.content.subjects.clone(),
+        };
+        report.refresh();
+        report
+    }
+
+    /// Adds up the sessions again after the period, the range or the filters changed.
+    pub fn refresh(&mut self) {
+        let parse = |text: &str| time::Date::parse(text.trim(), FORMAT).map(Date::from);
+        let (from, to) = match (parse(&self.from), parse(&self.to)) {
+            (Ok(from), Ok(to)) => (from, to),
+            _ => {
+                self.status = "Enter the range as YYYY-MM-DD.".to_string();
+                return;
+            }
+        };
+        let hours = match parse_hours(&self.hours) {
+            Some(hours) => hours,
+            None => {
+                self.status = "Enter the hours as HH:MM-HH:MM.".to_string();
+                return;
+            }
+        };
+        let tag = self.tag.trim();
+        let filter = Filter {
+            days: self.days,
+            hours,
+            tag: match tag.is_empty() {
+                true => None,
+                false => Some(tag),
+            },
+            rounding: self.rounding,
+            roll_up: match self.roll_up {
+                true => Some(&self.subjects),
+                false => None,
+            },
+        };
+        self.periods = periods(
+            &self.sessions,
+            self.period,
+            self.month_start,
+            self.color_by_action,
+            from,
+            to,
+            filter,
+        );
+        self.status = match self.periods.is_empty() {
+            true => "No sessions in this range.".to_string(),
+            false => String::new(),
+        };
+    }
+}
+
+/// The window typed as `09:00-17:00`, `Some(None)` if it was left empty.
+pub(crate) fn parse_hours(text: &str) -> Option<Option<(time::Time, time::Time)>> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Some(None);
+    }
+    let (begin, end) = text.split_at(text.find(|c| c == '-' || c == '–')?);
+    let end = end.trim_start_matches(|c| c == '-' || c == '–');
+    let begin = time::Time::parse(begin.trim(), HOURS_FORMAT).ok()?;
+    let end = time::Time::parse(end.trim(), HOURS_FORMAT).ok()?;
+    if begin < end {
+        Some(Some((begin, end)))
+    } else {
+        None
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Filter<'a> {
+    days: Days,
+    hours: Option<(time::Time, time::Time)>,
+    tag: Option<&'a str>,
+    rounding: Rounding,
+    /// Subjects to find the top-level projects in, if time is added up by them.
+    roll_up: Option<&'a Vector<Subject>>,
+}
+
+impl Filter<'_> {
+    /// The part of the session's duration spent on the counted days and hours.
+    fn spent_time(self, session: &Session) -> SpentTime {
+        if let Some(tag) = self.tag {
+            if collation::find(&session.tags, tag).is_none() {
+                return SpentTime::default();
+            }
+        }
+        if self.days == Days::All && self.hours.is_none() {
+            return session.duration();
+        }
+        let mut within = time::Duration::zero();
+        // Only the stretches worked count, not the breaks in between. Days
+        // are cut in the offset the stretch was worked in.
+        for interval in &session.worked() {
+            let offset = interval.started.offset();
+            let started = *interval.started;
+            let ended = interval.ended.to_offset(offset);
+            let mut date = started.date();
+            while date <= ended.date() {
+                if self.days.contains(date.weekday()) {
+                    let (begin, end) = match self.hours {
+                        Some((begin, end)) => (date.with_time(begin), date.with_time(end)),
+                        None => (date.midnight(), date.next_day().midnight()),
+                    };
+                    let begin = started.max(begin.assume_offset(offset));
+                    let end = ended.min(end.assume_offset(offset));
+                    if end > begin {
+                        within += end - begin;
+                    }
+                }
+                date = date.next_day();
+            }
+        }
+        SpentTime::from(Duration::try_from(within).unwrap_or_default())
+    }
+}
+
+/// Totals of the sessions started between `from` and `to`, latest period first.
+fn periods(
+    sessions: &History,
+    period: Period,
+    month_start: u8,
+    by_action: bool,
+    from: Date,
+    to: Date,
+    filter: Filter<'_>,
+) -> Vector<PeriodTotal> {
+    let counted = sessions.iter().filter_map(|session| {
+        let date = Date::from(*session.started);
+        if date < from || date > to {
+            return None;
+        }
+        let spent_time = filter.spent_time(session);
+        if spent_time.as_secs() == 0 {
+            return None;
+        }
+        Some((date, session.topic.clone(), spent_time))
+    });
+    let mut periods: BTreeMap<Date, HashMap<Topic, SpentTime>> = BTreeMap::new();
+    for ((date, topic), spent_time) in filter.rounding.add_up(counted) {
+        // Rounding down can leave nothing of short sessions.
+        if spent_time.as_secs() == 0 {
+            continue;
+        }
+        let topics = periods.entry(period.begin(date, month_start)).or_default();
+        *topics.entry(topic).or_default() += spent_time;
+    }
+
+    periods
+        .into_iter()
+        .rev()
+        .map(|(begin, topics)| {
+            let mut rolled: HashMap<Topic, Summary> = HashMap::new();
+            for (topic, spent_time) in topics {
+                // Earnings follow the rate of the sub-project the time was spent on.
+                let earned = billing::earned(&topic.subject, spent_time);
+                let topic = match filter.roll_up {
+                    Some(subjects) => Topic {
+                        subject: hierarchy::root(subjects, &topic.subject),
+                        ..topic
+                    },
+                    None => topic,
+                };
+                let entry = rolled.entry(topic.clone()).or_insert_with(|| Summary {
+                    color: topic.color(by_action),
+                    earned: None,
+                    topic,
+                    spent_time: SpentTime::default(),
+                });
+                entry.spent_time += spent_time;
+                if let Some(earned) = earned {
+                    entry.earned = Some(entry.earned.unwrap_or(0) + earned);
+                }
+            }
+            let mut entries: Vec<Summary> = rolled.into_values().collect();
+            entries.sort_by(|a, b| {
+                (&a.topic.action, &a.topic.subject).cmp(&(&b.topic.action, &b.topic.subject))
+            });
+            PeriodTotal {
+                label: period.label(begin).into(),
+                spent_time: entries.iter().map(|entry| entry.spent_time).sum(),
+                earned: entries
+                    .iter()
+                    .filter_map(|entry| entry.earned)
+                    .fold(None, |sum, earned| Some(sum.unwrap_or(0) + earned)),
+                entries: entries.into(),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,63 @@
+//! Rounding of the time reported and exported, for clients billing in
+//! increments like 15 minutes. Recorded sessions always keep their exact times.
+
+use super::{Date, Settings, SpentTime};
+#[cfg(feature = "gui")]
+use druid::Data;
+use std::{collections::HashMap, hash::Hash};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub struct Rounding {
+    /// Length of the increments, 0 to not round at all.
+    pub minutes: u32,
+    /// Round up to the next increment rather than to the nearest one.
+    pub up: bool,
+    /// Round the time of each day rather than of each session.
+    pub per_day: bool,
+}
+
+impl Rounding {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Rounding {
+            minutes: settings.rounding_minutes as u32,
+            up: settings.round_up,
+            per_day: settings.round_per_day,
+        }
+    }
+
+    pub fn round(self, spent: SpentTime) -> SpentTime {
+        let step = self.minutes as u64 * 60;
+        if step == 0 {
+            return spent;
+        }
+        let seconds = spent.as_secs();
+        let steps = match self.up {
+            true => (seconds + step - 1) / step,
+            false => (seconds + step / 2) / step,
+        };
+        SpentTime::from_secs(steps * step)
+    }
+
+    /// Adds up the time of sessions by day and `key`, rounding the time of
+    /// each session or the total of each day.
+    pub fn add_up<K: Hash + Eq>(
+        self,
+        sessions: impl IntoIterator<Item = (Date, K, SpentTime)>,
+    ) -> HashMap<(Date, K), SpentTime> {
+        let mut days: HashMap<(Date, K), SpentTime> = HashMap::new();
+        for (day, key, spent) in sessions {
+            let spent = match self.per_day {
+                true => spent,
+                false => self.round(spent),
+            };
+            *days.entry((day, key)).or_default() += spent;
+        }
+        if self.per_day {
+            for spent in days.values_mut() {
+                *spent = self.round(*spent);
+            }
+        }
+        days
+    }
+}
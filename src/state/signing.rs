@@ -0,0 +1,116 @@
+use ed25519_dalek::{Keypair, PublicKey, Signature, Signer, Verifier};
+use std::{
+    convert::TryFrom,
+    error::Error,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+static HEADER: &str = "zeitig-signature v1";
+
+/// Outcome of a successful verification.
+pub struct Verified {
+    pub public_key: String,
+    /// Whether the file was signed with the key of this profile.
+    pub own_key: bool,
+}
+
+/// Path of the detached signature belonging to `path`.
+pub fn signature_file(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".sig");
+    path.with_file_name(name)
+}
+
+/// Loads the signing key of this profile, generating it on first use.
+fn keypair() -> Result<Keypair, Box<dyn Error>> {
+    let path = super::paths::signing_key_file();
+    if path.exists() {
+        let bytes = fs::read(&path)?;
+        return Ok(Keypair::from_bytes(&bytes)?);
+    }
+    log::info!("Generating signing key at {}.", path.display());
+    let keypair = Keypair::generate(&mut rand::rngs::OsRng);
+    super::paths::write_atomic(&path, |out| {
+        // Restricted before the key is written, so it is never readable by others.
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            out.get_ref()
+                .set_permissions(fs::Permissions::from_mode(0o600))?;
+        }
+        out.write_all(&keypair.to_bytes()[..])
+    })?;
+    Ok(keypair)
+}
+
+/// Writes a detached signature next to the file at `path`.
+pub fn sign_file(path: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let keypair = keypair()?;
+    let signature = keypair.sign(&fs::read(path)?);
+    let signature_path = signature_file(path);
+    super::paths::write_atomic(&signature_path, |out| {
+        write!(
+            out,
+            "{}\npublic-key: {}\nsignature: {}\n",
+            HEADER,
+            to_hex(keypair.public.as_bytes()),
+            to_hex(&signature.to_bytes()),
+        )
+    })?;
+    Ok(signature_path)
+}
+
+/// Checks the file at `path` against its detached signature.
+pub fn verify_file(path: &Path) -> Result<Verified, Box<dyn Error>> {
+    let signature_path = signature_file(path);
+    let text = fs::read_to_string(&signature_path)
+        .map_err(|err| format!("Cannot read {}: {}", signature_path.display(), err))?;
+    let mut lines = text.lines();
+    if lines.next() != Some(HEADER) {
+        return Err("Unknown signature format.".into());
+    }
+    let mut public_key = None;
+    let mut signature = None;
+    for line in lines {
+        if let Some(value) = line.strip_prefix("public-key: ") {
+            public_key = Some(from_hex(value)?);
+        } else if let Some(value) = line.strip_prefix("signature: ") {
+            signature = Some(from_hex(value)?);
+        }
+    }
+    let public_key = public_key.ok_or("Signature is missing the public key.")?;
+    let signature = signature.ok_or("Signature is missing the signature.")?;
+
+    let key = PublicKey::from_bytes(&public_key)?;
+    let signature = Signature::try_from(&signature[..])?;
+    key.verify(&fs::read(path)?, &signature)
+        .map_err(|_| "The file does not match its signature.")?;
+
+    let own_key = match fs::read(super::paths::signing_key_file()) {
+        Ok(bytes) => Keypair::from_bytes(&bytes)
+            .map(|keypair| keypair.public == key)
+            .unwrap_or(false),
+        Err(_) => false,
+    };
+    Ok(Verified {
+        public_key: to_hex(&public_key),
+        own_key,
+    })
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(text: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    let text = text.trim();
+    if text.len() % 2 != 0 || !text.is_ascii() {
+        return Err("Invalid hex value.".into());
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| Ok(u8::from_str_radix(&text[i..i + 2], 16)?))
+        .collect()
+}
@@ -0,0 +1,113 @@
+//! Copies of the data file taken on startup and before destructive changes,
+//! of which only the newest few are kept.
+
+use super::backend::Backend;
+#[cfg(feature = "gui")]
+use druid::Data;
+use im::Vector;
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+const PREFIX: &str = "zeitig-";
+const EXTENSION: &str = "db";
+/// Format of the time a copy was taken in its file name, sorting like the times.
+const STAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// A copy in the backup folder, as offered for restoring.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub struct Snapshot {
+    #[cfg_attr(feature = "gui", data(same_fn = "PartialEq::eq"))]
+    pub path: Arc<PathBuf>,
+    /// When it was taken, as shown to the user.
+    pub taken: Arc<str>,
+}
+
+/// Takes copies into `dir` and removes all but the newest `keep` of them.
+pub struct Rotation {
+    pub dir: PathBuf,
+    /// 0 to not take any copies.
+    pub keep: usize,
+}
+
+impl Rotation {
+    pub fn new(dir: PathBuf, keep: f64) -> Self {
+        Rotation {
+            dir,
+            keep: keep as usize,
+        }
+    }
+
+    /// Takes a copy unless turned off, returning where it was written.
+    pub fn copy(&self, backend: &mut dyn Backend) -> Result<Option<PathBuf>, Box<dyn Error>> {
+        if self.keep == 0 {
+            return Ok(None);
+        }
+        std::fs::create_dir_all(&self.dir)?;
+        let stamp = time::OffsetDateTime::now_local().format(STAMP_FORMAT);
+        let path = self.dir.join(format!("{}{}.{}", PREFIX, stamp, EXTENSION));
+        // Taken within the same second, so that copy is just as recent.
+        if !path.exists() {
+            backend.snapshot(&path)?;
+        }
+        Ok(Some(path))
+    }
+
+    /// Removes all but the newest copies.
+    pub fn prune(&self) -> Result<(), Box<dyn Error>> {
+        if self.keep == 0 {
+            return Ok(());
+        }
+        for old in list(&self.dir).iter().skip(self.keep) {
+            std::fs::remove_file(&*old.path)?;
+        }
+        Ok(())
+    }
+
+    /// Copies and prunes, only logging failures as they should not prevent
+    /// the change the copy is taken for.
+    pub fn take(&self, backend: &mut dyn Backend) {
+        if let Err(err) = self.copy(backend).and_then(|_| self.prune()) {
+            log::warn!(
+                "Cannot copy the data file into {}: {}",
+                self.dir.display(),
+                err
+            );
+        }
+    }
+}
+
+/// The copies in `dir`, the newest first.
+pub fn list(dir: &Path) -> Vector<Snapshot> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vector::new(),
+    };
+    let mut snapshots: Vec<(String, Snapshot)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let stamp = path
+                .file_name()?
+                .to_str()?
+                .strip_prefix(PREFIX)?
+                .strip_suffix(EXTENSION)?
+                .strip_suffix('.')?
+                .to_string();
+            let taken = time::PrimitiveDateTime::parse(&stamp, STAMP_FORMAT).ok()?;
+            let snapshot = Snapshot {
+                path: Arc::new(path),
+                taken: taken.format("%Y-%m-%d %H:%M:%S").into(),
+            };
+            Some((stamp, snapshot))
+        })
+        .collect();
+    snapshots.sort_by(|(a, _), (b, _)| b.cmp(a));
+    snapshots
+        .into_iter()
+        .map(|(_, snapshot)| snapshot)
+        .collect()
+}
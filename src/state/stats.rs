@@ -0,0 +1,141 @@
+//! Time per subject and per day over the last days, drawn as charts.
+
+use super::{
+    export,
+    streak::{self, Streak},
+    AppState, Date, History, SpentTime,
+};
+#[cfg(feature = "gui")]
+use druid::{Data, Lens};
+use im::Vector;
+use std::{collections::BTreeMap, sync::Arc};
+
+/// Subjects beyond this many are added up as one slice.
+const SLICES: usize = 8;
+/// Shades for subjects without a color of their own, so that neighbouring
+/// slices can still be told apart from each other.
+const SHADES: [u32; 4] = [0x70_80_90, 0xa0_aa_b4, 0x58_64_70, 0xc0_c8_d0];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub enum StatsRange {
+    Week,
+    Month,
+    Year,
+}
+
+impl StatsRange {
+    fn days(self) -> i64 {
+        match self {
+            StatsRange::Week => 7,
+            StatsRange::Month => 30,
+            StatsRange::Year => 365,
+        }
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct Slice {
+    pub label: Arc<str>,
+    pub spent_time: SpentTime,
+    /// Share of the whole range, between 0 and 1.
+    pub share: f64,
+    pub color: u32,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct DayBar {
+    pub day: Date,
+    pub spent_time: SpentTime,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct Stats {
+    pub range: StatsRange,
+    /// The subjects worked on the most first.
+    pub subjects: Vector<Slice>,
+    /// Every day of the range, the oldest first.
+    pub days: Vector<DayBar>,
+    pub total: SpentTime,
+    /// Over all sessions, whatever the range.
+    pub streaks: Vector<Streak>,
+    pub status: String,
+    /// All of them, to switch between ranges without loading them again.
+    sessions: History,
+}
+
+impl Stats {
+    pub fn generate(data: &AppState) -> Self {
+        let (sessions, status) = export::full_or_recent_history(&data.content, &data.history);
+        let today = Date::from(time::OffsetDateTime::now_local());
+        let minutes = data.content.settings.streak_minutes;
+        let mut stats = Stats {
+            range: StatsRange::Week,
+            subjects: Vector::new(),
+            days: Vector::new(),
+            total: SpentTime::default(),
+            streaks: streak::streaks(&sessions, minutes, today),
+            status,
+            sessions,
+        };
+        stats.refresh();
+        stats
+    }
+
+    /// Adds up the sessions of the selected range again, sessions count
+    /// for the day they started on.
+    pub fn refresh(&mut self) {
+        let today = Date::from(time::OffsetDateTime::now_local());
+        let first = Date::from(*today - time::Duration::days(self.range.days() - 1));
+        let mut subjects: BTreeMap<Arc<str>, (SpentTime, Option<u32>)> = BTreeMap::new();
+        let mut days: BTreeMap<Date, SpentTime> = BTreeMap::new();
+        let sessions = self.sessions.iter().filter(|session| {
+            let day = Date::from(*session.started);
+            day >= first && day <= today
+        });
+        for session in sessions {
+            let duration = session.duration();
+            let subject = &session.topic.subject;
+            let entry = subjects
+                .entry(subject.name.clone())
+                .or_insert((SpentTime::default(), subject.color));
+            entry.0 += duration;
+            *days
+                .entry(Date::from(*session.started))
+                .or_insert_with(SpentTime::default) += duration;
+        }
+
+        let mut subjects: Vec<_> = subjects.into_iter().collect();
+        subjects.sort_by(|a, b| (b.1).0.cmp(&(a.1).0));
+        if subjects.len() > SLICES {
+            let other = subjects.split_off(SLICES - 1);
+            let spent = other.into_iter().map(|(_, (spent, _))| spent).sum();
+            subjects.push(("Other".into(), (spent, None)));
+        }
+        self.total = subjects.iter().map(|(_, (spent, _))| *spent).sum();
+        let total = self.total.as_secs().max(1) as f64;
+        let mut shades = SHADES.iter().cycle();
+        self.subjects = subjects
+            .into_iter()
+            .map(|(label, (spent_time, color))| Slice {
+                label,
+                share: spent_time.as_secs() as f64 / total,
+                color: color.unwrap_or_else(|| *shades.next().unwrap()),
+                spent_time,
+            })
+            .collect();
+
+        self.days.clear();
+        let mut day = first;
+        while day <= today {
+            self.days.push_back(DayBar {
+                day,
+                spent_time: days.get(&day).copied().unwrap_or_default(),
+            });
+            day = Date::from(*day + time::Duration::days(1));
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Days in a row a subject was worked on, to keep up a daily practice.
+
+use super::{Date, History, SpentTime, Subject};
+#[cfg(feature = "gui")]
+use druid::{Data, Lens};
+use im::Vector;
+use std::collections::BTreeMap;
+
+/// Streaks of this many days earn a badge of the given name.
+const BADGES: [(u32, &str); 5] = [
+    (3, "3 days"),
+    (7, "A week"),
+    (30, "A month"),
+    (100, "100 days"),
+    (365, "A year"),
+];
+
+#[derive(Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct Streak {
+    pub subject: Subject,
+    /// Days in a row up to today, or up to yesterday while today's time is
+    /// still missing.
+    pub current: u32,
+    pub longest: u32,
+}
+
+impl Streak {
+    /// Names of the badges earned by the longest streak.
+    pub fn badges(&self) -> Vec<&'static str> {
+        BADGES
+            .iter()
+            .filter(|(days, _)| self.longest >= *days)
+            .map(|(_, name)| *name)
+            .collect()
+    }
+}
+
+/// The streaks of every subject with at least one day of `minutes`, the
+/// longest current streaks first. Sessions count for the day they started on.
+pub fn streaks(sessions: &History, minutes: f64, today: Date) -> Vector<Streak> {
+    let mut worked: BTreeMap<usize, (Subject, BTreeMap<Date, SpentTime>)> = BTreeMap::new();
+    for session in sessions {
+        let subject = &session.topic.subject;
+        let (_, days) = worked
+            .entry(subject.id)
+            .or_insert_with(|| (subject.clone(), BTreeMap::new()));
+        *days
+            .entry(Date::from(*session.started))
+            .or_insert_with(SpentTime::default) += session.duration();
+    }
+    let needed = (minutes.max(0.0) * 60.0) as u64;
+    let yesterday = Date::from(*today - time::Duration::days(1));
+    let mut streaks: Vec<Streak> = worked
+        .into_iter()
+        .filter_map(|(_, (subject, days))| {
+            let mut longest = 0;
+            let mut run = 0;
+            let mut last: Option<Date> = None;
+            let days = days
+                .into_iter()
+                .filter(|(_, spent)| spent.as_secs() >= needed);
+            for (day, _) in days {
+                let follows = last.map_or(false, |last| *day - *last == time::Duration::days(1));
+                run = if follows { run + 1 } else { 1 };
+                longest = longest.max(run);
+                last = Some(day);
+            }
+            let current = match last {
+                Some(last) if last == today || last == yesterday => run,
+                _ => 0,
+            };
+            match longest {
+                0 => None,
+                _ => Some(Streak {
+                    subject,
+                    current,
+                    longest,
+                }),
+            }
+        })
+        .collect();
+    streaks.sort_by(|a, b| (b.current, b.longest).cmp(&(a.current, a.longest)));
+    streaks.into_iter().collect()
+}
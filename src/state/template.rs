@@ -0,0 +1,137 @@
+use super::{
+    backup::{self, Backup, BackupAction, BackupGoal, BackupSubject},
+    collation, hierarchy, Content,
+};
+use serde::{Deserialize, Serialize};
+use std::{error::Error, io::Write, path::Path};
+
+const VERSION: u32 = 2;
+
+/// The structure of a setup without any tracked time, meant to be shared.
+#[derive(Serialize, Deserialize)]
+pub struct Template {
+    pub version: u32,
+    #[serde(default)]
+    pub actions: Vec<String>,
+    #[serde(default)]
+    pub subjects: Vec<TemplateSubject>,
+    #[serde(default)]
+    pub contexts: Vec<String>,
+    #[serde(default)]
+    pub goals: Vec<BackupGoal>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TemplateSubject {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// Written like `#3a7bd5`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Name of the subject it is nested in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+}
+
+impl Template {
+    pub fn from_content(content: &Content) -> Self {
+        Template {
+            version: VERSION,
+            actions: content.actions.iter().map(|a| a.name.to_string()).collect(),
+            subjects: content
+                .subjects
+                .iter()
+                .map(|subject| TemplateSubject {
+                    name: subject.name.to_string(),
+                    category: content.categories.get(subject).map(|c| c.to_string()),
+                    color: subject.color.map(backup::color_hex),
+                    parent: hierarchy::parent(&content.subjects, subject)
+                        .map(|parent| parent.name.to_string()),
+                })
+                .collect(),
+            contexts: content
+                .contexts
+                .iter()
+                .map(|c| c.name.to_string())
+                .collect(),
+            goals: content.goals.iter().map(BackupGoal::new).collect(),
+        }
+    }
+
+    pub fn read(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let template: Template = serde_json::from_slice(&std::fs::read(path)?)?;
+        if template.version > VERSION {
+            return Err(format!(
+                "The template uses version {} but only {} is supported.",
+                template.version, VERSION
+            )
+            .into());
+        }
+        Ok(template)
+    }
+
+    pub fn write(&self, out: &mut impl Write) -> std::io::Result<()> {
+        serde_json::to_writer_pretty(out, self)?;
+        Ok(())
+    }
+
+    /// A backup without sessions holding the same entries, to import it the
+    /// same way. Names already in `content` with another spelling take the
+    /// existing one, so that they are not created a second time.
+    pub fn into_backup(self, content: &Content) -> Backup {
+        let action = |name: String| match collation::find(&content.actions, &name) {
+            Some(action) => action.name.to_string(),
+            None => name,
+        };
+        let subject = |name: String| match collation::find(&content.subjects, &name) {
+            Some(subject) => subject.name.to_string(),
+            None => name,
+        };
+        let context = |name: String| match collation::find(&content.contexts, &name) {
+            Some(context) => context.name.to_string(),
+            None => name,
+        };
+        Backup {
+            version: backup::VERSION,
+            actions: self
+                .actions
+                .into_iter()
+                .map(|name| BackupAction {
+                    name: action(name),
+                    archived: false,
+                    color: None,
+                })
+                .collect(),
+            subjects: self
+                .subjects
+                .into_iter()
+                .map(|entry| BackupSubject {
+                    name: subject(entry.name),
+                    archived: false,
+                    category: entry.category,
+                    issue_url: None,
+                    estimate: None,
+                    rate: None,
+                    idle_rule: None,
+                    color: entry.color,
+                    parent: entry.parent.map(subject),
+                    final_report: None,
+                })
+                .collect(),
+            contexts: self.contexts.into_iter().map(context).collect(),
+            tags: Vec::new(),
+            sessions: Vec::new(),
+            goals: self
+                .goals
+                .into_iter()
+                .map(|goal| BackupGoal {
+                    action: action(goal.action),
+                    subject: subject(goal.subject),
+                    ..goal
+                })
+                .collect(),
+            pinned: Vec::new(),
+        }
+    }
+}
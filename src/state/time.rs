@@ -1,23 +1,87 @@
+#[cfg(feature = "gui")]
 use druid::Data;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::{
-    fmt::Display,
+    convert::TryFrom,
+    error::Error,
+    fmt::{self, Display},
+    iter::Sum,
     ops::{Add, AddAssign, Deref, DerefMut},
+    str::FromStr,
     time::Duration,
 };
 
-#[derive(Debug, Clone, Copy, Data)]
-pub struct DateTime(#[data(same_fn = "PartialEq::eq")] time::OffsetDateTime);
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub struct DateTime(
+    #[cfg_attr(feature = "gui", data(same_fn = "PartialEq::eq"))] time::OffsetDateTime,
+);
 
-#[derive(Debug, Clone, Copy, Data)]
-pub struct Date(#[data(same_fn = "PartialEq::eq")] time::Date);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub struct Date(#[cfg_attr(feature = "gui", data(same_fn = "PartialEq::eq"))] time::Date);
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct SpentTime(Duration);
 
+/// How a `SpentTime` is written out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub enum TimeFormat {
+    /// `1h 30m 0s`, as written by `Display`.
+    Long,
+    /// `1h 30m`, without the seconds.
+    Short,
+    /// `1:30`, hours and minutes.
+    Clock,
+    /// `1.5`, in hours.
+    Hours,
+}
+
+/// Unit of a number typed without one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Hours,
+    Minutes,
+}
+
+#[derive(Debug)]
+pub struct ParseSpentTimeError {
+    text: String,
+}
+
 impl DateTime {
     pub fn now() -> Self {
         DateTime(time::OffsetDateTime::now_local())
     }
+
+    /// The moment in the offset the local zone has at that moment, so that
+    /// times before a daylight saving change keep the offset they had.
+    pub fn local(at: time::OffsetDateTime) -> Self {
+        DateTime(at.to_offset(time::UtcOffset::local_offset_at(at)))
+    }
+
+    /// The moment in the offset it was recorded with, in seconds east of UTC,
+    /// or in the local one if it was recorded without.
+    pub fn in_offset(at: time::OffsetDateTime, seconds: Option<i32>) -> Self {
+        match seconds {
+            Some(seconds) => DateTime(at.to_offset(time::UtcOffset::seconds(seconds))),
+            None => DateTime::local(at),
+        }
+    }
+
+    /// A wall clock time in the local zone, with the offset the zone has then
+    /// rather than the current one.
+    pub fn from_local(at: time::PrimitiveDateTime) -> Self {
+        let guess = at.assume_offset(time::UtcOffset::local_offset_at(at.assume_utc()));
+        DateTime(at.assume_offset(time::UtcOffset::local_offset_at(guess)))
+    }
+
+    /// The first midnight after this moment, in the same offset.
+    pub fn next_midnight(&self) -> DateTime {
+        let midnight = self.0.date().next_day().midnight();
+        DateTime(midnight.assume_offset(self.0.offset()))
+    }
 }
 
 impl Deref for DateTime {
@@ -46,6 +110,43 @@ impl From<time::OffsetDateTime> for Date {
     }
 }
 
+impl Date {
+    /// The monday of the week this date belongs to.
+    pub fn week_begin(&self) -> Date {
+        let offset = self.0.weekday().number_days_from_monday();
+        Date(self.0 - time::Duration::days(offset as i64))
+    }
+
+    /// The first day of the month this date belongs to.
+    pub fn month_begin(&self) -> Date {
+        Date(self.0 - time::Duration::days(self.0.day() as i64 - 1))
+    }
+
+    /// The first day of the month this date belongs to, for months starting
+    /// on `day` like billing cycles do. Days after the 28th count as the 28th,
+    /// so that every month has them.
+    pub fn month_begin_on(&self, day: u8) -> Date {
+        let day = day.max(1).min(28);
+        let (year, month) = match self.0.day() >= day {
+            true => (self.0.year(), self.0.month()),
+            false => match self.0.month() {
+                1 => (self.0.year() - 1, 12),
+                month => (self.0.year(), month - 1),
+            },
+        };
+        Date(time::Date::try_from_ymd(year, month, day).unwrap())
+    }
+
+    /// The same day in the following month, which has to exist.
+    pub fn next_month(&self) -> Date {
+        let (year, month) = match self.0.month() {
+            12 => (self.0.year() + 1, 1),
+            month => (self.0.year(), month + 1),
+        };
+        Date(time::Date::try_from_ymd(year, month, self.0.day()).unwrap())
+    }
+}
+
 impl Deref for Date {
     type Target = time::Date;
 
@@ -54,6 +155,96 @@ impl Deref for Date {
     }
 }
 
+impl SpentTime {
+    pub fn from_secs(seconds: u64) -> Self {
+        SpentTime(Duration::from_secs(seconds))
+    }
+
+    pub fn checked_add(self, rhs: SpentTime) -> Option<SpentTime> {
+        self.0.checked_add(rhs.0).map(SpentTime)
+    }
+
+    /// `None` if `rhs` is longer.
+    pub fn checked_sub(self, rhs: SpentTime) -> Option<SpentTime> {
+        self.0.checked_sub(rhs.0).map(SpentTime)
+    }
+
+    /// Zero if `rhs` is longer.
+    pub fn saturating_sub(self, rhs: SpentTime) -> SpentTime {
+        SpentTime(self.0.checked_sub(rhs.0).unwrap_or_default())
+    }
+
+    pub fn format(self, format: TimeFormat) -> String {
+        let total = self.0.as_secs();
+        let (hours, minutes) = (total / 60 / 60, (total / 60) % 60);
+        match format {
+            TimeFormat::Long => self.to_string(),
+            TimeFormat::Short => format!("{}h {}m", hours, minutes),
+            TimeFormat::Clock => format!("{}:{:02}", hours, minutes),
+            TimeFormat::Hours => format!("{}", self.0.as_secs_f64() / 3600.0),
+        }
+    }
+
+    /// Reads a time like `1h30m`, `1.5h`, `90 min` or `1:30`, a plain number
+    /// is taken to be in `unit`. Decimal commas are accepted as well.
+    pub fn parse_in(text: &str, unit: Unit) -> Result<SpentTime, ParseSpentTimeError> {
+        let error = || ParseSpentTimeError {
+            text: text.to_string(),
+        };
+        let text = text.trim().replace(',', ".");
+        if text.contains(':') {
+            let parts = text
+                .split(':')
+                .map(|part| part.trim().parse::<u64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| error())?;
+            let (hours, minutes, seconds) = match parts.as_slice() {
+                [hours, minutes] if *minutes < 60 => (*hours, *minutes, 0),
+                [hours, minutes, seconds] if *minutes < 60 && *seconds < 60 => {
+                    (*hours, *minutes, *seconds)
+                }
+                _ => return Err(error()),
+            };
+            let seconds = hours
+                .checked_mul(3600)
+                .and_then(|hours| hours.checked_add(minutes * 60 + seconds))
+                .ok_or_else(error)?;
+            return Ok(SpentTime::from_secs(seconds));
+        }
+
+        let mut seconds = 0.0;
+        let mut rest = text.as_str();
+        let mut parts = 0;
+        while !rest.is_empty() {
+            let number_end = rest
+                .find(|c: char| !c.is_ascii_digit() && c != '.')
+                .unwrap_or(rest.len());
+            let number: f64 = rest[..number_end].parse().map_err(|_| error())?;
+            rest = rest[number_end..].trim_start();
+            let unit_end = rest
+                .find(|c: char| !c.is_alphabetic())
+                .unwrap_or(rest.len());
+            let factor = match (&rest[..unit_end], unit) {
+                ("h" | "hr" | "hrs" | "hour" | "hours", _) => 3600.0,
+                ("m" | "min" | "mins" | "minute" | "minutes", _) => 60.0,
+                ("s" | "sec" | "secs" | "second" | "seconds", _) => 1.0,
+                // Only a lone number may leave out its unit.
+                ("", Unit::Hours) if parts == 0 && unit_end == rest.len() => 3600.0,
+                ("", Unit::Minutes) if parts == 0 && unit_end == rest.len() => 60.0,
+                _ => return Err(error()),
+            };
+            seconds += number * factor;
+            parts += 1;
+            rest = rest[unit_end..].trim_start();
+        }
+        // Durations cannot hold more, and converting would panic.
+        if parts == 0 || !seconds.is_finite() || seconds >= u64::MAX as f64 {
+            return Err(error());
+        }
+        Ok(SpentTime(Duration::from_secs_f64(seconds)))
+    }
+}
+
 impl From<Duration> for SpentTime {
     fn from(duration: Duration) -> Self {
         SpentTime(duration)
@@ -90,6 +281,7 @@ impl Display for SpentTime {
     }
 }
 
+#[cfg(feature = "gui")]
 impl Data for SpentTime {
     fn same(&self, other: &Self) -> bool {
         self.0.as_secs() == other.0.as_secs()
@@ -109,3 +301,68 @@ impl AddAssign<SpentTime> for SpentTime {
         self.0 += rhs.0;
     }
 }
+
+impl Sum for SpentTime {
+    fn sum<I: Iterator<Item = SpentTime>>(iter: I) -> Self {
+        iter.fold(SpentTime::default(), Add::add)
+    }
+}
+
+/// Reads minutes if no unit is given.
+impl FromStr for SpentTime {
+    type Err = ParseSpentTimeError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        SpentTime::parse_in(text, Unit::Minutes)
+    }
+}
+
+impl Display for ParseSpentTimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Expected a time like 1h30m, 1.5h or 1:30, not '{}'.",
+            self.text
+        )
+    }
+}
+
+impl Error for ParseSpentTimeError {}
+
+/// Stored as whole seconds, like everywhere else.
+impl Serialize for SpentTime {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.0.as_secs())
+    }
+}
+
+/// Reads whole seconds, or a written out time for files edited by hand.
+impl<'de> Deserialize<'de> for SpentTime {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = SpentTime;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "seconds or a time like 1h30m")
+            }
+
+            fn visit_u64<E: de::Error>(self, seconds: u64) -> Result<SpentTime, E> {
+                Ok(SpentTime::from_secs(seconds))
+            }
+
+            fn visit_i64<E: de::Error>(self, seconds: i64) -> Result<SpentTime, E> {
+                u64::try_from(seconds)
+                    .map(SpentTime::from_secs)
+                    .map_err(|_| E::custom("a time cannot be negative"))
+            }
+
+            fn visit_str<E: de::Error>(self, text: &str) -> Result<SpentTime, E> {
+                SpentTime::parse_in(text, Unit::Minutes).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
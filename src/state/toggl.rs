@@ -0,0 +1,187 @@
+//! Reads the detailed CSV export of Toggl, so its users can bring their history along.
+//! Projects become subjects, with their client as category, and descriptions
+//! become actions.
+
+use super::{
+    backup::{Backup, BackupAction, BackupSession, BackupSubject, TIMESTAMP_FORMAT},
+    collation, companion,
+    import::csv_line,
+    Content, DateTime, History,
+};
+use std::error::Error;
+
+/// Names Toggl shows for entries left empty.
+const NO_PROJECT: &str = "Without project";
+const NO_DESCRIPTION: &str = "(no description)";
+
+/// Positions of the columns used, as the export varies in the others.
+struct Columns {
+    project: usize,
+    description: usize,
+    start_date: usize,
+    start_time: usize,
+    end_date: usize,
+    end_time: usize,
+    client: Option<usize>,
+    tags: Option<usize>,
+}
+
+impl Columns {
+    fn find(header: &[String]) -> Result<Self, String> {
+        let position = |name: &str| {
+            header
+                .iter()
+                .position(|column| column.trim().eq_ignore_ascii_case(name))
+        };
+        let required = |name: &str| {
+            position(name).ok_or_else(|| {
+                format!(
+                    "The file lacks the column '{}', expected a detailed export of Toggl.",
+                    name
+                )
+            })
+        };
+        Ok(Columns {
+            project: required("Project")?,
+            description: required("Description")?,
+            start_date: required("Start date")?,
+            start_time: required("Start time")?,
+            end_date: required("End date")?,
+            end_time: required("End time")?,
+            client: position("Client"),
+            tags: position("Tags"),
+        })
+    }
+}
+
+/// Reads the time entries into a backup, which can be imported like any
+/// other. Names are matched to the existing ones and their aliases ignoring
+/// case, unknown ones are created.
+pub fn parse(content: &Content, text: &str) -> Result<Backup, Box<dyn Error>> {
+    let mut backup = Backup::new(&Content::default(), &History::default());
+    let mut records = records(text);
+    let columns = match records.next() {
+        Some((_, header)) => Columns::find(&header)?,
+        None => return Err("The file is empty.".into()),
+    };
+    for (number, fields) in records {
+        entry(&mut backup, content, &columns, &fields)
+            .map_err(|err| format!("Line {}: {}", number, err))?;
+    }
+    Ok(backup)
+}
+
+/// The non-empty records with the line they start on. Quoted fields may
+/// span several lines, as descriptions can.
+fn records(text: &str) -> impl Iterator<Item = (usize, Vec<String>)> + '_ {
+    let mut lines = text.lines().enumerate();
+    std::iter::from_fn(move || loop {
+        let (index, line) = lines.next()?;
+        let mut record = line.to_string();
+        while record.matches('"').count() % 2 == 1 {
+            match lines.next() {
+                Some((_, line)) => {
+                    record.push('\n');
+                    record.push_str(line);
+                }
+                None => break,
+            }
+        }
+        let fields = csv_line(&record);
+        if !fields.is_empty() {
+            return Some((index + 1, fields));
+        }
+    })
+}
+
+fn entry(
+    backup: &mut Backup,
+    content: &Content,
+    columns: &Columns,
+    fields: &[String],
+) -> Result<(), String> {
+    let field = |index: usize| fields.get(index).map(|field| field.trim()).unwrap_or("");
+    let timestamp = |date: usize, time: usize| {
+        let text = format!("{} {}", field(date), field(time));
+        time::PrimitiveDateTime::parse(&text, "%Y-%m-%d %H:%M:%S")
+            .map(|at| *DateTime::from_local(at))
+            .map_err(|_| format!("Expected a time like 2020-06-01 09:30:00, not '{}'.", text))
+    };
+    let started = timestamp(columns.start_date, columns.start_time)?;
+    let ended = timestamp(columns.end_date, columns.end_time)?;
+    if ended <= started {
+        return Err("The entry ends before it starts.".to_string());
+    }
+
+    // Names are kept on a single line.
+    let description = field(columns.description).replace('\n', " ");
+    let description = Some(description.as_str())
+        .filter(|name| !name.is_empty())
+        .unwrap_or(NO_DESCRIPTION);
+    let action = match content.action_named(description) {
+        Some(action) => action.as_ref().to_string(),
+        None => companion::add_name(
+            &mut backup.actions,
+            description,
+            |entry| &entry.name,
+            |name| BackupAction {
+                name,
+                archived: false,
+                color: None,
+            },
+        ),
+    };
+    let project = Some(field(columns.project))
+        .filter(|name| !name.is_empty())
+        .unwrap_or(NO_PROJECT);
+    let client = columns
+        .client
+        .map(field)
+        .filter(|client| !client.is_empty());
+    let subject = match content.subject_named(project) {
+        Some(subject) => subject.as_ref().to_string(),
+        None => companion::add_name(
+            &mut backup.subjects,
+            project,
+            |entry| &entry.name,
+            |name| BackupSubject {
+                name,
+                archived: false,
+                category: client.map(str::to_string),
+                issue_url: None,
+                estimate: None,
+                rate: None,
+                idle_rule: None,
+                color: None,
+                parent: None,
+                final_report: None,
+            },
+        ),
+    };
+    let tags: Vec<String> = columns
+        .tags
+        .map(field)
+        .unwrap_or("")
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(|tag| match collation::find(&content.tags, tag) {
+            Some(existing) => existing.as_ref().to_string(),
+            None => tag.to_string(),
+        })
+        .collect();
+
+    backup.sessions.push(BackupSession {
+        action,
+        subject,
+        context: None,
+        started: started.format(TIMESTAMP_FORMAT),
+        ended: ended.format(TIMESTAMP_FORMAT),
+        paused: 0,
+        intervals: Vec::new(),
+        note: None,
+        interruptions: Vec::new(),
+        tags,
+    });
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use super::{Action, Context, Session, SpentTime, Subject};
+#[cfg(feature = "gui")]
+use druid::Data;
+use im::HashMap;
+
+/// A property sessions can be grouped by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dimension {
+    Action,
+    Subject,
+    Context,
+}
+
+impl Dimension {
+    fn bit(self) -> u8 {
+        match self {
+            Dimension::Action => 1,
+            Dimension::Subject => 2,
+            Dimension::Context => 4,
+        }
+    }
+}
+
+/// A combination of dimensions, like subject and context.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub struct Grouping(u8);
+
+impl Grouping {
+    pub fn of(dimensions: &[Dimension]) -> Self {
+        Grouping(dimensions.iter().fold(0, |bits, d| bits | d.bit()))
+    }
+
+    /// Every non-empty combination of dimensions.
+    pub fn all() -> impl Iterator<Item = Grouping> {
+        (1..8).map(Grouping)
+    }
+
+    pub fn from_bits(bits: u8) -> Option<Self> {
+        if bits > 0 && bits < 8 {
+            Some(Grouping(bits))
+        } else {
+            None
+        }
+    }
+
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub fn contains(self, dimension: Dimension) -> bool {
+        self.0 & dimension.bit() != 0
+    }
+
+    pub fn with(self, dimension: Dimension, included: bool) -> Self {
+        if included {
+            Grouping(self.0 | dimension.bit())
+        } else {
+            Grouping(self.0 & !dimension.bit())
+        }
+    }
+
+    pub fn key_of(self, session: &Session) -> GroupKey {
+        GroupKey {
+            action: Some(session.topic.action.clone()).filter(|_| self.contains(Dimension::Action)),
+            subject: Some(session.topic.subject.clone())
+                .filter(|_| self.contains(Dimension::Subject)),
+            context: session
+                .context
+                .clone()
+                .filter(|_| self.contains(Dimension::Context)),
+        }
+    }
+}
+
+/// The values of a group, `None` for dimensions outside of the grouping
+/// and for sessions without a context.
+#[derive(Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub struct GroupKey {
+    pub action: Option<Action>,
+    pub subject: Option<Subject>,
+    pub context: Option<Context>,
+}
+
+impl GroupKey {
+    pub fn label(&self) -> String {
+        let mut parts: Vec<&str> = Vec::new();
+        if let Some(action) = &self.action {
+            parts.push(action.as_ref());
+        }
+        if let Some(subject) = &self.subject {
+            parts.push(subject.as_ref());
+        }
+        match &self.context {
+            Some(context) => parts.push(context.as_ref()),
+            None if parts.is_empty() => parts.push("No context"),
+            None => {}
+        }
+        parts.join(" ")
+    }
+}
+
+/// Spent time per group for every grouping, kept up to date as sessions are
+/// added so reports don't need to walk the whole history.
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub struct Totals(HashMap<(Grouping, GroupKey), SpentTime>);
+
+impl Totals {
+    pub fn set(&mut self, grouping: Grouping, key: GroupKey, time: SpentTime) {
+        self.0.insert((grouping, key), time);
+    }
+
+    pub fn add(&mut self, session: &Session) {
+        for grouping in Grouping::all() {
+            *self
+                .0
+                .entry((grouping, grouping.key_of(session)))
+                .or_default() += session.duration();
+        }
+    }
+
+    pub fn remove(&mut self, session: &Session) {
+        for grouping in Grouping::all() {
+            if let Some(time) = self.0.get_mut(&(grouping, grouping.key_of(session))) {
+                *time = time.saturating_sub(session.duration());
+            }
+        }
+    }
+
+    /// Changes the group keys in place, for example to take over a new name.
+    pub fn update_keys(&mut self, update: impl Fn(&mut GroupKey)) {
+        self.0 = self
+            .0
+            .iter()
+            .map(|((grouping, key), time)| {
+                let mut key = key.clone();
+                update(&mut key);
+                ((*grouping, key), *time)
+            })
+            .collect();
+    }
+
+    pub fn group_by(&self, grouping: Grouping) -> impl Iterator<Item = (&GroupKey, SpentTime)> {
+        self.0
+            .iter()
+            .filter(move |((g, _), _)| *g == grouping)
+            .map(|((_, key), time)| (key, *time))
+    }
+}
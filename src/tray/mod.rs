@@ -0,0 +1,191 @@
+//! Icon in the system tray, showing whether a session is running and
+//! offering to start or stop one without opening the window.
+
+use crate::{
+    controller::backend_msg,
+    state::{AppState, Topic},
+    ui::tracker,
+};
+use druid::{
+    commands, widget::Controller, Data, Env, Event, EventCtx, ExtEventSink, LifeCycle,
+    LifeCycleCtx, Selector, Target, UpdateCtx, Widget,
+};
+
+#[cfg(target_os = "linux")]
+mod sni;
+
+/// Brings the hidden window back.
+pub const SHOW: Selector = Selector::new("zeitig.tray.show");
+/// Closes the window even if it would only be hidden.
+pub const QUIT: Selector = Selector::new("zeitig.tray.quit");
+
+/// Topics offered in the tray menu.
+const RECENT: usize = 5;
+
+/// What the tray shows, a copy of the relevant parts of the state.
+#[derive(Clone, Default, PartialEq)]
+pub struct TrayState {
+    pub running: bool,
+    /// The selected topic, sessions can only be started with one.
+    pub selected: Option<Topic>,
+    /// Latest distinct topics of the history, the newest first.
+    pub recent: Vec<Topic>,
+}
+
+impl TrayState {
+    fn of(data: &AppState) -> Self {
+        let selected = match (&data.setup.selected_action, &data.setup.selected_subject) {
+            (Some(action), Some(subject)) => Some(Topic {
+                action: action.clone(),
+                subject: subject.clone(),
+            }),
+            _ => None,
+        };
+        TrayState {
+            running: data.active.is_some(),
+            selected,
+            recent: data.history.recent_topics(RECENT).into_iter().collect(),
+        }
+    }
+}
+
+/// Commands sent from the tray to the window.
+struct Commands(ExtEventSink);
+
+impl Commands {
+    fn submit<T: Send + 'static>(&self, selector: Selector<T>, payload: T) {
+        if let Err(err) = self
+            .0
+            .submit_command(selector, Box::new(payload), Target::Global)
+        {
+            log::error!("Cannot send a command from the tray: {}", err);
+        }
+    }
+
+    fn toggle_session(&self) {
+        self.submit(tracker::TOGGLE_SESSION, ());
+    }
+
+    fn start(&self, topic: Topic) {
+        self.submit(tracker::START_TOPIC, topic);
+    }
+
+    fn show(&self) {
+        self.submit(SHOW, ());
+    }
+
+    fn quit(&self) {
+        self.submit(QUIT, ());
+    }
+}
+
+#[cfg(target_os = "linux")]
+type Handle = sni::Handle;
+
+/// Trays are not supported on this platform, the window closes as usual.
+#[cfg(not(target_os = "linux"))]
+struct Handle;
+
+#[cfg(not(target_os = "linux"))]
+impl Handle {
+    fn spawn(_commands: Commands, _state: TrayState) -> Option<Self> {
+        None
+    }
+
+    fn is_shown(&self) -> bool {
+        false
+    }
+
+    fn update(&self, _state: TrayState) {}
+
+    fn shutdown(&self) {}
+}
+
+/// Keeps the tray in sync with the state and hides the window on close
+/// instead, if enabled in the settings.
+#[derive(Default)]
+pub struct TrayController {
+    tray: Option<Handle>,
+    state: TrayState,
+    quitting: bool,
+}
+
+impl TrayController {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<W: Widget<AppState>> Controller<AppState, W> for TrayController {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut AppState,
+        env: &Env,
+    ) {
+        match event {
+            Event::Command(cmd) if cmd.is(commands::CLOSE_WINDOW) => {
+                let shown = self.tray.as_ref().map_or(false, Handle::is_shown);
+                if shown && data.content.settings.close_to_tray && !self.quitting {
+                    ctx.set_handled();
+                    ctx.window().hide();
+                    return;
+                }
+            }
+            Event::Command(cmd) if cmd.is(SHOW) => {
+                ctx.window().show();
+                ctx.window().bring_to_front_and_focus();
+            }
+            Event::Command(cmd) if cmd.is(QUIT) => {
+                self.quitting = true;
+                ctx.submit_command(commands::CLOSE_WINDOW);
+            }
+            Event::Command(cmd) if cmd.is(backend_msg::STOPPED) => {
+                if let Some(tray) = self.tray.take() {
+                    tray.shutdown();
+                }
+            }
+            _ => {}
+        }
+        child.event(ctx, event, data, env)
+    }
+
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &AppState,
+        env: &Env,
+    ) {
+        if let (LifeCycle::WidgetAdded, false) = (event, data.safe_mode) {
+            self.state = TrayState::of(data);
+            let commands = Commands(ctx.get_external_handle());
+            self.tray = Handle::spawn(commands, self.state.clone());
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(
+        &mut self,
+        child: &mut W,
+        ctx: &mut UpdateCtx,
+        old_data: &AppState,
+        data: &AppState,
+        env: &Env,
+    ) {
+        let changed = !old_data.active.same(&data.active)
+            || !old_data.setup.same(&data.setup)
+            || !old_data.history.same(&data.history);
+        if let (Some(tray), true) = (&self.tray, changed) {
+            let state = TrayState::of(data);
+            if state != self.state {
+                self.state = state.clone();
+                tray.update(state);
+            }
+        }
+        child.update(ctx, old_data, data, env)
+    }
+}
@@ -0,0 +1,148 @@
+//! Tray icon following the StatusNotifierItem specification, which most
+//! Linux desktops implement.
+
+use super::{Commands, TrayState};
+use ksni::{
+    menu::{MenuItem, StandardItem},
+    ToolTip, TrayService,
+};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+struct Model {
+    commands: Commands,
+    state: TrayState,
+    /// Whether a tray host shows the icon, the window must not be hidden otherwise.
+    shown: Arc<AtomicBool>,
+}
+
+impl ksni::Tray for Model {
+    fn activate(&mut self, _x: i32, _y: i32) {
+        self.commands.show();
+    }
+
+    fn id(&self) -> String {
+        "zeitig".to_string()
+    }
+
+    fn title(&self) -> String {
+        "Zeitig".to_string()
+    }
+
+    fn icon_name(&self) -> String {
+        match self.state.running {
+            true => "media-record",
+            false => "media-playback-pause",
+        }
+        .to_string()
+    }
+
+    fn tool_tip(&self) -> ToolTip {
+        let title = match (&self.state.selected, self.state.running) {
+            (Some(topic), true) => format!("Tracking {} {}", topic.action.name, topic.subject.name),
+            _ => "Not tracking".to_string(),
+        };
+        ToolTip {
+            title,
+            ..Default::default()
+        }
+    }
+
+    fn watcher_online(&self) {
+        self.shown.store(true, Ordering::SeqCst);
+    }
+
+    fn watcher_offine(&self) -> bool {
+        self.shown.store(false, Ordering::SeqCst);
+        // Keep waiting, the icon appears once a tray host is started.
+        true
+    }
+
+    fn menu(&self) -> Vec<MenuItem<Self>> {
+        let mut menu = vec![StandardItem {
+            label: match self.state.running {
+                true => "Stop",
+                false => "Start",
+            }
+            .to_string(),
+            enabled: self.state.running || self.state.selected.is_some(),
+            activate: Box::new(|model: &mut Self| model.commands.toggle_session()),
+            ..Default::default()
+        }
+        .into()];
+        if !self.state.recent.is_empty() {
+            menu.push(MenuItem::Separator);
+        }
+        for topic in &self.state.recent {
+            let label = format!("{} {}", topic.action.name, topic.subject.name);
+            let topic = topic.clone();
+            menu.push(
+                StandardItem {
+                    // Underscores would be taken as access keys.
+                    label: label.replace('_', "__"),
+                    activate: Box::new(move |model: &mut Self| model.commands.start(topic.clone())),
+                    ..Default::default()
+                }
+                .into(),
+            );
+        }
+        menu.push(MenuItem::Separator);
+        menu.push(
+            StandardItem {
+                label: "Show window".to_string(),
+                activate: Box::new(|model: &mut Self| model.commands.show()),
+                ..Default::default()
+            }
+            .into(),
+        );
+        menu.push(
+            StandardItem {
+                label: "Quit".to_string(),
+                activate: Box::new(|model: &mut Self| model.commands.quit()),
+                ..Default::default()
+            }
+            .into(),
+        );
+        menu
+    }
+}
+
+pub struct Handle {
+    handle: ksni::Handle<Model>,
+    shown: Arc<AtomicBool>,
+}
+
+impl Handle {
+    /// Registers the icon with the desktop on a thread of its own.
+    pub fn spawn(commands: Commands, state: TrayState) -> Option<Self> {
+        let shown = Arc::new(AtomicBool::new(false));
+        let service = TrayService::new(Model {
+            commands,
+            state,
+            shown: shown.clone(),
+        });
+        let handle = service.handle();
+        let failed = shown.clone();
+        std::thread::spawn(move || {
+            if let Err(err) = service.run() {
+                failed.store(false, Ordering::SeqCst);
+                log::warn!("Cannot show the tray icon: {}", err);
+            }
+        });
+        Some(Handle { handle, shown })
+    }
+
+    pub fn is_shown(&self) -> bool {
+        self.shown.load(Ordering::SeqCst)
+    }
+
+    pub fn update(&self, state: TrayState) {
+        self.handle.update(|model| model.state = state);
+    }
+
+    pub fn shutdown(&self) {
+        self.handle.shutdown();
+    }
+}
@@ -0,0 +1,184 @@
+use druid::{
+    widget::{Button, CrossAxisAlignment, Flex, Label, List, Scroll, TextBox},
+    Command, Env, EventCtx, Selector, Target, Widget, WidgetExt, WindowDesc,
+};
+
+use crate::{
+    controller::{backend_msg, CommandReceiver},
+    state::{entry::ManualEntry, Action, AppState, Subject},
+    ui,
+    widgets::{truncated, Maybe},
+};
+
+const SELECT_ACTION: Selector<Action> = Selector::new("zeitig.entry.select_action");
+const SELECT_SUBJECT: Selector<Subject> = Selector::new("zeitig.entry.select_subject");
+
+pub fn window() -> WindowDesc<AppState> {
+    WindowDesc::new(ui)
+        .title(|data: &AppState, _: &Env| match &data.entry {
+            Some(entry) if entry.editing.is_some() => "Edit session".to_string(),
+            _ => "Add session".to_string(),
+        })
+        .window_size((360.0, 500.0))
+}
+
+fn handle_command(_ctx: &mut EventCtx, data: &mut AppState, cmd: &Command) {
+    if let Some(entry) = data.entry.as_mut() {
+        if let Some(action) = cmd.get(SELECT_ACTION) {
+            entry.action = Some(action.clone());
+        }
+        if let Some(subject) = cmd.get(SELECT_SUBJECT) {
+            entry.subject = Some(subject.clone());
+        }
+    }
+}
+
+fn add(ctx: &mut EventCtx, data: &mut AppState) {
+    let (result, editing) = match &data.entry {
+        Some(entry) => (entry.to_session(), entry.editing.clone()),
+        None => return,
+    };
+    // Neither the recorded session nor its new times may lie in the locked period.
+    let content = &data.content;
+    let locked = result.as_ref().map_or(false, |session| {
+        content.is_locked(&session.started)
+            || editing
+                .as_ref()
+                .map_or(false, |original| content.is_locked(&original.key()))
+    });
+    let result = if locked {
+        Err(locked_status(data))
+    } else {
+        result
+    };
+    let status = match (result, editing) {
+        (Ok(session), Some(original)) => {
+            let recorded = ui::history::change_session(ctx, data, original, Some(session));
+            if let Some(entry) = data.entry.as_mut() {
+                entry.editing = recorded;
+            }
+            "Session saved.".to_string()
+        }
+        (Ok(session), None) => {
+            *data.content.time_table.get_mut(session.topic.clone()) += session.duration();
+            data.content.totals.add(&session);
+            data.history.insert(session.clone());
+            ctx.submit_command(backend_msg::ADD_SESSION.with(session).to(Target::Global));
+            "Session added.".to_string()
+        }
+        (Err(err), _) => err,
+    };
+    if let Some(entry) = data.entry.as_mut() {
+        entry.status = status;
+    }
+}
+
+fn locked_status(data: &AppState) -> String {
+    match data.content.locked_before {
+        Some(before) => format!(
+            "Sessions before {} are locked, unlock them in Manage first.",
+            *before
+        ),
+        None => String::new(),
+    }
+}
+
+pub fn ui() -> impl Widget<AppState> {
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_flex_child(topics(), 1.0)
+        .with_spacer(5.0)
+        .with_child(Maybe::or_empty(form()).lens(AppState::entry))
+        .with_spacer(5.0)
+        .with_child(
+            Button::dynamic(|data: &AppState, _| match &data.entry {
+                Some(entry) if entry.editing.is_some() => "Save".to_string(),
+                _ => "Add session".to_string(),
+            })
+            .on_click(|ctx, data: &mut AppState, _| add(ctx, data)),
+        )
+        .padding(10.0)
+        .env_scope(ui::apply_settings)
+        .controller(CommandReceiver::new(handle_command))
+}
+
+fn topics() -> impl Widget<AppState> {
+    Flex::row()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_flex_child(
+            Scroll::new(List::new(|| {
+                truncated(|action: &Action, _| {
+                    ui::listed_name(action.as_ref(), action.archived, action.temporary)
+                })
+                .padding(3.0)
+                .on_click(|ctx, action: &mut Action, _| {
+                    ctx.submit_command(SELECT_ACTION.with(action.clone()));
+                })
+            }))
+            .vertical()
+            .lens(ui::ListedActions)
+            .expand_width(),
+            1.0,
+        )
+        .with_flex_child(
+            Scroll::new(List::new(|| {
+                truncated(|subject: &Subject, _| {
+                    ui::listed_name(subject.as_ref(), subject.archived, subject.temporary)
+                })
+                .padding(3.0)
+                .on_click(|ctx, subject: &mut Subject, _| {
+                    ctx.submit_command(SELECT_SUBJECT.with(subject.clone()));
+                })
+            }))
+            .vertical()
+            .lens(ui::ListedSubjects)
+            .expand_width(),
+            1.0,
+        )
+        .expand_height()
+}
+
+fn form() -> impl Widget<ManualEntry> {
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(truncated(|entry: &ManualEntry, _| {
+            format!(
+                "{} {}",
+                entry.action.as_ref().map_or("No Action", |a| a.as_ref()),
+                entry.subject.as_ref().map_or("No Subject", |s| s.as_ref())
+            )
+        }))
+        .with_spacer(5.0)
+        .with_child(
+            Flex::row()
+                .with_child(Label::new("Start").fix_width(80.0))
+                .with_flex_child(
+                    TextBox::new()
+                        .with_placeholder("YYYY-MM-DD HH:MM")
+                        .lens(ManualEntry::start)
+                        .expand_width(),
+                    1.0,
+                ),
+        )
+        .with_spacer(3.0)
+        .with_child(
+            Flex::row()
+                .with_child(Label::new("Minutes").fix_width(80.0))
+                .with_flex_child(
+                    TextBox::new().lens(ManualEntry::minutes).expand_width(),
+                    1.0,
+                ),
+        )
+        .with_spacer(3.0)
+        .with_child(
+            TextBox::multiline()
+                .with_placeholder("Note (Markdown)")
+                .lens(ManualEntry::note)
+                .expand_width()
+                .fix_height(60.0),
+        )
+        .with_spacer(5.0)
+        .with_child(Label::dynamic(|entry: &ManualEntry, _| {
+            entry.status.clone()
+        }))
+}
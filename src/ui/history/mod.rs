@@ -0,0 +1,327 @@
+use druid::{
+    widget::{Button, CrossAxisAlignment, Either, Flex, Label, List, Scroll, SizedBox, TextBox},
+    Command, Data, EventCtx, Lens, LensExt, Selector, Target, Widget, WidgetExt,
+};
+use im::Vector;
+
+use crate::{
+    controller::{backend_msg, CommandReceiver, EnterController},
+    platform,
+    state::{
+        collation,
+        entry::ManualEntry,
+        history::{Edit, SessionQuery},
+        AppState, Session, Setup,
+    },
+    ui,
+    widgets::{color_dot, truncated, Markdown, Maybe, Note, OPEN_URL},
+};
+
+const EDIT_SESSION: Selector<Session> = Selector::new("zeitig.history.edit_session");
+const DELETE_SESSION: Selector<Session> = Selector::new("zeitig.history.delete_session");
+const CONFIRM_DELETE: Selector<Session> = Selector::new("zeitig.history.confirm_delete");
+const CANCEL_DELETE: Selector = Selector::new("zeitig.history.cancel_delete");
+
+/// A recorded session and whether it belongs to the locked billing period,
+/// or cannot be changed in read-only mode.
+#[derive(Clone, Data, Lens)]
+struct Row {
+    session: Session,
+    locked: bool,
+    color: Option<u32>,
+    /// Waiting for its deletion to be confirmed.
+    deleting: bool,
+}
+
+/// Read-only view of the history matching the search, most recent session first.
+struct Recent;
+
+impl Lens<AppState, Vector<Row>> for Recent {
+    fn with<V, F: FnOnce(&Vector<Row>) -> V>(&self, data: &AppState, f: F) -> V {
+        f(&recent(data))
+    }
+
+    fn with_mut<V, F: FnOnce(&mut Vector<Row>) -> V>(&self, data: &mut AppState, f: F) -> V {
+        f(&mut recent(data))
+    }
+}
+
+fn recent(data: &AppState) -> Vector<Row> {
+    let search = data.setup.history_search.trim();
+    let sessions = match &data.setup.found {
+        Some(found) => found.iter(),
+        None => data.history.iter(),
+    };
+    sessions
+        .rev()
+        .filter(|session| search.is_empty() || matches(session, search))
+        .map(|session| Row {
+            session: session.clone(),
+            locked: data.read_only || data.content.is_locked(&session.key()),
+            color: session.topic.color(data.content.settings.color_by_action),
+            deleting: data.setup.deleting.as_ref() == Some(session),
+        })
+        .collect()
+}
+
+/// Names match fuzzily and regardless of accents, notes are too long for that
+/// and have to contain the search.
+fn matches(session: &Session, search: &str) -> bool {
+    let note = session.note.as_deref().unwrap_or_default();
+    collation::fold(note).contains(&collation::fold(search))
+        || [
+            session.topic.action.as_ref(),
+            session.topic.subject.as_ref(),
+        ]
+        .iter()
+        .copied()
+        .chain(session.tags.iter().map(|tag| tag.as_ref()))
+        .any(|name| collation::fuzzy_score(search, name).is_some())
+}
+
+/// Read-only view of the note of a session, linked like its subject.
+struct SessionNote;
+
+impl Lens<Session, Option<Note>> for SessionNote {
+    fn with<V, F: FnOnce(&Option<Note>) -> V>(&self, data: &Session, f: F) -> V {
+        f(&note_of(data))
+    }
+
+    fn with_mut<V, F: FnOnce(&mut Option<Note>) -> V>(&self, data: &mut Session, f: F) -> V {
+        f(&mut note_of(data))
+    }
+}
+
+fn note_of(session: &Session) -> Option<Note> {
+    session.note.clone().map(|text| Note {
+        text,
+        issue_url: session.topic.subject.issue_url.clone(),
+    })
+}
+
+/// Replaces a recorded session, or deletes it if `changed` is `None`.
+/// In strict mode the change is kept as an amendment instead.
+/// Returns the session as it is recorded now.
+pub fn change_session(
+    ctx: &mut EventCtx,
+    data: &mut AppState,
+    original: Session,
+    changed: Option<Session>,
+) -> Option<Session> {
+    let strict = data.content.settings.strict_sessions;
+    let changed = changed.map(|mut session| {
+        session.original_start = if strict { Some(original.key()) } else { None };
+        session
+    });
+    data.amend_session(&original, changed.clone());
+    if let Some(found) = data.setup.found.as_mut() {
+        found.retain(|session| *session != original);
+        found.extend(changed.clone());
+    }
+    let command = match (strict, changed.clone()) {
+        (true, changed) => backend_msg::AMEND_SESSION.with((original, changed)),
+        (false, Some(changed)) => backend_msg::UPDATE_SESSION.with((original, changed)),
+        (false, None) => backend_msg::DELETE_SESSION.with(original),
+    };
+    ctx.submit_command(command.to(Target::Global));
+    changed
+}
+
+fn delete_session(ctx: &mut EventCtx, data: &mut AppState, session: &Session) {
+    change_session(ctx, data, session.clone(), None);
+    data.undo.push(Edit::DeleteSession(session.clone()));
+}
+
+fn handle_command(ctx: &mut EventCtx, data: &mut AppState, cmd: &Command) {
+    if let Some(session) = cmd.get(EDIT_SESSION) {
+        data.entry = Some(ManualEntry::edit(session));
+        ctx.new_window(ui::entry::window());
+    }
+    if let Some(session) = cmd.get(DELETE_SESSION) {
+        match data.content.settings.confirm_delete_session {
+            true => data.setup.deleting = Some(session.clone()),
+            false => delete_session(ctx, data, session),
+        }
+    }
+    if let Some(session) = cmd.get(CONFIRM_DELETE) {
+        data.setup.deleting = None;
+        delete_session(ctx, data, session);
+    }
+    if cmd.is(CANCEL_DELETE) {
+        data.setup.deleting = None;
+    }
+    if let Some(found) = cmd.get(backend_msg::SESSIONS_FOUND) {
+        data.setup.found = Some(found.iter().cloned().collect());
+    }
+    if let Some(url) = cmd.get(OPEN_URL) {
+        platform::open_url(url);
+    }
+}
+
+pub fn ui() -> impl Widget<AppState> {
+    Flex::column()
+        .with_child(
+            TextBox::new()
+                .with_placeholder("Search notes, topics and tags")
+                .expand_width()
+                .lens(AppState::setup.then(Setup::history_search))
+                .padding(5.0),
+        )
+        .with_child(duration_bounds().lens(AppState::setup))
+        .with_flex_child(
+            Scroll::new(List::new(session).lens(Recent).padding(5.0))
+                .vertical()
+                .expand_width(),
+            1.0,
+        )
+        .with_child(Either::new(
+            |data: &AppState, _| data.history.is_complete() || data.setup.found.is_some(),
+            SizedBox::empty(),
+            Button::new("Load older sessions")
+                .on_click(|ctx, data: &mut AppState, _| {
+                    let before = data.history.oldest().map(Session::key);
+                    ctx.submit_command(
+                        backend_msg::LOAD_OLDER_SESSIONS
+                            .with(before)
+                            .to(Target::Global),
+                    );
+                })
+                .padding(5.0),
+        ))
+        .with_child(Either::new(
+            |data: &AppState, _| data.read_only,
+            SizedBox::empty(),
+            Button::new("Add session")
+                .on_click(|ctx, data: &mut AppState, _| {
+                    data.entry = Some(ManualEntry::generate(data));
+                    ctx.new_window(ui::entry::window());
+                })
+                .padding(5.0),
+        ))
+        .env_scope(ui::apply_settings)
+        .controller(CommandReceiver::new(handle_command))
+}
+
+/// Looks for sessions by their duration in the whole history, or goes back
+/// to the recent history once both bounds are cleared.
+fn find_sessions(ctx: &mut EventCtx, setup: &mut Setup) {
+    match SessionQuery::parse(&setup.longer_than, &setup.shorter_than) {
+        Ok(query) if query.is_empty() => setup.found = None,
+        Ok(query) => ctx.submit_command(backend_msg::FIND_SESSIONS.with(query).to(Target::Global)),
+        Err(_) => {}
+    }
+}
+
+fn duration_bounds() -> impl Widget<Setup> {
+    fn bound(placeholder: &str, lens: impl Lens<Setup, String> + 'static) -> impl Widget<Setup> {
+        TextBox::new()
+            .with_placeholder(placeholder)
+            .fix_width(60.0)
+            .lens(lens)
+            .controller(EnterController::new(find_sessions))
+    }
+    Flex::column()
+        .with_child(
+            Flex::row()
+                .with_child(Label::new("Longer than"))
+                .with_spacer(3.0)
+                .with_child(bound("3h", Setup::longer_than))
+                .with_spacer(5.0)
+                .with_child(Label::new("shorter than"))
+                .with_spacer(3.0)
+                .with_child(bound("5m", Setup::shorter_than))
+                .with_spacer(5.0)
+                .with_child(
+                    Button::new("Find").on_click(|ctx, setup, _| find_sessions(ctx, setup)),
+                ),
+        )
+        .with_child(Label::dynamic(|setup: &Setup, _| {
+            SessionQuery::parse(&setup.longer_than, &setup.shorter_than)
+                .err()
+                .unwrap_or_default()
+        }))
+        .padding((5.0, 0.0))
+}
+
+fn session() -> impl Widget<Row> {
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Flex::row()
+                .with_child(color_dot().lens(Row::color))
+                .with_spacer(3.0)
+                .with_flex_child(
+                    truncated(|row: &Row, _| {
+                        format!(
+                            "{} {}",
+                            row.session.topic.action.as_ref(),
+                            row.session.topic.subject.as_ref()
+                        )
+                    })
+                    .expand_width(),
+                    1.0,
+                )
+                .with_child(Either::new(
+                    |row: &Row, _| row.locked,
+                    Label::new("Locked"),
+                    Either::new(
+                        |row: &Row, _| row.deleting,
+                        confirm_delete(),
+                        edit_buttons(),
+                    ),
+                )),
+        )
+        .with_child(
+            Label::dynamic(|session: &Session, _| {
+                let tags: String = session
+                    .tags
+                    .iter()
+                    .map(|tag| format!(" #{}", tag.name))
+                    .collect();
+                format!(
+                    "    {} - {} ({}){}",
+                    session.started.format("%Y-%m-%d %H:%M"),
+                    session.ended.format("%H:%M"),
+                    session.duration(),
+                    tags
+                )
+            })
+            .lens(Row::session),
+        )
+        .with_child(
+            Maybe::or_empty(Markdown::new().padding((20.0, 0.0, 0.0, 0.0)))
+                .lens(Row::session.then(SessionNote)),
+        )
+        .padding((0.0, 0.0, 0.0, 5.0))
+}
+
+fn edit_buttons() -> impl Widget<Row> {
+    Flex::row()
+        .with_child(
+            Button::new("Edit").on_click(|ctx, session: &mut Session, _| {
+                ctx.submit_command(EDIT_SESSION.with(session.clone()))
+            }),
+        )
+        .with_spacer(3.0)
+        .with_child(
+            Button::new("Delete").on_click(|ctx, session: &mut Session, _| {
+                ctx.submit_command(DELETE_SESSION.with(session.clone()))
+            }),
+        )
+        .lens(Row::session)
+}
+
+/// Asks before deleting, if the settings say so.
+fn confirm_delete() -> impl Widget<Row> {
+    Flex::row()
+        .with_child(Label::new("Delete?"))
+        .with_spacer(3.0)
+        .with_child(
+            Button::new("Yes").on_click(|ctx, session: &mut Session, _| {
+                ctx.submit_command(CONFIRM_DELETE.with(session.clone()))
+            }),
+        )
+        .with_spacer(3.0)
+        .with_child(Button::new("No").on_click(|ctx, _, _| ctx.submit_command(CANCEL_DELETE)))
+        .lens(Row::session)
+}
@@ -1,23 +1,207 @@
 use druid::{
-    widget::{CrossAxisAlignment, Flex, Label, List, Scroll},
-    Widget, WidgetExt,
+    widget::{Button, Checkbox, CrossAxisAlignment, Flex, Label, List, Scroll},
+    Env, EventCtx, Lens, Widget, WidgetExt,
 };
+use im::Vector;
+use std::{io, path::PathBuf};
 
 use crate::{
     state::{
-        insights::{Insights, Summary, Week},
-        AppState,
+        export,
+        insights::{
+            ChecklistSummary, ContextSummary, GroupTotal, Insights, InterruptionSummary, Summary,
+            Week,
+        },
+        totals::{Dimension, Grouping},
+        AppState, Date,
     },
-    widgets::Maybe,
+    widgets::{color_dot, heatmap, week_timeline, Maybe, TimelineBlock},
 };
 
-#[allow(dead_code)]
 pub fn ui() -> impl Widget<AppState> {
-    Maybe::or_empty(inner_ui()).lens(AppState::insights)
+    Flex::column()
+        .with_flex_child(Maybe::or_empty(inner_ui()).lens(AppState::insights), 1.0)
+        .with_child(exports())
+}
+
+fn exports() -> impl Widget<AppState> {
+    Flex::row()
+        .with_child(
+            Button::new("Export by category").on_click(|_, data: &mut AppState, _| {
+                let sign = data.content.settings.sign_exports;
+                let content = &data.content;
+                let result = export::full_history(content).and_then(|history| {
+                    export::to_file("categories", "csv", sign, |out| {
+                        export::categories_csv(content, &history, out)
+                    })
+                });
+                show_export_result(data, result);
+            }),
+        )
+        .with_spacer(5.0)
+        .with_child(
+            Button::new("Export per day").on_click(|_, data: &mut AppState, _| {
+                let settings = &data.content.settings;
+                let result = export::full_history(&data.content).and_then(|history| {
+                    export::to_file("daily", "csv", settings.sign_exports, |out| {
+                        export::daily_csv(&history, settings, out)
+                    })
+                });
+                show_export_result(data, result);
+            }),
+        )
+        .with_spacer(5.0)
+        .with_child(
+            Button::new("Export calendar").on_click(|_, data: &mut AppState, _| {
+                let sign = data.content.settings.sign_exports;
+                let result = export::full_history(&data.content).and_then(|history| {
+                    export::to_file("sessions", "ics", sign, |out| {
+                        export::calendar_ics(&history, out)
+                    })
+                });
+                show_export_result(data, result);
+            }),
+        )
+        .with_spacer(5.0)
+        .with_flex_child(
+            Label::dynamic(|data: &AppState, _| {
+                data.insights
+                    .as_ref()
+                    .map(|insights| insights.status.clone())
+                    .unwrap_or_default()
+            }),
+            1.0,
+        )
+        .padding(5.0)
+}
+
+fn show_export_result(data: &mut AppState, result: io::Result<PathBuf>) {
+    let status = match result {
+        Ok(path) => format!("Exported to {}", path.display()),
+        Err(err) => {
+            log::error!("Export failed: {}", err);
+            format!("Export failed: {}", err)
+        }
+    };
+    if let Some(insights) = data.insights.as_mut() {
+        insights.status = status;
+    }
 }
 
 fn inner_ui() -> impl Widget<Insights> {
-    Scroll::new(List::new(|| {
+    Scroll::new(
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(
+                Label::dynamic(|days: &Vector<(u32, Option<u32>)>, _| {
+                    let tracked = days.iter().filter(|(minutes, _)| *minutes > 0).count();
+                    format!("Last year, tracked on {} days", tracked)
+                })
+                .lens(Insights::days),
+            )
+            .with_spacer(3.0)
+            .with_child(heatmap().lens(Insights::days))
+            .with_spacer(10.0)
+            .with_child(timeline())
+            .with_spacer(10.0)
+            .with_child(weeks())
+            .with_child(Label::new("Interruptions"))
+            .with_spacer(3.0)
+            .with_child(interruptions())
+            .with_spacer(10.0)
+            .with_child(Label::new("Checklists"))
+            .with_spacer(3.0)
+            .with_child(checklists())
+            .with_spacer(10.0)
+            .with_child(Label::new("All time"))
+            .with_spacer(3.0)
+            .with_child(totals()),
+    )
+    .vertical()
+}
+
+fn timeline() -> impl Widget<Insights> {
+    let week = |weeks: i64| {
+        move |_: &mut EventCtx, insights: &mut Insights, _: &Env| {
+            let begin = *insights.timeline_week + time::Duration::weeks(weeks);
+            insights.timeline_week = Date::from(begin);
+        }
+    };
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Flex::row()
+                .with_child(Button::new("<").on_click(week(-1)))
+                .with_spacer(5.0)
+                .with_child(Label::dynamic(|insights: &Insights, _| {
+                    format!("Week {}", *insights.timeline_week)
+                }))
+                .with_spacer(5.0)
+                .with_child(Button::new(">").on_click(week(1))),
+        )
+        .with_spacer(3.0)
+        .with_child(week_timeline().lens(WeekBlocks))
+}
+
+/// Read-only view of the stretches worked in the week shown on the timeline.
+struct WeekBlocks;
+
+impl Lens<Insights, Vector<TimelineBlock>> for WeekBlocks {
+    fn with<V, F: FnOnce(&Vector<TimelineBlock>) -> V>(&self, data: &Insights, f: F) -> V {
+        f(&week_blocks(data))
+    }
+
+    fn with_mut<V, F: FnOnce(&mut Vector<TimelineBlock>) -> V>(
+        &self,
+        data: &mut Insights,
+        f: F,
+    ) -> V {
+        f(&mut week_blocks(data))
+    }
+}
+
+fn week_blocks(insights: &Insights) -> Vector<TimelineBlock> {
+    let offset = time::UtcOffset::current_local_offset();
+    let monday = *insights.timeline_week;
+    let begin = monday.midnight().assume_offset(offset);
+    let end = begin + time::Duration::weeks(1);
+    let hours = |duration: time::Duration| duration.as_seconds_f64() / 3600.0;
+    let mut blocks = Vector::new();
+    for session in &insights.sessions {
+        if *session.ended <= begin || *session.started >= end {
+            continue;
+        }
+        for interval in &session.worked() {
+            let mut from = interval.started.to_offset(offset).max(begin);
+            let until = interval.ended.to_offset(offset).min(end);
+            // Split at midnight, so each block stays within its day.
+            while from < until {
+                let midnight = from.date().midnight().assume_offset(offset);
+                let to = until.min(from.date().next_day().midnight().assume_offset(offset));
+                let topic = &session.topic;
+                blocks.push_back(TimelineBlock {
+                    day: (from.date() - monday).whole_days() as usize,
+                    start: hours(from - midnight),
+                    end: hours(to - midnight),
+                    color: topic.color(insights.color_by_action),
+                    label: format!(
+                        "{} {}, {} - {}",
+                        topic.action.as_ref(),
+                        topic.subject.as_ref(),
+                        from.format("%H:%M"),
+                        to.format("%H:%M")
+                    )
+                    .into(),
+                });
+                from = to;
+            }
+        }
+    }
+    blocks
+}
+
+fn weeks() -> impl Widget<Insights> {
+    List::new(|| {
         Flex::column()
             .cross_axis_alignment(CrossAxisAlignment::Start)
             .with_child(Label::dynamic(|week: &Week, _| {
@@ -26,18 +210,108 @@ fn inner_ui() -> impl Widget<Insights> {
             .with_spacer(3.0)
             .with_child(
                 List::new(|| {
-                    Label::dynamic(|sum: &Summary, _| {
-                        format!(
-                            "    {} {}: {}",
-                            sum.topic.action.as_ref(),
-                            sum.topic.subject.as_ref(),
-                            sum.spent_time
-                        )
-                    })
+                    Flex::row()
+                        .with_spacer(10.0)
+                        .with_child(color_dot().lens(Summary::color))
+                        .with_spacer(3.0)
+                        .with_child(Label::dynamic(|sum: &Summary, _| {
+                            format!(
+                                "{} {}: {}",
+                                sum.topic.action.as_ref(),
+                                sum.topic.subject.as_ref(),
+                                sum.spent_time
+                            )
+                        }))
                 })
                 .lens(Week::entries),
             )
+            .with_child(
+                List::new(|| {
+                    Label::dynamic(|sum: &ContextSummary, _| {
+                        format!("    @ {}: {}", sum.context, sum.spent_time)
+                    })
+                })
+                .lens(Week::contexts),
+            )
             .with_spacer(10.0)
-    }))
+    })
     .lens(Insights::weeks)
 }
+
+fn interruptions() -> impl Widget<Insights> {
+    List::new(|| {
+        Label::dynamic(|sum: &InterruptionSummary, _| {
+            format!("    {} {}: {}", *sum.date, sum.subject.as_ref(), sum.count)
+        })
+    })
+    .lens(Insights::interruptions)
+}
+
+fn checklists() -> impl Widget<Insights> {
+    List::new(|| {
+        Label::dynamic(|sum: &ChecklistSummary, _| {
+            format!(
+                "    {}: {}/{} done",
+                sum.subject.as_ref(),
+                sum.done,
+                sum.total
+            )
+        })
+    })
+    .lens(Insights::checklists)
+}
+
+/// Whether a dimension is part of the grouping, never leaving it empty.
+struct Includes(Dimension);
+
+impl Lens<Grouping, bool> for Includes {
+    fn with<V, F: FnOnce(&bool) -> V>(&self, data: &Grouping, f: F) -> V {
+        f(&data.contains(self.0))
+    }
+
+    fn with_mut<V, F: FnOnce(&mut bool) -> V>(&self, data: &mut Grouping, f: F) -> V {
+        let mut included = data.contains(self.0);
+        let value = f(&mut included);
+        let grouping = data.with(self.0, included);
+        if grouping.bits() != 0 {
+            *data = grouping;
+        }
+        value
+    }
+}
+
+/// Read-only view of the totals for the selected grouping.
+struct GroupedTotals;
+
+impl Lens<Insights, Vector<GroupTotal>> for GroupedTotals {
+    fn with<V, F: FnOnce(&Vector<GroupTotal>) -> V>(&self, data: &Insights, f: F) -> V {
+        f(&data.grouped_totals())
+    }
+
+    fn with_mut<V, F: FnOnce(&mut Vector<GroupTotal>) -> V>(&self, data: &mut Insights, f: F) -> V {
+        f(&mut data.grouped_totals())
+    }
+}
+
+fn totals() -> impl Widget<Insights> {
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Flex::row()
+                .with_child(Checkbox::new("Action").lens(Includes(Dimension::Action)))
+                .with_spacer(5.0)
+                .with_child(Checkbox::new("Subject").lens(Includes(Dimension::Subject)))
+                .with_spacer(5.0)
+                .with_child(Checkbox::new("Context").lens(Includes(Dimension::Context)))
+                .lens(Insights::grouping),
+        )
+        .with_spacer(3.0)
+        .with_child(
+            List::new(|| {
+                Label::dynamic(|total: &GroupTotal, _| {
+                    format!("    {}: {}", total.label, total.spent_time)
+                })
+            })
+            .lens(GroupedTotals),
+        )
+}
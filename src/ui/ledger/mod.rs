@@ -0,0 +1,220 @@
+use druid::{
+    widget::{Button, CrossAxisAlignment, Flex, Label, List, Scroll, TextBox},
+    Command, EventCtx, LensExt, Selector, Widget, WidgetExt,
+};
+
+use crate::{
+    controller::{backend_msg, CommandReceiver},
+    state::{
+        ledger::{self, Adjustment, DayOff, DayOffKind, Ledger, LedgerWeek},
+        AppState, Content, Date, TimeFormat,
+    },
+    ui,
+    widgets::Maybe,
+};
+
+const ADD_DAY_OFF: Selector<DayOffKind> = Selector::new("zeitig.ledger.add_day_off");
+const REMOVE_DAY_OFF: Selector<Date> = Selector::new("zeitig.ledger.remove_day_off");
+const ADD_ADJUSTMENT: Selector = Selector::new("zeitig.ledger.add_adjustment");
+const REMOVE_ADJUSTMENT: Selector<Adjustment> = Selector::new("zeitig.ledger.remove_adjustment");
+
+/// Adds up the weeks again and takes over the balance they carry into this one.
+fn refresh(data: &mut AppState) {
+    if let Some(ledger) = data.ledger.as_mut() {
+        ledger.refresh(&data.content);
+        data.setup.carryover = ledger.carryover(&data.content);
+    }
+}
+
+fn add_day_off(ctx: &mut EventCtx, data: &mut AppState, kind: DayOffKind) {
+    let day = match data.ledger.as_ref().map(Ledger::parse_day) {
+        Some(Ok(day)) => day,
+        Some(Err(status)) => return data.ledger.as_mut().unwrap().status = status,
+        None => return,
+    };
+    let days_off = &mut data.content.days_off;
+    days_off.retain(|off| off.day != day);
+    days_off.push_back(DayOff { day, kind });
+    days_off.sort_by(|a, b| a.day.cmp(&b.day));
+    ctx.submit_command(backend_msg::SET_DAYS_OFF.with(days_off.clone()));
+    refresh(data);
+}
+
+fn add_adjustment(ctx: &mut EventCtx, data: &mut AppState) {
+    let adjustment = match data.ledger.as_ref().map(Ledger::to_adjustment) {
+        Some(Ok(adjustment)) => adjustment,
+        Some(Err(status)) => return data.ledger.as_mut().unwrap().status = status,
+        None => return,
+    };
+    let adjustments = &mut data.content.adjustments;
+    adjustments.push_back(adjustment);
+    adjustments.sort_by(|a, b| a.day.cmp(&b.day));
+    ctx.submit_command(backend_msg::SET_ADJUSTMENTS.with(adjustments.clone()));
+    if let Some(ledger) = data.ledger.as_mut() {
+        ledger.amount.clear();
+        ledger.note.clear();
+        ledger.status.clear();
+    }
+    refresh(data);
+}
+
+fn handle_command(ctx: &mut EventCtx, data: &mut AppState, cmd: &Command) {
+    if let Some(kind) = cmd.get(ADD_DAY_OFF) {
+        add_day_off(ctx, data, *kind);
+    }
+    if let Some(day) = cmd.get(REMOVE_DAY_OFF) {
+        data.content.days_off.retain(|off| off.day != *day);
+        ctx.submit_command(backend_msg::SET_DAYS_OFF.with(data.content.days_off.clone()));
+        refresh(data);
+    }
+    if cmd.is(ADD_ADJUSTMENT) {
+        add_adjustment(ctx, data);
+    }
+    if let Some(adjustment) = cmd.get(REMOVE_ADJUSTMENT) {
+        data.content
+            .adjustments
+            .retain(|listed| listed != adjustment);
+        ctx.submit_command(backend_msg::SET_ADJUSTMENTS.with(data.content.adjustments.clone()));
+        refresh(data);
+    }
+}
+
+pub fn ui() -> impl Widget<AppState> {
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Label::dynamic(|data: &AppState, _| {
+            match data.overtime_balance() {
+                Some(balance) => format!("Balance: {}", ledger::format_balance(balance)),
+                None => String::new(),
+            }
+        }))
+        .with_spacer(5.0)
+        .with_child(inputs().lens(AppState::ledger))
+        .with_spacer(5.0)
+        .with_flex_child(
+            Scroll::new(
+                Flex::column()
+                    .cross_axis_alignment(CrossAxisAlignment::Start)
+                    .with_child(Label::new("Days off"))
+                    .with_child(List::new(day_off).lens(AppState::content.then(Content::days_off)))
+                    .with_spacer(5.0)
+                    .with_child(Label::new("Adjustments"))
+                    .with_child(
+                        List::new(adjustment).lens(AppState::content.then(Content::adjustments)),
+                    )
+                    .with_spacer(5.0)
+                    .with_child(Label::new("Weeks"))
+                    .with_child(
+                        Maybe::or_empty(List::new(week).lens(Ledger::weeks)).lens(AppState::ledger),
+                    )
+                    .expand_width(),
+            )
+            .vertical()
+            .expand(),
+            1.0,
+        )
+        .with_child(
+            Maybe::or_empty(Label::dynamic(|ledger: &Ledger, _| ledger.status.clone()))
+                .lens(AppState::ledger),
+        )
+        .padding(5.0)
+        .env_scope(ui::apply_settings)
+        .controller(CommandReceiver::new(handle_command))
+}
+
+fn inputs() -> impl Widget<Option<Ledger>> {
+    Maybe::or_empty(
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(
+                Flex::row()
+                    .with_child(Label::new("On"))
+                    .with_spacer(3.0)
+                    .with_child(
+                        TextBox::new()
+                            .with_placeholder("YYYY-MM-DD")
+                            .fix_width(110.0)
+                            .lens(Ledger::day),
+                    )
+                    .with_spacer(5.0)
+                    .with_child(Button::new("Holiday").on_click(|ctx, _, _| {
+                        ctx.submit_command(ADD_DAY_OFF.with(DayOffKind::Holiday))
+                    }))
+                    .with_spacer(3.0)
+                    .with_child(Button::new("Vacation").on_click(|ctx, _, _| {
+                        ctx.submit_command(ADD_DAY_OFF.with(DayOffKind::Vacation))
+                    })),
+            )
+            .with_spacer(5.0)
+            .with_child(
+                Flex::row()
+                    .with_child(
+                        TextBox::new()
+                            .with_placeholder("+2h")
+                            .fix_width(60.0)
+                            .lens(Ledger::amount),
+                    )
+                    .with_spacer(3.0)
+                    .with_flex_child(
+                        TextBox::new()
+                            .with_placeholder("Note")
+                            .expand_width()
+                            .lens(Ledger::note),
+                        1.0,
+                    )
+                    .with_spacer(3.0)
+                    .with_child(
+                        Button::new("Adjust")
+                            .on_click(|ctx, _, _| ctx.submit_command(ADD_ADJUSTMENT)),
+                    ),
+            ),
+    )
+}
+
+fn day_off() -> impl Widget<DayOff> {
+    Flex::row()
+        .with_child(Label::dynamic(|off: &DayOff, _| {
+            let kind = match off.kind {
+                DayOffKind::Holiday => "Holiday",
+                DayOffKind::Vacation => "Vacation",
+            };
+            format!("{} {}", off.day.format("%Y-%m-%d"), kind)
+        }))
+        .with_spacer(5.0)
+        .with_child(
+            Button::new("Remove").on_click(|ctx, off: &mut DayOff, _| {
+                ctx.submit_command(REMOVE_DAY_OFF.with(off.day))
+            }),
+        )
+}
+
+fn adjustment() -> impl Widget<Adjustment> {
+    Flex::row()
+        .with_child(Label::dynamic(|adjustment: &Adjustment, _| {
+            format!(
+                "{} {} {}",
+                adjustment.day.format("%Y-%m-%d"),
+                ledger::format_balance(adjustment.seconds),
+                adjustment.note
+            )
+        }))
+        .with_spacer(5.0)
+        .with_child(
+            Button::new("Remove").on_click(|ctx, adjustment: &mut Adjustment, _| {
+                ctx.submit_command(REMOVE_ADJUSTMENT.with(adjustment.clone()))
+            }),
+        )
+}
+
+fn week() -> impl Widget<LedgerWeek> {
+    Label::dynamic(|week: &LedgerWeek, _| {
+        format!(
+            "Week of {}: {} of {}, {}, balance {}",
+            week.begin.format("%Y-%m-%d"),
+            week.worked.format(TimeFormat::Short),
+            week.target.format(TimeFormat::Short),
+            ledger::format_balance(week.change),
+            ledger::format_balance(week.balance)
+        )
+    })
+}
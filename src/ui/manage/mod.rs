@@ -0,0 +1,1202 @@
+use druid::{
+    widget::{Button, Checkbox, CrossAxisAlignment, Flex, Label, List, Scroll, Stepper, TextBox},
+    Command, Data, EventCtx, LensExt, Selector, Target, Widget, WidgetExt,
+};
+use im::Vector;
+use std::sync::Arc;
+
+use crate::{
+    controller::{backend_msg, CommandReceiver, ImportKind, IMPORT},
+    state::{
+        billing, export,
+        launch::{self, LaunchRule},
+        manage::{
+            AliasEntry, BulkRename, Manage, Renamed, SplitPart, SplitRule, SplitSubject,
+            SubjectEntry, TrimCandidate, TrimSessions, LOCK_FORMAT,
+        },
+        template::Template,
+        Action, Alias, AliasTarget, AppState, Date, Settings, Subject, Topic,
+    },
+    widgets::Maybe,
+};
+
+const LOCK: Selector = Selector::new("zeitig.manage.lock");
+const UNLOCK: Selector = Selector::new("zeitig.manage.unlock");
+const PREVIEW_RENAME: Selector = Selector::new("zeitig.manage.preview_rename");
+const APPLY_RENAME: Selector = Selector::new("zeitig.manage.apply_rename");
+const PREVIEW_SPLIT: Selector = Selector::new("zeitig.manage.preview_split");
+const APPLY_SPLIT: Selector = Selector::new("zeitig.manage.apply_split");
+const FIND_LONG_SESSIONS: Selector = Selector::new("zeitig.manage.find_long_sessions");
+const TRIM_SESSIONS: Selector = Selector::new("zeitig.manage.trim_sessions");
+const ADD_ACTION_ALIAS: Selector = Selector::new("zeitig.manage.add_action_alias");
+const ADD_SUBJECT_ALIAS: Selector = Selector::new("zeitig.manage.add_subject_alias");
+const REMOVE_ALIAS: Selector<Alias> = Selector::new("zeitig.manage.remove_alias");
+const ADD_LAUNCH_RULE: Selector = Selector::new("zeitig.manage.add_launch_rule");
+const REMOVE_LAUNCH_RULE: Selector<LaunchRule> = Selector::new("zeitig.manage.remove_launch_rule");
+
+pub fn ui() -> impl Widget<AppState> {
+    Flex::column()
+        .with_flex_child(Maybe::or_empty(inner_ui()).lens(AppState::manage), 1.0)
+        .with_child(
+            Flex::row()
+                .with_child(
+                    Button::new("Save").on_click(|ctx, data: &mut AppState, _| save(ctx, data)),
+                )
+                .with_spacer(5.0)
+                .with_child(
+                    Button::new("Export template")
+                        .on_click(|_, data: &mut AppState, _| export_template(data)),
+                )
+                .with_spacer(5.0)
+                .with_child(Button::new("Import template").on_click(|ctx, _, _| {
+                    ctx.submit_command(IMPORT.with(ImportKind::Template).to(Target::Global))
+                }))
+                .padding(5.0),
+        )
+        .with_child(
+            Label::dynamic(|data: &AppState, _| {
+                data.manage
+                    .as_ref()
+                    .map(|manage| manage.status.clone())
+                    .unwrap_or_default()
+            })
+            .padding((5.0, 0.0, 5.0, 5.0)),
+        )
+        .controller(CommandReceiver::new(handle_command))
+}
+
+fn handle_command(ctx: &mut EventCtx, data: &mut AppState, cmd: &Command) {
+    if cmd.is(PREVIEW_RENAME) {
+        preview_rename(data);
+        return;
+    }
+    if cmd.is(APPLY_RENAME) {
+        apply_rename(ctx, data);
+        return;
+    }
+    if cmd.is(PREVIEW_SPLIT) || cmd.is(APPLY_SPLIT) {
+        split_subject(ctx, data, cmd.is(APPLY_SPLIT));
+        return;
+    }
+    if cmd.is(FIND_LONG_SESSIONS) {
+        find_long_sessions(data);
+        return;
+    }
+    if cmd.is(TRIM_SESSIONS) {
+        trim_sessions(ctx, data);
+        return;
+    }
+    if cmd.is(ADD_ACTION_ALIAS) || cmd.is(ADD_SUBJECT_ALIAS) {
+        add_alias(ctx, data, cmd.is(ADD_ACTION_ALIAS));
+        return;
+    }
+    if let Some(alias) = cmd.get(REMOVE_ALIAS) {
+        data.content.aliases.retain(|kept| kept != alias);
+        ctx.submit_command(
+            backend_msg::DELETE_ALIAS
+                .with(alias.clone())
+                .to(Target::Global),
+        );
+        if let Some(manage) = data.manage.as_mut() {
+            manage.aliases = AliasEntry::all(&data.content);
+        }
+        return;
+    }
+    if cmd.is(ADD_LAUNCH_RULE) {
+        add_launch_rule(ctx, data);
+        return;
+    }
+    if let Some(rule) = cmd.get(REMOVE_LAUNCH_RULE) {
+        data.content.launch_rules.retain(|kept| kept != rule);
+        set_launch_rules(ctx, data);
+        return;
+    }
+    let manage = match data.manage.as_mut() {
+        Some(manage) => manage,
+        None => return,
+    };
+    let today = Date::from(time::OffsetDateTime::now_local());
+    let before = if cmd.is(LOCK) {
+        match time::Date::parse(manage.lock_before.trim(), LOCK_FORMAT).map(Date::from) {
+            Ok(before) if before <= today => Some(before),
+            Ok(_) => {
+                manage.status = "Only periods that have ended can be locked.".to_string();
+                return;
+            }
+            Err(_) => {
+                manage.status = "Enter the date as YYYY-MM-DD.".to_string();
+                return;
+            }
+        }
+    } else if cmd.is(UNLOCK) && manage.locked_before.is_some() {
+        None
+    } else {
+        return;
+    };
+    manage.locked_before = before;
+    manage.status = match before {
+        Some(before) => format!("Sessions before {} are locked.", *before),
+        None => "Sessions are unlocked.".to_string(),
+    };
+    data.content.locked_before = before;
+    ctx.submit_command(
+        backend_msg::SET_LOCKED_BEFORE
+            .with(before)
+            .to(Target::Global),
+    );
+}
+
+fn preview_rename(data: &mut AppState) {
+    let manage = match data.manage.as_mut() {
+        Some(manage) => manage,
+        None => return,
+    };
+    match manage.bulk_rename.renames(&data.content) {
+        Ok(renames) => {
+            manage.status = match renames.len() {
+                0 => "No names match the pattern.".to_string(),
+                count => format!("{} names would be changed.", count),
+            };
+            manage.bulk_rename.preview = renames;
+        }
+        Err(err) => {
+            manage.status = err;
+            manage.bulk_rename.preview.clear();
+        }
+    }
+}
+
+/// Renames everything the preview shows, unless one of the new names is
+/// empty or taken.
+fn apply_rename(ctx: &mut EventCtx, data: &mut AppState) {
+    let renames = match data
+        .manage
+        .as_ref()
+        .map(|m| m.bulk_rename.renames(&data.content))
+    {
+        Some(Ok(renames)) => renames,
+        Some(Err(err)) => {
+            set_status(data, err);
+            return;
+        }
+        None => return,
+    };
+    if renames.iter().any(|renamed| renamed.conflict) {
+        preview_rename(data);
+        set_status(
+            data,
+            "Some names would be empty or taken, nothing was renamed.",
+        );
+        return;
+    }
+    for renamed in &renames {
+        if renamed.action {
+            let action = data.content.actions.iter().find(|a| a.id == renamed.id);
+            if let Some(action) = action.cloned() {
+                let action = Action {
+                    name: renamed.to.clone(),
+                    ..action
+                };
+                data.rename_action(&action);
+                ctx.submit_command(backend_msg::RENAME_ACTION.with(action).to(Target::Global));
+            }
+        } else {
+            let subject = data.content.subjects.iter().find(|s| s.id == renamed.id);
+            if let Some(subject) = subject.cloned() {
+                let subject = Subject {
+                    name: renamed.to.clone(),
+                    ..subject
+                };
+                data.rename_subject(&subject);
+                ctx.submit_command(backend_msg::RENAME_SUBJECT.with(subject).to(Target::Global));
+            }
+        }
+    }
+    if let Some(manage) = data.manage.as_mut() {
+        for entry in manage.subjects.iter_mut() {
+            if let Some(subject) = data.content.subjects.iter().find(|s| **s == entry.subject) {
+                entry.subject = subject.clone();
+            }
+        }
+        manage.bulk_rename.preview.clear();
+        manage.status = format!("Renamed {} names.", renames.len());
+    }
+}
+
+/// Shows what the split would move, or moves it. The whole history is
+/// looked at, not only the recent sessions.
+fn split_subject(ctx: &mut EventCtx, data: &mut AppState, apply: bool) {
+    let split = match data.manage.as_ref() {
+        Some(manage) => manage.split.clone(),
+        None => return,
+    };
+    let history = match export::full_history(&data.content) {
+        Ok(history) => history,
+        Err(err) => {
+            set_status(data, format!("Cannot load the sessions: {}", err));
+            return;
+        }
+    };
+    let moves = match split.moves(&data.content, &history) {
+        Ok(moves) => moves,
+        Err(err) => {
+            set_status(data, err);
+            return;
+        }
+    };
+    let manage = match data.manage.as_mut() {
+        Some(manage) => manage,
+        None => return,
+    };
+    if moves.is_empty() {
+        manage.status = "No sessions match the rules.".to_string();
+        manage.split.preview.clear();
+        return;
+    }
+    manage.split.preview = SplitSubject::parts(&data.content, &moves);
+    if apply {
+        manage.status = format!("Moving {} sessions.", moves.len());
+        ctx.submit_command(backend_msg::MOVE_SESSIONS.with(moves).to(Target::Global));
+    } else {
+        manage.status = format!("{} sessions would be moved.", moves.len());
+    }
+}
+
+/// Lists the sessions of the whole history longer than the threshold.
+fn find_long_sessions(data: &mut AppState) {
+    let history = match export::full_history(&data.content) {
+        Ok(history) => history,
+        Err(err) => {
+            set_status(data, format!("Cannot load the sessions: {}", err));
+            return;
+        }
+    };
+    let manage = match data.manage.as_mut() {
+        Some(manage) => manage,
+        None => return,
+    };
+    match manage.trim.long_sessions(&data.content, &history) {
+        Ok(candidates) => {
+            manage.status = format!("{} sessions are longer than that.", candidates.len());
+            manage.trim.candidates = candidates;
+        }
+        Err(err) => {
+            manage.status = err;
+            manage.trim.candidates.clear();
+        }
+    }
+}
+
+/// Trims every chosen session to its suggested end in one go.
+fn trim_sessions(ctx: &mut EventCtx, data: &mut AppState) {
+    let manage = match data.manage.as_mut() {
+        Some(manage) => manage,
+        None => return,
+    };
+    let replacements: Vector<_> = manage
+        .trim
+        .candidates
+        .iter()
+        .filter(|candidate| candidate.chosen)
+        .map(|candidate| (candidate.session.clone(), candidate.trimmed()))
+        .collect();
+    if replacements.is_empty() {
+        manage.status = "Choose the sessions to trim.".to_string();
+        return;
+    }
+    manage.status = format!("Trimming {} sessions.", replacements.len());
+    ctx.submit_command(
+        backend_msg::REPLACE_SESSIONS
+            .with(replacements)
+            .to(Target::Global),
+    );
+}
+
+/// Adds the alias typed for the action or subject of the typed name, unless
+/// it already names one.
+fn add_alias(ctx: &mut EventCtx, data: &mut AppState, for_action: bool) {
+    let manage = match data.manage.as_mut() {
+        Some(manage) => manage,
+        None => return,
+    };
+    let name = manage.alias_name.trim();
+    let stands_for = manage.alias_target.trim();
+    if name.is_empty() || stands_for.is_empty() {
+        manage.status = "Enter an alias and the name it stands for.".to_string();
+        return;
+    }
+    let content = &data.content;
+    let (target, taken) = if for_action {
+        let target = content
+            .action_named(stands_for)
+            .map(|action| (AliasTarget::Action(action.id), action.name.clone()));
+        (target, content.action_named(name).is_some())
+    } else {
+        let target = content
+            .subject_named(stands_for)
+            .map(|subject| (AliasTarget::Subject(subject.id), subject.name.clone()));
+        (target, content.subject_named(name).is_some())
+    };
+    let (target, target_name) = match (target, for_action) {
+        (Some(target), _) => target,
+        (None, true) => {
+            manage.status = format!("No action is named {}.", stands_for);
+            return;
+        }
+        (None, false) => {
+            manage.status = format!("No subject is named {}.", stands_for);
+            return;
+        }
+    };
+    if taken {
+        let kind = if for_action { "an action" } else { "a subject" };
+        manage.status = format!("{} already names {}.", name, kind);
+        return;
+    }
+    let alias = Alias {
+        name: name.into(),
+        target,
+    };
+    manage.status = format!("{} now stands for {}.", name, target_name);
+    manage.alias_name.clear();
+    manage.alias_target.clear();
+    data.content.aliases.push_back(alias.clone());
+    manage.aliases = AliasEntry::all(&data.content);
+    ctx.submit_command(backend_msg::ADD_ALIAS.with(alias).to(Target::Global));
+}
+
+/// Adds a rule for the typed process and topic, replacing the one the
+/// process had.
+fn add_launch_rule(ctx: &mut EventCtx, data: &mut AppState) {
+    let manage = match data.manage.as_mut() {
+        Some(manage) => manage,
+        None => return,
+    };
+    let process = manage.launch_process.trim();
+    if process.is_empty() {
+        manage.status = "Enter the name of the process to watch for.".to_string();
+        return;
+    }
+    let content = &data.content;
+    let action = match content.action_named(manage.launch_action.trim()) {
+        Some(action) => action.clone(),
+        None => {
+            manage.status = format!("No action is named {}.", manage.launch_action.trim());
+            return;
+        }
+    };
+    let subject = match content.subject_named(manage.launch_subject.trim()) {
+        Some(subject) => subject.clone(),
+        None => {
+            manage.status = format!("No subject is named {}.", manage.launch_subject.trim());
+            return;
+        }
+    };
+    let rule = LaunchRule {
+        process: process.into(),
+        topic: Topic { action, subject },
+    };
+    manage.status = format!(
+        "Launching {} offers to start {} {}.",
+        rule.process, rule.topic.action.name, rule.topic.subject.name
+    );
+    manage.launch_process.clear();
+    manage.launch_action.clear();
+    manage.launch_subject.clear();
+    let rules = &mut data.content.launch_rules;
+    rules.retain(|kept| !launch::same_process(&kept.process, &rule.process));
+    rules.push_back(rule);
+    set_launch_rules(ctx, data);
+}
+
+fn set_launch_rules(ctx: &mut EventCtx, data: &mut AppState) {
+    let rules = data.content.launch_rules.clone();
+    if let Some(manage) = data.manage.as_mut() {
+        manage.launch_rules = rules.clone();
+    }
+    ctx.submit_command(backend_msg::SET_LAUNCH_RULES.with(rules).to(Target::Global));
+}
+
+fn set_status(data: &mut AppState, status: impl Into<String>) {
+    if let Some(manage) = data.manage.as_mut() {
+        manage.status = status.into();
+    }
+}
+
+fn export_template(data: &mut AppState) {
+    let template = Template::from_content(&data.content);
+    let status = match export::to_file(
+        "template",
+        "json",
+        data.content.settings.sign_exports,
+        |out| template.write(out),
+    ) {
+        Ok(path) => format!("Exported to {}", path.display()),
+        Err(err) => {
+            log::error!("Template export failed: {}", err);
+            format!("Export failed: {}", err)
+        }
+    };
+    if let Some(manage) = data.manage.as_mut() {
+        manage.status = status;
+    }
+}
+
+fn save(ctx: &mut EventCtx, data: &mut AppState) {
+    let manage = match &data.manage {
+        Some(manage) => manage,
+        None => return,
+    };
+    for entry in &manage.subjects {
+        let category = entry.category.trim();
+        let category: Option<Arc<str>> = if category.is_empty() {
+            None
+        } else {
+            Some(category.into())
+        };
+        if data.content.categories.get(&entry.subject) != category {
+            data.content
+                .categories
+                .set(&entry.subject, category.clone());
+            ctx.submit_command(
+                backend_msg::SET_CATEGORY
+                    .with((entry.subject.clone(), category))
+                    .to(Target::Global),
+            );
+        }
+    }
+    if !manage.settings.same(&data.content.settings) {
+        data.content.settings = manage.settings.clone();
+        ctx.submit_command(
+            backend_msg::SAVE_SETTINGS
+                .with(manage.settings.clone())
+                .to(Target::Global),
+        );
+    }
+}
+
+fn inner_ui() -> impl Widget<Manage> {
+    Scroll::new(
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(Label::new("Reporting categories"))
+            .with_spacer(3.0)
+            .with_child(List::new(subject_row).lens(Manage::subjects))
+            .with_spacer(10.0)
+            .with_child(Label::new("Aliases"))
+            .with_spacer(3.0)
+            .with_child(List::new(alias_row).lens(Manage::aliases))
+            .with_child(
+                Flex::row()
+                    .with_child(
+                        TextBox::new()
+                            .with_placeholder("Alias")
+                            .lens(Manage::alias_name)
+                            .fix_width(80.0),
+                    )
+                    .with_spacer(5.0)
+                    .with_child(
+                        TextBox::new()
+                            .with_placeholder("Stands for")
+                            .lens(Manage::alias_target)
+                            .fix_width(140.0),
+                    )
+                    .with_spacer(5.0)
+                    .with_child(
+                        Button::new("Add for action")
+                            .on_click(|ctx, _, _| ctx.submit_command(ADD_ACTION_ALIAS)),
+                    )
+                    .with_spacer(5.0)
+                    .with_child(
+                        Button::new("Add for subject")
+                            .on_click(|ctx, _, _| ctx.submit_command(ADD_SUBJECT_ALIAS)),
+                    )
+                    .padding(3.0),
+            )
+            .with_spacer(10.0)
+            .with_child(Label::new("Launch rules"))
+            .with_spacer(3.0)
+            .with_child(
+                Checkbox::new("Offer to start a topic when its application is launched")
+                    .lens(Manage::settings.then(Settings::watch_launches))
+                    .padding(3.0),
+            )
+            .with_child(List::new(launch_rule_row).lens(Manage::launch_rules))
+            .with_child(
+                Flex::row()
+                    .with_child(
+                        TextBox::new()
+                            .with_placeholder("Process")
+                            .lens(Manage::launch_process)
+                            .fix_width(100.0),
+                    )
+                    .with_spacer(5.0)
+                    .with_child(
+                        TextBox::new()
+                            .with_placeholder("Action")
+                            .lens(Manage::launch_action)
+                            .fix_width(100.0),
+                    )
+                    .with_spacer(5.0)
+                    .with_child(
+                        TextBox::new()
+                            .with_placeholder("Subject")
+                            .lens(Manage::launch_subject)
+                            .fix_width(100.0),
+                    )
+                    .with_spacer(5.0)
+                    .with_child(
+                        Button::new("Add rule")
+                            .on_click(|ctx, _, _| ctx.submit_command(ADD_LAUNCH_RULE)),
+                    )
+                    .padding(3.0),
+            )
+            .with_spacer(10.0)
+            .with_child(Label::new("Rename in bulk"))
+            .with_spacer(3.0)
+            .with_child(bulk_rename().lens(Manage::bulk_rename))
+            .with_spacer(10.0)
+            .with_child(Label::new("Split a subject"))
+            .with_spacer(3.0)
+            .with_child(split_subject_ui().lens(Manage::split))
+            .with_spacer(10.0)
+            .with_child(Label::new("Trim long sessions"))
+            .with_spacer(3.0)
+            .with_child(trim_sessions_ui().lens(Manage::trim))
+            .with_spacer(10.0)
+            .with_child(Label::new("Sessions"))
+            .with_spacer(3.0)
+            .with_child(
+                Checkbox::new("Never modify recorded sessions, keep edits as amendments")
+                    .lens(Manage::settings.then(Settings::strict_sessions))
+                    .padding(3.0),
+            )
+            .with_child(
+                Checkbox::new("Split sessions running past midnight into one per day")
+                    .lens(Manage::settings.then(Settings::split_at_midnight))
+                    .padding(3.0),
+            )
+            .with_spacer(10.0)
+            .with_child(Label::new("Billing"))
+            .with_spacer(3.0)
+            .with_child(
+                Label::dynamic(|manage: &Manage, _| match manage.locked_before {
+                    Some(before) => format!("Sessions before {} are locked", *before),
+                    None => "No sessions are locked".to_string(),
+                })
+                .padding(3.0),
+            )
+            .with_child(
+                Flex::row()
+                    .with_child(Label::dynamic(|day: &f64, _| match *day as u32 {
+                        1 => "Months start on the 1st".to_string(),
+                        day => format!("Months start on day {}", day),
+                    }))
+                    .with_spacer(5.0)
+                    .with_child(Stepper::new().with_range(1.0, 28.0).with_step(1.0))
+                    .lens(Manage::settings.then(Settings::month_start))
+                    .padding(3.0),
+            )
+            .with_child(
+                Flex::row()
+                    .with_child(Label::dynamic(|minutes: &f64, _| {
+                        format!(
+                            "Days with {} minutes on a subject keep its streak",
+                            *minutes as u32
+                        )
+                    }))
+                    .with_spacer(5.0)
+                    .with_child(Stepper::new().with_range(1.0, 240.0).with_step(5.0))
+                    .lens(Manage::settings.then(Settings::streak_minutes))
+                    .padding(3.0),
+            )
+            .with_child(
+                Flex::row()
+                    .with_child(Label::new("Lock sessions before"))
+                    .with_spacer(5.0)
+                    .with_child(
+                        TextBox::new()
+                            .with_placeholder("YYYY-MM-DD")
+                            .lens(Manage::lock_before)
+                            .fix_width(110.0),
+                    )
+                    .with_spacer(5.0)
+                    .with_child(Button::new("Lock").on_click(|ctx, _, _| ctx.submit_command(LOCK)))
+                    .with_spacer(5.0)
+                    .with_child(
+                        Button::new("Unlock").on_click(|ctx, _, _| ctx.submit_command(UNLOCK)),
+                    )
+                    .padding(3.0),
+            )
+            .with_child(
+                Flex::row()
+                    .with_child(Label::dynamic(|minutes: &f64, _| match *minutes as u32 {
+                        0 => "Report and export the exact time".to_string(),
+                        minutes => {
+                            format!("Round reported and exported time to {} minutes", minutes)
+                        }
+                    }))
+                    .with_spacer(5.0)
+                    .with_child(Stepper::new().with_range(0.0, 60.0).with_step(5.0))
+                    .lens(Manage::settings.then(Settings::rounding_minutes))
+                    .padding(3.0),
+            )
+            .with_child(
+                Flex::row()
+                    .with_child(Checkbox::new("Always round up").lens(Settings::round_up))
+                    .with_spacer(5.0)
+                    .with_child(
+                        Checkbox::new("Round each day instead of each session")
+                            .lens(Settings::round_per_day),
+                    )
+                    .lens(Manage::settings)
+                    .padding(3.0),
+            )
+            .with_child(
+                Flex::row()
+                    .with_child(Label::new("Write amounts as in"))
+                    .with_spacer(5.0)
+                    .with_child(
+                        TextBox::new()
+                            .with_placeholder("en-US")
+                            .lens(Settings::money_locale)
+                            .fix_width(70.0),
+                    )
+                    .with_spacer(5.0)
+                    .with_child(Label::new("with the currency"))
+                    .with_spacer(5.0)
+                    .with_child(
+                        TextBox::new()
+                            .with_placeholder("€")
+                            .lens(Settings::currency)
+                            .fix_width(50.0),
+                    )
+                    .with_spacer(5.0)
+                    .with_child(Label::dynamic(|settings: &Settings, _| {
+                        format!("like {}", billing::money(settings, 123_450))
+                    }))
+                    .lens(Manage::settings)
+                    .padding(3.0),
+            )
+            .with_spacer(10.0)
+            .with_child(Label::new("Window"))
+            .with_spacer(3.0)
+            .with_child(
+                Checkbox::new("Keep running in the tray when the window is closed")
+                    .lens(Manage::settings.then(Settings::close_to_tray))
+                    .padding(3.0),
+            )
+            .with_child(
+                Checkbox::new("Color sessions by their action instead of their subject")
+                    .lens(Manage::settings.then(Settings::color_by_action))
+                    .padding(3.0),
+            )
+            .with_child(
+                Checkbox::new("Count the running time in minutes, without seconds")
+                    .lens(Manage::settings.then(Settings::hide_seconds))
+                    .padding(3.0),
+            )
+            .with_child(
+                Flex::row()
+                    .with_child(Label::new("Start or stop from anywhere with"))
+                    .with_spacer(5.0)
+                    .with_child(
+                        TextBox::new()
+                            .with_placeholder("Ctrl+Alt+Space")
+                            .fix_width(140.0),
+                    )
+                    .lens(Manage::settings.then(Settings::hotkey))
+                    .padding(3.0),
+            )
+            .with_child(
+                Flex::row()
+                    .with_child(Label::new("Serve a read-only dashboard on port"))
+                    .with_spacer(5.0)
+                    .with_child(TextBox::new().with_placeholder("off").fix_width(70.0))
+                    .lens(Manage::settings.then(Settings::dashboard_port))
+                    .padding(3.0),
+            )
+            .with_spacer(10.0)
+            .with_child(Label::new("Exports"))
+            .with_spacer(3.0)
+            .with_child(
+                Checkbox::new("Sign exports so they can be checked with `zeitig verify`")
+                    .lens(Manage::settings.then(Settings::sign_exports))
+                    .padding(3.0),
+            )
+            .with_spacer(10.0)
+            .with_child(Label::new("Idle time"))
+            .with_spacer(3.0)
+            .with_child(
+                Flex::row()
+                    .with_child(Label::dynamic(|minutes: &f64, _| match *minutes as u32 {
+                        0 => "Never ask about idle time".to_string(),
+                        minutes => format!("Ask after {} idle minutes", minutes),
+                    }))
+                    .with_spacer(5.0)
+                    .with_child(Stepper::new().with_range(0.0, 120.0).with_step(1.0))
+                    .lens(Manage::settings.then(Settings::idle_minutes))
+                    .padding(3.0),
+            )
+            .with_child(
+                Checkbox::new("Pause while the screen is locked or the computer sleeps")
+                    .lens(Manage::settings.then(Settings::pause_on_lock))
+                    .padding(3.0),
+            )
+            .with_child(
+                Checkbox::new("End the session instead of pausing it")
+                    .lens(Manage::settings.then(Settings::end_when_away))
+                    .padding(3.0),
+            )
+            .with_child(
+                Checkbox::new("Go on without the time away, instead of asking")
+                    .lens(Manage::settings.then(Settings::resume_on_unlock))
+                    .padding(3.0),
+            )
+            .with_child(
+                Flex::row()
+                    .with_child(Label::dynamic(|minutes: &f64, _| match *minutes as u32 {
+                        0 => "Never suggest a break".to_string(),
+                        minutes => format!("Suggest a break after {} minutes", minutes),
+                    }))
+                    .with_spacer(5.0)
+                    .with_child(Stepper::new().with_range(0.0, 480.0).with_step(15.0))
+                    .lens(Manage::settings.then(Settings::long_session_minutes))
+                    .padding(3.0),
+            )
+            .with_spacer(10.0)
+            .with_child(Label::new("Confirmations"))
+            .with_spacer(3.0)
+            .with_child(
+                Flex::column()
+                    .cross_axis_alignment(CrossAxisAlignment::Start)
+                    .with_child(
+                        Checkbox::new("Ask before deleting a session")
+                            .lens(Settings::confirm_delete_session),
+                    )
+                    .with_spacer(3.0)
+                    .with_child(
+                        Checkbox::new("Ask before deleting an action or subject")
+                            .lens(Settings::confirm_delete_topic),
+                    )
+                    .with_spacer(3.0)
+                    .with_child(
+                        Checkbox::new("Ask before importing or restoring data")
+                            .lens(Settings::confirm_import),
+                    )
+                    .lens(Manage::settings)
+                    .padding(3.0),
+            )
+            .with_child(
+                Flex::row()
+                    .with_child(Label::dynamic(|minutes: &f64, _| match *minutes as u32 {
+                        0 => "Never ask before stopping a session".to_string(),
+                        minutes => format!(
+                            "Ask before stopping sessions longer than {} minutes",
+                            minutes
+                        ),
+                    }))
+                    .with_spacer(5.0)
+                    .with_child(Stepper::new().with_range(0.0, 480.0).with_step(15.0))
+                    .lens(Manage::settings.then(Settings::confirm_stop_minutes))
+                    .padding(3.0),
+            )
+            .with_spacer(10.0)
+            .with_child(Label::new("Reminders"))
+            .with_spacer(3.0)
+            .with_child(
+                Flex::row()
+                    .with_child(Label::new("Remind to track on weekdays during"))
+                    .with_spacer(5.0)
+                    .with_child(
+                        TextBox::new()
+                            .with_placeholder("09:00-17:00")
+                            .fix_width(120.0),
+                    )
+                    .lens(Manage::settings.then(Settings::work_hours))
+                    .padding(3.0),
+            )
+            .with_child(
+                Flex::row()
+                    .with_child(Label::dynamic(|minutes: &f64, _| match *minutes as u32 {
+                        0 => "Only remind when nothing was tracked that day".to_string(),
+                        minutes => {
+                            format!("Remind again after {} minutes without a session", minutes)
+                        }
+                    }))
+                    .with_spacer(5.0)
+                    .with_child(Stepper::new().with_range(0.0, 240.0).with_step(15.0))
+                    .lens(Manage::settings.then(Settings::remind_idle_minutes))
+                    .padding(3.0),
+            )
+            .with_spacer(10.0)
+            .with_child(Label::new("Overtime"))
+            .with_spacer(3.0)
+            .with_child(
+                Flex::row()
+                    .with_child(Label::dynamic(|hours: &f64, _| match *hours as u32 {
+                        0 => "Keep no overtime balance".to_string(),
+                        hours => format!("Expect {} hours a week, Monday to Friday", hours),
+                    }))
+                    .with_spacer(5.0)
+                    .with_child(Stepper::new().with_range(0.0, 80.0).with_step(1.0))
+                    .lens(Manage::settings.then(Settings::weekly_hours))
+                    .padding(3.0),
+            )
+            .with_child(
+                Flex::row()
+                    .with_child(Label::new("Count the balance from"))
+                    .with_spacer(5.0)
+                    .with_child(
+                        TextBox::new()
+                            .with_placeholder("the first session")
+                            .fix_width(120.0),
+                    )
+                    .lens(Manage::settings.then(Settings::ledger_start))
+                    .padding(3.0),
+            )
+            .with_spacer(10.0)
+            .with_child(Label::new("Announcements"))
+            .with_spacer(3.0)
+            .with_child(
+                Flex::row()
+                    .with_child(Label::dynamic(|minutes: &f64, _| match *minutes as u32 {
+                        0 => "Never announce the time running".to_string(),
+                        minutes => format!("Announce the time running every {} minutes", minutes),
+                    }))
+                    .with_spacer(5.0)
+                    .with_child(Stepper::new().with_range(0.0, 240.0).with_step(15.0))
+                    .lens(Manage::settings.then(Settings::milestone_minutes))
+                    .padding(3.0),
+            )
+            .with_child(
+                Flex::row()
+                    .with_child(Label::new("Also read them out with"))
+                    .with_spacer(5.0)
+                    .with_child(TextBox::new().with_placeholder("espeak").fix_width(140.0))
+                    .lens(Manage::settings.then(Settings::speech_command))
+                    .padding(3.0),
+            )
+            .with_spacer(10.0)
+            .with_child(Label::new("Names"))
+            .with_spacer(3.0)
+            .with_child(
+                Flex::row()
+                    .with_child(Label::dynamic(|length: &f64, _| match *length as u32 {
+                        0 => "Always show names in full".to_string(),
+                        length => format!("Shorten names longer than {} characters", length),
+                    }))
+                    .with_spacer(5.0)
+                    .with_child(Stepper::new().with_range(0.0, 200.0).with_step(1.0))
+                    .lens(Manage::settings.then(Settings::name_length))
+                    .padding(3.0),
+            )
+            .with_child(
+                Flex::row()
+                    .with_child(Label::dynamic(|months: &f64, _| match *months as u32 {
+                        0 => "Never suggest archiving unused names".to_string(),
+                        months => format!("Suggest archiving names unused for {} months", months),
+                    }))
+                    .with_spacer(5.0)
+                    .with_child(Stepper::new().with_range(0.0, 36.0).with_step(1.0))
+                    .lens(Manage::settings.then(Settings::stale_months))
+                    .padding(3.0),
+            )
+            .with_child(
+                Flex::row()
+                    .with_child(Label::dynamic(|kept: &f64, _| match *kept as u32 {
+                        0 => "Never copy the data file".to_string(),
+                        kept => format!("Keep {} copies of the data file", kept),
+                    }))
+                    .with_spacer(5.0)
+                    .with_child(Stepper::new().with_range(0.0, 100.0).with_step(1.0))
+                    .lens(Manage::settings.then(Settings::backups_kept))
+                    .padding(3.0),
+            )
+            .with_spacer(10.0)
+            .with_child(Label::new("Pomodoro"))
+            .with_spacer(3.0)
+            .with_child(
+                Checkbox::new("Split sessions into work intervals and breaks")
+                    .lens(Manage::settings.then(Settings::pomodoro))
+                    .padding(3.0),
+            )
+            .with_child(
+                Flex::row()
+                    .with_child(Label::dynamic(|minutes: &f64, _| {
+                        format!("Work for {} minutes", minutes)
+                    }))
+                    .with_spacer(5.0)
+                    .with_child(Stepper::new().with_range(1.0, 120.0).with_step(1.0))
+                    .lens(Manage::settings.then(Settings::work_minutes))
+                    .padding(3.0),
+            )
+            .with_child(
+                Flex::row()
+                    .with_child(Label::dynamic(|minutes: &f64, _| {
+                        format!("Break for {} minutes", minutes)
+                    }))
+                    .with_spacer(5.0)
+                    .with_child(Stepper::new().with_range(1.0, 60.0).with_step(1.0))
+                    .lens(Manage::settings.then(Settings::break_minutes))
+                    .padding(3.0),
+            ),
+    )
+    .vertical()
+}
+
+fn bulk_rename() -> impl Widget<BulkRename> {
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Flex::row()
+                .with_child(
+                    TextBox::new()
+                        .with_placeholder("Regular expression")
+                        .lens(BulkRename::pattern)
+                        .fix_width(160.0),
+                )
+                .with_spacer(5.0)
+                .with_child(
+                    TextBox::new()
+                        .with_placeholder("Replacement, $1 for groups")
+                        .lens(BulkRename::replacement)
+                        .fix_width(160.0),
+                )
+                .padding(3.0),
+        )
+        .with_child(
+            Flex::row()
+                .with_child(Checkbox::new("Actions").lens(BulkRename::actions))
+                .with_spacer(5.0)
+                .with_child(Checkbox::new("Subjects").lens(BulkRename::subjects))
+                .with_spacer(5.0)
+                .with_child(
+                    Button::new("Preview").on_click(|ctx, _, _| ctx.submit_command(PREVIEW_RENAME)),
+                )
+                .with_spacer(5.0)
+                .with_child(
+                    Button::new("Rename").on_click(|ctx, _, _| ctx.submit_command(APPLY_RENAME)),
+                )
+                .padding(3.0),
+        )
+        .with_child(
+            List::new(|| {
+                Label::dynamic(|renamed: &Renamed, _| {
+                    format!(
+                        "{} {} → {}{}",
+                        if renamed.action { "Action" } else { "Subject" },
+                        renamed.from,
+                        renamed.to,
+                        if renamed.conflict {
+                            " (empty or taken)"
+                        } else {
+                            ""
+                        }
+                    )
+                })
+                .padding(3.0)
+            })
+            .lens(BulkRename::preview),
+        )
+}
+
+fn split_subject_ui() -> impl Widget<SplitSubject> {
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            TextBox::new()
+                .with_placeholder("Subject to split")
+                .lens(SplitSubject::subject)
+                .fix_width(160.0)
+                .padding(3.0),
+        )
+        .with_child(List::new(split_rule).lens(SplitSubject::rules))
+        .with_child(
+            Flex::row()
+                .with_child(
+                    Button::new("Add rule").on_click(|_, split: &mut SplitSubject, _| {
+                        split.rules.push_back(SplitRule::new())
+                    }),
+                )
+                .with_spacer(5.0)
+                .with_child(Button::new("Remove rule").on_click(
+                    |_, split: &mut SplitSubject, _| {
+                        split.rules.pop_back();
+                    },
+                ))
+                .with_spacer(5.0)
+                .with_child(
+                    Button::new("Preview").on_click(|ctx, _, _| ctx.submit_command(PREVIEW_SPLIT)),
+                )
+                .with_spacer(5.0)
+                .with_child(
+                    Button::new("Move sessions")
+                        .on_click(|ctx, _, _| ctx.submit_command(APPLY_SPLIT)),
+                )
+                .padding(3.0),
+        )
+        .with_child(
+            List::new(|| {
+                Label::dynamic(|part: &SplitPart, _| {
+                    format!(
+                        "{}: {} sessions, {}{}",
+                        part.into,
+                        part.sessions,
+                        part.spent_time,
+                        if part.new { " (new subject)" } else { "" }
+                    )
+                })
+                .padding(3.0)
+            })
+            .lens(SplitSubject::preview),
+        )
+}
+
+fn trim_sessions_ui() -> impl Widget<TrimSessions> {
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Flex::row()
+                .with_child(Label::new("Longer than"))
+                .with_spacer(3.0)
+                .with_child(
+                    TextBox::new()
+                        .with_placeholder("10h")
+                        .lens(TrimSessions::longer_than)
+                        .fix_width(60.0),
+                )
+                .with_spacer(5.0)
+                .with_child(
+                    Button::new("Find")
+                        .on_click(|ctx, _, _| ctx.submit_command(FIND_LONG_SESSIONS)),
+                )
+                .with_spacer(5.0)
+                .with_child(
+                    Button::new("Trim chosen")
+                        .on_click(|ctx, _, _| ctx.submit_command(TRIM_SESSIONS)),
+                )
+                .padding(3.0),
+        )
+        .with_child(List::new(trim_candidate).lens(TrimSessions::candidates))
+}
+
+fn trim_candidate() -> impl Widget<TrimCandidate> {
+    Flex::row()
+        .with_child(Checkbox::new("").lens(TrimCandidate::chosen))
+        .with_flex_child(
+            Label::dynamic(|candidate: &TrimCandidate, _| {
+                let session = &candidate.session;
+                format!(
+                    "{} {}, {} - {} ({}), end at {} ({})",
+                    session.topic.action.as_ref(),
+                    session.topic.subject.as_ref(),
+                    session.started.format("%Y-%m-%d %H:%M"),
+                    session.ended.format("%H:%M"),
+                    session.duration(),
+                    candidate.suggested.format("%H:%M"),
+                    candidate.basis
+                )
+            })
+            .expand_width(),
+            1.0,
+        )
+        .padding(3.0)
+}
+
+fn split_rule() -> impl Widget<SplitRule> {
+    Flex::row()
+        .with_child(
+            TextBox::new()
+                .with_placeholder("Move to")
+                .lens(SplitRule::into)
+                .fix_width(120.0),
+        )
+        .with_spacer(5.0)
+        .with_child(
+            TextBox::new()
+                .with_placeholder("Note contains")
+                .lens(SplitRule::note)
+                .fix_width(110.0),
+        )
+        .with_spacer(5.0)
+        .with_child(
+            TextBox::new()
+                .with_placeholder("Tag")
+                .lens(SplitRule::tag)
+                .fix_width(70.0),
+        )
+        .with_spacer(5.0)
+        .with_child(
+            TextBox::new()
+                .with_placeholder("From")
+                .lens(SplitRule::from)
+                .fix_width(90.0),
+        )
+        .with_spacer(5.0)
+        .with_child(
+            TextBox::new()
+                .with_placeholder("Until")
+                .lens(SplitRule::until)
+                .fix_width(90.0),
+        )
+        .padding(3.0)
+}
+
+fn launch_rule_row() -> impl Widget<LaunchRule> {
+    Flex::row()
+        .with_child(
+            Label::dynamic(|rule: &LaunchRule, _| {
+                format!(
+                    "{} → {} {}",
+                    rule.process, rule.topic.action.name, rule.topic.subject.name
+                )
+            })
+            .padding(3.0),
+        )
+        .with_spacer(5.0)
+        .with_child(
+            Button::new("Remove").on_click(|ctx, rule: &mut LaunchRule, _| {
+                ctx.submit_command(REMOVE_LAUNCH_RULE.with(rule.clone()))
+            }),
+        )
+}
+
+fn alias_row() -> impl Widget<AliasEntry> {
+    Flex::row()
+        .with_flex_child(
+            Label::dynamic(|entry: &AliasEntry, _| {
+                let kind = match entry.alias.target {
+                    AliasTarget::Action(_) => "action",
+                    AliasTarget::Subject(_) => "subject",
+                };
+                format!("{} → {} ({})", entry.alias.name, entry.target, kind)
+            })
+            .expand_width(),
+            1.0,
+        )
+        .with_spacer(5.0)
+        .with_child(
+            Button::new("Remove").on_click(|ctx, entry: &mut AliasEntry, _| {
+                ctx.submit_command(REMOVE_ALIAS.with(entry.alias.clone()))
+            }),
+        )
+        .padding(3.0)
+}
+
+fn subject_row() -> impl Widget<SubjectEntry> {
+    Flex::row()
+        .with_flex_child(
+            Label::dynamic(|entry: &SubjectEntry, _| entry.subject.as_ref().to_string())
+                .expand_width(),
+            1.0,
+        )
+        .with_spacer(5.0)
+        .with_child(
+            TextBox::new()
+                .with_placeholder("Category")
+                .lens(SubjectEntry::category)
+                .fix_width(120.0),
+        )
+        .padding(3.0)
+}
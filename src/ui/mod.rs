@@ -1,5 +1,246 @@
+use crate::{
+    controller::{ImportKind, IMPORT},
+    state::{billing, paths, Action, AppState, Subject},
+    widgets::NAME_LENGTH,
+};
+use druid::{keyboard_types::Key, Data, Env, Lens, LocalizedString, MenuDesc, MenuItem, SysMods};
+use im::Vector;
+use std::sync::Arc;
+
 pub mod tracker;
 pub use tracker::ui as tracker;
 
-//mod insights;
-//pub use insights::ui as insights;
+pub mod insights;
+pub use insights::ui as insights;
+
+pub mod manage;
+pub use manage::ui as manage;
+
+pub mod report;
+pub use report::ui as report;
+
+pub mod ledger;
+pub use ledger::ui as ledger;
+
+pub mod history;
+pub use history::ui as history;
+
+pub mod stats;
+pub use stats::ui as stats;
+
+pub mod entry;
+
+pub mod talk;
+
+pub mod viewer;
+
+pub mod zen;
+
+/// Locale amounts of money are written in, see `billing::MoneyFormat`.
+pub const MONEY_LOCALE: druid::Key<Arc<str>> = druid::Key::new("zeitig.money_locale");
+/// Written next to amounts of money.
+pub const CURRENCY: druid::Key<Arc<str>> = druid::Key::new("zeitig.currency");
+
+/// Makes the display settings available to the widgets of a window.
+pub fn apply_settings(env: &mut Env, data: &AppState) {
+    let settings = &data.content.settings;
+    env.set(NAME_LENGTH, settings.name_length);
+    env.set(MONEY_LOCALE, Arc::from(settings.money_locale.as_str()));
+    env.set(CURRENCY, Arc::from(settings.currency.as_str()));
+}
+
+/// Writes an amount in cents as configured in the settings applied to `env`.
+pub fn money(env: &Env, cents: u64) -> String {
+    billing::MoneyFormat::for_locale(&env.get(MONEY_LOCALE)).format(cents, &env.get(CURRENCY))
+}
+
+/// Read-only view of the actions offered for selection.
+pub struct ListedActions;
+
+impl Lens<AppState, Vector<Action>> for ListedActions {
+    fn with<V, F: FnOnce(&Vector<Action>) -> V>(&self, data: &AppState, f: F) -> V {
+        f(&data.content.listed_actions(data.setup.show_archived))
+    }
+
+    fn with_mut<V, F: FnOnce(&mut Vector<Action>) -> V>(&self, data: &mut AppState, f: F) -> V {
+        // Actions are selected through commands, so changes to this copy are discarded.
+        f(&mut data.content.listed_actions(data.setup.show_archived))
+    }
+}
+
+/// Read-only view of the subjects offered for selection.
+pub struct ListedSubjects;
+
+impl Lens<AppState, Vector<Subject>> for ListedSubjects {
+    fn with<V, F: FnOnce(&Vector<Subject>) -> V>(&self, data: &AppState, f: F) -> V {
+        f(&data.content.listed_subjects(data.setup.show_archived))
+    }
+
+    fn with_mut<V, F: FnOnce(&mut Vector<Subject>) -> V>(&self, data: &mut AppState, f: F) -> V {
+        // Subjects are selected through commands, so changes to this copy are discarded.
+        f(&mut data.content.listed_subjects(data.setup.show_archived))
+    }
+}
+
+/// Colors offered for actions and subjects, as `0xRRGGBB`.
+pub const COLORS: &[(&str, u32)] = &[
+    ("Red", 0xe0_55_55),
+    ("Orange", 0xe8_9a_3c),
+    ("Yellow", 0xe0_c8_3c),
+    ("Green", 0x5c_b8_5c),
+    ("Teal", 0x3c_b0_a8),
+    ("Blue", 0x4a_8a_e0),
+    ("Purple", 0x9a_6a_d8),
+    ("Pink", 0xe0_70_b0),
+    ("Gray", 0x90_90_90),
+];
+
+/// Name of an entry in the selection lists, marking archived and temporary ones.
+pub fn listed_name(name: &str, archived: bool, temporary: bool) -> String {
+    if archived {
+        format!("{} (archived)", name)
+    } else if temporary {
+        format!("{} (temporary)", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// The title of the main window, naming the profile unless it is the default one.
+pub fn title() -> String {
+    match paths::profile() {
+        Some(profile) => format!("Zeitig – {}", profile),
+        None => "Zeitig".to_string(),
+    }
+}
+
+/// Submenu switching to the default or one of the named profiles, marking the current one.
+/// New profiles are created by starting with `--profile <name>`.
+fn profile_menu<T: Data>() -> MenuDesc<T> {
+    let current = paths::profile();
+    let item = |label: String, profile: Option<String>| {
+        let selected = profile == current;
+        MenuItem::new(label, tracker::SWITCH_PROFILE.with(profile)).selected_if(move || selected)
+    };
+    paths::profiles().into_iter().fold(
+        MenuDesc::new(LocalizedString::new("zeitig-menu-profile").with_placeholder("Profile"))
+            .append(item("Default".to_string(), None))
+            .append_separator(),
+        |menu, profile| menu.append(item(profile.clone(), Some(profile))),
+    )
+}
+
+pub fn menu<T: Data>() -> MenuDesc<T> {
+    MenuDesc::empty()
+        .append(
+            MenuDesc::new(LocalizedString::new("zeitig-menu-file").with_placeholder("File"))
+                .append(MenuItem::new(
+                    LocalizedString::new("zeitig-menu-export-json").with_placeholder("Export JSON"),
+                    tracker::EXPORT_BACKUP,
+                ))
+                .append(MenuItem::new(
+                    LocalizedString::new("zeitig-menu-export-anonymized")
+                        .with_placeholder("Export anonymized JSON"),
+                    tracker::EXPORT_ANONYMIZED,
+                ))
+                .append(MenuItem::new(
+                    LocalizedString::new("zeitig-menu-import-json").with_placeholder("Import JSON"),
+                    IMPORT.with(ImportKind::Backup),
+                ))
+                .append(MenuItem::new(
+                    LocalizedString::new("zeitig-menu-restore-backup")
+                        .with_placeholder("Restore backup…"),
+                    tracker::SHOW_SNAPSHOTS,
+                ))
+                .append(MenuItem::new(
+                    LocalizedString::new("zeitig-menu-export-phone")
+                        .with_placeholder("Export names for phone"),
+                    tracker::EXPORT_COMPANION,
+                ))
+                .append(MenuItem::new(
+                    LocalizedString::new("zeitig-menu-import-phone")
+                        .with_placeholder("Import sessions from phone"),
+                    IMPORT.with(ImportKind::Companion),
+                ))
+                .append(MenuItem::new(
+                    LocalizedString::new("zeitig-menu-import-toggl")
+                        .with_placeholder("Import from Toggl"),
+                    IMPORT.with(ImportKind::Toggl),
+                ))
+                .append_separator()
+                .append(profile_menu()),
+        )
+        .append(
+            MenuDesc::new(LocalizedString::new("zeitig-menu-edit").with_placeholder("Edit"))
+                .append(
+                    MenuItem::new(
+                        LocalizedString::new("zeitig-menu-undo").with_placeholder("Undo"),
+                        tracker::UNDO,
+                    )
+                    .hotkey(SysMods::Cmd, "z"),
+                )
+                .append(
+                    MenuItem::new(
+                        LocalizedString::new("zeitig-menu-redo").with_placeholder("Redo"),
+                        tracker::REDO,
+                    )
+                    .hotkey(SysMods::CmdShift, "Z"),
+                ),
+        )
+        .append(
+            MenuDesc::new(LocalizedString::new("zeitig-menu-view").with_placeholder("View"))
+                .append(
+                    MenuItem::new(
+                        LocalizedString::new("zeitig-menu-zen").with_placeholder("Focus mode"),
+                        zen::TOGGLE,
+                    )
+                    .hotkey(None, Key::F11),
+                )
+                .append(MenuItem::new(
+                    LocalizedString::new("zeitig-menu-talk").with_placeholder("Talk timer"),
+                    talk::OPEN,
+                ))
+                .append(MenuItem::new(
+                    LocalizedString::new("zeitig-menu-overtime").with_placeholder("Overtime"),
+                    tracker::OPEN_LEDGER,
+                ))
+                .append(MenuItem::new(
+                    LocalizedString::new("zeitig-menu-stats").with_placeholder("Stats"),
+                    tracker::OPEN_STATS,
+                ))
+                .append(
+                    MenuItem::new(
+                        LocalizedString::new("zeitig-menu-palette")
+                            .with_placeholder("Command palette"),
+                        tracker::TOGGLE_PALETTE,
+                    )
+                    .hotkey(SysMods::Cmd, "p"),
+                ),
+        )
+        .append(pinned_menu())
+}
+
+/// Accelerators for resuming the last topic and for the pinned ones, which
+/// also work while typing.
+fn pinned_menu<T: Data>() -> MenuDesc<T> {
+    let resume = MenuItem::new(
+        LocalizedString::new("zeitig-menu-resume-last").with_placeholder("Resume last topic"),
+        tracker::RESUME_LAST,
+    )
+    .hotkey(SysMods::Cmd, "r");
+    (1..=crate::state::Content::PINS).fold(
+        MenuDesc::new(LocalizedString::new("zeitig-menu-pinned").with_placeholder("Pinned"))
+            .append(resume)
+            .append_separator(),
+        |menu, key| {
+            menu.append(
+                MenuItem::new(
+                    LocalizedString::new("zeitig-menu-start-pinned")
+                        .with_placeholder(format!("Start pinned topic {}", key)),
+                    tracker::START_PINNED.with(key - 1),
+                )
+                .hotkey(SysMods::Cmd, key.to_string().as_str()),
+            )
+        },
+    )
+}
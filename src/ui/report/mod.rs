@@ -0,0 +1,177 @@
+use druid::{
+    widget::{Button, CrossAxisAlignment, Flex, Label, List, Scroll, TextBox},
+    Env, Lens, Widget, WidgetExt,
+};
+
+use crate::{
+    controller::EnterController,
+    state::{
+        insights::Summary,
+        report::{Days, Period, PeriodTotal, Report},
+        AppState, TimeFormat,
+    },
+    ui,
+    widgets::{color_dot, Maybe},
+};
+
+pub fn ui() -> impl Widget<AppState> {
+    Maybe::or_empty(inner_ui())
+        .lens(AppState::report)
+        .env_scope(ui::apply_settings)
+}
+
+fn inner_ui() -> impl Widget<Report> {
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(range())
+        .with_spacer(5.0)
+        .with_child(periods())
+        .with_spacer(5.0)
+        .with_child(filters())
+        .with_spacer(5.0)
+        .with_flex_child(
+            Scroll::new(totals().expand_width()).vertical().expand(),
+            1.0,
+        )
+        .with_child(Label::dynamic(|report: &Report, _| report.status.clone()))
+        .padding(5.0)
+}
+
+fn range() -> impl Widget<Report> {
+    Flex::row()
+        .with_child(Label::new("From"))
+        .with_spacer(3.0)
+        .with_child(date_input(Report::from))
+        .with_spacer(5.0)
+        .with_child(Label::new("to"))
+        .with_spacer(3.0)
+        .with_child(date_input(Report::to))
+        .with_spacer(5.0)
+        .with_child(Button::new("Show").on_click(|_, report: &mut Report, _| report.refresh()))
+}
+
+fn date_input(lens: impl Lens<Report, String> + 'static) -> impl Widget<Report> {
+    TextBox::new()
+        .with_placeholder("YYYY-MM-DD")
+        .fix_width(110.0)
+        .lens(lens)
+        .controller(EnterController::new(|_, report: &mut Report| {
+            report.refresh()
+        }))
+}
+
+fn periods() -> impl Widget<Report> {
+    let period = |label: &'static str, period: Period| {
+        Button::dynamic(move |report: &Report, _| match report.period == period {
+            true => format!("[{}]", label),
+            false => label.to_string(),
+        })
+        .on_click(move |_, report: &mut Report, _| {
+            report.period = period;
+            report.refresh();
+        })
+    };
+    Flex::row()
+        .with_child(period("Days", Period::Day))
+        .with_spacer(5.0)
+        .with_child(period("Weeks", Period::Week))
+        .with_spacer(5.0)
+        .with_child(period("Months", Period::Month))
+}
+
+fn filters() -> impl Widget<Report> {
+    let days = |label: &'static str, days: Days| {
+        Button::dynamic(move |report: &Report, _| match report.days == days {
+            true => format!("[{}]", label),
+            false => label.to_string(),
+        })
+        .on_click(move |_, report: &mut Report, _| {
+            report.days = days;
+            report.refresh();
+        })
+    };
+    Flex::row()
+        .with_child(days("All days", Days::All))
+        .with_spacer(5.0)
+        .with_child(days("Weekdays", Days::Weekdays))
+        .with_spacer(5.0)
+        .with_child(days("Weekends", Days::Weekends))
+        .with_spacer(10.0)
+        .with_child(Label::new("Hours"))
+        .with_spacer(3.0)
+        .with_child(
+            TextBox::new()
+                .with_placeholder("09:00-17:00")
+                .fix_width(110.0)
+                .lens(Report::hours)
+                .controller(EnterController::new(|_, report: &mut Report| {
+                    report.refresh()
+                })),
+        )
+        .with_spacer(10.0)
+        .with_child(
+            Button::dynamic(|report: &Report, _| match report.roll_up {
+                true => "[Projects]".to_string(),
+                false => "Projects".to_string(),
+            })
+            .on_click(|_, report: &mut Report, _| {
+                report.roll_up = !report.roll_up;
+                report.refresh();
+            }),
+        )
+        .with_spacer(10.0)
+        .with_child(Label::new("Tag"))
+        .with_spacer(3.0)
+        .with_child(
+            TextBox::new()
+                .with_placeholder("any")
+                .fix_width(80.0)
+                .lens(Report::tag)
+                .controller(EnterController::new(|_, report: &mut Report| {
+                    report.refresh()
+                })),
+        )
+}
+
+fn totals() -> impl Widget<Report> {
+    List::new(|| {
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(Label::dynamic(|total: &PeriodTotal, env| {
+                let time = total.spent_time.format(TimeFormat::Short);
+                format!(
+                    "{}: {}{}",
+                    total.label,
+                    time,
+                    earned_label(total.earned, env)
+                )
+            }))
+            .with_spacer(3.0)
+            .with_child(
+                List::new(|| {
+                    Flex::row()
+                        .with_spacer(10.0)
+                        .with_child(color_dot().lens(Summary::color))
+                        .with_spacer(3.0)
+                        .with_child(Label::dynamic(|sum: &Summary, env| {
+                            format!(
+                                "{} {}: {}{}",
+                                sum.topic.action.as_ref(),
+                                sum.topic.subject.as_ref(),
+                                sum.spent_time.format(TimeFormat::Short),
+                                earned_label(sum.earned, env)
+                            )
+                        }))
+                })
+                .lens(PeriodTotal::entries),
+            )
+            .with_spacer(10.0)
+    })
+    .lens(Report::periods)
+}
+
+fn earned_label(earned: Option<u64>, env: &Env) -> String {
+    earned.map_or_else(String::new, |cents| {
+        format!(", earned {}", ui::money(env, cents))
+    })
+}
@@ -0,0 +1,186 @@
+use druid::{
+    widget::{Button, CrossAxisAlignment, Flex, Label, List, Scroll},
+    Lens, Widget, WidgetExt,
+};
+use im::Vector;
+
+use crate::{
+    state::{
+        stats::{DayBar, Slice, Stats, StatsRange},
+        streak::Streak,
+        AppState, SpentTime, TimeFormat,
+    },
+    ui,
+    widgets::{bar_chart, color_dot, pie_chart, ChartPart, Maybe},
+};
+
+pub fn ui() -> impl Widget<AppState> {
+    Maybe::or_empty(inner_ui())
+        .lens(AppState::stats)
+        .padding(5.0)
+        .env_scope(ui::apply_settings)
+}
+
+fn inner_ui() -> impl Widget<Stats> {
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(ranges())
+        .with_spacer(5.0)
+        .with_child(Label::dynamic(|stats: &Stats, _| {
+            format!("Total: {}", stats.total.format(TimeFormat::Short))
+        }))
+        .with_spacer(5.0)
+        .with_child(
+            Flex::row()
+                .cross_axis_alignment(CrossAxisAlignment::Start)
+                .with_child(pie_chart().lens(SubjectParts))
+                .with_spacer(10.0)
+                .with_flex_child(
+                    Scroll::new(List::new(legend).lens(Stats::subjects))
+                        .vertical()
+                        .fix_height(160.0)
+                        .expand_width(),
+                    1.0,
+                ),
+        )
+        .with_spacer(10.0)
+        .with_child(Label::new("Per day"))
+        .with_spacer(3.0)
+        .with_child(bar_chart().lens(DayParts))
+        .with_child(Label::dynamic(|stats: &Stats, _| {
+            match (stats.days.front(), stats.days.back()) {
+                (Some(first), Some(last)) => format!(
+                    "{} to {}, most {}",
+                    first.day.format("%Y-%m-%d"),
+                    last.day.format("%Y-%m-%d"),
+                    most(&stats.days).format(TimeFormat::Short)
+                ),
+                _ => String::new(),
+            }
+        }))
+        .with_spacer(10.0)
+        .with_child(Label::new("Streaks"))
+        .with_spacer(3.0)
+        .with_flex_child(
+            Scroll::new(List::new(streak).lens(Stats::streaks))
+                .vertical()
+                .expand(),
+            1.0,
+        )
+        .with_child(Label::dynamic(|stats: &Stats, _| stats.status.clone()))
+}
+
+fn ranges() -> impl Widget<Stats> {
+    let range = |label: &'static str, range: StatsRange| {
+        Button::dynamic(move |stats: &Stats, _| match stats.range == range {
+            true => format!("[{}]", label),
+            false => label.to_string(),
+        })
+        .on_click(move |_, stats: &mut Stats, _| {
+            stats.range = range;
+            stats.refresh();
+        })
+    };
+    Flex::row()
+        .with_child(range("Week", StatsRange::Week))
+        .with_spacer(5.0)
+        .with_child(range("Month", StatsRange::Month))
+        .with_spacer(5.0)
+        .with_child(range("Year", StatsRange::Year))
+}
+
+fn legend() -> impl Widget<Slice> {
+    Flex::row()
+        .with_child(color_dot().lens(SliceColor))
+        .with_spacer(5.0)
+        .with_child(Label::dynamic(|slice: &Slice, _| {
+            format!(
+                "{} {} ({:.0}%)",
+                slice.label,
+                slice.spent_time.format(TimeFormat::Short),
+                slice.share * 100.0
+            )
+        }))
+}
+
+fn streak() -> impl Widget<Streak> {
+    Label::dynamic(|streak: &Streak, _| {
+        let mut line = format!(
+            "{}: {} days, longest {}",
+            streak.subject.name, streak.current, streak.longest
+        );
+        let badges = streak.badges();
+        if let Some(best) = badges.last() {
+            line.push_str(&format!(" [{}]", best));
+        }
+        line
+    })
+}
+
+fn most(days: &Vector<DayBar>) -> SpentTime {
+    days.iter()
+        .map(|bar| bar.spent_time)
+        .max_by(|a, b| a.cmp(b))
+        .unwrap_or_default()
+}
+
+/// Read-only view of a slice's color, which is always set.
+struct SliceColor;
+
+impl Lens<Slice, Option<u32>> for SliceColor {
+    fn with<V, F: FnOnce(&Option<u32>) -> V>(&self, data: &Slice, f: F) -> V {
+        f(&Some(data.color))
+    }
+
+    fn with_mut<V, F: FnOnce(&mut Option<u32>) -> V>(&self, data: &mut Slice, f: F) -> V {
+        f(&mut Some(data.color))
+    }
+}
+
+/// Read-only view of the time per subject as slices of the pie.
+struct SubjectParts;
+
+impl Lens<Stats, Vector<ChartPart>> for SubjectParts {
+    fn with<V, F: FnOnce(&Vector<ChartPart>) -> V>(&self, data: &Stats, f: F) -> V {
+        f(&subject_parts(data))
+    }
+
+    fn with_mut<V, F: FnOnce(&mut Vector<ChartPart>) -> V>(&self, data: &mut Stats, f: F) -> V {
+        f(&mut subject_parts(data))
+    }
+}
+
+fn subject_parts(stats: &Stats) -> Vector<ChartPart> {
+    stats
+        .subjects
+        .iter()
+        .map(|slice| ChartPart {
+            value: slice.spent_time.as_secs() as f64,
+            color: Some(slice.color),
+        })
+        .collect()
+}
+
+/// Read-only view of the time per day as bars.
+struct DayParts;
+
+impl Lens<Stats, Vector<ChartPart>> for DayParts {
+    fn with<V, F: FnOnce(&Vector<ChartPart>) -> V>(&self, data: &Stats, f: F) -> V {
+        f(&day_parts(data))
+    }
+
+    fn with_mut<V, F: FnOnce(&mut Vector<ChartPart>) -> V>(&self, data: &mut Stats, f: F) -> V {
+        f(&mut day_parts(data))
+    }
+}
+
+fn day_parts(stats: &Stats) -> Vector<ChartPart> {
+    stats
+        .days
+        .iter()
+        .map(|bar| ChartPart {
+            value: bar.spent_time.as_secs() as f64,
+            color: None,
+        })
+        .collect()
+}
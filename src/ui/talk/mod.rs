@@ -0,0 +1,107 @@
+use druid::{
+    widget::{Button, Flex, Label, MainAxisAlignment, Painter, Stepper},
+    Color, RenderContext, Selector, Target, Widget, WidgetExt, WindowDesc,
+};
+
+use crate::{
+    state::{AppState, Setup},
+    ui::{self, tracker},
+};
+
+const GREEN: Color = Color::rgb8(0x2e, 0x7d, 0x32);
+const YELLOW: Color = Color::rgb8(0xf9, 0xa8, 0x25);
+const RED: Color = Color::rgb8(0xc6, 0x28, 0x28);
+const NEUTRAL: Color = Color::rgb8(0x30, 0x30, 0x30);
+
+pub const OPEN: Selector = Selector::new("zeitig.talk.open");
+
+/// Share of the talk that may remain before the timer turns yellow.
+const WARN_SHARE: f64 = 0.2;
+
+pub fn window() -> WindowDesc<AppState> {
+    WindowDesc::new(ui)
+        .title("Talk timer")
+        .window_size((320.0, 200.0))
+        .menu(ui::menu())
+}
+
+/// Seconds left of the planned talk, negative once it runs over.
+fn remaining(data: &AppState) -> Option<i64> {
+    let planned = (data.setup.countdown_minutes * 60.0) as i64;
+    if planned == 0 {
+        return None;
+    }
+    let elapsed = data
+        .active
+        .as_ref()
+        .map(|session| session.duration.as_secs() as i64)
+        .unwrap_or(0);
+    Some(planned - elapsed)
+}
+
+fn color(data: &AppState) -> Color {
+    let planned = data.setup.countdown_minutes * 60.0;
+    match remaining(data) {
+        None => NEUTRAL,
+        Some(left) if left < 0 => RED,
+        Some(left) if (left as f64) < planned * WARN_SHARE => YELLOW,
+        Some(_) => GREEN,
+    }
+}
+
+fn countdown_text(data: &AppState) -> String {
+    match remaining(data) {
+        None => "--:--".to_string(),
+        Some(left) => {
+            let sign = if left < 0 { "+" } else { "" };
+            let left = left.abs();
+            format!("{}{:02}:{:02}", sign, left / 60, left % 60)
+        }
+    }
+}
+
+pub fn ui() -> impl Widget<AppState> {
+    Flex::column()
+        .main_axis_alignment(MainAxisAlignment::Center)
+        .with_child(
+            Label::dynamic(|data: &AppState, _| countdown_text(data))
+                .with_text_size(64.0)
+                .with_text_color(Color::WHITE),
+        )
+        .with_spacer(10.0)
+        .with_child(
+            Flex::row()
+                .with_child(
+                    Label::dynamic(|setup: &Setup, _| format!("{} min", setup.countdown_minutes))
+                        .with_text_color(Color::WHITE),
+                )
+                .with_child(
+                    Stepper::new()
+                        .with_range(0.0, 240.0)
+                        .with_step(1.0)
+                        .lens(Setup::countdown_minutes),
+                )
+                .lens(AppState::setup),
+        )
+        .with_spacer(10.0)
+        .with_child(
+            Button::dynamic(|data: &AppState, _| match data.active {
+                None => "Start".to_string(),
+                Some(_) => "Stop".to_string(),
+            })
+            .on_click(|ctx, data: &mut AppState, _| match data.active {
+                Some(_) => ctx.submit_command(tracker::END_SESSION.to(Target::Global)),
+                None => {
+                    let setup = &data.setup;
+                    if setup.selected_action.is_some() && setup.selected_subject.is_some() {
+                        tracker::start_new_session(data);
+                    }
+                }
+            }),
+        )
+        .expand()
+        .background(Painter::new(|ctx, data: &AppState, _| {
+            let bounds = ctx.size().to_rect();
+            ctx.fill(bounds, &color(data));
+        }))
+}
@@ -1,182 +1,2723 @@
 use crate::{
     controller::{
-        backend_msg, AutoSaver, BackendController, CloseController, CommandReceiver,
-        EnterController, Ticker,
+        backend_msg, AutoSaver, BackendController, CloseController, CommandReceiver, DragReorder,
+        EnterController, FocusOn, HotkeyController, ImportController, ImportKind, LaunchController,
+        ListKeys, NotifyController, NumberKeys, PresenceController, ReminderController, RightClick,
+        Ticker, CAME_BACK, DAY_ENDED, IMPORT, INTERVAL_ENDED, WENT_AWAY,
     },
+    ipc::IpcController,
+    platform,
     state::{
-        Action, ActiveSession, AppState, Content, Creating, Creator, DateTime, Session, Setup,
-        SpentTime, Subject, Topic,
+        backup::Backup,
+        billing,
+        cleanup::Cleanup,
+        closing, collation, companion, export,
+        goal::{Goal, GoalInput},
+        health::{self, Finding},
+        hierarchy,
+        history::{Conflict, Edit, Merge},
+        idle::IdleRule,
+        insights::Insights,
+        ledger::{self, Ledger},
+        manage::Manage,
+        order::ListOrder,
+        palette::{Palette, PaletteCommand, PaletteEntry},
+        paths,
+        pomodoro::{Phase, Pomodoro},
+        report::{Period, Report},
+        snapshot::{self, Snapshot},
+        stats::Stats,
+        Action, ActiveSession, AliasTarget, Annotation, AppState, Away, ChecklistItem, Confirming,
+        Content, Context, Creating, Creator, DateTime, History, Interruption, Interval, ListFilter,
+        Renaming, Session, Setup, SpentTime, Startup, Subject, TagChoice, TimeFormat, Topic, Unit,
     },
-    widgets::Maybe,
+    tray::{self, TrayController},
+    ui,
+    widgets::{color_dot, name_label, progress_ring, truncated, Maybe, Tooltip},
 };
 use druid::{
+    lens,
     widget::{
-        Button, CrossAxisAlignment, Flex, Label, List, MainAxisAlignment, Painter, Scroll,
-        SizedBox, TextBox,
+        Button, Checkbox, CrossAxisAlignment, Either, Flex, Label, LineBreaking, List,
+        MainAxisAlignment, Painter, ProgressBar, Scroll, SizedBox, TextBox,
     },
-    Command, Data, EventCtx, LensExt, Selector, UnitPoint, Widget, WidgetExt,
+    Command, ContextMenu, Data, EventCtx, Lens, LensExt, LocalizedString, MenuDesc, MenuItem,
+    Point, Selector, UnitPoint, Widget, WidgetExt, WindowDesc,
 };
-use std::time::Duration;
+use im::Vector;
+use std::{convert::TryFrom, error::Error, io::Write, path::PathBuf, time::Duration};
 
 const SELECT_ACTION: Selector<Action> = Selector::new("zeitig.select_action");
 const SELECT_SUBJECT: Selector<Subject> = Selector::new("zeitig.select_subject");
+const SELECT_CONTEXT: Selector<Context> = Selector::new("zeitig.select_context");
+const TOGGLE_CHECKLIST_ITEM: Selector<usize> = Selector::new("zeitig.toggle_checklist_item");
+const SHOW_ACTION_MENU: Selector<(Action, Point)> = Selector::new("zeitig.show_action_menu");
+const SHOW_SUBJECT_MENU: Selector<(Subject, Point)> = Selector::new("zeitig.show_subject_menu");
+const ARCHIVE_ACTION: Selector<(Action, bool)> = Selector::new("zeitig.archive_action");
+const ARCHIVE_SUBJECT: Selector<(Subject, bool)> = Selector::new("zeitig.archive_subject");
+const ASK_ARCHIVE_SUBJECT: Selector<Subject> = Selector::new("zeitig.ask_archive_subject");
+const ARCHIVE_WITH_REPORT: Selector<Subject> = Selector::new("zeitig.archive_with_report");
+const SHOW_FINAL_REPORT: Selector<Subject> = Selector::new("zeitig.show_final_report");
+const MERGE_ACTION: Selector<(Action, Action)> = Selector::new("zeitig.merge_action");
+const MERGE_SUBJECT: Selector<(Subject, Subject)> = Selector::new("zeitig.merge_subject");
+const MARK_ACTION_TEMPORARY: Selector<(Action, bool)> =
+    Selector::new("zeitig.mark_action_temporary");
+const MARK_SUBJECT_TEMPORARY: Selector<(Subject, bool)> =
+    Selector::new("zeitig.mark_subject_temporary");
+const RENAME_ACTION: Selector<Action> = Selector::new("zeitig.rename_action");
+const RENAME_SUBJECT: Selector<Subject> = Selector::new("zeitig.rename_subject");
+const EDIT_ISSUE_URL: Selector<Subject> = Selector::new("zeitig.edit_issue_url");
+const EDIT_ESTIMATE: Selector<Subject> = Selector::new("zeitig.edit_estimate");
+const EDIT_RATE: Selector<Subject> = Selector::new("zeitig.edit_rate");
+const EDIT_IDLE_RULE: Selector<Subject> = Selector::new("zeitig.edit_idle_rule");
+const SET_ACTION_COLOR: Selector<(Action, Option<u32>)> = Selector::new("zeitig.set_action_color");
+const SET_SUBJECT_COLOR: Selector<(Subject, Option<u32>)> =
+    Selector::new("zeitig.set_subject_color");
+const EDIT_GOAL: Selector = Selector::new("zeitig.edit_goal");
+const DELETE_ACTION: Selector<Action> = Selector::new("zeitig.delete_action");
+const DISMISS_CLEANUP: Selector = Selector::new("zeitig.dismiss_cleanup");
+const FIX_FINDING: Selector<Finding> = Selector::new("zeitig.fix_finding");
+const DISMISS_FINDING: Selector<Finding> = Selector::new("zeitig.dismiss_finding");
+const KEEP_MINE: Selector = Selector::new("zeitig.keep_mine");
+const LOAD_THEIRS: Selector = Selector::new("zeitig.load_theirs");
+const DELETE_SUBJECT: Selector<Subject> = Selector::new("zeitig.delete_subject");
+/// Moves an entry by the number of rows it was dragged.
+const MOVE_ACTION: Selector<(Action, isize)> = Selector::new("zeitig.move_action");
+const MOVE_SUBJECT: Selector<(Subject, isize)> = Selector::new("zeitig.move_subject");
+/// Nests a subject in the one with the given id, or moves it to the top level.
+const SET_SUBJECT_PARENT: Selector<(Subject, Option<usize>)> =
+    Selector::new("zeitig.set_subject_parent");
+/// Shows or hides the subjects nested in the one with the given id.
+const TOGGLE_COLLAPSED: Selector<usize> = Selector::new("zeitig.toggle_collapsed");
+const TOGGLE_FAVORITE_ACTION: Selector<Action> = Selector::new("zeitig.toggle_favorite_action");
+const TOGGLE_FAVORITE_SUBJECT: Selector<Subject> = Selector::new("zeitig.toggle_favorite_subject");
+pub const END_SESSION: Selector = Selector::new("zeitig.end_session");
+/// Starts a session on the pinned topic with the given index.
+pub const START_PINNED: Selector<usize> = Selector::new("zeitig.start_pinned");
+const TOGGLE_PIN: Selector = Selector::new("zeitig.toggle_pin");
+/// Starts a session on the selected topic, or ends the running one.
+pub const TOGGLE_SESSION: Selector = Selector::new("zeitig.toggle_session");
+/// Carries out an operation once it has been confirmed.
+pub const CONFIRMED: Selector<Confirming> = Selector::new("zeitig.confirmed");
+/// The start and stop button was clicked, stopping long sessions may have to be confirmed.
+const TOGGLE_CLICKED: Selector = Selector::new("zeitig.toggle_clicked");
+/// Switches to the topic and starts a session on it.
+pub const START_TOPIC: Selector<Topic> = Selector::new("zeitig.start_topic");
+/// Starts a session on the topic worked on last.
+pub const RESUME_LAST: Selector = Selector::new("zeitig.resume_last");
+/// Topics listed to switch to.
+const RECENT_TOPICS: usize = 5;
+const START_RECENT: Selector<Topic> = Selector::new("zeitig.start_recent");
+/// Opens the command palette, or closes it again.
+pub const TOGGLE_PALETTE: Selector = Selector::new("zeitig.toggle_palette");
+const FOCUS_PALETTE: Selector = Selector::new("zeitig.focus_palette");
+const RUN_PALETTE: Selector<PaletteCommand> = Selector::new("zeitig.run_palette");
+/// Opens the window listing the overtime balance week by week.
+pub const OPEN_LEDGER: Selector = Selector::new("zeitig.open_ledger");
+pub const OPEN_STATS: Selector = Selector::new("zeitig.open_stats");
+/// Reverts the latest destructive edit.
+pub const UNDO: Selector = Selector::new("zeitig.undo");
+/// Applies the latest undone edit again.
+pub const REDO: Selector = Selector::new("zeitig.redo");
+/// Writes all data to a JSON backup in the export directory.
+pub const EXPORT_BACKUP: Selector = Selector::new("zeitig.export_backup");
+/// Writes a backup with made-up names and without notes, to share it.
+pub const EXPORT_ANONYMIZED: Selector = Selector::new("zeitig.export_anonymized");
+/// Writes the names for the phone companion as text and as a QR code.
+pub const EXPORT_COMPANION: Selector = Selector::new("zeitig.export_companion");
+/// Lists the copies of the data file to choose one to restore.
+pub const SHOW_SNAPSHOTS: Selector = Selector::new("zeitig.show_snapshots");
+/// Starts the application again on the given profile, `None` for the default one.
+pub const SWITCH_PROFILE: Selector<Option<String>> = Selector::new("zeitig.switch_profile");
+const RESTORE_SNAPSHOT: Selector<Snapshot> = Selector::new("zeitig.restore_snapshot");
+const DISMISS_SNAPSHOTS: Selector = Selector::new("zeitig.dismiss_snapshots");
+const TOGGLE_PAUSE: Selector = Selector::new("zeitig.toggle_pause");
 
-fn start_new_session(data: &mut AppState) {
+pub fn start_new_session(data: &mut AppState) {
+    data.active = Some(ActiveSession::new(DateTime::now()))
+}
+
+/// Starts a session on the selected topic, as the Start button does.
+fn start_session(data: &mut AppState) {
+    start_new_session(data);
+    data.pomodoro = Pomodoro::start(&data.content.settings);
+}
+
+/// Switches to `topic` and starts a session on it.
+fn start_topic(ctx: &mut EventCtx, data: &mut AppState, topic: Topic) {
+    end_session(ctx, data);
+    data.setup.selected_action = Some(topic.action);
+    data.setup.selected_subject = Some(topic.subject);
+    start_session(data);
+}
+
+/// Takes up the topic of the latest session again, unless it is already running.
+fn resume_last(ctx: &mut EventCtx, data: &mut AppState) {
+    let topic = match data.history.recent_topics(1).pop_front() {
+        Some(topic) => topic,
+        None => return,
+    };
+    if data.active.is_some() && selected_topic(data).as_ref() == Some(&topic) {
+        return;
+    }
+    start_topic(ctx, data, topic);
+}
+
+/// Moves an entry by dragging, unless its list is filtered and so ordered
+/// by how well the entries match. Returns whether the order changed.
+fn shift_entry(
+    order: &mut ListOrder,
+    listed: impl Iterator<Item = usize>,
+    filter: &ListFilter,
+    id: usize,
+    shift: isize,
+) -> bool {
+    if !filter.text.trim().is_empty() {
+        return false;
+    }
+    let before = order.clone();
+    order.shift(&listed.collect::<Vec<_>>(), id, shift);
+    *order != before
+}
+
+fn toggle_palette(ctx: &mut EventCtx, data: &mut AppState) {
+    data.setup.creating = match &data.setup.creating {
+        Creating::Palette(_) => Creating::Nothing,
+        _ => {
+            ctx.submit_command(FOCUS_PALETTE);
+            Creating::Palette(Palette::new(&data.content))
+        }
+    };
+}
+
+fn run_palette(ctx: &mut EventCtx, data: &mut AppState, command: &PaletteCommand) {
+    data.setup.creating = Creating::Nothing;
+    match command {
+        PaletteCommand::SelectAction(action) => {
+            ctx.submit_command(SELECT_ACTION.with(action.clone()))
+        }
+        PaletteCommand::SelectSubject(subject) => {
+            ctx.submit_command(SELECT_SUBJECT.with(subject.clone()))
+        }
+        PaletteCommand::StartStop => ctx.submit_command(TOGGLE_CLICKED),
+        PaletteCommand::ResumeLast => resume_last(ctx, data),
+        PaletteCommand::Pause => toggle_pause(data),
+        PaletteCommand::NewAction => data.setup.creating = Creating::Action(String::new()),
+        PaletteCommand::NewSubject => data.setup.creating = Creating::Subject(String::new()),
+        PaletteCommand::Insights => open_insights(ctx, data),
+        PaletteCommand::Reports => open_reports(ctx, data),
+        PaletteCommand::Ledger => open_ledger(ctx, data),
+        PaletteCommand::Stats => open_stats(ctx, data),
+        PaletteCommand::History => open_history(ctx),
+        PaletteCommand::Manage => open_manage(ctx, data),
+        PaletteCommand::Focus => ctx.new_window(ui::zen::window()),
+        PaletteCommand::Undo => ctx.submit_command(UNDO),
+    }
+}
+
+fn open_insights(ctx: &mut EventCtx, data: &mut AppState) {
+    data.insights = Some(Insights::generate(data));
+    ctx.new_window(WindowDesc::new(ui::insights).title("Insights"));
+}
+
+fn open_reports(ctx: &mut EventCtx, data: &mut AppState) {
+    data.report = Some(Report::generate(data));
+    ctx.new_window(WindowDesc::new(ui::report).title("Reports"));
+}
+
+fn open_ledger(ctx: &mut EventCtx, data: &mut AppState) {
+    data.ledger = Some(Ledger::generate(data));
+    ctx.new_window(WindowDesc::new(ui::ledger).title("Overtime"));
+}
+
+fn open_stats(ctx: &mut EventCtx, data: &mut AppState) {
+    data.stats = Some(Stats::generate(data));
+    ctx.new_window(WindowDesc::new(ui::stats).title("Stats"));
+}
+
+fn open_history(ctx: &mut EventCtx) {
+    ctx.new_window(WindowDesc::new(ui::history).title("History"));
+}
+
+fn open_manage(ctx: &mut EventCtx, data: &mut AppState) {
+    data.manage = Some(Manage::generate(data));
+    ctx.new_window(WindowDesc::new(ui::manage).title("Manage"));
+}
+
+fn selected_topic(data: &AppState) -> Option<Topic> {
+    match (&data.setup.selected_action, &data.setup.selected_subject) {
+        (Some(action), Some(subject)) => Some(Topic {
+            action: action.clone(),
+            subject: subject.clone(),
+        }),
+        _ => None,
+    }
+}
+
+fn export_backup(data: &AppState, anonymized: bool) {
+    let content = &data.content;
+    let name = if anonymized { "anonymized" } else { "backup" };
+    let result = export::full_history(content).and_then(|history| {
+        export::to_file(name, "json", content.settings.sign_exports, |out| {
+            let backup = Backup::new(content, &history);
+            match anonymized {
+                true => backup.anonymized().write(out),
+                false => backup.write(out),
+            }
+        })
+    });
+    match result {
+        Ok(path) => platform::notify("Backup exported", &path.display().to_string()),
+        Err(err) => {
+            log::error!("Backup export failed: {}", err);
+            platform::notify("Backup export failed", &err.to_string());
+        }
+    }
+}
+
+fn export_companion(data: &AppState) {
+    match write_companion(&data.content) {
+        Ok(path) => platform::notify("Names exported for phone", &path.display().to_string()),
+        Err(err) => {
+            log::error!("Phone export failed: {}", err);
+            platform::notify("Phone export failed", &err.to_string());
+        }
+    }
+}
+
+/// Writes the names as text and as a QR code to scan, returns the path of the latter.
+fn write_companion(content: &Content) -> Result<PathBuf, Box<dyn Error>> {
+    let mut names = Vec::new();
+    companion::write_names(content, &mut names)?;
+    let code = companion::qr_code(std::str::from_utf8(&names)?)?;
+    export::to_file("phone", "txt", false, |out| out.write_all(&names))?;
+    let path = export::to_file("phone", "svg", false, |out| out.write_all(code.as_bytes()))?;
+    Ok(path)
+}
+
+/// Starts a new instance on `profile` and quits this one, which records the
+/// running session on the way.
+fn switch_profile(ctx: &mut EventCtx, profile: &Option<String>) {
+    if *profile == paths::profile() {
+        return;
+    }
+    let spawned = std::env::current_exe().and_then(|exe| {
+        let mut command = std::process::Command::new(exe);
+        if let Some(profile) = profile {
+            command.arg("--profile").arg(profile);
+        }
+        command.spawn()
+    });
+    match spawned {
+        Ok(_) => ctx.submit_command(tray::QUIT),
+        Err(err) => {
+            log::error!("Cannot switch the profile: {}", err);
+            platform::notify("Cannot switch the profile", &err.to_string());
+        }
+    }
+}
+
+fn toggle_pin(ctx: &mut EventCtx, data: &mut AppState) {
+    let topic = match selected_topic(data) {
+        Some(topic) => topic,
+        None => return,
+    };
+    let pinned = &mut data.content.pinned;
+    match pinned.index_of(&topic) {
+        Some(index) => {
+            pinned.remove(index);
+        }
+        None if pinned.len() < Content::PINS => pinned.push_back(topic),
+        None => return,
+    }
+    ctx.submit_command(backend_msg::SET_PINNED.with(pinned.clone()));
+}
+
+fn toggle_pause(data: &mut AppState) {
+    // Resuming during a pomodoro break skips the rest of it.
+    if let Some(pomodoro) = data.pomodoro.as_mut() {
+        if pomodoro.is_break() {
+            pomodoro.advance(&data.content.settings);
+            start_new_session(data);
+            return;
+        }
+    }
+    if let Some(active) = data.active.as_mut() {
+        // The idle prompt has to be answered first.
+        if active.idle_since.is_some() {
+            return;
+        }
+        match active.paused_since {
+            Some(_) => active.resume(),
+            None => active.pause(DateTime::now()),
+        }
+    }
+}
+
+/// Pauses or ends the running session as the user went away at `since`.
+fn went_away(ctx: &mut EventCtx, data: &mut AppState, since: DateTime) {
+    let running = data.active.as_ref().map_or(false, |active| {
+        active.paused_since.is_none() && active.idle_since.is_none()
+    });
+    // A pause made by hand is left to the user.
+    if !data.content.settings.pause_on_lock || !running || data.setup.away.is_some() {
+        return;
+    }
+    if let Some(active) = data.active.as_mut() {
+        active.pause(since);
+    }
+    let ended = match data.content.settings.end_when_away {
+        true => stop_session(ctx, data),
+        false => None,
+    };
+    if let Some(session) = &ended {
+        data.undo.push(Edit::StopSession(session.clone()));
+    }
+    data.setup.away = Some(Away {
+        since,
+        ended,
+        back: false,
+    });
+}
+
+/// Asks how to count the time away, unless the settings say to go on without it.
+fn came_back(data: &mut AppState) {
+    let away = match data.setup.away.as_mut() {
+        Some(away) => away,
+        None => return,
+    };
+    away.back = true;
+    if data.content.settings.resume_on_unlock {
+        leave_away_out(data);
+    } else {
+        platform::notify(
+            "Welcome back",
+            &format!(
+                "Away since {}, choose how to count the time in the window.",
+                away.since.format("%H:%M")
+            ),
+        );
+    }
+}
+
+/// Goes on as if the user had not been away.
+fn count_away(ctx: &mut EventCtx, data: &mut AppState) {
+    match data.setup.away.take() {
+        Some(Away {
+            ended: Some(session),
+            ..
+        }) if data.active.is_none() => resume_session(ctx, data, &session),
+        Some(Away { ended: None, .. }) => {
+            if let Some(active) = data.active.as_mut() {
+                active.undo_pause();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Goes on from now, leaving the time away out.
+fn leave_away_out(data: &mut AppState) {
+    match data.setup.away.take() {
+        Some(Away { ended: Some(_), .. }) if data.active.is_none() => start_session(data),
+        Some(Away { ended: None, .. }) => {
+            if let Some(active) = data.active.as_mut() {
+                active.resume();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Ends the work at the time the user went away.
+fn stop_at_away(ctx: &mut EventCtx, data: &mut AppState) {
+    if let Some(Away { ended: None, .. }) = data.setup.away.take() {
+        end_session(ctx, data);
+    }
+}
+
+fn record_interruption(data: &mut AppState) {
+    if let Some(active) = data.active.as_mut() {
+        let cause = data.setup.interruption_cause.trim();
+        active.interruptions.push_back(Interruption {
+            at: DateTime::now(),
+            cause: if cause.is_empty() {
+                None
+            } else {
+                Some(cause.into())
+            },
+        });
+        data.setup.interruption_cause.clear();
+    }
+}
+
+pub fn end_session(ctx: &mut EventCtx, data: &mut AppState) {
+    if let Some(session) = stop_session(ctx, data) {
+        data.undo.push(Edit::StopSession(session.clone()));
+        data.setup.creating = Creating::Note(Annotation::new(session, &data.content.tags));
+    }
+}
+
+/// Ends the running session, returning the session recorded for it.
+fn stop_session(ctx: &mut EventCtx, data: &mut AppState) -> Option<Session> {
+    data.pomodoro = None;
+    let active = data.active.take()?;
+    // Pending idle time and a pause at the end are dropped when stopping.
+    let ended = active
+        .idle_since
+        .or(active.paused_since)
+        .unwrap_or_else(DateTime::now);
+    record_session(ctx, data, active, ended)
+}
+
+/// Takes a stopped session up again as if it had never been stopped.
+fn resume_session(ctx: &mut EventCtx, data: &mut AppState, session: &Session) {
+    if matches!(&data.setup.creating, Creating::Note(annotation) if annotation.session == *session)
+    {
+        data.setup.creating = Creating::Nothing;
+    }
+    data.amend_session(session, None);
+    // Taking back the stop rather than editing the records, so this ignores strict mode.
+    ctx.submit_command(backend_msg::DELETE_SESSION.with(session.clone()));
+    data.setup.selected_action = Some(session.topic.action.clone());
+    data.setup.selected_subject = Some(session.topic.subject.clone());
+    data.setup.selected_context = session.context.clone();
+    // The last stretch goes on, counting the time since the stop as well.
+    let mut intervals = session.worked();
+    let last = intervals.pop_back().unwrap();
+    let earlier: SpentTime = intervals.iter().map(Interval::duration).sum();
     data.active = Some(ActiveSession {
-        started: DateTime::now(),
-        duration: SpentTime::default(),
+        duration: earlier + Interval::new(last.started, DateTime::now()).duration(),
+        interruptions: session.interruptions.clone(),
+        intervals,
+        resumed: last.started,
+        ..ActiveSession::new(session.started)
+    });
+}
+
+/// Records the session if it lasted long enough to be kept.
+fn record_session(
+    ctx: &mut EventCtx,
+    data: &mut AppState,
+    active: ActiveSession,
+    ended: DateTime,
+) -> Option<Session> {
+    if *active.duration > Duration::from_secs(30) {
+        let topic = Topic {
+            action: data.setup.selected_action.clone().unwrap(),
+            subject: data.setup.selected_subject.clone().unwrap(),
+        };
+        *data.content.time_table.get_mut(topic.clone()) += active.duration;
+        let intervals = active.intervals_until(ended);
+        let session = Session {
+            topic,
+            started: active.started,
+            ended,
+            context: data.setup.selected_context.clone(),
+            interruptions: active.interruptions,
+            original_start: None,
+            intervals,
+            note: None,
+            tags: Vector::new(),
+        };
+        data.content.totals.add(&session);
+        data.history.add(session.clone());
+        ctx.submit_command(backend_msg::ADD_SESSION.with(session.clone()));
+        Some(session)
+    } else {
+        None
+    }
+}
+
+/// Records the work interval as its own session and continues with a paused
+/// session for the break, or starts the next work interval after a break.
+fn finish_interval(ctx: &mut EventCtx, data: &mut AppState) {
+    let phase = match data.pomodoro.as_mut() {
+        Some(pomodoro) => {
+            pomodoro.advance(&data.content.settings);
+            pomodoro.phase
+        }
+        None => return,
+    };
+    match phase {
+        Phase::Break => {
+            platform::notify("Pomodoro finished", "Time for a break.");
+            if let Some(active) = data.active.take() {
+                let ended = active.paused_since.unwrap_or_else(DateTime::now);
+                record_session(ctx, data, active, ended);
+            }
+            start_new_session(data);
+            if let Some(active) = data.active.as_mut() {
+                active.pause(DateTime::now());
+            }
+        }
+        Phase::Work => {
+            platform::notify("Break is over", "Back to work.");
+            start_new_session(data);
+        }
+    }
+}
+
+/// Records the part of the running session before midnight, which goes on
+/// as a session of the new day. Repeated for sessions running over several days.
+fn split_at_midnight(ctx: &mut EventCtx, data: &mut AppState) {
+    loop {
+        let (midnight, before) = match data.active.as_mut() {
+            Some(active) if active.idle_since.is_none() => {
+                let midnight = active.started.next_midnight();
+                if *midnight > *DateTime::now() {
+                    return;
+                }
+                (midnight, active.split_at(midnight))
+            }
+            _ => return,
+        };
+        record_session(ctx, data, before, midnight);
+    }
+}
+
+fn keep_idle_time(data: &mut AppState) {
+    if let Some(active) = data.active.as_mut() {
+        if let Some(since) = active.idle_since.take() {
+            let idle = Duration::try_from(*DateTime::now() - *since).unwrap_or_default();
+            active.duration += SpentTime::from(idle);
+        }
+    }
+}
+
+/// Records the session up to the start of the idle time and continues with a new one.
+fn discard_idle_time(ctx: &mut EventCtx, data: &mut AppState) {
+    if let Some(mut active) = data.active.take() {
+        match active.idle_since.take() {
+            Some(since) => {
+                record_session(ctx, data, active, since);
+                start_new_session(data);
+            }
+            None => data.active = Some(active),
+        }
+    }
+}
+
+fn handle_command(ctx: &mut EventCtx, data: &mut AppState, cmd: &Command) {
+    if cmd.is(END_SESSION) {
+        end_session(ctx, data);
+    }
+    if cmd.is(TOGGLE_SESSION) {
+        match data.active {
+            Some(_) => end_session(ctx, data),
+            None => start_session(data),
+        }
+    }
+    if cmd.is(TOGGLE_CLICKED) {
+        let minutes = data.content.settings.confirm_stop_minutes;
+        let long = data.active.as_ref().map_or(false, |active| {
+            minutes > 0.0 && active.duration.as_secs() as f64 >= minutes * 60.0
+        });
+        match long {
+            true => data.setup.creating = Creating::Confirm(Confirming::StopSession),
+            false => ctx.submit_command(TOGGLE_SESSION),
+        }
+    }
+    if let Some(operation) = cmd.get(CONFIRMED) {
+        carry_out(ctx, data, operation);
+    }
+    if let Some(topic) = cmd
+        .get(START_PINNED)
+        .and_then(|slot| data.content.pinned.get(*slot))
+    {
+        start_topic(ctx, data, topic.clone());
+    }
+    if let Some(topic) = cmd.get(START_TOPIC) {
+        start_topic(ctx, data, topic.clone());
+    }
+    if cmd.is(RESUME_LAST) {
+        resume_last(ctx, data);
+    }
+    if cmd.is(TOGGLE_PALETTE) {
+        toggle_palette(ctx, data);
+    }
+    if cmd.is(OPEN_LEDGER) {
+        open_ledger(ctx, data);
+    }
+    if cmd.is(OPEN_STATS) {
+        open_stats(ctx, data);
+    }
+    if let Some((action, shift)) = cmd.get(MOVE_ACTION) {
+        let listed = data.content.listed_actions(data.setup.show_archived);
+        let order = &mut data.content.action_order;
+        let listed = listed.iter().map(|action| action.id);
+        if shift_entry(order, listed, &data.setup.action_filter, action.id, *shift) {
+            ctx.submit_command(backend_msg::SET_ACTION_ORDER.with(order.clone()));
+        }
+    }
+    if let Some((subject, shift)) = cmd.get(MOVE_SUBJECT) {
+        // Rows are dragged within the tree as shown.
+        let listed = filtered_subjects(data);
+        let order = &mut data.content.subject_order;
+        let listed = listed.iter().map(|subject| subject.id);
+        if shift_entry(
+            order,
+            listed,
+            &data.setup.subject_filter,
+            subject.id,
+            *shift,
+        ) {
+            ctx.submit_command(backend_msg::SET_SUBJECT_ORDER.with(order.clone()));
+        }
+    }
+    if let Some(action) = cmd.get(TOGGLE_FAVORITE_ACTION) {
+        let order = &mut data.content.action_order;
+        order.toggle_favorite(action.id);
+        ctx.submit_command(backend_msg::SET_ACTION_ORDER.with(order.clone()));
+    }
+    if let Some(subject) = cmd.get(TOGGLE_FAVORITE_SUBJECT) {
+        let order = &mut data.content.subject_order;
+        order.toggle_favorite(subject.id);
+        ctx.submit_command(backend_msg::SET_SUBJECT_ORDER.with(order.clone()));
+    }
+    if let Some(command) = cmd.get(RUN_PALETTE) {
+        run_palette(ctx, data, command);
+    }
+    if let Some(topic) = cmd.get(START_RECENT) {
+        data.setup.show_recent = false;
+        start_topic(ctx, data, topic.clone());
+    }
+    if cmd.is(TOGGLE_PIN) {
+        toggle_pin(ctx, data);
+    }
+    if cmd.is(INTERVAL_ENDED) {
+        finish_interval(ctx, data);
+    }
+    if cmd.is(DAY_ENDED) {
+        split_at_midnight(ctx, data);
+    }
+    if let Some(since) = cmd.get(WENT_AWAY) {
+        went_away(ctx, data, *since);
+    }
+    if cmd.is(CAME_BACK) {
+        came_back(data);
+    }
+    if cmd.is(TOGGLE_PAUSE) {
+        toggle_pause(data);
+    }
+    if cmd.is(ui::zen::TOGGLE) {
+        ctx.new_window(ui::zen::window());
+    }
+    if cmd.is(ui::talk::OPEN) {
+        ctx.new_window(ui::talk::window());
+    }
+    if cmd.is(EXPORT_BACKUP) {
+        export_backup(data, false);
+    }
+    if cmd.is(EXPORT_ANONYMIZED) {
+        export_backup(data, true);
+    }
+    if cmd.is(EXPORT_COMPANION) {
+        export_companion(data);
+    }
+    if let Some(older) = cmd.get(backend_msg::OLDER_SESSIONS_LOADED) {
+        data.history.prepend(older.clone());
+    }
+    if let Some(action) = cmd.get(SELECT_ACTION) {
+        end_session(ctx, data);
+        data.setup.selected_action = Some(action.clone());
+    }
+    if let Some(subject) = cmd.get(SELECT_SUBJECT) {
+        end_session(ctx, data);
+        data.setup.selected_subject = Some(subject.clone());
+    }
+    if let Some(context) = cmd.get(SELECT_CONTEXT) {
+        end_session(ctx, data);
+        data.setup.selected_context = match &data.setup.selected_context {
+            Some(selected) if selected == context => None,
+            _ => Some(context.clone()),
+        };
+    }
+    if let Some((action, position)) = cmd.get(SHOW_ACTION_MENU) {
+        let used = data
+            .content
+            .has_recorded_time(|topic| topic.action == *action);
+        let menu = item_menu(
+            action.archived,
+            action.temporary,
+            used,
+            RENAME_ACTION.with(action.clone()),
+            MARK_ACTION_TEMPORARY.with((action.clone(), !action.temporary)),
+            ARCHIVE_ACTION.with((action.clone(), !action.archived)),
+            DELETE_ACTION.with(action.clone()),
+        )
+        .append(favorite_item(
+            data.content.action_order.is_favorite(action.id),
+            TOGGLE_FAVORITE_ACTION.with(action.clone()),
+        ))
+        .append(merge_menu(
+            data.content
+                .listed_actions(false)
+                .into_iter()
+                .filter(|other| other != action)
+                .map(|other| {
+                    let name = other.name.to_string();
+                    (name, MERGE_ACTION.with((action.clone(), other)).into())
+                }),
+        ))
+        .append(color_menu(action.color, |color| {
+            SET_ACTION_COLOR.with((action.clone(), color)).into()
+        }));
+        ctx.show_context_menu(ContextMenu::new(menu, *position));
+    }
+    if let Some((subject, position)) = cmd.get(SHOW_SUBJECT_MENU) {
+        let used = data
+            .content
+            .has_recorded_time(|topic| topic.subject == *subject);
+        let archive: Command = match subject.archived {
+            true => ARCHIVE_SUBJECT.with((subject.clone(), false)).into(),
+            false => ASK_ARCHIVE_SUBJECT.with(subject.clone()).into(),
+        };
+        let mut menu = item_menu(
+            subject.archived,
+            subject.temporary,
+            used,
+            RENAME_SUBJECT.with(subject.clone()),
+            MARK_SUBJECT_TEMPORARY.with((subject.clone(), !subject.temporary)),
+            archive,
+            DELETE_SUBJECT.with(subject.clone()),
+        )
+        .append(MenuItem::new(
+            LocalizedString::new("zeitig-menu-issue-links").with_placeholder("Issue links…"),
+            EDIT_ISSUE_URL.with(subject.clone()),
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("zeitig-menu-estimate").with_placeholder("Estimate…"),
+            EDIT_ESTIMATE.with(subject.clone()),
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("zeitig-menu-rate").with_placeholder("Hourly rate…"),
+            EDIT_RATE.with(subject.clone()),
+        ))
+        .append(MenuItem::new(
+            LocalizedString::new("zeitig-menu-idle-rule").with_placeholder("Idle time…"),
+            EDIT_IDLE_RULE.with(subject.clone()),
+        ))
+        .append(favorite_item(
+            data.content.subject_order.is_favorite(subject.id),
+            TOGGLE_FAVORITE_SUBJECT.with(subject.clone()),
+        ))
+        .append(parent_menu(data, subject))
+        .append(merge_menu(
+            data.content
+                .listed_subjects(false)
+                .into_iter()
+                .filter(|other| other != subject)
+                .map(|other| {
+                    let name = hierarchy::path(&data.content.subjects, &other);
+                    (name, MERGE_SUBJECT.with((subject.clone(), other)).into())
+                }),
+        ))
+        .append(color_menu(subject.color, |color| {
+            SET_SUBJECT_COLOR.with((subject.clone(), color)).into()
+        }));
+        if subject.final_report.is_some() {
+            menu = menu.append(MenuItem::new(
+                LocalizedString::new("zeitig-menu-final-report").with_placeholder("Final report…"),
+                SHOW_FINAL_REPORT.with(subject.clone()),
+            ));
+        }
+        ctx.show_context_menu(ContextMenu::new(menu, *position));
+    }
+    if let Some((action, archived)) = cmd.get(ARCHIVE_ACTION) {
+        if let Some(listed) = data.content.actions.iter_mut().find(|a| *a == action) {
+            listed.archived = *archived;
+        }
+        if let Some(cleanup) = &mut data.cleanup {
+            cleanup.actions.retain(|suggested| suggested != action);
+        }
+        ctx.submit_command(backend_msg::SET_ACTION_ARCHIVED.with((action.clone(), *archived)));
+    }
+    if let Some((subject, archived)) = cmd.get(ARCHIVE_SUBJECT) {
+        if let Some(listed) = data.content.subjects.iter_mut().find(|s| *s == subject) {
+            listed.archived = *archived;
+        }
+        if let Some(cleanup) = &mut data.cleanup {
+            cleanup.subjects.retain(|suggested| suggested != subject);
+        }
+        ctx.submit_command(backend_msg::SET_SUBJECT_ARCHIVED.with((subject.clone(), *archived)));
+    }
+    if let Some(subject) = cmd.get(ASK_ARCHIVE_SUBJECT) {
+        data.setup.creating = Creating::Archive(subject.clone());
+    }
+    if let Some(subject) = cmd.get(ARCHIVE_WITH_REPORT) {
+        archive_with_report(ctx, data, subject);
+    }
+    if let Some(subject) = cmd.get(SHOW_FINAL_REPORT) {
+        data.setup.creating = Creating::FinalReport(subject.clone());
+    }
+    if let Some((action, temporary)) = cmd.get(MARK_ACTION_TEMPORARY) {
+        if let Some(listed) = data.content.actions.iter_mut().find(|a| *a == action) {
+            listed.temporary = *temporary;
+        }
+        ctx.submit_command(backend_msg::SET_ACTION_TEMPORARY.with((action.clone(), *temporary)));
+    }
+    if let Some((subject, temporary)) = cmd.get(MARK_SUBJECT_TEMPORARY) {
+        if let Some(listed) = data.content.subjects.iter_mut().find(|s| *s == subject) {
+            listed.temporary = *temporary;
+        }
+        ctx.submit_command(backend_msg::SET_SUBJECT_TEMPORARY.with((subject.clone(), *temporary)));
+    }
+    if let Some((action, color)) = cmd.get(SET_ACTION_COLOR) {
+        let updated = Action {
+            color: *color,
+            ..action.clone()
+        };
+        data.rename_action(&updated);
+        ctx.submit_command(backend_msg::SET_ACTION_COLOR.with(updated));
+    }
+    if let Some((subject, parent)) = cmd.get(SET_SUBJECT_PARENT) {
+        let updated = Subject {
+            parent: *parent,
+            ..subject.clone()
+        };
+        data.rename_subject(&updated);
+        ctx.submit_command(backend_msg::SET_PARENT.with(updated));
+    }
+    if let Some(id) = cmd.get(TOGGLE_COLLAPSED) {
+        let collapsed = &mut data.setup.collapsed;
+        match collapsed.index_of(id) {
+            Some(index) => {
+                collapsed.remove(index);
+            }
+            None => collapsed.push_back(*id),
+        }
+    }
+    if let Some((subject, color)) = cmd.get(SET_SUBJECT_COLOR) {
+        let updated = Subject {
+            color: *color,
+            ..subject.clone()
+        };
+        data.rename_subject(&updated);
+        ctx.submit_command(backend_msg::SET_SUBJECT_COLOR.with(updated));
+    }
+    if let Some(action) = cmd.get(RENAME_ACTION) {
+        data.setup.creating = Creating::RenameAction(Renaming::new(action.clone()));
+    }
+    if let Some(subject) = cmd.get(RENAME_SUBJECT) {
+        data.setup.creating = Creating::RenameSubject(Renaming::new(subject.clone()));
+    }
+    if cmd.is(EDIT_GOAL) {
+        if let Some(topic) = selected_topic(data) {
+            data.setup.creating = Creating::Goal(GoalInput::new(topic, data.selected_goal()));
+        }
+    }
+    if let Some(subject) = cmd.get(EDIT_ISSUE_URL) {
+        data.setup.creating = Creating::IssueUrl(Renaming {
+            item: subject.clone(),
+            name: subject.issue_url.as_deref().unwrap_or_default().to_string(),
+        });
+    }
+    if let Some(subject) = cmd.get(EDIT_ESTIMATE) {
+        data.setup.creating = Creating::Estimate(Renaming {
+            item: subject.clone(),
+            name: subject
+                .estimate
+                .map_or_else(String::new, |estimate| estimate.format(TimeFormat::Hours)),
+        });
+    }
+    if let Some(subject) = cmd.get(EDIT_RATE) {
+        data.setup.creating = Creating::Rate(Renaming {
+            item: subject.clone(),
+            name: subject
+                .rate
+                .map_or_else(String::new, |rate| billing::amount(rate as u64)),
+        });
+    }
+    if let Some(subject) = cmd.get(EDIT_IDLE_RULE) {
+        data.setup.creating = Creating::IdleRule(Renaming {
+            item: subject.clone(),
+            name: subject.idle_rule.to_string(),
+        });
+    }
+    if data.cleanup.as_ref().map_or(false, Cleanup::is_empty) || cmd.is(DISMISS_CLEANUP) {
+        data.cleanup = None;
+    }
+    if let Some(finding) = cmd.get(FIX_FINDING) {
+        fix_finding(ctx, data, finding);
+    }
+    if let Some(finding) = cmd.get(DISMISS_FINDING) {
+        data.findings.retain(|listed| !listed.same(finding));
+    }
+    if cmd.is(KEEP_MINE) {
+        if let Some(conflict) = data.conflict.take() {
+            ctx.submit_command(backend_msg::KEEP_CHANGE.with(conflict.change));
+        }
+    }
+    if cmd.is(LOAD_THEIRS) && data.conflict.take().is_some() {
+        ctx.submit_command(backend_msg::RELOAD);
+    }
+    if let Some(profile) = cmd.get(SWITCH_PROFILE) {
+        switch_profile(ctx, profile);
+    }
+    if cmd.is(SHOW_SNAPSHOTS) {
+        data.snapshots = Some(snapshot::list(&paths::snapshot_dir()));
+    }
+    if let Some(snapshot) = cmd.get(RESTORE_SNAPSHOT) {
+        data.snapshots = None;
+        let ask = data.content.settings.confirm_import;
+        confirm(
+            ctx,
+            data,
+            Confirming::RestoreSnapshot(snapshot.clone()),
+            ask,
+        );
+    }
+    if cmd.is(DISMISS_SNAPSHOTS) {
+        data.snapshots = None;
+    }
+    if let Some(action) = cmd.get(DELETE_ACTION) {
+        let ask = data.content.settings.confirm_delete_topic;
+        confirm(ctx, data, Confirming::DeleteAction(action.clone()), ask);
+    }
+    if let Some(subject) = cmd.get(DELETE_SUBJECT) {
+        let ask = data.content.settings.confirm_delete_topic;
+        confirm(ctx, data, Confirming::DeleteSubject(subject.clone()), ask);
+    }
+    if let Some((from, into)) = cmd.get(MERGE_ACTION) {
+        let ask = data.content.settings.confirm_delete_topic;
+        let (from, into) = (from.clone(), into.clone());
+        confirm(ctx, data, Confirming::MergeActions { from, into }, ask);
+    }
+    if let Some((from, into)) = cmd.get(MERGE_SUBJECT) {
+        let ask = data.content.settings.confirm_delete_topic;
+        let (from, into) = (from.clone(), into.clone());
+        confirm(ctx, data, Confirming::MergeSubjects { from, into }, ask);
+    }
+    if cmd.is(UNDO) {
+        undo(ctx, data);
+    }
+    if cmd.is(REDO) {
+        redo(ctx, data);
+    }
+    if let Some(id) = cmd.get(TOGGLE_CHECKLIST_ITEM) {
+        if let Some(item) = data
+            .content
+            .checklist
+            .iter_mut()
+            .find(|item| item.id == *id)
+        {
+            item.done = !item.done;
+            ctx.submit_command(backend_msg::SET_CHECKLIST_ITEM_DONE.with((item.id, item.done)));
+        }
+    }
+}
+
+/// Asks for `operation` to be confirmed in a dialog first if `ask` is set.
+fn confirm(ctx: &mut EventCtx, data: &mut AppState, operation: Confirming, ask: bool) {
+    match ask {
+        true => data.setup.creating = Creating::Confirm(operation),
+        false => carry_out(ctx, data, &operation),
+    }
+}
+
+fn carry_out(ctx: &mut EventCtx, data: &mut AppState, operation: &Confirming) {
+    match operation {
+        Confirming::DeleteAction(action) => {
+            if delete_action(ctx, data, action) {
+                data.undo.push(Edit::DeleteAction(action.clone()));
+            }
+        }
+        Confirming::DeleteSubject(subject) => {
+            if delete_subject(ctx, data, subject) {
+                data.undo.push(Edit::DeleteSubject(subject.clone()));
+            }
+        }
+        Confirming::MergeActions { from, into } => {
+            if let Some(merge) = merge_actions(ctx, data, from, into) {
+                data.undo.push(Edit::MergeActions(merge));
+            }
+        }
+        Confirming::MergeSubjects { from, into } => {
+            if let Some(merge) = merge_subjects(ctx, data, from, into) {
+                data.undo.push(Edit::MergeSubjects(merge));
+            }
+        }
+        Confirming::StopSession => end_session(ctx, data),
+        Confirming::RestoreSnapshot(snapshot) => {
+            ctx.submit_command(backend_msg::RESTORE_SNAPSHOT.with(snapshot.clone()))
+        }
+        // Left to the import controller, which holds what was read from the file.
+        Confirming::Import { .. } => {}
+    }
+}
+
+/// Every recorded session, or the recent ones if the others cannot be loaded.
+fn whole_history(data: &AppState) -> History {
+    export::full_or_recent_history(&data.content, &data.history).0
+}
+
+/// Moves everything recorded on the action `from` over to `into`, which
+/// also takes its place in the selection, and deletes it. Nothing is merged
+/// while any of its sessions is locked.
+fn merge_actions(
+    ctx: &mut EventCtx,
+    data: &mut AppState,
+    from: &Action,
+    into: &Action,
+) -> Option<Merge<Action>> {
+    let sessions = whole_history(data);
+    if locks_any(data, &sessions, |session| session.topic.action == *from) {
+        log::warn!(
+            "Not merging {} as some of its sessions are locked.",
+            from.name
+        );
+        return None;
+    }
+    let merge = merge_record(
+        &data.content,
+        &sessions,
+        (from, into),
+        (from.id, into.id),
+        AliasTarget::Action(from.id),
+        |topic| (topic.action.id, topic.subject.id),
+    );
+    if data.setup.selected_action.as_ref() == Some(from) {
+        data.setup.selected_action = Some(into.clone());
+    }
+    ctx.submit_command(backend_msg::MERGE_ACTIONS.with(merge.clone()));
+    Some(merge)
+}
+
+/// Like `merge_actions`, the checklist and nested subjects move along.
+fn merge_subjects(
+    ctx: &mut EventCtx,
+    data: &mut AppState,
+    from: &Subject,
+    into: &Subject,
+) -> Option<Merge<Subject>> {
+    let sessions = whole_history(data);
+    if locks_any(data, &sessions, |session| session.topic.subject == *from) {
+        log::warn!(
+            "Not merging {} as some of its sessions are locked.",
+            from.name
+        );
+        return None;
+    }
+    let content = &data.content;
+    let merge = Merge {
+        checklist: content
+            .checklist
+            .iter()
+            .filter(|item| item.subject == from.id)
+            .map(|item| item.id)
+            .collect(),
+        category: content.categories.get(from),
+        children: content
+            .subjects
+            .iter()
+            .filter(|subject| subject.parent == Some(from.id))
+            .map(|subject| subject.id)
+            .collect(),
+        ..merge_record(
+            content,
+            &sessions,
+            (from, into),
+            (from.id, into.id),
+            AliasTarget::Subject(from.id),
+            |topic| (topic.subject.id, topic.action.id),
+        )
+    };
+    if data.setup.selected_subject.as_ref() == Some(from) {
+        data.setup.selected_subject = Some(into.clone());
+    }
+    ctx.submit_command(backend_msg::MERGE_SUBJECTS.with(merge.clone()));
+    Some(merge)
+}
+
+/// What belongs to the action or subject `from` in the sessions and the
+/// content. `part` picks the id of its kind out of a topic, then the other one.
+fn merge_record<T: Clone>(
+    content: &Content,
+    sessions: &History,
+    (from, into): (&T, &T),
+    (from_id, into_id): (usize, usize),
+    alias: AliasTarget,
+    part: impl Fn(&Topic) -> (usize, usize),
+) -> Merge<T> {
+    let on_from = |topic: &Topic| part(topic).0 == from_id;
+    Merge {
+        from: from.clone(),
+        into: into.clone(),
+        sessions: sessions
+            .iter()
+            .filter(|session| on_from(&session.topic))
+            .map(Session::key)
+            .collect(),
+        time: content
+            .time_table
+            .iter()
+            .filter(|(topic, _)| on_from(topic))
+            .map(|(topic, time)| (part(topic).1, *time))
+            .collect(),
+        pins: (0..content.pinned.len())
+            .filter(|slot| on_from(&content.pinned[*slot]))
+            .collect(),
+        aliases: content
+            .aliases
+            .iter()
+            .filter(|entry| entry.target == alias)
+            .map(|entry| entry.name.clone())
+            .collect(),
+        launch_rules: content
+            .launch_rules
+            .iter()
+            .filter(|rule| on_from(&rule.topic))
+            .map(|rule| rule.process.clone())
+            .collect(),
+        goals: content
+            .goals
+            .iter()
+            .filter(|goal| on_from(&goal.topic))
+            .map(|goal| {
+                let other = part(&goal.topic).1;
+                let taken = !content
+                    .goals
+                    .iter()
+                    .any(|g| part(&g.topic) == (into_id, other));
+                (goal.clone(), taken)
+            })
+            .collect(),
+        checklist: Vector::new(),
+        category: None,
+        children: Vector::new(),
+    }
+}
+
+/// Whether any of the sessions matching `filter` lies in the locked billing period.
+fn locks_any(data: &AppState, sessions: &History, filter: impl Fn(&Session) -> bool) -> bool {
+    sessions
+        .iter()
+        .any(|session| filter(session) && data.content.is_locked(&session.key()))
+}
+
+/// Deletes an action without recorded time, returning whether it was deleted.
+fn delete_action(ctx: &mut EventCtx, data: &mut AppState, action: &Action) -> bool {
+    if data
+        .content
+        .has_recorded_time(|topic| topic.action == *action)
+    {
+        return false;
+    }
+    if data.setup.selected_action.as_ref() == Some(action) {
+        end_session(ctx, data);
+        data.setup.selected_action = None;
+    }
+    data.content.actions.retain(|listed| listed != action);
+    data.content.pinned.retain(|topic| topic.action != *action);
+    data.content
+        .launch_rules
+        .retain(|rule| rule.topic.action != *action);
+    data.content
+        .aliases
+        .retain(|alias| alias.target != AliasTarget::Action(action.id));
+    ctx.submit_command(backend_msg::DELETE_ACTION.with(action.clone()));
+    true
+}
+
+/// Deletes a subject without recorded time, returning whether it was deleted.
+fn delete_subject(ctx: &mut EventCtx, data: &mut AppState, subject: &Subject) -> bool {
+    if data
+        .content
+        .has_recorded_time(|topic| topic.subject == *subject)
+    {
+        return false;
+    }
+    if data.setup.selected_subject.as_ref() == Some(subject) {
+        end_session(ctx, data);
+        data.setup.selected_subject = None;
+    }
+    data.content.subjects.retain(|listed| listed != subject);
+    // Its sub-projects move up to the top level, as they do in the backend.
+    for listed in data.content.subjects.iter_mut() {
+        if listed.parent == Some(subject.id) {
+            listed.parent = None;
+        }
+    }
+    data.content
+        .pinned
+        .retain(|topic| topic.subject != *subject);
+    data.content
+        .launch_rules
+        .retain(|rule| rule.topic.subject != *subject);
+    data.content.categories.set(subject, None);
+    data.content
+        .checklist
+        .retain(|item| item.subject != subject.id);
+    data.content
+        .aliases
+        .retain(|alias| alias.target != AliasTarget::Subject(subject.id));
+    ctx.submit_command(backend_msg::DELETE_SUBJECT.with(subject.clone()));
+    true
+}
+
+/// Gives an action the name typed in the dialog, unless it is empty or taken by another one.
+fn rename_action(ctx: &mut EventCtx, data: &mut AppState, renaming: Renaming<Action>) {
+    let name = renaming.name.trim();
+    let others = data.content.actions.iter().filter(|a| **a != renaming.item);
+    if name.is_empty() || collation::find(others, name).is_some() {
+        return;
+    }
+    let renamed = Action {
+        name: name.into(),
+        ..renaming.item.clone()
+    };
+    data.rename_action(&renamed);
+    ctx.submit_command(backend_msg::RENAME_ACTION.with(renamed.clone()));
+    data.undo.push(Edit::RenameAction {
+        from: renaming.item,
+        to: renamed,
+    });
+}
+
+/// Archives `subject` and keeps a final report of the time spent on it, from
+/// every recorded session.
+fn archive_with_report(ctx: &mut EventCtx, data: &mut AppState, subject: &Subject) {
+    let sessions = whole_history(data);
+    let updated = Subject {
+        final_report: Some(closing::final_report(&data.content, &sessions, subject).into()),
+        ..subject.clone()
+    };
+    data.rename_subject(&updated);
+    ctx.submit_command(backend_msg::SET_FINAL_REPORT.with(updated.clone()));
+    ctx.submit_command(ARCHIVE_SUBJECT.with((updated, true)));
+}
+
+/// Gives a subject the name typed in the dialog, unless it is empty or taken by another one.
+fn rename_subject(ctx: &mut EventCtx, data: &mut AppState, renaming: Renaming<Subject>) {
+    let name = renaming.name.trim();
+    let others = data
+        .content
+        .subjects
+        .iter()
+        .filter(|s| **s != renaming.item);
+    if name.is_empty() || collation::find(others, name).is_some() {
+        return;
+    }
+    let renamed = Subject {
+        name: name.into(),
+        ..renaming.item.clone()
+    };
+    data.rename_subject(&renamed);
+    ctx.submit_command(backend_msg::RENAME_SUBJECT.with(renamed.clone()));
+    data.undo.push(Edit::RenameSubject {
+        from: renaming.item,
+        to: renamed,
+    });
+}
+
+/// Gives the action with the id of `named` its name again.
+fn restore_action_name(ctx: &mut EventCtx, data: &mut AppState, named: &Action) {
+    if let Some(action) = data.content.actions.iter().find(|a| *a == named) {
+        let renamed = Action {
+            name: named.name.clone(),
+            ..action.clone()
+        };
+        data.rename_action(&renamed);
+        ctx.submit_command(backend_msg::RENAME_ACTION.with(renamed));
+    }
+}
+
+/// Gives the subject with the id of `named` its name again.
+fn restore_subject_name(ctx: &mut EventCtx, data: &mut AppState, named: &Subject) {
+    if let Some(subject) = data.content.subjects.iter().find(|s| *s == named) {
+        let renamed = Subject {
+            name: named.name.clone(),
+            ..subject.clone()
+        };
+        data.rename_subject(&renamed);
+        ctx.submit_command(backend_msg::RENAME_SUBJECT.with(renamed));
+    }
+}
+
+/// Reverts the latest edit. Deleted actions and subjects are created again
+/// by name only, merged ones come back with their sessions, pins, aliases,
+/// categories, checklists and goals.
+fn undo(ctx: &mut EventCtx, data: &mut AppState) {
+    // A stopped session can only be taken up again while no other one runs.
+    if let (Some(Edit::StopSession(_)), Some(_)) = (data.undo.next_undo(), &data.active) {
+        return;
+    }
+    let edit = match data.undo.undo() {
+        Some(edit) => edit,
+        None => return,
+    };
+    match &edit {
+        Edit::StopSession(session) => resume_session(ctx, data, session),
+        Edit::DeleteSession(session) => {
+            data.restore_session(session.clone());
+            ctx.submit_command(backend_msg::RESTORE_SESSION.with(session.clone()));
+        }
+        Edit::DeleteAction(action) => {
+            ctx.submit_command(backend_msg::ADD_ACTION.with(action.name.to_string()))
+        }
+        Edit::DeleteSubject(subject) => {
+            ctx.submit_command(backend_msg::ADD_SUBJECT.with(subject.name.to_string()))
+        }
+        Edit::RenameAction { from, .. } => restore_action_name(ctx, data, from),
+        Edit::RenameSubject { from, .. } => restore_subject_name(ctx, data, from),
+        Edit::MergeActions(merge) => {
+            ctx.submit_command(backend_msg::SPLIT_ACTIONS.with(merge.clone()))
+        }
+        Edit::MergeSubjects(merge) => {
+            ctx.submit_command(backend_msg::SPLIT_SUBJECTS.with(merge.clone()))
+        }
+    }
+    data.undo.undone(edit);
+}
+
+/// Applies the latest undone edit again.
+fn redo(ctx: &mut EventCtx, data: &mut AppState) {
+    let edit = match data.undo.redo() {
+        Some(edit) => edit,
+        None => return,
+    };
+    let redone = match edit {
+        Edit::StopSession(_) => stop_session(ctx, data).map(Edit::StopSession),
+        Edit::DeleteSession(session) => {
+            ui::history::change_session(ctx, data, session.clone(), None);
+            Some(Edit::DeleteSession(session))
+        }
+        // Created again by the undo, so the entries have new ids by now.
+        Edit::DeleteAction(action) => {
+            match collation::find(&data.content.actions, &action.name).cloned() {
+                Some(action) if delete_action(ctx, data, &action) => {
+                    Some(Edit::DeleteAction(action))
+                }
+                _ => None,
+            }
+        }
+        Edit::DeleteSubject(subject) => {
+            match collation::find(&data.content.subjects, &subject.name).cloned() {
+                Some(subject) if delete_subject(ctx, data, &subject) => {
+                    Some(Edit::DeleteSubject(subject))
+                }
+                _ => None,
+            }
+        }
+        Edit::RenameAction { from, to } => {
+            restore_action_name(ctx, data, &to);
+            Some(Edit::RenameAction { from, to })
+        }
+        Edit::RenameSubject { from, to } => {
+            restore_subject_name(ctx, data, &to);
+            Some(Edit::RenameSubject { from, to })
+        }
+        Edit::MergeActions(merge) => collation::find(&data.content.actions, &merge.from.name)
+            .cloned()
+            .and_then(|from| merge_actions(ctx, data, &from, &merge.into))
+            .map(Edit::MergeActions),
+        Edit::MergeSubjects(merge) => collation::find(&data.content.subjects, &merge.from.name)
+            .cloned()
+            .and_then(|from| merge_subjects(ctx, data, &from, &merge.into))
+            .map(Edit::MergeSubjects),
+    };
+    if let Some(edit) = redone {
+        data.undo.redone(edit);
+    }
+}
+
+/// Links issue references in notes on the subject to the typed template, an empty one unlinks them.
+fn set_issue_url(ctx: &mut EventCtx, data: &mut AppState, editing: Renaming<Subject>) {
+    let url = editing.name.trim();
+    let updated = Subject {
+        issue_url: if url.is_empty() {
+            None
+        } else {
+            Some(url.into())
+        },
+        ..editing.item
+    };
+    data.rename_subject(&updated);
+    ctx.submit_command(backend_msg::SET_ISSUE_URL.with(updated));
+}
+
+/// Keeps the note and tags given after stopping a session.
+fn annotate(ctx: &mut EventCtx, data: &mut AppState, annotation: Annotation) {
+    let original = &annotation.session;
+    let annotated = annotation.annotated();
+    if annotated.note != original.note {
+        ctx.submit_command(backend_msg::SET_NOTE.with(annotated.clone()));
+    }
+    if annotated.tags != original.tags {
+        ctx.submit_command(backend_msg::SET_TAGS.with(annotated.clone()));
+    }
+    data.amend_session(original, Some(annotated));
+}
+
+/// Sets or removes the estimate typed in hours, returns `false` and keeps
+/// the dialog open if the input is not a number of hours.
+fn set_estimate(ctx: &mut EventCtx, data: &mut AppState, editing: Renaming<Subject>) -> bool {
+    let hours = editing.name.trim();
+    let estimate = if hours.is_empty() {
+        None
+    } else {
+        match SpentTime::parse_in(hours, Unit::Hours) {
+            Ok(estimate) if estimate.as_secs() > 0 => Some(estimate),
+            _ => return false,
+        }
+    };
+    let updated = Subject {
+        estimate,
+        ..editing.item
+    };
+    data.rename_subject(&updated);
+    ctx.submit_command(backend_msg::SET_ESTIMATE.with(updated));
+    true
+}
+
+/// Sets or removes the hourly rate typed in the dialog, returns `false` and
+/// keeps the dialog open if it is not an amount.
+fn set_rate(ctx: &mut EventCtx, data: &mut AppState, editing: Renaming<Subject>) -> bool {
+    let rate = match billing::parse_rate(&editing.name) {
+        Ok(rate) => rate,
+        Err(_) => return false,
+    };
+    let updated = Subject {
+        rate,
+        ..editing.item
+    };
+    data.rename_subject(&updated);
+    ctx.submit_command(backend_msg::SET_RATE.with(updated));
+    true
+}
+
+/// Sets the idle rule typed in the dialog, returns `false` and keeps the
+/// dialog open if it cannot be read.
+fn set_idle_rule(ctx: &mut EventCtx, data: &mut AppState, editing: Renaming<Subject>) -> bool {
+    let idle_rule = match IdleRule::parse(&editing.name) {
+        Ok(rule) => rule,
+        Err(_) => return false,
+    };
+    let updated = Subject {
+        idle_rule,
+        ..editing.item
+    };
+    data.rename_subject(&updated);
+    ctx.submit_command(backend_msg::SET_IDLE_RULE.with(updated));
+    true
+}
+
+/// Sets or removes the goal typed in the dialog, returns `false` and keeps
+/// the dialog open if the input is invalid.
+fn set_goal(ctx: &mut EventCtx, data: &mut AppState, input: GoalInput) -> bool {
+    let goal = match input.to_goal() {
+        Ok(goal) => goal,
+        Err(err) => {
+            if let Creating::Goal(input) = &mut data.setup.creating {
+                input.status = err;
+            }
+            return false;
+        }
+    };
+    let goals = &mut data.content.goals;
+    goals.retain(|goal| goal.topic != input.topic);
+    goals.extend(goal);
+    ctx.submit_command(backend_msg::SET_GOALS.with(goals.clone()));
+    true
+}
+
+fn fix_finding(ctx: &mut EventCtx, data: &mut AppState, finding: &Finding) {
+    match finding {
+        // Permissions are up to the user, the finding stays until dismissed.
+        Finding::ReadOnly(folder) => {
+            platform::open_url(folder);
+            return;
+        }
+        // A failed repair is reported as a new finding.
+        Finding::Damaged(_) => ctx.submit_command(backend_msg::REPAIR),
+        Finding::ClockBehind(_) => {}
+        Finding::Running(..) => ctx.submit_command(backend_msg::STOP_RUNNING),
+    }
+    data.findings.retain(|listed| !listed.same(finding));
+    if let (Finding::ClockBehind(_), Some(ended)) = (finding, health::clock_behind(&data.history)) {
+        data.findings.push_back(Finding::ClockBehind(ended));
+    }
+}
+
+fn add_checklist_item(ctx: &mut EventCtx, data: &mut AppState) {
+    let text = data.setup.new_checklist_item.trim();
+    if let (Some(subject), false) = (&data.setup.selected_subject, text.is_empty()) {
+        ctx.submit_command(
+            backend_msg::ADD_CHECKLIST_ITEM.with((subject.clone(), text.to_string())),
+        );
+        data.setup.new_checklist_item.clear();
+    }
+}
+
+pub fn ui() -> impl Widget<AppState> {
+    Either::new(
+        |data: &AppState, _| data.startup == Startup::Ready,
+        tracker(),
+        Label::dynamic(|data: &AppState, _| match &data.startup {
+            Startup::Failed(err) => format!("Failed to load data: {}", err),
+            _ => "Loading…".to_string(),
+        })
+        .center(),
+    )
+    .env_scope(ui::apply_settings)
+    .controller(NumberKeys::new(|ctx, _, number| {
+        ctx.submit_command(START_PINNED.with(number - 1))
+    }))
+    .controller(CommandReceiver::new(handle_command))
+    .controller(BackendController::new())
+    .controller(AutoSaver::new())
+    .controller(CloseController::new())
+    .controller(TrayController::new())
+    .controller(IpcController::new())
+    .controller(HotkeyController::new())
+    .controller(ImportController::new())
+    .controller(NotifyController::new())
+    .controller(ReminderController::new())
+    .controller(PresenceController::new())
+    .controller(LaunchController::new())
+}
+
+fn tracker() -> impl Widget<AppState> {
+    Flex::column()
+        .with_child(header())
+        .with_spacer(5.0)
+        .with_child(separator().lens::<AppState, _>(lens::Unit))
+        .with_spacer(10.0)
+        .with_child(contexts())
+        .with_child(pinned())
+        .with_child(recent())
+        .with_spacer(5.0)
+        .with_flex_child(lists(), 1.0)
+        .with_child(show_archived())
+        .with_child(conflict())
+        .with_child(snapshots())
+        .with_child(findings())
+        .with_child(cleanup())
+        .with_child(checklist())
+        .with_child(dialogs())
+        .with_child(buttons())
+}
+
+fn selected_action_label() -> impl Widget<Option<Action>> {
+    Maybe::new(name_label(), Label::new("No Action"))
+}
+
+fn selected_subject_label() -> impl Widget<Option<Subject>> {
+    Maybe::new(name_label(), Label::new("No Subject"))
+}
+
+fn selected_context_label() -> impl Widget<Option<Context>> {
+    Maybe::or_empty(truncated(|context: &Context, _| {
+        format!("@ {}", context.as_ref())
+    }))
+}
+
+fn session_duration_label() -> impl Widget<ClockState> {
+    Label::dynamic(|data: &ClockState, _| match &data.session {
+        Some(session) => {
+            let duration = session.duration.format(data.format);
+            match session.paused_since {
+                Some(_) => format!("Session: {} (paused)", duration),
+                None => format!("Session: {}", duration),
+            }
+        }
+        None => "Session: not running".to_string(),
     })
 }
 
-pub fn end_session(ctx: &mut EventCtx, data: &mut AppState) {
-    if data.active.is_some() {
-        let active = data.active.take().unwrap();
-        if *active.duration > Duration::from_secs(30) {
-            let topic = Topic {
-                action: data.setup.selected_action.clone().unwrap(),
-                subject: data.setup.selected_subject.clone().unwrap(),
-            };
-            *data.content.time_table.get_mut(topic.clone()) += active.duration;
-            let session = Session {
-                topic,
-                started: active.started,
-                ended: DateTime::now(),
-            };
-            data.history.add(session.clone());
-            ctx.submit_command(backend_msg::ADD_SESSION.with(session));
+fn separator() -> impl Widget<()> {
+    use druid::RenderContext;
+    Painter::new(|ctx, _, env| {
+        let bounds = ctx.size().to_rect();
+        ctx.fill(bounds, &env.get(druid::theme::BORDER_DARK));
+    })
+    .expand_width()
+    .fix_height(2.0)
+}
+
+/// Everything the per-second timer changes, so a tick only updates the clock.
+#[derive(Clone, Data, Lens)]
+struct ClockState {
+    session: Option<ActiveSession>,
+    total: SpentTime,
+    today: SpentTime,
+    goal: Option<GoalProgress>,
+    pomodoro: Option<Pomodoro>,
+    /// Overtime balance including today, if one is kept.
+    overtime: Option<i64>,
+    format: TimeFormat,
+}
+
+#[derive(Clone, Data, Lens)]
+struct GoalProgress {
+    goal: Goal,
+    progress: SpentTime,
+}
+
+fn goal_progress(data: &AppState) -> Option<GoalProgress> {
+    data.selected_goal().map(|goal| GoalProgress {
+        goal: goal.clone(),
+        progress: data.goal_progress(goal),
+    })
+}
+
+/// Read-only view of the running clock.
+struct Clock;
+
+impl Lens<AppState, ClockState> for Clock {
+    fn with<V, F: FnOnce(&ClockState) -> V>(&self, data: &AppState, f: F) -> V {
+        f(&ClockState {
+            session: data.active.clone(),
+            total: data.current_session_duration(),
+            today: data.today_duration(),
+            goal: goal_progress(data),
+            pomodoro: data.pomodoro.clone(),
+            overtime: data.overtime_balance(),
+            format: data.content.settings.live_format(),
+        })
+    }
+
+    fn with_mut<V, F: FnOnce(&mut ClockState) -> V>(&self, data: &mut AppState, f: F) -> V {
+        f(&mut ClockState {
+            session: data.active.clone(),
+            total: data.current_session_duration(),
+            today: data.today_duration(),
+            goal: goal_progress(data),
+            pomodoro: data.pomodoro.clone(),
+            overtime: data.overtime_balance(),
+            format: data.content.settings.live_format(),
+        })
+    }
+}
+
+#[derive(Clone, Copy, Data, PartialEq)]
+enum RunState {
+    Stopped,
+    Running,
+    Paused,
+    Break,
+}
+
+/// Whether a session is running, which stays the same between ticks.
+struct Run;
+
+impl Lens<AppState, RunState> for Run {
+    fn with<V, F: FnOnce(&RunState) -> V>(&self, data: &AppState, f: F) -> V {
+        f(&run_state(data))
+    }
+
+    fn with_mut<V, F: FnOnce(&mut RunState) -> V>(&self, data: &mut AppState, f: F) -> V {
+        // The buttons act through commands, so changes to this copy are discarded.
+        f(&mut run_state(data))
+    }
+}
+
+fn run_state(data: &AppState) -> RunState {
+    match &data.active {
+        None => RunState::Stopped,
+        Some(_) if data.pomodoro.as_ref().map_or(false, Pomodoro::is_break) => RunState::Break,
+        Some(active) if active.paused_since.is_some() => RunState::Paused,
+        Some(_) => RunState::Running,
+    }
+}
+
+fn clock() -> impl Widget<ClockState> {
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(session_duration_label())
+        .with_child(Label::dynamic(|data: &ClockState, _| {
+            format!("Total: {}", data.total.format(data.format))
+        }))
+        .with_child(Label::dynamic(|data: &ClockState, _| {
+            format!("Today: {}", data.today.format(data.format))
+        }))
+        .with_child(
+            Maybe::or_empty(
+                Label::dynamic(|balance: &i64, _| {
+                    format!("Overtime: {}", ledger::format_balance(*balance))
+                })
+                .on_click(|ctx, _, _| ctx.submit_command(OPEN_LEDGER)),
+            )
+            .lens(ClockState::overtime),
+        )
+        .with_child(
+            Maybe::or_empty(
+                Flex::row()
+                    .with_child(
+                        ProgressBar::new()
+                            .lens(lens::Map::new(
+                                |goal: &GoalProgress| goal.goal.fraction(goal.progress),
+                                |_, _| {},
+                            ))
+                            .fix_width(80.0),
+                    )
+                    .with_spacer(5.0)
+                    .with_child(Label::dynamic(|goal: &GoalProgress, _| {
+                        format!("{} of {}", goal.progress, goal.goal.label())
+                    })),
+            )
+            .lens(ClockState::goal),
+        )
+        .with_child(
+            Maybe::or_empty(Label::dynamic(|pomodoro: &Pomodoro, _| {
+                match pomodoro.phase {
+                    Phase::Work => format!(
+                        "Pomodoro {}: {} left",
+                        pomodoro.completed + 1,
+                        pomodoro.remaining
+                    ),
+                    Phase::Break => format!("Break: {} left", pomodoro.remaining),
+                }
+            }))
+            .lens(ClockState::pomodoro),
+        )
+}
+
+fn session_buttons() -> impl Widget<RunState> {
+    Flex::row()
+        .with_child(
+            Button::dynamic(|state: &RunState, _| match state {
+                RunState::Stopped => "Start".to_string(),
+                _ => "Stop".to_string(),
+            })
+            .on_click(|ctx, _, _| ctx.submit_command(TOGGLE_CLICKED)),
+        )
+        .with_child(Either::new(
+            |state: &RunState, _| *state != RunState::Stopped,
+            Flex::row().with_spacer(3.0).with_child(
+                Button::dynamic(|state: &RunState, _| match state {
+                    RunState::Paused => "Resume".to_string(),
+                    RunState::Break => "Skip break".to_string(),
+                    _ => "Pause".to_string(),
+                })
+                .on_click(|ctx, _, _| ctx.submit_command(TOGGLE_PAUSE)),
+            ),
+            SizedBox::empty(),
+        ))
+}
+
+fn header() -> impl Widget<AppState> {
+    Flex::column()
+        .with_child(
+            Flex::row()
+                .with_flex_child(clock().lens(Clock).expand_width(), 1.0)
+                .with_spacer(5.0)
+                .with_child(session_buttons().lens(Run))
+                .padding((10.0, 10.0, 10.0, 5.0))
+                .controller(Ticker::new()),
+        )
+        .with_child(
+            Flex::row()
+                .main_axis_alignment(MainAxisAlignment::Center)
+                .with_child(selected_action_label().lens(Setup::selected_action))
+                .with_child(Label::new(""))
+                .with_child(selected_subject_label().lens(Setup::selected_subject))
+                .with_child(Label::new(""))
+                .with_child(selected_context_label().lens(Setup::selected_context))
+                .lens(AppState::setup),
+        )
+        .with_child(interruption_row())
+        .with_child(idle_prompt())
+        .with_child(away_prompt())
+}
+
+fn away_prompt() -> impl Widget<AppState> {
+    Either::new(
+        |data: &AppState, _| data.setup.away.as_ref().map_or(false, |away| away.back),
+        Flex::row()
+            .with_flex_child(
+                Label::dynamic(|data: &AppState, _| match &data.setup.away {
+                    Some(away) => format!("Away since {}", away.since.format("%H:%M")),
+                    None => String::new(),
+                })
+                .expand_width(),
+                1.0,
+            )
+            .with_child(Button::new("Count it").on_click(|ctx, data, _| count_away(ctx, data)))
+            .with_spacer(3.0)
+            .with_child(Button::new("Leave it out").on_click(|_, data, _| leave_away_out(data)))
+            .with_spacer(3.0)
+            .with_child(Button::new("Stop").on_click(|ctx, data, _| stop_at_away(ctx, data)))
+            .padding((10.0, 5.0)),
+        SizedBox::empty(),
+    )
+}
+
+fn idle_prompt() -> impl Widget<AppState> {
+    Either::new(
+        |data: &AppState, _| {
+            data.active
+                .as_ref()
+                .map_or(false, |active| active.idle_since.is_some())
+        },
+        Flex::row()
+            .with_flex_child(
+                Label::dynamic(|data: &AppState, _| {
+                    match data.active.as_ref().and_then(|active| active.idle_since) {
+                        Some(since) => format!("Idle since {}", since.format("%H:%M")),
+                        None => String::new(),
+                    }
+                })
+                .expand_width(),
+                1.0,
+            )
+            .with_child(
+                Button::new("Keep").on_click(|_, data: &mut AppState, _| keep_idle_time(data)),
+            )
+            .with_spacer(3.0)
+            .with_child(
+                Button::new("Discard")
+                    .on_click(|ctx, data: &mut AppState, _| discard_idle_time(ctx, data)),
+            )
+            .padding((10.0, 5.0)),
+        SizedBox::empty(),
+    )
+}
+
+fn interruption_row() -> impl Widget<AppState> {
+    Either::new(
+        |data: &AppState, _| data.active.is_some(),
+        Flex::row()
+            .with_flex_child(
+                TextBox::new()
+                    .with_placeholder("Cause (optional)")
+                    .lens(AppState::setup.then(Setup::interruption_cause))
+                    .expand_width(),
+                1.0,
+            )
+            .with_spacer(3.0)
+            .with_child(
+                Button::new("Interrupted")
+                    .on_click(|_, data: &mut AppState, _| record_interruption(data)),
+            )
+            .padding((10.0, 5.0)),
+        SizedBox::empty(),
+    )
+}
+
+fn contexts() -> impl Widget<AppState> {
+    Scroll::new(
+        List::new(|| {
+            name_label()
+                .padding(3.0)
+                .on_click(|ctx, context: &mut Context, _| {
+                    ctx.submit_command(SELECT_CONTEXT.with(context.clone()));
+                })
+        })
+        .horizontal(),
+    )
+    .horizontal()
+    .lens(AppState::content.then(Content::contexts))
+    .center()
+}
+
+/// A listed action, whether it is pinned and whether it is highlighted
+/// from the keyboard.
+#[derive(Clone, Data, Lens)]
+struct ActionRow {
+    action: Action,
+    favorite: bool,
+    highlighted: bool,
+}
+
+/// A listed subject and the share of its estimate spent, if it has one,
+/// placed in the tree of projects and sub-projects.
+#[derive(Clone, Data, Lens)]
+struct SubjectRow {
+    subject: Subject,
+    progress: Option<f64>,
+    favorite: bool,
+    highlighted: bool,
+    depth: usize,
+    /// Other listed subjects are nested in it.
+    nested: bool,
+    collapsed: bool,
+}
+
+/// Read-only view of the subjects offered for selection, with their progress.
+struct SubjectRows;
+
+impl Lens<AppState, Vector<SubjectRow>> for SubjectRows {
+    fn with<V, F: FnOnce(&Vector<SubjectRow>) -> V>(&self, data: &AppState, f: F) -> V {
+        f(&subject_rows(data))
+    }
+
+    fn with_mut<V, F: FnOnce(&mut Vector<SubjectRow>) -> V>(&self, data: &mut AppState, f: F) -> V {
+        // Subjects are selected through commands, so changes to this copy are discarded.
+        f(&mut subject_rows(data))
+    }
+}
+
+/// Read-only view of the actions offered for selection, matching the filter.
+struct ActionRows;
+
+impl Lens<AppState, Vector<ActionRow>> for ActionRows {
+    fn with<V, F: FnOnce(&Vector<ActionRow>) -> V>(&self, data: &AppState, f: F) -> V {
+        f(&action_rows(data))
+    }
+
+    fn with_mut<V, F: FnOnce(&mut Vector<ActionRow>) -> V>(&self, data: &mut AppState, f: F) -> V {
+        // Actions are selected through commands, so changes to this copy are discarded.
+        f(&mut action_rows(data))
+    }
+}
+
+/// The text of a list filter, moving the highlight back to the best match
+/// whenever it changes.
+struct FilterText;
+
+impl Lens<ListFilter, String> for FilterText {
+    fn with<V, F: FnOnce(&String) -> V>(&self, data: &ListFilter, f: F) -> V {
+        f(&data.text)
+    }
+
+    fn with_mut<V, F: FnOnce(&mut String) -> V>(&self, data: &mut ListFilter, f: F) -> V {
+        let mut text = data.text.clone();
+        let value = f(&mut text);
+        if text != data.text {
+            data.text = text;
+            data.cursor = None;
         }
+        value
+    }
+}
+
+fn filtered_actions(data: &AppState) -> Vec<Action> {
+    let actions = data.content.listed_actions(data.setup.show_archived);
+    collation::fuzzy_filter(actions, &data.setup.action_filter.text)
+}
+
+/// The subjects as a tree, or the ones matching the filter by how well they match.
+fn visible_subjects(data: &AppState) -> Vec<hierarchy::Node> {
+    let subjects = data.content.listed_subjects(data.setup.show_archived);
+    let filter = &data.setup.subject_filter.text;
+    if filter.trim().is_empty() {
+        return hierarchy::tree(&subjects, &data.setup.collapsed);
     }
+    collation::fuzzy_filter(subjects, filter)
+        .into_iter()
+        .map(|subject| hierarchy::Node {
+            subject,
+            depth: 0,
+            nested: false,
+        })
+        .collect()
 }
 
-fn handle_command(ctx: &mut EventCtx, data: &mut AppState, cmd: &Command) {
-    if let Some(action) = cmd.get(SELECT_ACTION) {
-        end_session(ctx, data);
-        data.setup.selected_action = Some(action.clone());
-    }
-    if let Some(subject) = cmd.get(SELECT_SUBJECT) {
-        end_session(ctx, data);
-        data.setup.selected_subject = Some(subject.clone());
-    }
+fn filtered_subjects(data: &AppState) -> Vec<Subject> {
+    visible_subjects(data)
+        .into_iter()
+        .map(|node| node.subject)
+        .collect()
 }
 
-pub fn ui() -> impl Widget<AppState> {
-    Flex::column()
-        .with_child(header())
-        .with_spacer(5.0)
-        .with_child(separator())
-        .with_spacer(10.0)
-        .with_flex_child(lists(), 1.0)
-        .with_child(dialogs())
-        .with_child(buttons())
-        .controller(CommandReceiver::new(handle_command))
-        .controller(BackendController::new())
-        .controller(AutoSaver::new())
-        .controller(CloseController::new())
+fn action_rows(data: &AppState) -> Vector<ActionRow> {
+    let cursor = data.setup.action_filter.cursor;
+    filtered_actions(data)
+        .into_iter()
+        .enumerate()
+        .map(|(index, action)| ActionRow {
+            favorite: data.content.action_order.is_favorite(action.id),
+            action,
+            highlighted: cursor == Some(index),
+        })
+        .collect()
 }
 
-fn selected_action_label() -> impl Widget<Option<Action>> {
-    Maybe::new(
-        Label::dynamic(|action: &Action, _| format!("{}", action.as_ref())),
-        Label::new("No Action"),
-    )
+fn subject_rows(data: &AppState) -> Vector<SubjectRow> {
+    let cursor = data.setup.subject_filter.cursor;
+    visible_subjects(data)
+        .into_iter()
+        .enumerate()
+        .map(|(index, node)| SubjectRow {
+            progress: node.subject.estimate.map(|estimate| {
+                let spent = data.subject_progress(&node.subject);
+                spent.as_secs_f64() / estimate.as_secs_f64().max(1.0)
+            }),
+            favorite: data.content.subject_order.is_favorite(node.subject.id),
+            highlighted: cursor == Some(index),
+            depth: node.depth,
+            nested: node.nested,
+            collapsed: data.setup.collapsed.contains(&node.subject.id),
+            subject: node.subject,
+        })
+        .collect()
 }
 
-fn selected_subject_label() -> impl Widget<Option<Subject>> {
-    Maybe::new(
-        Label::dynamic(|subject: &Subject, _| format!("{}", subject.as_ref())),
-        Label::new("No Subject"),
-    )
+/// Indents a subject by how deeply it is nested, and shows or hides the
+/// ones nested in it when clicked.
+fn tree_toggle() -> impl Widget<SubjectRow> {
+    Label::dynamic(|row: &SubjectRow, _| {
+        let toggle = match (row.nested, row.collapsed) {
+            (false, _) => "  ",
+            (true, true) => "▸",
+            (true, false) => "▾",
+        };
+        format!("{}{}", "    ".repeat(row.depth), toggle)
+    })
+    .on_click(|ctx, row: &mut SubjectRow, _| {
+        if row.nested {
+            ctx.submit_command(TOGGLE_COLLAPSED.with(row.subject.id));
+        }
+    })
 }
 
-fn session_duration_label() -> impl Widget<Option<ActiveSession>> {
-    Maybe::new(
-        Label::dynamic(|session: &ActiveSession, _| format!("Session: {}", session.duration)),
-        Label::new("Session: not running"),
+/// Marks entries pinned to the top of their list.
+fn favorite_mark<T: Data>(favorite: fn(&T) -> bool) -> impl Widget<T> {
+    Either::new(
+        move |row: &T, _| favorite(row),
+        Label::new("★").padding((0.0, 0.0, 3.0, 0.0)),
+        SizedBox::empty(),
     )
 }
 
-fn separator<T: Data>() -> impl Widget<T> {
+/// Marks the row chosen with the arrow keys.
+fn highlight<T: Data>(highlighted: impl Fn(&T) -> bool + 'static) -> Painter<T> {
     use druid::RenderContext;
-    Painter::new(|ctx, _, env| {
-        let bounds = ctx.size().to_rect();
-        ctx.fill(bounds, &env.get(druid::theme::BORDER_DARK));
+    Painter::new(move |ctx, data: &T, env| {
+        if highlighted(data) {
+            let bounds = ctx.size().to_rect();
+            ctx.fill(bounds, &env.get(druid::theme::SELECTION_COLOR));
+        }
     })
-    .expand_width()
-    .fix_height(2.0)
 }
 
-fn header() -> impl Widget<AppState> {
-    Flex::column()
-        .with_child(
-            Flex::row()
-                .with_flex_child(
-                    Flex::column()
-                        .cross_axis_alignment(CrossAxisAlignment::Start)
-                        .with_child(session_duration_label().lens(AppState::active))
-                        .with_child(Label::dynamic(|data: &AppState, _| {
-                            format!("Total: {}", data.current_session_duration())
-                        }))
-                        .expand_width(),
-                    1.0,
-                )
-                .with_spacer(5.0)
-                .with_child(
-                    Button::dynamic(|data: &AppState, _| match data.active {
-                        None => "Start".to_string(),
-                        Some(_) => "Stop".to_string(),
+/// Text box narrowing a list down, where the arrow keys move through the
+/// matches and Enter selects one.
+fn list_filter(
+    filter: impl Lens<Setup, ListFilter> + Clone + 'static,
+    len: fn(&AppState) -> usize,
+    choose: fn(&mut EventCtx, &AppState, usize),
+) -> impl Widget<AppState> {
+    let lens = AppState::setup.then(filter);
+    TextBox::new()
+        .with_placeholder("Filter")
+        .expand_width()
+        .lens(lens.clone().then(FilterText))
+        .controller(ListKeys::new(
+            {
+                let lens = lens.clone();
+                move |data: &mut AppState, step| {
+                    let len = len(data);
+                    lens.with_mut(data, |filter| filter.move_cursor(step, len));
+                }
+            },
+            move |ctx, data: &mut AppState| {
+                let chosen = lens.with(data, |filter| filter.chosen(len(data)));
+                if let Some(index) = chosen {
+                    choose(ctx, data, index);
+                    lens.with_mut(data, |filter| *filter = ListFilter::default());
+                }
+            },
+        ))
+        .padding((0.0, 0.0, 0.0, 5.0))
+}
+
+fn lists() -> impl Widget<AppState> {
+    let actions = Scroll::new(List::new(|| {
+        Flex::row()
+            .with_child(favorite_mark(|row: &ActionRow| row.favorite))
+            .with_child(color_dot().lens(ActionRow::action.then(Action::color)))
+            .with_spacer(3.0)
+            .with_child(
+                truncated(|action: &Action, _| {
+                    ui::listed_name(action.as_ref(), action.archived, action.temporary)
+                })
+                .lens(ActionRow::action),
+            )
+            .padding(3.0)
+            .background(highlight(|row: &ActionRow| row.highlighted))
+            .on_click(|ctx, row: &mut ActionRow, _| {
+                ctx.submit_command(SELECT_ACTION.with(row.action.clone()));
+            })
+            .controller(RightClick::new(|ctx, row: &mut ActionRow, position| {
+                ctx.submit_command(SHOW_ACTION_MENU.with((row.action.clone(), position)));
+            }))
+            .controller(DragReorder::new(|ctx, row: &mut ActionRow, shift| {
+                ctx.submit_command(MOVE_ACTION.with((row.action.clone(), shift)));
+            }))
+            .align_horizontal(UnitPoint::CENTER)
+    }))
+    .vertical()
+    .lens(ActionRows)
+    .expand_width();
+    let subjects = Scroll::new(List::new(|| {
+        let row = Flex::row()
+            .with_child(favorite_mark(|row: &SubjectRow| row.favorite))
+            .with_child(color_dot().lens(SubjectRow::subject.then(Subject::color)))
+            .with_spacer(3.0)
+            .with_child(
+                truncated(|subject: &Subject, _| {
+                    ui::listed_name(subject.as_ref(), subject.archived, subject.temporary)
+                })
+                .lens(SubjectRow::subject),
+            )
+            .with_child(
+                Maybe::or_empty(
+                    Tooltip::new(progress_ring(), |progress: &f64, _| {
+                        Some(format!("{:.0}% of the estimate", progress * 100.0))
                     })
-                    .on_click(|ctx, data: &mut AppState, _| {
-                        match data.active {
-                            Some(_) => end_session(ctx, data),
-                            None => start_new_session(data),
-                        }
-                    }),
+                    .padding((3.0, 0.0, 0.0, 0.0)),
                 )
-                .padding((10.0, 10.0, 10.0, 5.0))
-                .controller(Ticker::new()),
+                .lens(SubjectRow::progress),
+            )
+            .padding(3.0)
+            .background(highlight(|row: &SubjectRow| row.highlighted))
+            .on_click(|ctx, row: &mut SubjectRow, _| {
+                ctx.submit_command(SELECT_SUBJECT.with(row.subject.clone()));
+            })
+            .controller(RightClick::new(|ctx, row: &mut SubjectRow, position| {
+                ctx.submit_command(SHOW_SUBJECT_MENU.with((row.subject.clone(), position)));
+            }))
+            .controller(DragReorder::new(|ctx, row: &mut SubjectRow, shift| {
+                ctx.submit_command(MOVE_SUBJECT.with((row.subject.clone(), shift)));
+            }));
+        Flex::row()
+            .with_child(tree_toggle())
+            .with_child(row)
+            .align_horizontal(UnitPoint::CENTER)
+    }))
+    .vertical()
+    .lens(SubjectRows)
+    .expand_width();
+    Flex::row()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_flex_child(
+            Flex::column()
+                .with_child(list_filter(
+                    Setup::action_filter,
+                    |data| filtered_actions(data).len(),
+                    |ctx, data, index| {
+                        let action = filtered_actions(data).swap_remove(index);
+                        ctx.submit_command(SELECT_ACTION.with(action));
+                    },
+                ))
+                .with_flex_child(actions, 1.0),
+            1.0,
         )
+        .with_flex_child(
+            Flex::column()
+                .with_child(list_filter(
+                    Setup::subject_filter,
+                    |data| filtered_subjects(data).len(),
+                    |ctx, data, index| {
+                        let subject = filtered_subjects(data).swap_remove(index);
+                        ctx.submit_command(SELECT_SUBJECT.with(subject));
+                    },
+                ))
+                .with_flex_child(subjects, 1.0),
+            1.0,
+        )
+        .expand_height()
+}
+
+/// A pinned topic together with the number key starting it.
+#[derive(Clone, Data)]
+struct Pin {
+    key: usize,
+    topic: Topic,
+}
+
+/// Read-only view of the pinned topics.
+struct Pins;
+
+impl Lens<AppState, Vector<Pin>> for Pins {
+    fn with<V, F: FnOnce(&Vector<Pin>) -> V>(&self, data: &AppState, f: F) -> V {
+        f(&pins(data))
+    }
+
+    fn with_mut<V, F: FnOnce(&mut Vector<Pin>) -> V>(&self, data: &mut AppState, f: F) -> V {
+        // Pins are started through commands, so changes to this copy are discarded.
+        f(&mut pins(data))
+    }
+}
+
+fn pins(data: &AppState) -> Vector<Pin> {
+    data.content
+        .pinned
+        .iter()
+        .enumerate()
+        .map(|(index, topic)| Pin {
+            key: index + 1,
+            topic: topic.clone(),
+        })
+        .collect()
+}
+
+/// Read-only view of the topics worked on last, the newest first.
+struct RecentTopics;
+
+impl Lens<AppState, Vector<Topic>> for RecentTopics {
+    fn with<V, F: FnOnce(&Vector<Topic>) -> V>(&self, data: &AppState, f: F) -> V {
+        f(&data.history.recent_topics(RECENT_TOPICS))
+    }
+
+    fn with_mut<V, F: FnOnce(&mut Vector<Topic>) -> V>(&self, data: &mut AppState, f: F) -> V {
+        f(&mut data.history.recent_topics(RECENT_TOPICS))
+    }
+}
+
+/// Resumes the latest topic in one click, or opens the list of the recent ones.
+fn recent() -> impl Widget<AppState> {
+    let buttons = Flex::row()
         .with_child(
-            Flex::row()
-                .main_axis_alignment(MainAxisAlignment::Center)
-                .with_child(selected_action_label().lens(Setup::selected_action))
-                .with_child(Label::new(""))
-                .with_child(selected_subject_label().lens(Setup::selected_subject))
-                .lens(AppState::setup),
+            Button::new("Resume last").on_click(|ctx, _, _| ctx.submit_command(RESUME_LAST)),
         )
+        .with_spacer(3.0)
+        .with_child(
+            Button::dynamic(|shown: &bool, _| match shown {
+                true => "▴".to_string(),
+                false => "▾".to_string(),
+            })
+            .on_click(|_, shown: &mut bool, _| *shown = !*shown)
+            .lens(AppState::setup.then(Setup::show_recent)),
+        );
+    let list = List::new(|| {
+        truncated(|topic: &Topic, _| {
+            format!("{} {}", topic.action.as_ref(), topic.subject.as_ref())
+        })
+        .padding(3.0)
+        .on_click(|ctx, topic: &mut Topic, _| ctx.submit_command(START_RECENT.with(topic.clone())))
+    })
+    .lens(RecentTopics);
+    Either::new(
+        |data: &AppState, _| data.history.iter().next().is_none(),
+        SizedBox::empty(),
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(buttons)
+            .with_child(Either::new(
+                |data: &AppState, _| data.setup.show_recent,
+                list,
+                SizedBox::empty(),
+            ))
+            .padding((10.0, 5.0, 10.0, 0.0)),
+    )
 }
 
-fn lists() -> impl Widget<AppState> {
+/// Whether the selected topic is pinned, `None` without a selected topic.
+struct SelectedPinned;
+
+impl Lens<AppState, Option<bool>> for SelectedPinned {
+    fn with<V, F: FnOnce(&Option<bool>) -> V>(&self, data: &AppState, f: F) -> V {
+        f(&selected_pinned(data))
+    }
+
+    fn with_mut<V, F: FnOnce(&mut Option<bool>) -> V>(&self, data: &mut AppState, f: F) -> V {
+        f(&mut selected_pinned(data))
+    }
+}
+
+fn selected_pinned(data: &AppState) -> Option<bool> {
+    selected_topic(data).map(|topic| data.content.pinned.contains(&topic))
+}
+
+fn pinned() -> impl Widget<AppState> {
     Flex::row()
-        .cross_axis_alignment(CrossAxisAlignment::Start)
         .with_flex_child(
-            Scroll::new(List::new(|| {
-                Label::dynamic(|action: &Action, _| action.as_ref().to_string())
+            Scroll::new(
+                List::new(|| {
+                    truncated(|pin: &Pin, _| {
+                        format!(
+                            "{} {} {}",
+                            pin.key,
+                            pin.topic.action.as_ref(),
+                            pin.topic.subject.as_ref()
+                        )
+                    })
                     .padding(3.0)
-                    .on_click(|ctx, action: &mut Action, _| {
-                        ctx.submit_command(SELECT_ACTION.with(action.clone()));
+                    .on_click(|ctx, pin: &mut Pin, _| {
+                        ctx.submit_command(START_PINNED.with(pin.key - 1));
                     })
-                    .align_horizontal(UnitPoint::CENTER)
-            }))
-            .vertical()
-            .lens(AppState::content.then(Content::actions))
+                })
+                .horizontal(),
+            )
+            .horizontal()
+            .lens(Pins)
             .expand_width(),
             1.0,
         )
-        .with_flex_child(
-            Scroll::new(List::new(|| {
-                Label::dynamic(|subject: &Subject, _| subject.as_ref().to_string())
-                    .padding(3.0)
-                    .on_click(|ctx, subject: &mut Subject, _| {
-                        ctx.submit_command(SELECT_SUBJECT.with(subject.clone()));
-                    })
-                    .align_horizontal(UnitPoint::CENTER)
+        .with_child(
+            Maybe::or_empty(
+                Button::dynamic(|pinned: &bool, _| match pinned {
+                    true => "Unpin".to_string(),
+                    false => "Pin".to_string(),
+                })
+                .on_click(|ctx, _, _| ctx.submit_command(TOGGLE_PIN)),
+            )
+            .lens(SelectedPinned),
+        )
+        .with_child(
+            Maybe::or_empty(
+                Button::new("Goal").on_click(|ctx, _, _| ctx.submit_command(EDIT_GOAL)),
+            )
+            .lens(SelectedPinned),
+        )
+        .padding((10.0, 0.0))
+}
+
+fn show_archived() -> impl Widget<AppState> {
+    Either::new(
+        |data: &AppState, _| {
+            data.content.actions.iter().any(|action| action.archived)
+                || data.content.subjects.iter().any(|subject| subject.archived)
+        },
+        Checkbox::new("Show archived")
+            .lens(AppState::setup.then(Setup::show_archived))
+            .padding((10.0, 5.0)),
+        SizedBox::empty(),
+    )
+}
+
+/// Asks which version of a session changed here and elsewhere to keep.
+fn conflict() -> impl Widget<AppState> {
+    Maybe::or_empty(
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(
+                Label::dynamic(|conflict: &Conflict, _| {
+                    let session = conflict.change.original();
+                    format!(
+                        "{} {} from {} was {} elsewhere since it was loaded here.",
+                        session.topic.action.as_ref(),
+                        session.topic.subject.as_ref(),
+                        session.started.format("%Y-%m-%d %H:%M"),
+                        if conflict.deleted {
+                            "deleted"
+                        } else {
+                            "changed"
+                        },
+                    )
+                })
+                .with_line_break_mode(LineBreaking::WordWrap),
+            )
+            .with_spacer(5.0)
+            .with_child(
+                Flex::row()
+                    .with_child(
+                        Button::new("Keep mine")
+                            .on_click(|ctx, _, _| ctx.submit_command(KEEP_MINE)),
+                    )
+                    .with_spacer(5.0)
+                    .with_child(
+                        Button::new("Load theirs")
+                            .on_click(|ctx, _, _| ctx.submit_command(LOAD_THEIRS)),
+                    ),
+            )
+            .padding(5.0)
+            .border(druid::theme::BORDER_LIGHT, 2.0)
+            .rounded(5.0)
+            .padding(10.0),
+    )
+    .lens(AppState::conflict)
+}
+
+/// The copies of the data file, to go back to one of them.
+fn snapshots() -> impl Widget<AppState> {
+    Maybe::or_empty(
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(Label::dynamic(
+                |snapshots: &Vector<Snapshot>, _| match snapshots.is_empty() {
+                    true => "No copies of the data file have been taken yet.".to_string(),
+                    false => "Restore the data as it was on".to_string(),
+                },
+            ))
+            .with_spacer(3.0)
+            .with_child(List::new(|| {
+                Flex::row()
+                    .with_flex_child(
+                        Label::dynamic(|snapshot: &Snapshot, _| snapshot.taken.to_string())
+                            .expand_width(),
+                        1.0,
+                    )
+                    .with_child(Button::new("Restore").on_click(
+                        |ctx, snapshot: &mut Snapshot, _| {
+                            ctx.submit_command(RESTORE_SNAPSHOT.with(snapshot.clone()))
+                        },
+                    ))
             }))
-            .vertical()
-            .lens(AppState::content.then(Content::subjects))
-            .expand_width(),
-            1.0,
+            .with_spacer(5.0)
+            .with_child(
+                Button::new("Cancel").on_click(|ctx, _, _| ctx.submit_command(DISMISS_SNAPSHOTS)),
+            )
+            .padding(5.0)
+            .border(druid::theme::BORDER_LIGHT, 2.0)
+            .rounded(5.0)
+            .padding(10.0),
+    )
+    .lens(AppState::snapshots)
+}
+
+/// Problems found when starting, each with a fix.
+fn findings() -> impl Widget<AppState> {
+    List::new(|| {
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(
+                Label::dynamic(|finding: &Finding, _| finding.message())
+                    .with_line_break_mode(LineBreaking::WordWrap),
+            )
+            .with_spacer(5.0)
+            .with_child(
+                Flex::row()
+                    .with_child(
+                        Button::dynamic(|finding: &Finding, _| finding.fix_label().to_string())
+                            .on_click(|ctx, finding: &mut Finding, _| {
+                                ctx.submit_command(FIX_FINDING.with(finding.clone()))
+                            }),
+                    )
+                    .with_spacer(5.0)
+                    .with_child(Button::new("Dismiss").on_click(
+                        |ctx, finding: &mut Finding, _| {
+                            ctx.submit_command(DISMISS_FINDING.with(finding.clone()))
+                        },
+                    )),
+            )
+            .padding(5.0)
+            .border(druid::theme::BORDER_LIGHT, 2.0)
+            .rounded(5.0)
+            .padding(10.0)
+    })
+    .lens(AppState::findings)
+}
+
+/// Suggests archiving the actions and subjects that have not been used for a while.
+fn cleanup() -> impl Widget<AppState> {
+    Maybe::or_empty(
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(Label::new("Not used for a long time, archive them?"))
+            .with_spacer(3.0)
+            .with_child(
+                List::new(|| {
+                    Flex::row()
+                        .with_flex_child(name_label().expand_width(), 1.0)
+                        .with_child(Button::new("Archive").on_click(
+                            |ctx, action: &mut Action, _| {
+                                ctx.submit_command(ARCHIVE_ACTION.with((action.clone(), true)))
+                            },
+                        ))
+                })
+                .lens(Cleanup::actions),
+            )
+            .with_child(
+                List::new(|| {
+                    Flex::row()
+                        .with_flex_child(name_label().expand_width(), 1.0)
+                        .with_child(Button::new("Archive").on_click(
+                            |ctx, subject: &mut Subject, _| {
+                                ctx.submit_command(ARCHIVE_SUBJECT.with((subject.clone(), true)))
+                            },
+                        ))
+                })
+                .lens(Cleanup::subjects),
+            )
+            .with_spacer(5.0)
+            .with_child(
+                Flex::row()
+                    .with_child(Button::new("Archive all").on_click(
+                        |ctx, cleanup: &mut Cleanup, _| {
+                            for action in &cleanup.actions {
+                                ctx.submit_command(ARCHIVE_ACTION.with((action.clone(), true)));
+                            }
+                            for subject in &cleanup.subjects {
+                                ctx.submit_command(ARCHIVE_SUBJECT.with((subject.clone(), true)));
+                            }
+                        },
+                    ))
+                    .with_spacer(5.0)
+                    .with_child(
+                        Button::new("Not now")
+                            .on_click(|ctx, _, _| ctx.submit_command(DISMISS_CLEANUP)),
+                    ),
+            )
+            .padding(5.0)
+            .border(druid::theme::BORDER_LIGHT, 2.0)
+            .rounded(5.0)
+            .padding(10.0),
+    )
+    .lens(AppState::cleanup)
+}
+
+/// Menu for archiving or deleting an action or subject. Only unused ones can
+/// be deleted, the others have to be archived to keep their history.
+fn item_menu(
+    archived: bool,
+    temporary: bool,
+    used: bool,
+    rename: impl Into<Command>,
+    mark_temporary: impl Into<Command>,
+    archive: impl Into<Command>,
+    delete: impl Into<Command>,
+) -> MenuDesc<AppState> {
+    let title = match archived {
+        true => LocalizedString::new("zeitig-menu-restore").with_placeholder("Restore"),
+        false => LocalizedString::new("zeitig-menu-archive").with_placeholder("Archive"),
+    };
+    MenuDesc::empty()
+        .append(MenuItem::new(
+            LocalizedString::new("zeitig-menu-rename").with_placeholder("Rename…"),
+            rename,
+        ))
+        .append(
+            MenuItem::new(
+                LocalizedString::new("zeitig-menu-temporary").with_placeholder("Temporary"),
+                mark_temporary,
+            )
+            .selected_if(move || temporary),
         )
-        .expand_height()
+        .append(MenuItem::new(title, archive))
+        .append(
+            MenuItem::new(
+                LocalizedString::new("zeitig-menu-delete").with_placeholder("Delete"),
+                delete,
+            )
+            .disabled_if(move || used),
+        )
+}
+
+/// Lists the entry before the others, checked if it already is.
+fn favorite_item(favorite: bool, toggle: impl Into<Command>) -> MenuItem<AppState> {
+    MenuItem::new(
+        LocalizedString::new("zeitig-menu-favorite").with_placeholder("Pin to top"),
+        toggle,
+    )
+    .selected_if(move || favorite)
+}
+
+/// Submenu merging an entry into one of the `others`, each given by its
+/// name and the command merging into it.
+fn merge_menu(others: impl Iterator<Item = (String, Command)>) -> MenuDesc<AppState> {
+    others.fold(
+        MenuDesc::new(
+            LocalizedString::new("zeitig-menu-merge-into").with_placeholder("Merge into"),
+        ),
+        |menu, (name, merge)| {
+            menu.append(MenuItem::new(
+                LocalizedString::new("zeitig-menu-merge-target").with_placeholder(name),
+                merge,
+            ))
+        },
+    )
+}
+
+/// Submenu nesting `subject` in another one, which cannot be nested in it in turn.
+fn parent_menu(data: &AppState, subject: &Subject) -> MenuDesc<AppState> {
+    let subjects = &data.content.subjects;
+    let current = subject.parent;
+    let top = MenuItem::new(
+        LocalizedString::new("zeitig-menu-top-level").with_placeholder("Top level"),
+        SET_SUBJECT_PARENT.with((subject.clone(), None)),
+    )
+    .selected_if(move || current.is_none());
+    data.content
+        .listed_subjects(false)
+        .iter()
+        .filter(|other| !hierarchy::within(subjects, other, subject.id))
+        .fold(
+            MenuDesc::new(
+                LocalizedString::new("zeitig-menu-nest-under").with_placeholder("Nest under"),
+            )
+            .append(top)
+            .append_separator(),
+            |menu, other| {
+                let id = other.id;
+                menu.append(
+                    MenuItem::new(
+                        LocalizedString::new("zeitig-menu-parent")
+                            .with_placeholder(hierarchy::path(subjects, other)),
+                        SET_SUBJECT_PARENT.with((subject.clone(), Some(id))),
+                    )
+                    .selected_if(move || current == Some(id)),
+                )
+            },
+        )
+}
+
+/// Submenu choosing one of the colors or none, marking the `current` one.
+fn color_menu(current: Option<u32>, set: impl Fn(Option<u32>) -> Command) -> MenuDesc<AppState> {
+    let none = MenuItem::new(
+        LocalizedString::new("zeitig-menu-color-none").with_placeholder("None"),
+        set(None),
+    )
+    .selected_if(move || current.is_none());
+    ui::COLORS.iter().fold(
+        MenuDesc::new(LocalizedString::new("zeitig-menu-color").with_placeholder("Color"))
+            .append(none)
+            .append_separator(),
+        |menu, (name, color)| {
+            menu.append(
+                MenuItem::new(*name, set(Some(*color)))
+                    .selected_if(move || current == Some(*color)),
+            )
+        },
+    )
+}
+
+/// The checklist of the selected subject.
+struct SelectedChecklist;
+
+impl Lens<AppState, Vector<ChecklistItem>> for SelectedChecklist {
+    fn with<V, F: FnOnce(&Vector<ChecklistItem>) -> V>(&self, data: &AppState, f: F) -> V {
+        match &data.setup.selected_subject {
+            Some(subject) => f(&data.content.checklist_of(subject)),
+            None => f(&Vector::new()),
+        }
+    }
+
+    fn with_mut<V, F: FnOnce(&mut Vector<ChecklistItem>) -> V>(
+        &self,
+        data: &mut AppState,
+        f: F,
+    ) -> V {
+        // Items are toggled through commands, so changes to this copy are discarded.
+        let mut items = match &data.setup.selected_subject {
+            Some(subject) => data.content.checklist_of(subject),
+            None => Vector::new(),
+        };
+        f(&mut items)
+    }
+}
+
+fn checklist() -> impl Widget<AppState> {
+    Either::new(
+        |data: &AppState, _| data.setup.selected_subject.is_some(),
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(
+                List::new(|| {
+                    Label::dynamic(|item: &ChecklistItem, _| {
+                        let mark = if item.done { "☑" } else { "☐" };
+                        format!("{} {}", mark, item.text)
+                    })
+                    .padding(2.0)
+                    .on_click(|ctx, item: &mut ChecklistItem, _| {
+                        ctx.submit_command(TOGGLE_CHECKLIST_ITEM.with(item.id));
+                    })
+                })
+                .lens(SelectedChecklist),
+            )
+            .with_child(
+                Flex::row()
+                    .with_flex_child(
+                        TextBox::new()
+                            .with_placeholder("New checklist item")
+                            .lens(AppState::setup.then(Setup::new_checklist_item))
+                            .controller(EnterController::new(add_checklist_item))
+                            .expand_width(),
+                        1.0,
+                    )
+                    .with_spacer(3.0)
+                    .with_child(
+                        Button::new("Add")
+                            .on_click(|ctx, data: &mut AppState, _| add_checklist_item(ctx, data)),
+                    ),
+            )
+            .padding((10.0, 5.0)),
+        SizedBox::empty(),
+    )
 }
 
 fn dialogs() -> impl Widget<AppState> {
@@ -184,13 +2725,47 @@ fn dialogs() -> impl Widget<AppState> {
     fn handle_advance(ctx: &mut EventCtx, data: &mut AppState, cmd: &Command) {
         if let Some(creating) = cmd.get(ADVANCE) {
             if creating == &Creating::Nothing {
+                let content = &data.content;
+                // Names differing only in case are treated as the existing entry.
                 match &data.setup.creating {
-                    Creating::Action(a) => {
+                    Creating::Action(a) if collation::find(&content.actions, a).is_none() => {
                         ctx.submit_command(backend_msg::ADD_ACTION.with(a.to_owned()));
                     }
-                    Creating::Subject(s) => {
+                    Creating::Subject(s) if collation::find(&content.subjects, s).is_none() => {
                         ctx.submit_command(backend_msg::ADD_SUBJECT.with(s.to_owned()));
                     }
+                    Creating::Context(c) if collation::find(&content.contexts, c).is_none() => {
+                        ctx.submit_command(backend_msg::ADD_CONTEXT.with(c.to_owned()));
+                    }
+                    Creating::RenameAction(renaming) => rename_action(ctx, data, renaming.clone()),
+                    Creating::RenameSubject(renaming) => {
+                        rename_subject(ctx, data, renaming.clone())
+                    }
+                    Creating::IssueUrl(editing) => set_issue_url(ctx, data, editing.clone()),
+                    Creating::Estimate(editing) => {
+                        if !set_estimate(ctx, data, editing.clone()) {
+                            return;
+                        }
+                    }
+                    Creating::Rate(editing) => {
+                        if !set_rate(ctx, data, editing.clone()) {
+                            return;
+                        }
+                    }
+                    Creating::IdleRule(editing) => {
+                        if !set_idle_rule(ctx, data, editing.clone()) {
+                            return;
+                        }
+                    }
+                    Creating::Tag(t) if collation::find(&content.tags, t).is_none() => {
+                        ctx.submit_command(backend_msg::ADD_TAG.with(t.to_owned()));
+                    }
+                    Creating::Note(annotation) => annotate(ctx, data, annotation.clone()),
+                    Creating::Goal(input) => {
+                        if !set_goal(ctx, data, input.clone()) {
+                            return;
+                        }
+                    }
                     _ => {}
                 }
             }
@@ -207,6 +2782,18 @@ fn dialogs() -> impl Widget<AppState> {
                 let subject = cmd.get_unchecked(backend_msg::SUBJECT_ADDED).clone();
                 data.content.subjects.insert_ord(subject);
             }
+            _ if cmd.is(backend_msg::CONTEXT_ADDED) => {
+                let context = cmd.get_unchecked(backend_msg::CONTEXT_ADDED).clone();
+                data.content.contexts.insert_ord(context);
+            }
+            _ if cmd.is(backend_msg::TAG_ADDED) => {
+                let tag = cmd.get_unchecked(backend_msg::TAG_ADDED).clone();
+                data.content.tags.insert_ord(tag);
+            }
+            _ if cmd.is(backend_msg::CHECKLIST_ITEM_ADDED) => {
+                let item = cmd.get_unchecked(backend_msg::CHECKLIST_ITEM_ADDED).clone();
+                data.content.checklist.push_back(item);
+            }
             _ => {}
         }
     }
@@ -223,43 +2810,222 @@ fn dialogs() -> impl Widget<AppState> {
             .rounded(5.0)
             .padding(10.0)
     }
+    fn goal_input() -> impl Widget<GoalInput> {
+        let period = |label: &'static str, period: Period| {
+            Button::dynamic(move |input: &GoalInput, _| match input.period == period {
+                true => format!("[{}]", label),
+                false => label.to_string(),
+            })
+            .on_click(move |_, input: &mut GoalInput, _| input.period = period)
+        };
+        Flex::column()
+            .with_child(
+                Flex::row()
+                    .with_flex_child(
+                        TextBox::new()
+                            .with_placeholder("Hours, empty to remove")
+                            .controller(EnterController::new(|ctx, _| finish(ctx)))
+                            .lens(GoalInput::hours)
+                            .expand_width(),
+                        1.0,
+                    )
+                    .with_spacer(3.0)
+                    .with_child(Button::new("Save").on_click(|ctx, _, _| finish(ctx))),
+            )
+            .with_spacer(3.0)
+            .with_child(
+                Flex::row()
+                    .with_child(period("Daily", Period::Day))
+                    .with_spacer(3.0)
+                    .with_child(period("Weekly", Period::Week))
+                    .with_spacer(3.0)
+                    .with_child(period("Monthly", Period::Month)),
+            )
+            .with_child(Label::dynamic(|input: &GoalInput, _| input.status.clone()))
+    }
+    fn palette_input() -> impl Widget<Palette> {
+        /// Read-only view of the entries matching what is typed.
+        struct Matches;
+
+        impl Lens<Palette, Vector<PaletteEntry>> for Matches {
+            fn with<V, F: FnOnce(&Vector<PaletteEntry>) -> V>(&self, data: &Palette, f: F) -> V {
+                f(&data.matches())
+            }
+
+            fn with_mut<V, F: FnOnce(&mut Vector<PaletteEntry>) -> V>(
+                &self,
+                data: &mut Palette,
+                f: F,
+            ) -> V {
+                f(&mut data.matches())
+            }
+        }
+
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(
+                TextBox::new()
+                    .with_placeholder("Type to search, Enter runs the first")
+                    .controller(FocusOn::new(FOCUS_PALETTE))
+                    .lens(Palette::query)
+                    .controller(EnterController::new(|ctx, palette: &mut Palette| {
+                        if let Some(entry) = palette.matches().pop_front() {
+                            ctx.submit_command(RUN_PALETTE.with(entry.command));
+                        }
+                    }))
+                    .expand_width(),
+            )
+            .with_spacer(3.0)
+            .with_child(
+                List::new(|| {
+                    Label::dynamic(|entry: &PaletteEntry, _| entry.label.to_string())
+                        .padding(3.0)
+                        .on_click(|ctx, entry: &mut PaletteEntry, _| {
+                            ctx.submit_command(RUN_PALETTE.with(entry.command.clone()))
+                        })
+                })
+                .lens(Matches),
+            )
+    }
+    fn tag_choice() -> impl Widget<TagChoice> {
+        Button::dynamic(|choice: &TagChoice, _| match choice.chosen {
+            true => format!("[{}]", choice.tag.name),
+            false => choice.tag.name.to_string(),
+        })
+        .on_click(|_, choice: &mut TagChoice, _| choice.chosen = !choice.chosen)
+        .align_left()
+    }
+    fn name_input(confirm: &str) -> impl Widget<String> {
+        Flex::row()
+            .with_flex_child(
+                TextBox::new()
+                    .controller(EnterController::new(|ctx, _| finish(ctx)))
+                    .expand_width(),
+                1.0,
+            )
+            .with_spacer(3.0)
+            .with_child(Button::new(confirm).on_click(|ctx, _, _| finish(ctx)))
+    }
     Creator::new()
         .nothing(SizedBox::empty())
         .choosing(base(
             "What to add?",
-            Flex::row()
-                .with_child(Button::new("Action").on_click(|ctx, _, _| {
-                    ctx.submit_command(ADVANCE.with(Creating::Action(String::new())))
-                }))
+            Flex::column()
+                .with_child(
+                    Flex::row()
+                        .with_child(Button::new("Action").on_click(|ctx, _, _| {
+                            ctx.submit_command(ADVANCE.with(Creating::Action(String::new())))
+                        }))
+                        .with_spacer(5.0)
+                        .with_child(Button::new("Subject").on_click(|ctx, _, _| {
+                            ctx.submit_command(ADVANCE.with(Creating::Subject(String::new())))
+                        }))
+                        .with_spacer(5.0)
+                        .with_child(Button::new("Context").on_click(|ctx, _, _| {
+                            ctx.submit_command(ADVANCE.with(Creating::Context(String::new())))
+                        }))
+                        .with_spacer(5.0)
+                        .with_child(Button::new("Tag").on_click(|ctx, _, _| {
+                            ctx.submit_command(ADVANCE.with(Creating::Tag(String::new())))
+                        })),
+                )
                 .with_spacer(5.0)
-                .with_child(Button::new("Subject").on_click(|ctx, _, _| {
-                    ctx.submit_command(ADVANCE.with(Creating::Subject(String::new())))
+                .with_child(Button::new("Import from file").on_click(|ctx, _, _| {
+                    ctx.submit_command(IMPORT.with(ImportKind::Topics));
+                    finish(ctx);
                 })),
         ))
-        .action(base(
-            "Add new action:",
-            Flex::row()
-                .with_flex_child(
-                    TextBox::new()
-                        .controller(EnterController::new(|ctx, _| finish(ctx)))
-                        .expand_width(),
-                    1.0,
-                )
-                .with_spacer(3.0)
-                .with_child(Button::new("Add").on_click(|ctx, _, _| finish(ctx))),
+        .action(base("Add new action:", name_input("Add")))
+        .subject(base("Add new subject:", name_input("Add")))
+        .context(base("Add new context:", name_input("Add")))
+        .tag(base("Add new tag:", name_input("Add")))
+        .rename_action(base(
+            "Rename action:",
+            name_input("Rename").lens(lens!(Renaming<Action>, name)),
         ))
-        .subject(base(
-            "Add new subject:",
-            Flex::row()
-                .with_flex_child(
-                    TextBox::new()
-                        .controller(EnterController::new(|ctx, _| finish(ctx)))
-                        .expand_width(),
-                    1.0,
-                )
-                .with_spacer(3.0)
-                .with_child(Button::new("Add").on_click(|ctx, _, _| finish(ctx))),
+        .rename_subject(base(
+            "Rename subject:",
+            name_input("Rename").lens(lens!(Renaming<Subject>, name)),
+        ))
+        .issue_url(base(
+            "Link issue references to, {id} is replaced:",
+            name_input("Save").lens(lens!(Renaming<Subject>, name)),
         ))
+        .estimate(base(
+            "Hours expected in total, empty to remove:",
+            name_input("Save").lens(lens!(Renaming<Subject>, name)),
+        ))
+        .rate(base(
+            "Hourly rate the time is billed at, empty if it is not billable:",
+            name_input("Save").lens(lens!(Renaming<Subject>, name)),
+        ))
+        .idle_rule(base(
+            "When idle: ignore, ask 10 or pause 5 (minutes), empty for the default:",
+            name_input("Save").lens(lens!(Renaming<Subject>, name)),
+        ))
+        .note(base(
+            "What was done?",
+            Flex::column()
+                .with_child(name_input("Save").lens(Annotation::note))
+                .with_child(List::new(tag_choice).lens(Annotation::tags)),
+        ))
+        .goal(base("Goal for the selected topic:", goal_input()))
+        .archive(base(
+            "Archive subject:",
+            Flex::column()
+                .with_child(Label::dynamic(|subject: &Subject, _| {
+                    format!("Keep a final report with {}?", subject.name)
+                }))
+                .with_spacer(5.0)
+                .with_child(
+                    Flex::row()
+                        .with_child(Button::new("With report").on_click(
+                            |ctx, subject: &mut Subject, _| {
+                                ctx.submit_command(ARCHIVE_WITH_REPORT.with(subject.clone()));
+                                finish(ctx);
+                            },
+                        ))
+                        .with_spacer(3.0)
+                        .with_child(Button::new("Just archive").on_click(
+                            |ctx, subject: &mut Subject, _| {
+                                ctx.submit_command(ARCHIVE_SUBJECT.with((subject.clone(), true)));
+                                finish(ctx);
+                            },
+                        ))
+                        .with_spacer(3.0)
+                        .with_child(Button::new("Cancel").on_click(|ctx, _, _| finish(ctx))),
+                ),
+        ))
+        .final_report(base(
+            "Final report:",
+            Flex::column()
+                .with_child(Label::dynamic(|subject: &Subject, _| {
+                    let report = subject.final_report.as_deref().unwrap_or_default();
+                    format!("{}\n{}", subject.name, report)
+                }))
+                .with_spacer(5.0)
+                .with_child(Button::new("Close").on_click(|ctx, _, _| finish(ctx))),
+        ))
+        .confirm(base(
+            "Please confirm:",
+            Flex::column()
+                .with_child(Label::dynamic(|confirming: &Confirming, _| {
+                    confirming.question()
+                }))
+                .with_spacer(5.0)
+                .with_child(
+                    Flex::row()
+                        .with_child(Button::new("Yes").on_click(
+                            |ctx, confirming: &mut Confirming, _| {
+                                ctx.submit_command(CONFIRMED.with(confirming.clone()));
+                                finish(ctx);
+                            },
+                        ))
+                        .with_spacer(3.0)
+                        .with_child(Button::new("No").on_click(|ctx, _, _| finish(ctx))),
+                ),
+        ))
+        .palette(base("Go to:", palette_input()))
         .lens(AppState::setup.then(Setup::creating))
         .controller(CommandReceiver::new(handle_advance))
         .controller(CommandReceiver::new(handle_creation))
@@ -281,12 +3047,31 @@ fn buttons() -> impl Widget<AppState> {
         )
         .with_flex_child(
             Button::new("Insights")
-                .on_click(|_ctx, _data: &mut AppState, _| {
-                    //if data.insights.is_none() {
-                    //    data.insights = Some(Insights::generate(data));
-                    //}
-                    //ctx.new_window(WindowDesc::new(ui::insights).title("Insights"));
-                })
+                .on_click(|ctx, data, _| open_insights(ctx, data))
+                .expand_width(),
+            1.0,
+        )
+        .with_flex_child(
+            Button::new("Reports")
+                .on_click(|ctx, data, _| open_reports(ctx, data))
+                .expand_width(),
+            1.0,
+        )
+        .with_flex_child(
+            Button::new("Stats")
+                .on_click(|ctx, data, _| open_stats(ctx, data))
+                .expand_width(),
+            1.0,
+        )
+        .with_flex_child(
+            Button::new("History")
+                .on_click(|ctx, _, _| open_history(ctx))
+                .expand_width(),
+            1.0,
+        )
+        .with_flex_child(
+            Button::new("Manage")
+                .on_click(|ctx, data, _| open_manage(ctx, data))
                 .expand_width(),
             1.0,
         )
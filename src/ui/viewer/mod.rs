@@ -0,0 +1,64 @@
+//! The window of the read-only mode, looking at the history and reports of a
+//! file without tracking anything or changing it.
+
+use crate::{
+    controller::{BackendController, CloseController},
+    state::{insights::Insights, paths, report::Report, AppState, Startup},
+    ui,
+};
+use druid::{
+    widget::{Button, Either, Flex, Label},
+    Widget, WidgetExt, WindowDesc,
+};
+
+pub fn window() -> WindowDesc<AppState> {
+    WindowDesc::new(ui)
+        .title(format!("{} (read-only)", ui::title()))
+        .window_size((400.0, 500.0))
+}
+
+pub fn ui() -> impl Widget<AppState> {
+    Either::new(
+        |data: &AppState, _| data.startup == Startup::Ready,
+        viewer(),
+        Label::dynamic(|data: &AppState, _| match &data.startup {
+            Startup::Failed(err) => format!("Failed to load data: {}", err),
+            _ => "Loading…".to_string(),
+        })
+        .center(),
+    )
+    .env_scope(ui::apply_settings)
+    .controller(BackendController::new())
+    .controller(CloseController::new())
+}
+
+fn viewer() -> impl Widget<AppState> {
+    let file = paths::read_only()
+        .map(|file| file.display().to_string())
+        .unwrap_or_default();
+    Flex::column()
+        .with_child(Label::new(format!("Looking at {}", file)).padding(5.0))
+        .with_child(
+            Flex::row()
+                .with_flex_child(
+                    Button::new("Insights")
+                        .on_click(|ctx, data: &mut AppState, _| {
+                            data.insights = Some(Insights::generate(data));
+                            ctx.new_window(WindowDesc::new(ui::insights).title("Insights"));
+                        })
+                        .expand_width(),
+                    1.0,
+                )
+                .with_flex_child(
+                    Button::new("Reports")
+                        .on_click(|ctx, data: &mut AppState, _| {
+                            data.report = Some(Report::generate(data));
+                            ctx.new_window(WindowDesc::new(ui::report).title("Reports"));
+                        })
+                        .expand_width(),
+                    1.0,
+                )
+                .padding(5.0),
+        )
+        .with_flex_child(ui::history(), 1.0)
+}
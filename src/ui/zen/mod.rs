@@ -0,0 +1,70 @@
+use druid::{
+    commands,
+    widget::{Button, Flex, Label, MainAxisAlignment},
+    Color, Selector, Target, Widget, WidgetExt, WindowDesc, WindowState,
+};
+
+use crate::{
+    controller::CommandReceiver,
+    state::AppState,
+    ui::{self, tracker},
+};
+
+/// Opens focus mode from the tracker and closes it again from within.
+pub const TOGGLE: Selector = Selector::new("zeitig.zen.toggle");
+
+const BACKGROUND: Color = Color::rgb8(0x10, 0x10, 0x10);
+const FOREGROUND: Color = Color::rgb8(0xd0, 0xd0, 0xd0);
+
+pub fn window() -> WindowDesc<AppState> {
+    WindowDesc::new(ui)
+        .title("Zeitig")
+        .menu(ui::menu())
+        .show_titlebar(false)
+        .set_window_state(WindowState::MAXIMIZED)
+}
+
+pub fn ui() -> impl Widget<AppState> {
+    Flex::column()
+        .main_axis_alignment(MainAxisAlignment::Center)
+        .with_child(
+            Label::dynamic(|data: &AppState, _| match &data.active {
+                Some(session) => session.duration.format(data.content.settings.live_format()),
+                None => "Not running".to_string(),
+            })
+            .with_text_size(96.0)
+            .with_text_color(FOREGROUND),
+        )
+        .with_spacer(10.0)
+        .with_child(
+            Label::dynamic(|data: &AppState, _| {
+                match (&data.setup.selected_action, &data.setup.selected_subject) {
+                    (Some(action), Some(subject)) => {
+                        format!("{} {}", action.as_ref(), subject.as_ref())
+                    }
+                    _ => String::new(),
+                }
+            })
+            .with_text_size(24.0)
+            .with_text_color(FOREGROUND),
+        )
+        .with_spacer(30.0)
+        .with_child(
+            Flex::row()
+                .with_child(Button::new("Stop").on_click(|ctx, _, _| {
+                    ctx.submit_command(tracker::END_SESSION.to(Target::Global));
+                }))
+                .with_spacer(10.0)
+                .with_child(
+                    Button::new("Leave focus mode")
+                        .on_click(|ctx, _, _| ctx.submit_command(commands::CLOSE_WINDOW)),
+                ),
+        )
+        .expand()
+        .background(BACKGROUND)
+        .controller(CommandReceiver::new(|ctx, _, cmd| {
+            if cmd.is(TOGGLE) {
+                ctx.submit_command(commands::CLOSE_WINDOW);
+            }
+        }))
+}
@@ -0,0 +1,107 @@
+use druid::{
+    kurbo::{BezPath, Circle, Line, Rect},
+    theme,
+    widget::Painter,
+    Color, Data, Env, Point, RenderContext, Widget, WidgetExt,
+};
+use im::Vector;
+use std::f64::consts::PI;
+
+const PIE: f64 = 160.0;
+const BARS_HEIGHT: f64 = 120.0;
+/// Angle covered by each straight piece of a slice's edge.
+const STEP: f64 = PI / 90.0;
+
+/// A slice of the pie or a bar.
+#[derive(Clone, Data)]
+pub struct ChartPart {
+    pub value: f64,
+    /// As `0xRRGGBB`, the primary color of the theme without one.
+    pub color: Option<u32>,
+}
+
+impl ChartPart {
+    fn color(&self, env: &Env) -> Color {
+        match self.color {
+            Some(color) => Color::from_rgba32_u32(color << 8 | 0xff),
+            None => env.get(theme::PRIMARY_LIGHT),
+        }
+    }
+}
+
+fn slice(center: Point, radius: f64, start: f64, sweep: f64) -> BezPath {
+    let point = |angle: f64| {
+        Point::new(
+            center.x + radius * angle.cos(),
+            center.y + radius * angle.sin(),
+        )
+    };
+    let mut path = BezPath::new();
+    path.move_to(center);
+    let steps = (sweep / STEP).ceil().max(1.0) as usize;
+    for step in 0..=steps {
+        path.line_to(point(start + sweep * step as f64 / steps as f64));
+    }
+    path.close_path();
+    path
+}
+
+/// The parts as slices of a circle, clockwise from the top.
+pub fn pie_chart() -> impl Widget<Vector<ChartPart>> {
+    Painter::new(|ctx, parts: &Vector<ChartPart>, env| {
+        let center = ctx.size().to_rect().center();
+        let radius = PIE / 2.0 - 1.0;
+        let total: f64 = parts.iter().map(|part| part.value.max(0.0)).sum();
+        if total <= 0.0 {
+            ctx.fill(
+                Circle::new(center, radius),
+                &env.get(theme::BACKGROUND_LIGHT),
+            );
+            return;
+        }
+        let mut start = -PI / 2.0;
+        for part in parts.iter().filter(|part| part.value > 0.0) {
+            let sweep = 2.0 * PI * part.value / total;
+            ctx.fill(slice(center, radius, start, sweep), &part.color(env));
+            start += sweep;
+        }
+    })
+    .fix_size(PIE, PIE)
+}
+
+/// The parts as bars side by side over the whole width, the highest one
+/// reaching the top.
+pub fn bar_chart() -> impl Widget<Vector<ChartPart>> {
+    Painter::new(|ctx, parts: &Vector<ChartPart>, env| {
+        let size = ctx.size();
+        let most = parts
+            .iter()
+            .map(|part| part.value)
+            .fold(0.0, f64::max)
+            .max(f64::EPSILON);
+        let width = size.width / parts.len().max(1) as f64;
+        // Leave a gap between bars while they are wide enough for one.
+        let gap = if width > 4.0 { 1.0 } else { 0.0 };
+        for (index, part) in parts.iter().enumerate() {
+            if part.value <= 0.0 {
+                continue;
+            }
+            let height = size.height * part.value / most;
+            let left = width * index as f64;
+            let bar = Rect::new(
+                left + gap,
+                size.height - height,
+                left + width - gap,
+                size.height,
+            );
+            ctx.fill(bar, &part.color(env));
+        }
+        ctx.stroke(
+            Line::new((0.0, size.height), (size.width, size.height)),
+            &env.get(theme::BORDER_DARK),
+            1.0,
+        );
+    })
+    .fix_height(BARS_HEIGHT)
+    .expand_width()
+}
@@ -0,0 +1,40 @@
+use druid::{kurbo::Rect, theme, widget::Painter, Color, RenderContext, Widget, WidgetExt};
+use im::Vector;
+
+const CELL: f64 = 10.0;
+const GAP: f64 = 2.0;
+/// Weeks shown, enough for a year starting on a monday.
+const WEEKS: usize = 53;
+
+/// Minutes per day as squares in columns of weeks, the first day being a
+/// monday. The more minutes, the stronger the color, which is the one given
+/// for the day or the theme's.
+pub fn heatmap() -> impl Widget<Vector<(u32, Option<u32>)>> {
+    Painter::new(|ctx, days: &Vector<(u32, Option<u32>)>, env| {
+        let most = days
+            .iter()
+            .map(|(minutes, _)| *minutes)
+            .max()
+            .unwrap_or(0)
+            .max(1) as f64;
+        let empty = env.get(theme::BACKGROUND_LIGHT);
+        for (index, (minutes, color)) in days.iter().enumerate() {
+            let filled = match color {
+                Some(color) => Color::from_rgba32_u32(color << 8 | 0xff),
+                None => env.get(theme::PRIMARY_LIGHT),
+            };
+            let (week, weekday) = (index / 7, index % 7);
+            let origin = ((CELL + GAP) * week as f64, (CELL + GAP) * weekday as f64);
+            let cell = Rect::from_origin_size(origin, (CELL, CELL));
+            match *minutes {
+                0 => ctx.fill(cell, &empty),
+                minutes => {
+                    // Any time at all should be visible, even next to long days.
+                    let strength = 0.25 + 0.75 * minutes as f64 / most;
+                    ctx.fill(cell, &filled.clone().with_alpha(strength));
+                }
+            }
+        }
+    })
+    .fix_size((CELL + GAP) * WEEKS as f64, (CELL + GAP) * 7.0)
+}
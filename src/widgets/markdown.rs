@@ -0,0 +1,194 @@
+use druid::{
+    text::{RichText, RichTextBuilder},
+    widget::{prelude::*, LineBreaking, RawLabel},
+    Data, FontStyle, FontWeight, Selector,
+};
+use std::sync::Arc;
+
+/// Submitted with the url when a link in rendered Markdown is clicked.
+pub const OPEN_URL: Selector<String> = Selector::new("zeitig.markdown.open_url");
+
+/// Text written in lightweight Markdown and where its issue references link to.
+#[derive(Clone, Data)]
+pub struct Note {
+    pub text: Arc<str>,
+    /// Template for issue references like `PROJ-123` or `#456`, with `{id}`
+    /// standing for the reference without a leading `#`.
+    pub issue_url: Option<Arc<str>>,
+}
+
+/// Shows a note written in lightweight Markdown: `**bold**`, `*italic*`,
+/// list items starting with `- ` or `* ` and `[label](url)` links.
+/// Bare urls and issue references are linked as well.
+/// The note is only rendered again when it changes.
+pub struct Markdown {
+    rendered: RichText,
+    label: RawLabel<RichText>,
+}
+
+impl Markdown {
+    pub fn new() -> Markdown {
+        Markdown {
+            rendered: RichTextBuilder::new().build(),
+            label: RawLabel::new().with_line_break_mode(LineBreaking::WordWrap),
+        }
+    }
+}
+
+impl Widget<Note> for Markdown {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut Note, env: &Env) {
+        self.label.event(ctx, event, &mut self.rendered, env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &Note, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.rendered = render(data);
+        }
+        self.label.lifecycle(ctx, event, &self.rendered, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &Note, data: &Note, env: &Env) {
+        if !old_data.same(data) {
+            let old = std::mem::replace(&mut self.rendered, render(data));
+            self.label.update(ctx, &old, &self.rendered, env);
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &Note,
+        env: &Env,
+    ) -> Size {
+        self.label.layout(ctx, bc, &self.rendered, env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &Note, env: &Env) {
+        self.label.paint(ctx, &self.rendered, env);
+    }
+}
+
+fn render(note: &Note) -> RichText {
+    let issue_url = note.issue_url.as_deref();
+    let mut builder = RichTextBuilder::new();
+    for (index, line) in note.text.lines().enumerate() {
+        if index > 0 {
+            builder.push("\n");
+        }
+        let item = line.trim_start();
+        match item.strip_prefix("- ").or_else(|| item.strip_prefix("* ")) {
+            Some(rest) => {
+                builder.push(&line[..line.len() - item.len()]);
+                builder.push("• ");
+                push_inline(&mut builder, rest, issue_url);
+            }
+            None => push_inline(&mut builder, line, issue_url),
+        }
+    }
+    builder.build()
+}
+
+fn push_inline(builder: &mut RichTextBuilder, mut text: &str, issue_url: Option<&str>) {
+    while !text.is_empty() {
+        let plain = text.find(|c| c == '*' || c == '[').unwrap_or(text.len());
+        if plain > 0 {
+            push_plain(builder, &text[..plain], issue_url);
+            text = &text[plain..];
+        } else if let Some((bold, rest)) = enclosed(text, "**", "**") {
+            builder.push(bold).weight(FontWeight::BOLD);
+            text = rest;
+        } else if let Some((italic, rest)) = enclosed(text, "*", "*") {
+            builder.push(italic).style(FontStyle::Italic);
+            text = rest;
+        } else if let Some((label, url, rest)) = link(text) {
+            builder
+                .push(label)
+                .underline(true)
+                .link(OPEN_URL.with(url.to_string()));
+            text = rest;
+        } else {
+            // Markers without a counterpart are shown as they are.
+            builder.push(&text[..1]);
+            text = &text[1..];
+        }
+    }
+}
+
+/// Splits text starting with `open` at the next `close` into the
+/// non-empty part in between and the rest after `close`.
+fn enclosed<'a>(text: &'a str, open: &str, close: &str) -> Option<(&'a str, &'a str)> {
+    let text = text.strip_prefix(open)?;
+    let end = text.find(close).filter(|&end| end > 0)?;
+    Some((&text[..end], &text[end + close.len()..]))
+}
+
+fn link(text: &str) -> Option<(&str, &str, &str)> {
+    let (label, rest) = enclosed(text, "[", "](")?;
+    let (url, rest) = enclosed(rest, "", ")")?;
+    Some((label, url, rest))
+}
+
+/// Pushes text without markup, linking the urls and issue references in it.
+fn push_plain(builder: &mut RichTextBuilder, text: &str, issue_url: Option<&str>) {
+    let mut pending = 0;
+    let mut at = 0;
+    while let Some(next) = text[at..].chars().next() {
+        // Links only start at the beginning of a word.
+        let starts_word = !text[..at].ends_with(|c: char| c.is_alphanumeric());
+        match autolink(&text[at..], issue_url).filter(|_| starts_word) {
+            Some((length, url)) => {
+                builder.push(&text[pending..at]);
+                builder
+                    .push(&text[at..at + length])
+                    .underline(true)
+                    .link(OPEN_URL.with(url));
+                at += length;
+                pending = at;
+            }
+            None => at += next.len_utf8(),
+        }
+    }
+    builder.push(&text[pending..]);
+}
+
+/// Length and target of the url or issue reference `text` starts with.
+fn autolink(text: &str, issue_url: Option<&str>) -> Option<(usize, String)> {
+    if let Some(scheme) = ["https://", "http://"]
+        .iter()
+        .find(|s| text.starts_with(*s))
+    {
+        let end = text.find(char::is_whitespace).unwrap_or(text.len());
+        // Punctuation after a url most likely belongs to the sentence.
+        let url = text[..end].trim_end_matches(|c| ".,:;!?)'\"".contains(c));
+        return Some((url.len(), url.to_string())).filter(|_| url.len() > scheme.len());
+    }
+    let template = issue_url?;
+    let reference = issue_reference(text)?;
+    let id = reference.trim_start_matches('#');
+    Some((reference.len(), template.replace("{id}", id)))
+}
+
+/// The issue reference like `PROJ-123` or `#456` that `text` starts with.
+fn issue_reference(text: &str) -> Option<&str> {
+    let number = |text: &str| {
+        text.find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(text.len())
+    };
+    let prefix = if text.starts_with('#') {
+        1
+    } else {
+        let key = text
+            .find(|c: char| !c.is_ascii_uppercase() && !c.is_ascii_digit())
+            .unwrap_or(text.len());
+        if !text.starts_with(|c: char| c.is_ascii_uppercase()) || !text[key..].starts_with('-') {
+            return None;
+        }
+        key + 1
+    };
+    let end = prefix + number(&text[prefix..]);
+    if end == prefix || text[end..].starts_with(|c: char| c.is_alphanumeric()) {
+        return None;
+    }
+    Some(&text[..end])
+}
@@ -1,2 +1,26 @@
 mod maybe;
 pub use maybe::Maybe;
+
+mod tooltip;
+pub use tooltip::Tooltip;
+
+mod name;
+pub use name::{name_label, truncated, NAME_LENGTH};
+
+mod markdown;
+pub use markdown::{Markdown, Note, OPEN_URL};
+
+mod ring;
+pub use ring::progress_ring;
+
+mod heatmap;
+pub use heatmap::heatmap;
+
+mod swatch;
+pub use swatch::color_dot;
+
+mod timeline;
+pub use timeline::{week_timeline, TimelineBlock};
+
+mod charts;
+pub use charts::{bar_chart, pie_chart, ChartPart};
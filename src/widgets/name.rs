@@ -0,0 +1,42 @@
+use druid::{widget::Label, Data, Env, Key, Widget};
+use std::rc::Rc;
+
+use super::Tooltip;
+
+/// Characters shown of a name before it is shortened, 0 to never shorten.
+pub const NAME_LENGTH: Key<f64> = Key::new("zeitig.name_length");
+
+/// Shortens `text` to `max` characters by replacing its middle with an ellipsis,
+/// so both the start and the distinguishing end of a name stay visible.
+pub fn truncate_middle(text: &str, max: usize) -> Option<String> {
+    let length = text.chars().count();
+    if max == 0 || length <= max {
+        return None;
+    }
+    let kept = max.saturating_sub(1);
+    let head: String = text.chars().take((kept + 1) / 2).collect();
+    let tail: String = text.chars().skip(length - kept / 2).collect();
+    Some(format!("{}…{}", head, tail))
+}
+
+/// A label that shortens its text to `NAME_LENGTH` and shows it in full on hover.
+pub fn truncated<T: Data>(text: impl Fn(&T, &Env) -> String + 'static) -> impl Widget<T> {
+    let text = Rc::new(text);
+    let full = text.clone();
+    let shorten = |text: &str, env: &Env| truncate_middle(text, env.get(NAME_LENGTH) as usize);
+    Tooltip::new(
+        Label::dynamic(move |data: &T, env| {
+            let text = text(data, env);
+            shorten(&text, env).unwrap_or(text)
+        }),
+        move |data: &T, env: &Env| {
+            let text = full(data, env);
+            shorten(&text, env).map(|_| text)
+        },
+    )
+}
+
+/// A truncated label showing the name of an action, subject or context.
+pub fn name_label<T: Data + AsRef<str>>() -> impl Widget<T> {
+    truncated(|name: &T, _| name.as_ref().to_string())
+}
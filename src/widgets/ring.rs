@@ -0,0 +1,32 @@
+use druid::{
+    kurbo::{Arc, Circle, Vec2},
+    theme,
+    widget::Painter,
+    RenderContext, Widget, WidgetExt,
+};
+use std::f64::consts::PI;
+
+const SIZE: f64 = 14.0;
+const STROKE: f64 = 2.5;
+
+/// A small ring filling up clockwise with a share between 0 and 1.
+pub fn progress_ring() -> impl Widget<f64> {
+    Painter::new(|ctx, fraction: &f64, env| {
+        let center = ctx.size().to_rect().center();
+        let radius = (SIZE - STROKE) / 2.0;
+        ctx.stroke(
+            Circle::new(center, radius),
+            &env.get(theme::BORDER_DARK),
+            STROKE,
+        );
+        let arc = Arc {
+            center,
+            radii: Vec2::new(radius, radius),
+            start_angle: -PI / 2.0,
+            sweep_angle: 2.0 * PI * fraction.max(0.0).min(1.0),
+            x_rotation: 0.0,
+        };
+        ctx.stroke(arc, &env.get(theme::PRIMARY_LIGHT), STROKE);
+    })
+    .fix_size(SIZE, SIZE)
+}
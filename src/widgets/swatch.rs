@@ -0,0 +1,18 @@
+use druid::{kurbo::Circle, widget::Painter, Color, RenderContext, Widget, WidgetExt};
+
+const SIZE: f64 = 8.0;
+
+/// A dot in the given `0xRRGGBB` color, or empty space without one so
+/// that names stay aligned.
+pub fn color_dot() -> impl Widget<Option<u32>> {
+    Painter::new(|ctx, color: &Option<u32>, _| {
+        if let Some(color) = color {
+            let center = ctx.size().to_rect().center();
+            ctx.fill(
+                Circle::new(center, SIZE / 2.0),
+                &Color::from_rgba32_u32(color << 8 | 0xff),
+            );
+        }
+    })
+    .fix_size(SIZE, SIZE)
+}
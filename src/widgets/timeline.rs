@@ -0,0 +1,143 @@
+use druid::{
+    kurbo::Line,
+    piet::{FontFamily, Text, TextLayout, TextLayoutBuilder},
+    theme,
+    widget::prelude::*,
+    Color, Data, Point, Rect, Vec2,
+};
+use im::Vector;
+use std::sync::Arc;
+
+use super::Tooltip;
+
+/// Height of an hour.
+const HOUR: f64 = 20.0;
+/// Width of a day.
+const DAY: f64 = 90.0;
+/// Width of the hours written left of the days.
+const AXIS: f64 = 40.0;
+/// Height of the weekdays written above the days.
+const HEADER: f64 = 20.0;
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Time worked on a day of the week, never past its midnight.
+#[derive(Clone, Data)]
+pub struct TimelineBlock {
+    /// Day of the week, 0 for monday.
+    pub day: usize,
+    /// Hours since midnight.
+    pub start: f64,
+    pub end: f64,
+    /// As `0xRRGGBB`, the primary color of the theme without one.
+    pub color: Option<u32>,
+    /// Shown while the mouse rests on the block.
+    pub label: Arc<str>,
+}
+
+impl TimelineBlock {
+    fn rect(&self) -> Rect {
+        let left = AXIS + DAY * self.day as f64;
+        Rect::new(
+            left + 1.0,
+            HEADER + HOUR * self.start,
+            left + DAY - 1.0,
+            HEADER + HOUR * self.end,
+        )
+    }
+}
+
+/// A week as columns of days with the time of day running down, like a calendar.
+struct Timeline;
+
+impl Widget<Vector<TimelineBlock>> for Timeline {
+    fn event(&mut self, _: &mut EventCtx, _: &Event, _: &mut Vector<TimelineBlock>, _: &Env) {}
+
+    fn lifecycle(
+        &mut self,
+        _: &mut LifeCycleCtx,
+        _: &LifeCycle,
+        _: &Vector<TimelineBlock>,
+        _: &Env,
+    ) {
+    }
+
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &Vector<TimelineBlock>,
+        data: &Vector<TimelineBlock>,
+        _: &Env,
+    ) {
+        if !old_data.same(data) {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _: &Vector<TimelineBlock>,
+        _: &Env,
+    ) -> Size {
+        bc.constrain(Size::new(AXIS + DAY * 7.0, HEADER + HOUR * 24.0))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &Vector<TimelineBlock>, env: &Env) {
+        let text_size = env.get(theme::TEXT_SIZE_NORMAL) * 0.8;
+        let text_color = env.get(theme::LABEL_COLOR);
+        let grid = env.get(theme::BORDER_LIGHT);
+        let right = AXIS + DAY * 7.0;
+        let write = |ctx: &mut PaintCtx, text: String, center: Point| {
+            if let Ok(layout) = ctx
+                .text()
+                .new_text_layout(text)
+                .font(FontFamily::SYSTEM_UI, text_size)
+                .text_color(text_color.clone())
+                .build()
+            {
+                let size = layout.size();
+                let origin = center - Vec2::new(size.width / 2.0, size.height / 2.0);
+                ctx.draw_text(&layout, origin);
+            }
+        };
+        for (day, name) in WEEKDAYS.iter().enumerate() {
+            let left = AXIS + DAY * day as f64;
+            write(
+                ctx,
+                name.to_string(),
+                Point::new(left + DAY / 2.0, HEADER / 2.0),
+            );
+            ctx.stroke(
+                Line::new((left, HEADER), (left, HEADER + HOUR * 24.0)),
+                &grid,
+                1.0,
+            );
+        }
+        for hour in (0..=24).step_by(2) {
+            let y = HEADER + HOUR * hour as f64;
+            ctx.stroke(Line::new((AXIS, y), (right, y)), &grid, 1.0);
+            if hour < 24 {
+                write(ctx, format!("{:02}:00", hour), Point::new(AXIS / 2.0, y));
+            }
+        }
+        let primary = env.get(theme::PRIMARY_LIGHT);
+        for block in data {
+            let color = match block.color {
+                Some(color) => Color::from_rgba32_u32(color << 8 | 0xff),
+                None => primary.clone(),
+            };
+            ctx.fill(block.rect(), &color);
+        }
+    }
+}
+
+/// The blocks of a week on a timeline, naming the one under the mouse.
+pub fn week_timeline() -> impl Widget<Vector<TimelineBlock>> {
+    Tooltip::at(Timeline, |blocks: &Vector<TimelineBlock>, mouse, _| {
+        blocks
+            .iter()
+            .find(|block| block.rect().contains(mouse))
+            .map(|block| block.label.to_string())
+    })
+}
@@ -0,0 +1,126 @@
+use druid::{
+    piet::{FontFamily, Text, TextLayout, TextLayoutBuilder},
+    theme,
+    widget::prelude::*,
+    Data, Point, Rect, TimerToken, Vec2, WidgetPod,
+};
+use std::time::Duration;
+
+const DELAY: Duration = Duration::from_millis(600);
+const OFFSET: Vec2 = Vec2::new(12.0, 16.0);
+
+/// Shows a line of text next to the mouse once it rests on the child.
+/// The text is computed from the data, no tooltip is shown for `None`.
+pub struct Tooltip<T> {
+    child: WidgetPod<T, Box<dyn Widget<T>>>,
+    text: Box<dyn Fn(&T, Point, &Env) -> Option<String>>,
+    timer: TimerToken,
+    mouse: Point,
+    shown: bool,
+}
+
+impl<T: Data> Tooltip<T> {
+    pub fn new(
+        child: impl Widget<T> + 'static,
+        text: impl Fn(&T, &Env) -> Option<String> + 'static,
+    ) -> Tooltip<T> {
+        Tooltip::at(child, move |data, _, env| text(data, env))
+    }
+
+    /// A tooltip depending on where the mouse rests, in the child's coordinates.
+    pub fn at(
+        child: impl Widget<T> + 'static,
+        text: impl Fn(&T, Point, &Env) -> Option<String> + 'static,
+    ) -> Tooltip<T> {
+        Tooltip {
+            child: WidgetPod::new(Box::new(child)),
+            text: Box::new(text),
+            timer: TimerToken::INVALID,
+            mouse: Point::ZERO,
+            shown: false,
+        }
+    }
+
+    fn hide(&mut self, ctx: &mut EventCtx) {
+        self.timer = TimerToken::INVALID;
+        if self.shown {
+            self.shown = false;
+            ctx.request_paint();
+        }
+    }
+}
+
+impl<T: Data> Widget<T> for Tooltip<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseMove(mouse) if ctx.is_hot() => {
+                self.hide(ctx);
+                self.mouse = mouse.pos;
+                self.timer = ctx.request_timer(DELAY);
+            }
+            Event::MouseMove(_) | Event::MouseDown(_) => self.hide(ctx),
+            Event::Timer(token) if *token == self.timer => {
+                self.timer = TimerToken::INVALID;
+                self.shown = true;
+                ctx.request_paint();
+            }
+            _ => (),
+        }
+        self.child.event(ctx, event, data, env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::HotChanged(false) = event {
+            self.timer = TimerToken::INVALID;
+            if self.shown {
+                self.shown = false;
+                ctx.request_paint();
+            }
+        }
+        self.child.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+        if self.shown {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_layout_rect(ctx, data, env, size.to_rect());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+        if !self.shown {
+            return;
+        }
+        if let Some(text) = (self.text)(data, self.mouse, env) {
+            let origin = self.mouse + OFFSET;
+            let text_size = env.get(theme::TEXT_SIZE_NORMAL);
+            let text_color = env.get(theme::LABEL_COLOR);
+            let background = env.get(theme::BACKGROUND_LIGHT);
+            let border = env.get(theme::BORDER_DARK);
+            // Painted last so the tooltip is not covered or clipped by other widgets.
+            ctx.paint_with_z_index(1, move |ctx| {
+                let layout = match ctx
+                    .text()
+                    .new_text_layout(text)
+                    .font(FontFamily::SYSTEM_UI, text_size)
+                    .text_color(text_color)
+                    .build()
+                {
+                    Ok(layout) => layout,
+                    Err(_) => return,
+                };
+                let rect = Rect::from_origin_size(origin, layout.size()).inflate(4.0, 2.0);
+                ctx.fill(rect, &background);
+                ctx.stroke(rect, &border, 1.0);
+                ctx.draw_text(&layout, origin);
+            });
+        }
+    }
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use common::{at, minutes};
+use im::Vector;
+use zeitig::state::{ActiveSession, Interruption, Interval};
+
+fn stretches(intervals: &Vector<Interval>) -> Vec<(time::OffsetDateTime, time::OffsetDateTime)> {
+    intervals
+        .iter()
+        .map(|interval| (*interval.started, *interval.ended))
+        .collect()
+}
+
+#[test]
+fn splits_a_running_session_at_midnight() {
+    let mut active = ActiveSession::new(at(1, 22, 0));
+    active.pause(at(1, 23, 0));
+    active.paused_since = None;
+    active.resumed = at(1, 23, 30);
+    active.duration = minutes(120);
+    active.interruptions.push_back(Interruption {
+        at: at(1, 22, 15),
+        cause: None,
+    });
+    active.interruptions.push_back(Interruption {
+        at: at(1, 24, 10),
+        cause: None,
+    });
+    let midnight = at(1, 22, 0).next_midnight();
+
+    let before = active.split_at(midnight);
+    assert_eq!(*before.started, *at(1, 22, 0));
+    assert_eq!(
+        stretches(&before.intervals),
+        vec![(*at(1, 22, 0), *at(1, 23, 0)), (*at(1, 23, 30), *midnight)]
+    );
+    assert_eq!(before.duration, minutes(90));
+    assert_eq!(before.paused_since.map(|at| *at), Some(*midnight));
+    assert_eq!(before.interruptions.len(), 1);
+
+    assert_eq!(*active.started, *midnight);
+    assert_eq!(*active.resumed, *midnight);
+    assert!(active.intervals.is_empty());
+    assert!(active.paused_since.is_none());
+    assert_eq!(active.duration, minutes(30));
+    assert_eq!(active.interruptions.len(), 1);
+    assert_eq!(*active.interruptions[0].at, *at(1, 24, 10));
+}
+
+#[test]
+fn splits_a_session_paused_over_midnight() {
+    let mut active = ActiveSession::new(at(1, 22, 0));
+    active.duration = minutes(90);
+    active.pause(at(1, 23, 30));
+    let midnight = at(1, 22, 0).next_midnight();
+
+    let before = active.split_at(midnight);
+    assert_eq!(
+        stretches(&before.intervals),
+        vec![(*at(1, 22, 0), *at(1, 23, 30))]
+    );
+    assert_eq!(before.duration, minutes(90));
+
+    assert_eq!(*active.started, *midnight);
+    assert!(active.intervals.is_empty());
+    assert_eq!(active.paused_since.map(|at| *at), Some(*midnight));
+    assert_eq!(active.duration, minutes(0));
+}
@@ -0,0 +1,40 @@
+//! Fixtures shared by the tests. Not every test uses all of them.
+#![allow(dead_code)]
+
+use zeitig::state::{Date, DateTime, History, Running, Session, SpentTime, Topic};
+
+pub fn minutes(minutes: u64) -> SpentTime {
+    SpentTime::from_secs(minutes * 60)
+}
+
+/// A day in March 2021, which starts on a Monday.
+pub fn day(day: u8) -> Date {
+    Date::from(time::Date::try_from_ymd(2021, 3, day).unwrap())
+}
+
+/// A moment on a day in March 2021, in UTC. Hours from 24 on fall on the
+/// following days.
+pub fn at(on: u8, hour: u8, minute: u8) -> DateTime {
+    on_day(day(on), hour, minute)
+}
+
+/// A moment on `day`, in UTC. Hours from 24 on fall on the following days.
+pub fn on_day(day: Date, hour: u8, minute: u8) -> DateTime {
+    let date = *day + time::Duration::days(hour as i64 / 24);
+    let at = date.try_with_hms(hour % 24, minute, 0).unwrap();
+    DateTime::from(at.assume_utc())
+}
+
+/// A session on `topic` worked throughout for `minutes`.
+pub fn session(topic: &Topic, started: DateTime, minutes: i64) -> Session {
+    let running = Running {
+        topic: topic.clone(),
+        context: None,
+        started,
+    };
+    running.session(DateTime::from(*started + time::Duration::minutes(minutes)))
+}
+
+pub fn history(sessions: Vec<Session>) -> History {
+    History::new(sessions.into_iter().collect(), true)
+}
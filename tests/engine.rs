@@ -0,0 +1,40 @@
+use std::{
+    path::Path,
+    time::{Duration, SystemTime},
+};
+use zeitig::engine::{Stopped, Topic, Tracker};
+
+#[test]
+fn tracks_by_names() {
+    let mut tracker = Tracker::open(Path::new(":memory:")).unwrap();
+    tracker.create_action("Write").unwrap();
+    tracker.create_subject("Thesis").unwrap();
+    assert!(tracker.create_action("write").is_err());
+    assert_eq!(tracker.actions(), vec!["Write".to_string()]);
+
+    let topic = tracker.find_topic("WRITE", "thesis").unwrap();
+    assert_eq!(
+        topic,
+        Topic {
+            action: "Write".into(),
+            subject: "Thesis".into(),
+        }
+    );
+    assert!(tracker.find_topic("Read", "Thesis").is_err());
+
+    assert_eq!(tracker.start(&topic, None).unwrap(), Stopped::Nothing);
+    assert_eq!(tracker.running().unwrap().unwrap().topic, topic);
+    match tracker.stop().unwrap() {
+        Stopped::Discarded(running) => assert_eq!(running.topic, topic),
+        _ => panic!("A session of less than a minute is not recorded."),
+    }
+    assert_eq!(tracker.running().unwrap(), None);
+
+    let ended = SystemTime::now() - Duration::from_secs(3600);
+    let started = ended - Duration::from_secs(5400);
+    tracker.record(&topic, None, started, ended).unwrap();
+    let history = tracker.history().unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].topic, topic);
+    assert_eq!(history[0].worked, Duration::from_secs(5400));
+}
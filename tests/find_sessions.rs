@@ -0,0 +1,62 @@
+mod common;
+
+use common::{at, minutes, session};
+use zeitig::state::{
+    backend::{Backend, Sqlite},
+    history::SessionQuery,
+    Content, Interval, Topic,
+};
+
+fn found(backend: &mut Sqlite, content: &Content, query: SessionQuery) -> Vec<u8> {
+    let history = backend.find_sessions(content, &query).unwrap();
+    history
+        .iter()
+        .map(|session| session.started.day())
+        .collect()
+}
+
+#[test]
+fn bounds_apply_to_the_time_worked_as_amended() {
+    let mut backend = Sqlite::new(":memory:").unwrap();
+    backend.setup().unwrap();
+    let topic = Topic {
+        action: backend.create_action("Work").unwrap(),
+        subject: backend.create_subject("Thesis").unwrap(),
+    };
+
+    // Four hours on the first with a break of one, one hour on the second.
+    let mut paused = session(&topic, at(1, 8, 0), 4 * 60);
+    paused.intervals = vec![
+        Interval::new(at(1, 8, 0), at(1, 9, 0)),
+        Interval::new(at(1, 10, 0), at(1, 12, 0)),
+    ]
+    .into_iter()
+    .collect();
+    let short = session(&topic, at(2, 8, 0), 60);
+    backend.add_session(&paused).unwrap();
+    backend.add_session(&short).unwrap();
+    let content = backend.load_content().unwrap();
+
+    let longer_than = |time| SessionQuery {
+        longer_than: Some(time),
+        shorter_than: None,
+    };
+    assert!(found(&mut backend, &content, longer_than(minutes(210))).is_empty());
+    assert_eq!(
+        found(&mut backend, &content, longer_than(minutes(150))),
+        vec![1]
+    );
+
+    let mut amended = short.clone();
+    amended.ended = at(2, 12, 0);
+    backend.amend_session(&short, Some(&amended)).unwrap();
+    assert_eq!(
+        found(&mut backend, &content, longer_than(minutes(210))),
+        vec![2]
+    );
+    let shorter_than = SessionQuery {
+        longer_than: None,
+        shorter_than: Some(minutes(200)),
+    };
+    assert_eq!(found(&mut backend, &content, shorter_than), vec![1]);
+}
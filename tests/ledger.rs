@@ -0,0 +1,120 @@
+mod common;
+
+use common::{day, history, on_day, session};
+use zeitig::state::{
+    ledger::{self, Adjustment, DayOff, DayOffKind},
+    Action, Content, Date, DateTime, Session, Subject, Topic,
+};
+
+const HOUR: i64 = 3600;
+
+/// The Monday `weeks` weeks before the current one.
+fn weeks_ago(weeks: i64) -> Date {
+    let week = Date::from(*DateTime::now()).week_begin();
+    Date::from(*week - time::Duration::weeks(weeks))
+}
+
+fn plus_days(day: Date, days: i64) -> Date {
+    Date::from(*day + time::Duration::days(days))
+}
+
+/// A session of `hours` started at eight on `day`.
+fn work(day: Date, hours: i64) -> Session {
+    let topic = Topic {
+        action: Action::new(1, "Work"),
+        subject: Subject::new(1, "Office"),
+    };
+    session(&topic, on_day(day, 8, 0), hours * 60)
+}
+
+/// Content with 40 weekly hours, accounted from `start` on.
+fn content(start: Option<Date>) -> Content {
+    let mut content = Content::default();
+    content.settings.weekly_hours = 40.0;
+    content.settings.ledger_start = start
+        .map(|start| start.format("%Y-%m-%d"))
+        .unwrap_or_default();
+    content
+}
+
+#[test]
+fn work_is_expected_on_weekdays_only() {
+    let mut content = content(None);
+    content.days_off.push_back(DayOff {
+        day: day(3),
+        kind: DayOffKind::Holiday,
+    });
+    assert_eq!(ledger::target(&content, day(1)), 8 * HOUR);
+    assert_eq!(ledger::target(&content, day(3)), 0);
+    assert_eq!(ledger::target(&content, day(6)), 0);
+    assert_eq!(ledger::target(&content, day(7)), 0);
+    assert_eq!(ledger::target(&Content::default(), day(1)), 0);
+}
+
+#[test]
+fn days_off_and_weekends_add_to_the_balance() {
+    let monday = weeks_ago(1);
+    let mut content = content(Some(monday));
+    content.days_off.push_back(DayOff {
+        day: plus_days(monday, 2),
+        kind: DayOffKind::Holiday,
+    });
+    let mut sessions: Vec<Session> = vec![9, 8, 1, 8, 6, 1]
+        .into_iter()
+        .enumerate()
+        .map(|(days, hours)| work(plus_days(monday, days as i64), hours))
+        .collect();
+    // The current week is not carried over yet.
+    sessions.push(work(weeks_ago(0), 3));
+    let sessions = history(sessions);
+    assert_eq!(ledger::carryover(&content, &sessions), Some(HOUR));
+
+    // A week without any work misses all of its hours.
+    content.settings.ledger_start = weeks_ago(2).format("%Y-%m-%d");
+    assert_eq!(
+        ledger::carryover(&content, &sessions),
+        Some(HOUR - 40 * HOUR)
+    );
+}
+
+#[test]
+fn adjustments_count_within_the_range() {
+    let monday = weeks_ago(1);
+    let mut content = content(Some(monday));
+    for &(day, seconds) in &[
+        (plus_days(monday, -1), 5 * HOUR),
+        (plus_days(monday, 1), -HOUR / 2),
+        (weeks_ago(0), 2 * HOUR),
+    ] {
+        content.adjustments.push_back(Adjustment {
+            day,
+            seconds,
+            note: "Paid out".into(),
+        });
+    }
+    let sessions = history(
+        (0..5)
+            .map(|days| work(plus_days(monday, days), 8))
+            .collect(),
+    );
+    assert_eq!(ledger::carryover(&content, &sessions), Some(-HOUR / 2));
+}
+
+#[test]
+fn the_account_starts_with_the_week_of_the_first_session() {
+    let monday = weeks_ago(1);
+    let sessions = history(vec![
+        work(plus_days(monday, 2), 8),
+        work(plus_days(monday, 3), 8),
+        work(plus_days(monday, 4), 8),
+    ]);
+    assert_eq!(
+        ledger::carryover(&content(None), &sessions),
+        Some(-16 * HOUR)
+    );
+    assert_eq!(
+        ledger::carryover(&content(Some(weeks_ago(0))), &sessions),
+        Some(0)
+    );
+    assert_eq!(ledger::carryover(&Content::default(), &sessions), None);
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use common::{at, minutes, session};
+use im::Vector;
+use zeitig::state::{
+    backend::{Backend, Sqlite},
+    history::Merge,
+    totals::{Dimension, Grouping},
+    Action, Content, DateTime, Interval, SpentTime, Topic,
+};
+
+fn per_action(content: &Content) -> Vec<(String, SpentTime)> {
+    let mut totals: Vec<(String, SpentTime)> = content
+        .totals
+        .group_by(Grouping::of(&[Dimension::Action]))
+        .map(|(key, time)| (key.action.as_ref().unwrap().name.to_string(), time))
+        .collect();
+    totals.sort_by(|a, b| a.0.cmp(&b.0));
+    totals
+}
+
+fn merge(from: Action, into: Action, sessions: Vector<DateTime>) -> Merge<Action> {
+    Merge {
+        from,
+        into,
+        sessions,
+        time: Vector::new(),
+        pins: Vector::new(),
+        aliases: Vector::new(),
+        launch_rules: Vector::new(),
+        goals: Vector::new(),
+        checklist: Vector::new(),
+        category: None,
+        children: Vector::new(),
+    }
+}
+
+#[test]
+fn merging_keeps_the_amended_time_worked() {
+    let mut backend = Sqlite::new(":memory:").unwrap();
+    backend.setup().unwrap();
+    let work = backend.create_action("Work").unwrap();
+    let study = backend.create_action("Study").unwrap();
+    let topic = Topic {
+        action: work.clone(),
+        subject: backend.create_subject("Thesis").unwrap(),
+    };
+
+    // Four hours with a break of one, then amended to end an hour earlier.
+    let mut original = session(&topic, at(1, 8, 0), 4 * 60);
+    original.intervals = vec![
+        Interval::new(at(1, 8, 0), at(1, 9, 0)),
+        Interval::new(at(1, 10, 0), at(1, 12, 0)),
+    ]
+    .into_iter()
+    .collect();
+    backend.add_session(&original).unwrap();
+    let content = backend.load_content().unwrap();
+    backend.load_history(&content).unwrap();
+    let mut amended = original.clone();
+    amended.ended = at(1, 11, 0);
+    amended
+        .intervals
+        .set(1, Interval::new(at(1, 10, 0), at(1, 11, 0)));
+    backend.amend_session(&original, Some(&amended)).unwrap();
+    assert_eq!(
+        per_action(&backend.load_content().unwrap()),
+        vec![("Work".to_string(), minutes(120))]
+    );
+
+    let merged = merge(work, study, Vector::unit(original.key()));
+    backend.merge_actions(&merged).unwrap();
+    assert_eq!(
+        per_action(&backend.load_content().unwrap()),
+        vec![("Study".to_string(), minutes(120))]
+    );
+
+    backend.split_actions(&merged).unwrap();
+    assert_eq!(
+        per_action(&backend.load_content().unwrap()),
+        vec![("Work".to_string(), minutes(120))]
+    );
+}
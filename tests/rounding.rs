@@ -0,0 +1,72 @@
+mod common;
+
+use common::{day, minutes};
+use zeitig::state::{rounding::Rounding, SpentTime};
+
+fn rounding(up: bool, per_day: bool) -> Rounding {
+    Rounding {
+        minutes: 15,
+        up,
+        per_day,
+    }
+}
+
+#[test]
+fn rounds_to_the_nearest_increment() {
+    let nearest = rounding(false, false);
+    assert_eq!(nearest.round(minutes(7)), minutes(0));
+    assert_eq!(
+        nearest.round(SpentTime::from_secs(7 * 60 + 30)),
+        minutes(15)
+    );
+    assert_eq!(nearest.round(minutes(22)), minutes(15));
+    assert_eq!(nearest.round(minutes(23)), minutes(30));
+    assert_eq!(nearest.round(minutes(30)), minutes(30));
+}
+
+#[test]
+fn rounds_up_to_the_next_increment() {
+    let up = rounding(true, false);
+    assert_eq!(up.round(minutes(0)), minutes(0));
+    assert_eq!(up.round(SpentTime::from_secs(1)), minutes(15));
+    assert_eq!(up.round(minutes(15)), minutes(15));
+    assert_eq!(up.round(minutes(16)), minutes(30));
+}
+
+#[test]
+fn keeps_the_time_without_increments() {
+    let exact = Rounding {
+        minutes: 0,
+        up: true,
+        per_day: false,
+    };
+    assert_eq!(
+        exact.round(SpentTime::from_secs(61)),
+        SpentTime::from_secs(61)
+    );
+}
+
+#[test]
+fn rounds_each_session_or_each_day() {
+    let sessions = vec![
+        (day(1), "a", minutes(5)),
+        (day(1), "a", minutes(5)),
+        (day(1), "b", minutes(20)),
+        (day(2), "a", minutes(5)),
+    ];
+
+    let per_session = rounding(true, false).add_up(sessions.clone());
+    assert_eq!(per_session[&(day(1), "a")], minutes(30));
+    assert_eq!(per_session[&(day(1), "b")], minutes(30));
+    assert_eq!(per_session[&(day(2), "a")], minutes(15));
+
+    let per_day = rounding(true, true).add_up(sessions.clone());
+    assert_eq!(per_day[&(day(1), "a")], minutes(15));
+    assert_eq!(per_day[&(day(1), "b")], minutes(30));
+    assert_eq!(per_day[&(day(2), "a")], minutes(15));
+
+    let nearest_per_day = rounding(false, true).add_up(sessions);
+    assert_eq!(nearest_per_day[&(day(1), "a")], minutes(15));
+    assert_eq!(nearest_per_day[&(day(1), "b")], minutes(15));
+    assert_eq!(nearest_per_day[&(day(2), "a")], minutes(0));
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use common::minutes;
+use std::time::Duration;
+use zeitig::state::{SpentTime, TimeFormat, Unit};
+
+#[test]
+fn parses_written_out_times() {
+    assert_eq!("1h30m".parse::<SpentTime>().unwrap(), minutes(90));
+    assert_eq!("1h 30m".parse::<SpentTime>().unwrap(), minutes(90));
+    assert_eq!("1.5h".parse::<SpentTime>().unwrap(), minutes(90));
+    assert_eq!("1,5 hours".parse::<SpentTime>().unwrap(), minutes(90));
+    assert_eq!("90 min".parse::<SpentTime>().unwrap(), minutes(90));
+    assert_eq!("1:30".parse::<SpentTime>().unwrap(), minutes(90));
+    assert_eq!(
+        "1:30:15".parse::<SpentTime>().unwrap(),
+        SpentTime::from_secs(5415)
+    );
+    assert_eq!(
+        "45s".parse::<SpentTime>().unwrap(),
+        SpentTime::from_secs(45)
+    );
+}
+
+#[test]
+fn plain_numbers_take_the_given_unit() {
+    assert_eq!("90".parse::<SpentTime>().unwrap(), minutes(90));
+    assert_eq!(
+        SpentTime::parse_in("1.5", Unit::Hours).unwrap(),
+        minutes(90)
+    );
+    assert_eq!(
+        SpentTime::parse_in("2h", Unit::Minutes).unwrap(),
+        minutes(120)
+    );
+}
+
+#[test]
+fn rejects_malformed_times() {
+    for text in &["", "h", "1x", "1h 30", "1:75", "-5", "1::2", "1e400h"] {
+        assert!(text.parse::<SpentTime>().is_err(), "{} was accepted", text);
+    }
+}
+
+#[test]
+fn rejects_times_too_long_to_hold() {
+    let hours = u64::MAX / 3600 + 1;
+    for text in &[
+        format!("{}:00", hours),
+        format!("{}:59:59", u64::MAX / 3600),
+    ] {
+        assert!(text.parse::<SpentTime>().is_err(), "{} was accepted", text);
+    }
+    let longest = format!("{}:00", u64::MAX / 3600);
+    assert!(longest.parse::<SpentTime>().is_ok());
+}
+
+#[test]
+fn formats() {
+    let time = SpentTime::from_secs(5415);
+    assert_eq!(time.format(TimeFormat::Long), "1h 30m 15s");
+    assert_eq!(time.format(TimeFormat::Long), time.to_string());
+    assert_eq!(time.format(TimeFormat::Short), "1h 30m");
+    assert_eq!(time.format(TimeFormat::Clock), "1:30");
+    assert_eq!(minutes(90).format(TimeFormat::Hours), "1.5");
+}
+
+#[test]
+fn formatted_times_parse_back() {
+    let time = minutes(90);
+    for format in &[TimeFormat::Long, TimeFormat::Short, TimeFormat::Clock] {
+        assert_eq!(time.format(*format).parse::<SpentTime>().unwrap(), time);
+    }
+    let hours = time.format(TimeFormat::Hours);
+    assert_eq!(SpentTime::parse_in(&hours, Unit::Hours).unwrap(), time);
+}
+
+#[test]
+fn checked_arithmetic() {
+    assert_eq!(minutes(30).checked_add(minutes(60)), Some(minutes(90)));
+    assert_eq!(minutes(90).checked_sub(minutes(60)), Some(minutes(30)));
+    assert_eq!(minutes(30).checked_sub(minutes(60)), None);
+    assert_eq!(
+        minutes(30).saturating_sub(minutes(60)),
+        SpentTime::default()
+    );
+    let longest = SpentTime::from(Duration::new(u64::MAX, 0));
+    assert_eq!(longest.checked_add(SpentTime::from_secs(1)), None);
+    assert_eq!(
+        vec![minutes(30), minutes(60)]
+            .into_iter()
+            .sum::<SpentTime>(),
+        minutes(90)
+    );
+}
+
+#[test]
+fn serde_round_trip() {
+    for time in &[SpentTime::default(), SpentTime::from_secs(1), minutes(90)] {
+        let json = serde_json::to_string(time).unwrap();
+        assert_eq!(serde_json::from_str::<SpentTime>(&json).unwrap(), *time);
+    }
+    assert_eq!(serde_json::to_string(&minutes(90)).unwrap(), "5400");
+}
+
+#[test]
+fn serde_reads_written_out_times() {
+    assert_eq!(
+        serde_json::from_str::<SpentTime>("\"1h30m\"").unwrap(),
+        minutes(90)
+    );
+    assert!(serde_json::from_str::<SpentTime>("-5").is_err());
+    assert!(serde_json::from_str::<SpentTime>("\"soon\"").is_err());
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use common::{at, day, history, session};
+use zeitig::state::{streak, Action, Subject, Topic};
+
+fn practice(subject: &Subject) -> Topic {
+    Topic {
+        action: Action::new(1, "Practice"),
+        subject: subject.clone(),
+    }
+}
+
+#[test]
+fn a_gap_ends_the_streak() {
+    let piano = practice(&Subject::new(1, "Piano"));
+    let sessions = history(vec![
+        session(&piano, at(1, 9, 0), 30),
+        session(&piano, at(2, 9, 0), 30),
+        session(&piano, at(3, 9, 0), 30),
+        session(&piano, at(5, 9, 0), 30),
+        session(&piano, at(6, 9, 0), 30),
+    ]);
+    let streaks = streak::streaks(&sessions, 15.0, day(6));
+    assert_eq!(streaks.len(), 1);
+    assert_eq!(streaks[0].current, 2);
+    assert_eq!(streaks[0].longest, 3);
+    assert_eq!(streaks[0].badges(), vec!["3 days"]);
+}
+
+#[test]
+fn days_short_of_the_minutes_do_not_count() {
+    let piano = practice(&Subject::new(1, "Piano"));
+    let sessions = history(vec![
+        session(&piano, at(1, 9, 0), 30),
+        session(&piano, at(2, 9, 0), 10),
+        session(&piano, at(2, 9, 0), 10),
+        session(&piano, at(3, 9, 0), 10),
+        session(&piano, at(4, 9, 0), 30),
+    ]);
+    let streaks = streak::streaks(&sessions, 15.0, day(4));
+    assert_eq!(streaks[0].current, 1);
+    assert_eq!(streaks[0].longest, 2);
+}
+
+#[test]
+fn a_streak_goes_on_while_today_is_missing() {
+    let piano = practice(&Subject::new(1, "Piano"));
+    let sessions = history(vec![
+        session(&piano, at(1, 9, 0), 30),
+        session(&piano, at(2, 9, 0), 30),
+    ]);
+    assert_eq!(
+        streak::streaks(&sessions, 15.0, day(3))[0].current,
+        2
+    );
+    let streaks = streak::streaks(&sessions, 15.0, day(4));
+    assert_eq!(streaks[0].current, 0);
+    assert_eq!(streaks[0].longest, 2);
+}
+
+#[test]
+fn the_longest_current_streaks_come_first() {
+    let piano = practice(&Subject::new(1, "Piano"));
+    let spanish = practice(&Subject::new(2, "Spanish"));
+    let running = practice(&Subject::new(3, "Running"));
+    let sessions = history(vec![
+        session(&piano, at(4, 9, 0), 30),
+        session(&spanish, at(1, 9, 0), 30),
+        session(&spanish, at(2, 9, 0), 30),
+        session(&spanish, at(3, 9, 0), 30),
+        session(&spanish, at(4, 9, 0), 30),
+        session(&running, at(1, 9, 0), 5),
+    ]);
+    let streaks = streak::streaks(&sessions, 15.0, day(4));
+    let names: Vec<&str> = streaks.iter().map(|s| s.subject.name.as_ref()).collect();
+    assert_eq!(names, vec!["Spanish", "Piano"]);
+}